@@ -12,4 +12,7 @@ pub struct FigureDescription {
     pub filename: String,
     pub consts: pxu::CouplingConstants,
     pub paper_ref: Vec<String>,
+    // Whether the figure is saved in the binary format instead of as RON
+    #[serde(default)]
+    pub binary: bool,
 }
@@ -11,7 +11,7 @@ const FILENAME: &str = "cache";
 
 const HEADER: &str = "name md5(tex) md5(pdf)";
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct CacheEntry {
     tex_hash: String,
     pdf_hash: String,
@@ -136,6 +136,13 @@ impl Cache {
         Ok(())
     }
 
+    // Keep the entry of a figure that was not built this time
+    pub fn keep(&mut self, other: &Cache, name: &str) {
+        if let Some(entry) = other.entries.get(name) {
+            self.entries.insert(name.to_owned(), entry.clone());
+        }
+    }
+
     pub fn save(self) -> Result<()> {
         let path = PathBuf::from(&self.dirname).join(FILENAME);
         let mut writer = BufWriter::new(File::create(path)?);
@@ -0,0 +1,206 @@
+use std::fmt::Write;
+
+use crate::fig_style::{color_rgb, HorizontalAnchor, Mark, Style, VerticalAnchor, PT_PER_CM};
+use crate::fig_writer::{Bounds, Drawing};
+use crate::utils::Size;
+
+// Asymptote measures the line widths in PostScript points
+const BP_PER_PT: f64 = 72.0 / 72.27;
+
+fn asy_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\\\""))
+}
+
+fn color_pen(color: &str) -> String {
+    let rgb = color_rgb(color).unwrap_or_else(|| {
+        log::warn!("Unknown color {color}");
+        "000000"
+    });
+    format!("rgb(\"{rgb}\")")
+}
+
+fn line_pen(style: &Style) -> String {
+    let mut pen = format!(
+        "{}+linewidth({:.3})",
+        color_pen(&style.color),
+        style.line_width * BP_PER_PT
+    );
+    if style.opacity != 1.0 {
+        let _ = write!(pen, "+opacity({})", style.opacity);
+    }
+    if let Some([on, off]) = style.dash {
+        let _ = write!(
+            pen,
+            "+linetype(new real[] {{{:.3}, {:.3}}}, scale=false, adjust=false)",
+            on * BP_PER_PT,
+            off * BP_PER_PT
+        );
+    }
+    pen
+}
+
+// The direction of a label from its position, where the label is on the
+// opposite side from its anchor
+fn label_align(style: &Style) -> &'static str {
+    match (style.horizontal_anchor(), style.vertical_anchor()) {
+        (HorizontalAnchor::Left, VerticalAnchor::Top) => "SE",
+        (HorizontalAnchor::Left, _) => "E",
+        (HorizontalAnchor::Center, VerticalAnchor::Top) => "S",
+        (HorizontalAnchor::Center, VerticalAnchor::Baseline) => "N",
+        (HorizontalAnchor::Center, VerticalAnchor::Center) => "NoAlign",
+        (HorizontalAnchor::Right, VerticalAnchor::Top) => "SW",
+        (HorizontalAnchor::Right, _) => "W",
+    }
+}
+
+// An Asymptote file drawing a figure. Asymptote typesets the labels with
+// LaTeX, so the text of the nodes is used as it is.
+#[derive(Debug)]
+pub struct AsymptoteFigure {
+    bounds: Bounds,
+    size: Size,
+    frame: bool,
+    left: f64,
+    lines: Vec<String>,
+}
+
+impl AsymptoteFigure {
+    pub fn new(bounds: Bounds, size: Size, frame: bool) -> Self {
+        Self {
+            bounds,
+            size,
+            frame,
+            left: 0.0,
+            lines: vec![],
+        }
+    }
+
+    // The position in cm from the lower left corner of the plot
+    fn transform(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            (x - self.bounds.x_range.start) / self.bounds.width() * self.size.width,
+            (y - self.bounds.y_range.start) / self.bounds.height() * self.size.height,
+        )
+    }
+
+    // The commands draw on the picture p, which is clipped to the plot area
+    // if asked for
+    fn push(&mut self, commands: Vec<String>, clip: bool) {
+        if clip {
+            self.lines.push(format!(
+                "{{ picture p; unitsize(p, 1cm); {} clip(p, plot_area); add(p); }}",
+                commands.join(" ")
+            ));
+        } else {
+            self.lines.push(format!(
+                "{{ picture p = currentpicture; {} }}",
+                commands.join(" ")
+            ));
+        }
+    }
+}
+
+impl Drawing for AsymptoteFigure {
+    fn extend_left(&mut self, value: f64) {
+        self.left += value;
+    }
+
+    fn add_plot(&mut self, options: &[&str], points: &[(f64, f64)]) {
+        let style = Style::new(options);
+        let points = points
+            .iter()
+            .map(|&p| self.transform(p))
+            .map(|(x, y)| format!("({x:.4}, {y:.4})"))
+            .collect::<Vec<_>>();
+
+        let mut commands = vec![];
+
+        if !style.only_marks && points.len() > 1 {
+            let path = points.join("--");
+            if let Some(fill) = &style.fill {
+                commands.push(format!(
+                    "fill(p, {path}--cycle, {}+opacity({}));",
+                    color_pen(fill),
+                    style.fill_opacity
+                ));
+            }
+            if style.draw {
+                commands.push(format!("draw(p, {path}, {});", line_pen(&style)));
+            }
+        }
+
+        if style.mark() != Mark::None {
+            let radius = style.mark_size / PT_PER_CM;
+            let edge = format!(
+                "{}+linewidth({:.3})",
+                color_pen(&style.color),
+                0.4 * BP_PER_PT
+            );
+            for point in points {
+                commands.push(format!(
+                    "filldraw(p, circle({point}, {radius:.4}), {}, {edge});",
+                    color_pen(style.mark_fill())
+                ));
+            }
+        }
+
+        if !commands.is_empty() {
+            self.push(commands, true);
+        }
+    }
+
+    fn add_node(&mut self, text: &str, pos: (f64, f64), options: &[&str], clip: bool) {
+        let style = Style::new(options);
+        let (x, y) = self.transform(pos);
+
+        let mut label = format!("Label({})", asy_string(text));
+        if style.scale != 1.0 {
+            label = format!("scale({})*{label}", style.scale);
+        }
+        if style.rotate != 0.0 {
+            label = format!("rotate({:.1})*{label}", style.rotate);
+        }
+        let command = format!(
+            "label(p, {label}, ({x:.4}, {y:.4}), {}, {});",
+            label_align(&style),
+            color_pen(&style.color)
+        );
+        self.push(vec![command], clip);
+    }
+
+    fn finish(&self, indicator: Option<&str>) -> String {
+        let (width, height) = (self.size.width, self.size.height);
+
+        let mut asy = String::new();
+        let _ = writeln!(asy, "settings.outformat = \"pdf\";");
+        let _ = writeln!(asy, "unitsize(1cm);\n");
+        let _ = writeln!(asy, "path plot_area = box((0, 0), ({width}, {height}));");
+        let _ = writeln!(
+            asy,
+            "draw(box(({}, 0), ({width}, {height})), invisible);\n",
+            0.0 - self.left
+        );
+
+        for line in self.lines.iter() {
+            let _ = writeln!(asy, "{line}");
+        }
+
+        if self.frame {
+            let _ = writeln!(asy, "draw(plot_area, linewidth({:.3}));", 0.4 * BP_PER_PT);
+        }
+
+        if let Some(indicator) = indicator {
+            let sep = 0.1;
+            let _ = writeln!(
+                asy,
+                "label({}, ({}, {}), SW, FillDraw(white, linewidth({:.3})));",
+                asy_string(&format!("$\\scriptstyle {indicator}$")),
+                width - sep,
+                height - sep,
+                0.4 * BP_PER_PT
+            );
+        }
+
+        asy
+    }
+}
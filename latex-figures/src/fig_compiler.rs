@@ -6,9 +6,11 @@ use std::thread;
 
 use indicatif::ProgressBar;
 
+use make_paths::ProviderUsage;
+
 use crate::cache;
-use crate::fig_writer::FigureWriter;
-use crate::utils::{Settings, Size, PDF_EXT, PROGRESS_EXT, TEX_EXT};
+use crate::fig_writer::{Bounds, FigureWriter};
+use crate::utils::{OutputFormat, Settings, Size, PDF_EXT, PROGRESS_EXT, TEX_EXT};
 
 pub struct FigureCompiler {
     pub name: String,
@@ -16,6 +18,8 @@ pub struct FigureCompiler {
     child: Child,
     plot_count: u64,
     size: Size,
+    component: Option<pxu::Component>,
+    bounds: Bounds,
     cached: bool,
 }
 
@@ -24,6 +28,11 @@ pub struct FinishedFigure {
     pub name: String,
     pub caption: String,
     pub size: Size,
+    pub component: Option<pxu::Component>,
+    pub bounds: Bounds,
+    // The contours and paths used by the figure, which are only recorded when
+    // listing the figures
+    pub usage: ProviderUsage,
     pub lualatex_error: bool,
 }
 
@@ -33,6 +42,8 @@ impl FigureCompiler {
         cache: Arc<cache::Cache>,
         settings: &Settings,
     ) -> Result<Self> {
+        let component = figure.plane();
+        let bounds = figure.bounds.clone();
         let FigureWriter {
             name,
             caption,
@@ -40,7 +51,21 @@ impl FigureCompiler {
             plot_count,
             ..
         } = figure;
-        if !settings.rebuild && cache.check(&name)? {
+        if settings.format != OutputFormat::Tex || settings.dry_run() {
+            // The figure is already drawn or only listed, and there is nothing
+            // to compile
+            let child = Command::new("/bin/true").spawn()?;
+            Ok(Self {
+                name,
+                caption,
+                child,
+                plot_count: 0,
+                size,
+                component,
+                bounds,
+                cached: true,
+            })
+        } else if !settings.rebuild && cache.check(&name)? {
             log::info!("[{name}]: Matches cached entry");
             let child = Command::new("/bin/true").spawn()?;
             Ok(Self {
@@ -49,6 +74,8 @@ impl FigureCompiler {
                 child,
                 plot_count: 0,
                 size,
+                component,
+                bounds,
                 cached: true,
             })
         } else {
@@ -71,6 +98,8 @@ impl FigureCompiler {
                 child,
                 plot_count,
                 size,
+                component,
+                bounds,
                 cached: false,
             })
         }
@@ -175,6 +204,9 @@ impl FigureCompiler {
             name: self.name,
             caption: self.caption,
             size: self.size,
+            component: self.component,
+            bounds: self.bounds,
+            usage: Default::default(),
             lualatex_error,
         })
     }
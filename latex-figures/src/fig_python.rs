@@ -0,0 +1,216 @@
+use std::fmt::Write;
+
+use crate::fig_style::{HorizontalAnchor, Mark, Style, VerticalAnchor, PT_PER_CM};
+use crate::fig_writer::{Bounds, Drawing};
+use crate::utils::Size;
+
+const FONT_SIZE: f64 = 10.0;
+
+fn python_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn python_list(values: impl Iterator<Item = f64>) -> String {
+    let values = values.map(|x| format!("{x:.5}")).collect::<Vec<_>>();
+    format!("[{}]", values.join(", "))
+}
+
+// Translate the LaTeX of a node to matplotlib mathtext, which handles most of
+// the math but not the commands changing the size of the text. The size is
+// instead returned as a factor.
+fn mathtext(text: &str) -> (String, f64) {
+    let mut result = String::new();
+    let mut size: f64 = 1.0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let mut command = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+            command.push(c);
+        }
+        match command.as_str() {
+            "scriptstyle" | "scriptsize" => size = size.min(0.7),
+            "footnotesize" => size = size.min(0.8),
+            "tiny" => {}
+            "mbox" | "text" => {
+                result.push_str("\\mathrm");
+                continue;
+            }
+            _ => {
+                result.push('\\');
+                result.push_str(&command);
+                continue;
+            }
+        }
+        while chars.next_if_eq(&' ').is_some() {}
+    }
+    (result, size)
+}
+
+// A Python script drawing a figure with matplotlib, with the coordinates of
+// all plots included in the script
+#[derive(Debug)]
+pub struct PythonFigure {
+    bounds: Bounds,
+    size: Size,
+    frame: bool,
+    left: f64,
+    lines: Vec<String>,
+}
+
+impl PythonFigure {
+    pub fn new(bounds: Bounds, size: Size, frame: bool) -> Self {
+        Self {
+            bounds,
+            size,
+            frame,
+            left: 0.0,
+            lines: vec![],
+        }
+    }
+
+    // Matplotlib draws the lines, patches and texts in separate layers unless
+    // the order is given explicitly
+    fn zorder(&self) -> usize {
+        self.lines.len() + 1
+    }
+}
+
+impl Drawing for PythonFigure {
+    fn extend_left(&mut self, value: f64) {
+        self.left += value;
+    }
+
+    fn add_plot(&mut self, options: &[&str], points: &[(f64, f64)]) {
+        let style = Style::new(options);
+        let xs = python_list(points.iter().map(|p| p.0));
+        let ys = python_list(points.iter().map(|p| p.1));
+
+        if !style.only_marks && points.len() > 1 {
+            if let Some(fill) = &style.fill {
+                let line = format!(
+                    "ax.fill({xs}, {ys}, color={}, alpha={}, linewidth=0, zorder={})",
+                    python_string(fill),
+                    style.fill_opacity,
+                    self.zorder()
+                );
+                self.lines.push(line);
+            }
+            if style.draw {
+                let mut line = format!(
+                    "ax.plot({xs}, {ys}, color={}, alpha={}, linewidth={:.2}, solid_joinstyle=\"round\"",
+                    python_string(&style.color),
+                    style.opacity,
+                    style.line_width
+                );
+                if let Some([on, off]) = style.dash {
+                    let _ = write!(line, ", dashes=({on}, {off})");
+                }
+                let _ = write!(line, ", zorder={})", self.zorder());
+                self.lines.push(line);
+            }
+        }
+
+        if style.mark() != Mark::None {
+            let line = format!(
+                "ax.plot({xs}, {ys}, linestyle=\"none\", marker=\"o\", markersize={:.2}, markerfacecolor={}, markeredgecolor={}, markeredgewidth=0.4, zorder={})",
+                2.0 * style.mark_size,
+                python_string(style.mark_fill()),
+                python_string(&style.color),
+                self.zorder()
+            );
+            self.lines.push(line);
+        }
+    }
+
+    fn add_node(&mut self, text: &str, (x, y): (f64, f64), options: &[&str], clip: bool) {
+        let style = Style::new(options);
+        let (text, size) = mathtext(text);
+        let ha = match style.horizontal_anchor() {
+            HorizontalAnchor::Left => "left",
+            HorizontalAnchor::Center => "center",
+            HorizontalAnchor::Right => "right",
+        };
+        let va = match style.vertical_anchor() {
+            VerticalAnchor::Top => "top",
+            VerticalAnchor::Center => "center",
+            VerticalAnchor::Baseline => "baseline",
+        };
+        let line = format!(
+            "ax.text({x:.5}, {y:.5}, {}, ha=\"{ha}\", va=\"{va}\", rotation={:.1}, rotation_mode=\"anchor\", fontsize={:.2}, color={}, clip_on={}, zorder={})",
+            python_string(&text),
+            style.rotate,
+            FONT_SIZE * size * style.scale,
+            python_string(&style.color),
+            if clip { "True" } else { "False" },
+            self.zorder()
+        );
+        self.lines.push(line);
+    }
+
+    fn finish(&self, indicator: Option<&str>) -> String {
+        let width = self.size.width + self.left;
+        let height = self.size.height;
+
+        let mut script = String::new();
+        let _ = writeln!(script, "import sys\n");
+        let _ = writeln!(script, "import matplotlib.pyplot as plt\n");
+        let _ = writeln!(script, "plt.rcParams[\"lines.scale_dashes\"] = False");
+        let _ = writeln!(script, "plt.rcParams[\"font.family\"] = \"serif\"");
+        let _ = writeln!(script, "plt.rcParams[\"mathtext.fontset\"] = \"cm\"\n");
+        let _ = writeln!(script, "CM = 1 / 2.54\n");
+        let _ = writeln!(
+            script,
+            "fig = plt.figure(figsize=({width} * CM, {height} * CM))"
+        );
+        let _ = writeln!(
+            script,
+            "ax = fig.add_axes(({}, 0, {}, 1))",
+            self.left / width,
+            self.size.width / width
+        );
+        let _ = writeln!(
+            script,
+            "ax.set_xlim({}, {})",
+            self.bounds.x_range.start, self.bounds.x_range.end
+        );
+        let _ = writeln!(
+            script,
+            "ax.set_ylim({}, {})",
+            self.bounds.y_range.start, self.bounds.y_range.end
+        );
+        if self.frame {
+            let _ = writeln!(script, "ax.set_xticks([])");
+            let _ = writeln!(script, "ax.set_yticks([])");
+        } else {
+            let _ = writeln!(script, "ax.set_axis_off()");
+        }
+        let _ = writeln!(script);
+
+        for line in self.lines.iter() {
+            let _ = writeln!(script, "{line}");
+        }
+
+        if let Some(indicator) = indicator {
+            let (text, _) = mathtext(&format!("${indicator}$"));
+            let sep = 0.1 * PT_PER_CM;
+            let _ = writeln!(
+                script,
+                "\nax.annotate({}, xy=(1, 1), xycoords=\"axes fraction\", xytext=(-{sep:.2}, -{sep:.2}), textcoords=\"offset points\", ha=\"right\", va=\"top\", fontsize={:.2}, bbox=dict(boxstyle=\"square,pad=0.2\", facecolor=\"white\", edgecolor=\"black\", linewidth=0.4), zorder={})",
+                python_string(&text),
+                0.7 * FONT_SIZE,
+                self.zorder()
+            );
+        }
+
+        let _ = writeln!(script, "\nif __name__ == \"__main__\":");
+        let _ = writeln!(
+            script,
+            "    fig.savefig(sys.argv[1] if len(sys.argv) > 1 else __file__.removesuffix(\".py\") + \".pdf\")"
+        );
+        script
+    }
+}
@@ -0,0 +1,275 @@
+// The style of the plots and nodes in the figures that are drawn without
+// LaTeX, translated from the TikZ options. All lengths are in pt.
+
+pub const PT_PER_CM: f64 = 72.27 / 2.54;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mark {
+    None,
+    Filled,
+    Open,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HorizontalAnchor {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerticalAnchor {
+    Top,
+    Center,
+    Baseline,
+}
+
+// The TikZ options of a plot or a node that can be drawn without LaTeX. All
+// other options, such as decorations, are ignored.
+#[derive(Debug)]
+pub struct Style {
+    pub color: String,
+    pub opacity: f64,
+    pub draw: bool,
+    pub fill: Option<String>,
+    pub fill_opacity: f64,
+    pub line_width: f64,
+    pub dash: Option<[f64; 2]>,
+    pub only_marks: bool,
+    mark: Option<Mark>,
+    pub mark_size: f64,
+    mark_fill: Option<String>,
+    anchor: String,
+    pub rotate: f64,
+    pub scale: f64,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            color: "black".to_owned(),
+            opacity: 1.0,
+            draw: true,
+            fill: None,
+            fill_opacity: 1.0,
+            line_width: 0.4,
+            dash: None,
+            only_marks: false,
+            mark: None,
+            mark_size: 2.0,
+            mark_fill: None,
+            anchor: "center".to_owned(),
+            rotate: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+
+// Split the options at the commas that are not inside braces
+fn split_options(options: &[&str]) -> Vec<String> {
+    let mut result = vec![];
+    for option in options {
+        let mut depth = 0;
+        let mut current = String::new();
+        for c in option.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    result.push(std::mem::take(&mut current));
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(c);
+        }
+        result.push(current);
+    }
+    result
+        .into_iter()
+        .map(|option| option.trim().to_owned())
+        .filter(|option| !option.is_empty())
+        .collect()
+}
+
+fn parse_length(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let (value, factor) = if let Some(value) = s.strip_suffix("cm") {
+        (value, PT_PER_CM)
+    } else if let Some(value) = s.strip_suffix("mm") {
+        (value, PT_PER_CM / 10.0)
+    } else {
+        (s.strip_suffix("pt").unwrap_or(s), 1.0)
+    };
+    value.trim().parse::<f64>().ok().map(|x| x * factor)
+}
+
+// A named xcolor color, possibly mixed with white as in "Red!50". The mix is
+// approximated by the opacity of the color.
+fn parse_color(s: &str) -> Option<(String, f64)> {
+    let mut parts = s.split('!');
+    let name = parts.next()?.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let opacity = parts
+        .next()
+        .and_then(|pct| pct.trim().parse::<f64>().ok())
+        .map(|pct| pct / 100.0)
+        .unwrap_or(1.0);
+    Some((name.to_lowercase(), opacity))
+}
+
+impl Style {
+    pub fn new(options: &[&str]) -> Self {
+        let mut style = Self::default();
+        for option in split_options(options) {
+            style.apply(&option);
+        }
+        style
+    }
+
+    fn apply(&mut self, option: &str) {
+        if let Some((key, value)) = option.split_once('=') {
+            let value = value.trim();
+            match key.trim() {
+                "line width" => self.line_width = parse_length(value).unwrap_or(self.line_width),
+                "mark size" => self.mark_size = parse_length(value).unwrap_or(self.mark_size),
+                "mark" => {
+                    self.mark = Some(match value {
+                        "none" => Mark::None,
+                        "o" => Mark::Open,
+                        _ => Mark::Filled,
+                    })
+                }
+                "mark options" => {
+                    let inner = Style::new(&[value.trim_start_matches('{').trim_end_matches('}')]);
+                    self.mark_fill = inner.fill;
+                }
+                "draw" if value == "none" => self.draw = false,
+                "draw" | "color" => {
+                    if let Some((color, opacity)) = parse_color(value) {
+                        self.color = color;
+                        self.opacity = opacity;
+                    }
+                }
+                "fill" if value == "none" => self.fill = None,
+                "fill" => self.fill = parse_color(value).map(|(color, _)| color),
+                "opacity" => {
+                    if let Ok(opacity) = value.parse() {
+                        self.opacity = opacity;
+                        self.fill_opacity = opacity;
+                    }
+                }
+                "fill opacity" => self.fill_opacity = value.parse().unwrap_or(self.fill_opacity),
+                "draw opacity" => self.opacity = value.parse().unwrap_or(self.opacity),
+                "anchor" => value.clone_into(&mut self.anchor),
+                "rotate" => self.rotate = value.parse().unwrap_or_default(),
+                "scale" => self.scale = value.parse().unwrap_or(1.0),
+                _ => {}
+            }
+            return;
+        }
+
+        let line_width = match option {
+            "ultra thin" => Some(0.1),
+            "very thin" => Some(0.2),
+            "thin" => Some(0.4),
+            "semithick" => Some(0.6),
+            "thick" => Some(0.8),
+            "very thick" => Some(1.2),
+            "ultra thick" => Some(1.6),
+            _ => None,
+        };
+        if let Some(line_width) = line_width {
+            self.line_width = line_width;
+            return;
+        }
+
+        match option {
+            "solid" => self.dash = None,
+            "dashed" => self.dash = Some([3.0, 3.0]),
+            "densely dashed" => self.dash = Some([3.0, 2.0]),
+            "loosely dashed" => self.dash = Some([3.0, 6.0]),
+            "dotted" => self.dash = Some([0.4, 2.0]),
+            "densely dotted" => self.dash = Some([0.4, 1.0]),
+            "loosely dotted" => self.dash = Some([0.4, 4.0]),
+            "only marks" => self.only_marks = true,
+            "decorate" | "scatter" | "smooth" => {}
+            _ => {
+                if let Some((color, opacity)) = parse_color(option) {
+                    self.color = color;
+                    self.opacity = opacity;
+                }
+            }
+        }
+    }
+
+    // Marks are only drawn when asked for, as in pgfplots when the options of a
+    // plot are given
+    pub fn mark(&self) -> Mark {
+        self.mark.unwrap_or(if self.only_marks {
+            Mark::Filled
+        } else {
+            Mark::None
+        })
+    }
+
+    pub fn mark_fill(&self) -> &str {
+        match (&self.mark_fill, self.mark()) {
+            (Some(fill), _) => fill,
+            (None, Mark::Open) => "white",
+            (None, _) => &self.color,
+        }
+    }
+
+    pub fn horizontal_anchor(&self) -> HorizontalAnchor {
+        if self.anchor.ends_with("west") {
+            HorizontalAnchor::Left
+        } else if self.anchor.ends_with("east") {
+            HorizontalAnchor::Right
+        } else {
+            HorizontalAnchor::Center
+        }
+    }
+
+    pub fn vertical_anchor(&self) -> VerticalAnchor {
+        if self.anchor.starts_with("north") {
+            VerticalAnchor::Top
+        } else if self.anchor.starts_with("south") || self.anchor == "base" {
+            VerticalAnchor::Baseline
+        } else {
+            VerticalAnchor::Center
+        }
+    }
+}
+
+// The RGB value of the named colors used in the figures, for the formats
+// without the SVG color names
+pub fn color_rgb(name: &str) -> Option<&'static str> {
+    let rgb = match name {
+        "black" => "000000",
+        "white" => "ffffff",
+        "red" => "ff0000",
+        "green" => "008000",
+        "blue" => "0000ff",
+        "cyan" => "00ffff",
+        "magenta" => "ff00ff",
+        "yellow" => "ffff00",
+        "gray" => "808080",
+        "lightgray" => "d3d3d3",
+        "orange" => "ffa500",
+        "darkorange" => "ff8c00",
+        "coral" => "ff7f50",
+        "lightcoral" => "f08080",
+        "firebrick" => "b22222",
+        "darkcyan" => "008b8b",
+        "darkorchid" => "9932cc",
+        "mediumorchid" => "ba55d3",
+        "darkviolet" => "9400d3",
+        "deeppink" => "ff1493",
+        "lightslateblue" => "8470ff",
+        _ => return None,
+    };
+    Some(rgb)
+}
@@ -0,0 +1,313 @@
+use std::fmt::Write;
+
+use crate::fig_style::{HorizontalAnchor, Mark, Style, VerticalAnchor, PT_PER_CM};
+use crate::fig_writer::{Bounds, Drawing};
+use crate::utils::Size;
+
+// All lengths in the SVG files are in mm
+const MM_PER_CM: f64 = 10.0;
+const MM_PER_PT: f64 = MM_PER_CM / PT_PER_CM;
+const FONT_SIZE: f64 = 10.0 * MM_PER_PT;
+const INDICATOR_SEP: f64 = 1.0;
+
+fn stroke_attributes(style: &Style) -> String {
+    let mut attributes = format!(
+        r#"stroke="{}" stroke-width="{:.3}""#,
+        style.color,
+        style.line_width * MM_PER_PT
+    );
+    if style.opacity != 1.0 {
+        let _ = write!(attributes, r#" stroke-opacity="{}""#, style.opacity);
+    }
+    if let Some([on, off]) = style.dash {
+        let _ = write!(
+            attributes,
+            r#" stroke-dasharray="{:.3} {:.3}""#,
+            on * MM_PER_PT,
+            off * MM_PER_PT
+        );
+    }
+    attributes
+}
+
+fn fill_attributes(style: &Style) -> String {
+    match &style.fill {
+        Some(fill) if style.fill_opacity != 1.0 => {
+            format!(r#"fill="{fill}" fill-opacity="{}""#, style.fill_opacity)
+        }
+        Some(fill) => format!(r#"fill="{fill}""#),
+        None => r#"fill="none""#.to_owned(),
+    }
+}
+
+fn text_anchor(style: &Style) -> (&'static str, &'static str) {
+    let horizontal = match style.horizontal_anchor() {
+        HorizontalAnchor::Left => "start",
+        HorizontalAnchor::Center => "middle",
+        HorizontalAnchor::Right => "end",
+    };
+    let vertical = match style.vertical_anchor() {
+        VerticalAnchor::Top => "hanging",
+        VerticalAnchor::Center => "central",
+        VerticalAnchor::Baseline => "alphabetic",
+    };
+    (horizontal, vertical)
+}
+
+fn escape(c: char, out: &mut String) {
+    match c {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        _ => out.push(c),
+    }
+}
+
+// Translate the LaTeX of a node to SVG text. Only the simple math used in the
+// figures is handled: sub- and superscripts, a few symbols and the size of the
+// text, which is returned as a factor.
+fn text_markup(chars: &mut std::iter::Peekable<std::str::Chars>, out: &mut String, size: &mut f64) {
+    while let Some(c) = chars.next() {
+        match c {
+            '$' | '{' => {}
+            '}' => return,
+            '\'' => out.push('′'),
+            '^' | '_' => {
+                let shift = if c == '^' { "super" } else { "sub" };
+                let _ = write!(out, r#"<tspan baseline-shift="{shift}" font-size="70%">"#);
+                if chars.peek() == Some(&'{') {
+                    chars.next();
+                    text_markup(chars, out, size);
+                } else if let Some(c) = chars.next() {
+                    escape(c, out);
+                }
+                out.push_str("</tspan>");
+            }
+            '\\' => {
+                let mut command = String::new();
+                while let Some(c) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                    command.push(*c);
+                    chars.next();
+                }
+                if command.is_empty() {
+                    // An escaped character or a space command
+                    if let Some(c) = chars.next() {
+                        if c.is_ascii_punctuation() && c != ',' && c != ';' {
+                            escape(c, out);
+                        }
+                    }
+                    continue;
+                }
+                // As in TeX, the spaces after a command are skipped
+                while chars.next_if_eq(&' ').is_some() {}
+                let symbol = match command.as_str() {
+                    "scriptstyle" | "scriptsize" => {
+                        *size = size.min(0.7);
+                        ""
+                    }
+                    "footnotesize" => {
+                        *size = size.min(0.8);
+                        ""
+                    }
+                    "pm" => "±",
+                    "mp" => "∓",
+                    "infty" => "∞",
+                    "pi" => "π",
+                    "theta" => "θ",
+                    "alpha" => "α",
+                    "beta" => "β",
+                    "gamma" => "γ",
+                    "epsilon" => "ε",
+                    "cdot" => "·",
+                    "ldots" | "dots" => "…",
+                    "prime" => "′",
+                    _ => "",
+                };
+                out.push_str(symbol);
+            }
+            _ => escape(c, out),
+        }
+    }
+}
+
+// An SVG version of a figure, drawn from the same calls as the TeX file
+#[derive(Debug)]
+pub struct SvgFigure {
+    bounds: Bounds,
+    size: Size,
+    frame: bool,
+    left: f64,
+    elements: Vec<String>,
+}
+
+impl SvgFigure {
+    pub fn new(bounds: Bounds, size: Size, frame: bool) -> Self {
+        Self {
+            bounds,
+            size,
+            frame,
+            left: 0.0,
+            elements: vec![],
+        }
+    }
+
+    fn width(&self) -> f64 {
+        self.size.width * MM_PER_CM
+    }
+
+    fn height(&self) -> f64 {
+        self.size.height * MM_PER_CM
+    }
+
+    fn transform(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            (x - self.bounds.x_range.start) / self.bounds.width() * self.width(),
+            (self.bounds.y_range.end - y) / self.bounds.height() * self.height(),
+        )
+    }
+
+    fn push(&mut self, element: String, clip: bool) {
+        if clip {
+            self.elements
+                .push(format!(r#"<g clip-path="url(#clip)">{element}</g>"#));
+        } else {
+            self.elements.push(element);
+        }
+    }
+}
+
+impl Drawing for SvgFigure {
+    fn extend_left(&mut self, value: f64) {
+        self.left += value;
+    }
+
+    fn add_plot(&mut self, options: &[&str], points: &[(f64, f64)]) {
+        let style = Style::new(options);
+        let points = points
+            .iter()
+            .map(|&p| self.transform(p))
+            .collect::<Vec<_>>();
+
+        if !style.only_marks && points.len() > 1 {
+            let mut d = String::new();
+            for (i, (x, y)) in points.iter().enumerate() {
+                let _ = write!(d, "{}{x:.3} {y:.3} ", if i == 0 { "M" } else { "L" });
+            }
+            let stroke = if style.draw {
+                stroke_attributes(&style)
+            } else {
+                r#"stroke="none""#.to_owned()
+            };
+            let fill = fill_attributes(&style);
+            self.push(
+                format!(
+                    r#"<path d="{}" {stroke} {fill} stroke-linejoin="round"/>"#,
+                    d.trim_end()
+                ),
+                true,
+            );
+        }
+
+        if style.mark() == Mark::None {
+            return;
+        }
+
+        let fill = style.mark_fill();
+        for (x, y) in points {
+            self.push(
+                format!(
+                    r#"<circle cx="{x:.3}" cy="{y:.3}" r="{:.3}" fill="{fill}" stroke="{}" stroke-width="{:.3}"/>"#,
+                    style.mark_size * MM_PER_PT,
+                    style.color,
+                    0.4 * MM_PER_PT
+                ),
+                true,
+            );
+        }
+    }
+
+    fn add_node(&mut self, text: &str, pos: (f64, f64), options: &[&str], clip: bool) {
+        let style = Style::new(options);
+        let (x, y) = self.transform(pos);
+
+        let mut markup = String::new();
+        let mut size = 1.0;
+        text_markup(&mut text.chars().peekable(), &mut markup, &mut size);
+
+        let (anchor, baseline) = text_anchor(&style);
+        let mut element = format!(
+            r#"<text x="{x:.3}" y="{y:.3}" font-family="serif" font-size="{:.3}" fill="{}" text-anchor="{anchor}" dominant-baseline="{baseline}""#,
+            FONT_SIZE * size * style.scale,
+            style.color
+        );
+        if style.rotate != 0.0 {
+            let _ = write!(
+                element,
+                r#" transform="rotate({:.1} {x:.3} {y:.3})""#,
+                -style.rotate
+            );
+        }
+        let _ = write!(element, ">{markup}</text>");
+        self.push(element, clip);
+    }
+
+    fn finish(&self, indicator: Option<&str>) -> String {
+        let left = self.left * MM_PER_CM;
+        let (width, height) = (self.width(), self.height());
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}cm" height="{}cm" viewBox="{:.3} 0 {:.3} {height:.3}">"#,
+            self.size.width + self.left,
+            self.size.height,
+            0.0 - left,
+            width + left,
+        );
+        let _ = writeln!(
+            svg,
+            r#"<defs><clipPath id="clip"><rect x="0" y="0" width="{width:.3}" height="{height:.3}"/></clipPath></defs>"#
+        );
+        for element in self.elements.iter() {
+            let _ = writeln!(svg, "{element}");
+        }
+        if self.frame {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="0" y="0" width="{width:.3}" height="{height:.3}" fill="none" stroke="black" stroke-width="{:.3}"/>"#,
+                0.4 * MM_PER_PT
+            );
+        }
+
+        if let Some(indicator) = indicator {
+            let mut markup = String::new();
+            let mut size = 0.7;
+            text_markup(&mut indicator.chars().peekable(), &mut markup, &mut size);
+            let font_size = FONT_SIZE * size;
+
+            // The width of the text is not known, so it is estimated from the
+            // number of characters
+            let box_width = (indicator.chars().filter(|c| c.is_alphanumeric()).count() as f64
+                + 1.0)
+                * 0.6
+                * font_size;
+            let box_height = 1.6 * font_size;
+            let x = width - INDICATOR_SEP - box_width;
+            let y = INDICATOR_SEP;
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{x:.3}" y="{y:.3}" width="{box_width:.3}" height="{box_height:.3}" fill="white" stroke="black" stroke-width="{:.3}"/>"#,
+                0.4 * MM_PER_PT
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.3}" y="{:.3}" font-family="serif" font-style="italic" font-size="{font_size:.3}" text-anchor="middle" dominant-baseline="central">{markup}</text>"#,
+                x + box_width / 2.0,
+                y + box_height / 2.0
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
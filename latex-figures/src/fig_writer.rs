@@ -18,8 +18,12 @@ use flo_curves::{
 };
 
 use crate::cache;
+use crate::fig_asy::AsymptoteFigure;
 use crate::fig_compiler::FigureCompiler;
-use crate::utils::{error, Settings, Size, TEX_EXT};
+use crate::fig_python::PythonFigure;
+use crate::fig_svg::SvgFigure;
+use crate::theme::{role_options, Theme};
+use crate::utils::{error, skipped, OutputFormat, Settings, Size, TEX_EXT};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Bounds {
@@ -111,13 +115,55 @@ impl SizeExtension {
     }
 }
 
-#[derive(Debug)]
+// The TeX file, which is not written when the figure is drawn without LaTeX
+type TexWriter = BufWriter<Box<dyn Write + Send>>;
+
+// A figure that is drawn without LaTeX, from the coordinates of the plots and
+// nodes of the TeX figure
+pub trait Drawing {
+    fn add_plot(&mut self, options: &[&str], points: &[(f64, f64)]);
+    fn add_node(&mut self, text: &str, pos: (f64, f64), options: &[&str], clip: bool);
+    fn extend_left(&mut self, value: f64);
+    fn finish(&self, indicator: Option<&str>) -> String;
+}
+
+fn new_drawing(
+    settings: &Settings,
+    bounds: &Bounds,
+    size: &Size,
+    frame: bool,
+) -> Option<Box<dyn Drawing>> {
+    if settings.dry_run() {
+        return None;
+    }
+    match settings.format {
+        OutputFormat::Tex => None,
+        OutputFormat::Svg => Some(Box::new(SvgFigure::new(
+            bounds.clone(),
+            size.clone(),
+            frame,
+        ))),
+        OutputFormat::Python => Some(Box::new(PythonFigure::new(
+            bounds.clone(),
+            size.clone(),
+            frame,
+        ))),
+        OutputFormat::Asymptote => Some(Box::new(AsymptoteFigure::new(
+            bounds.clone(),
+            size.clone(),
+            frame,
+        ))),
+    }
+}
+
 pub struct FigureWriter {
     pub name: String,
     pub caption: String,
     pub bounds: Bounds,
     pub size: Size,
-    writer: BufWriter<File>,
+    writer: TexWriter,
+    drawing: Option<Box<dyn Drawing>>,
+    pub theme: Arc<Theme>,
     pub plot_count: u64,
     pub component: pxu::Component,
     y_shift: Option<f64>,
@@ -125,16 +171,18 @@ pub struct FigureWriter {
     extension: SizeExtension,
     scope_closed: bool,
     is_r: bool,
+    custom_axis: bool,
 }
 
 impl FigureWriter {
     const FILE_START_1: &'static str = r#"
 \nonstopmode
-\documentclass[10pt,a4paper]{article}
+\documentclass"#;
+    const FILE_START_2: &'static str = r#"
 \usepackage{luatextra}
 \begin{luacode}
 progress_file=io.open(""#;
-    const FILE_START_2: &'static str = r#"","w")
+    const FILE_START_3: &'static str = r#"","w")
 \end{luacode}
 \usepackage[svgnames]{xcolor}
 \usepackage{pgfplots}
@@ -145,7 +193,8 @@ progress_file=io.open(""#;
 \PreviewEnvironment{tikzpicture}
 \setlength\PreviewBorder{0pt}
 \pdfvariable suppressoptionalinfo \numexpr 1023 \relax
-\begin{document}
+"#;
+    const FILE_START_4: &'static str = r#"\begin{document}
 \pagestyle{empty}
 \begin{tikzpicture}
 "#;
@@ -157,11 +206,25 @@ progress_file=io.open(""#;
 \end{document}
 "#;
 
-    fn open_tex_file(name: &str, settings: &Settings, pb: &ProgressBar) -> Result<BufWriter<File>> {
+    fn open_tex_file(name: &str, settings: &Settings, pb: &ProgressBar) -> Result<TexWriter> {
         if name.contains(' ') {
             return Err(error(&format!("Unexpected space in filename '{name}'")));
         }
 
+        if !settings.is_selected(name) {
+            return Err(skipped(name));
+        }
+
+        if settings.dry_run() {
+            pb.set_message(format!("Listing {name}"));
+            return Ok(BufWriter::new(Box::new(std::io::sink())));
+        }
+
+        if settings.format != OutputFormat::Tex {
+            pb.set_message(format!("Drawing {name}.{}", settings.format.extension()));
+            return Ok(BufWriter::new(Box::new(std::io::sink())));
+        }
+
         let mut path = PathBuf::from(&settings.output_dir).join(name);
         path.set_extension(TEX_EXT);
 
@@ -169,13 +232,17 @@ progress_file=io.open(""#;
         pb.set_message(format!("Generating {}", path.to_string_lossy()));
 
         let file = File::create(&path)?;
-        let mut writer = BufWriter::new(file);
+        let mut writer: TexWriter = BufWriter::new(Box::new(file));
 
         let mut progress_path = path.clone();
         progress_path.set_extension("prg");
         writer.write_all(Self::FILE_START_1.as_bytes())?;
-        write!(writer, "{}", progress_path.to_string_lossy())?;
+        write!(writer, "{}", settings.document_class)?;
         writer.write_all(Self::FILE_START_2.as_bytes())?;
+        write!(writer, "{}", progress_path.to_string_lossy())?;
+        writer.write_all(Self::FILE_START_3.as_bytes())?;
+        writer.write_all(settings.preamble.as_bytes())?;
+        writer.write_all(Self::FILE_START_4.as_bytes())?;
 
         let _ = std::fs::remove_file(progress_path);
 
@@ -219,9 +286,14 @@ progress_file=io.open(""#;
             writer,
             "\\clip ({x_min},{y_min}) rectangle ({x_max},{y_max});"
         )?;
+
+        let drawing = new_drawing(settings, &bounds, &size, false);
+
         Ok(Self {
             name: name.to_owned(),
             writer,
+            drawing,
+            theme: settings.theme.clone(),
             bounds,
             size,
             plot_count: 0,
@@ -232,6 +304,7 @@ progress_file=io.open(""#;
             extension: Default::default(),
             scope_closed: false,
             is_r: false,
+            custom_axis: false,
         })
     }
 
@@ -260,9 +333,16 @@ progress_file=io.open(""#;
         writeln!(writer, "\\begin{{axis}}[xmin={x_min},xmax={x_max},ymin={y_min},ymax={y_max},width={width}cm,height={height}cm,{}]", axis_options.join(","))?;
         writeln!(writer, "\\begin{{scope}}")?;
 
+        let frame = !axis_options
+            .iter()
+            .any(|option| option.contains("hide axis"));
+        let drawing = new_drawing(settings, &bounds, &size, frame);
+
         Ok(Self {
             name: name.to_owned(),
             writer,
+            drawing,
+            theme: settings.theme.clone(),
             bounds,
             size,
             plot_count: 0,
@@ -273,6 +353,7 @@ progress_file=io.open(""#;
             extension: Default::default(),
             scope_closed: false,
             is_r: false,
+            custom_axis: true,
         })
     }
 
@@ -284,14 +365,45 @@ progress_file=io.open(""#;
         self.component_indicator = ComponentIndicator::Custom(s.to_owned());
     }
 
-    fn format_coordinate(&self, p: Complex64) -> String {
-        format!(
-            "({:.5},{:.5})",
+    fn coordinate(&self, p: Complex64) -> (f64, f64) {
+        (
             if self.is_r { -p.re } else { p.re },
-            p.im + self.y_shift.unwrap_or_default()
+            p.im + self.y_shift.unwrap_or_default(),
         )
     }
 
+    fn format_coordinate(&self, p: Complex64) -> String {
+        let (x, y) = self.coordinate(p);
+        format!("({x:.5},{y:.5})")
+    }
+
+    fn draw_plot(&mut self, options: &[&str], contour: &[Complex64]) {
+        if self.drawing.is_some() {
+            let points = contour
+                .iter()
+                .map(|&z| self.coordinate(z))
+                .collect::<Vec<_>>();
+            if let Some(drawing) = self.drawing.as_mut() {
+                drawing.add_plot(options, &points);
+            }
+        }
+    }
+
+    fn draw_node(&mut self, text: &str, pos: (f64, f64), options: &[&str]) {
+        let clip = !self.scope_closed;
+        if let Some(drawing) = self.drawing.as_mut() {
+            drawing.add_node(text, pos, options, clip);
+        }
+    }
+
+    // TikZ and pgfplots commands that are passed on unchanged can only be
+    // drawn by LaTeX
+    fn skip_drawing(&self, command: &str) {
+        if self.drawing.is_some() {
+            log::warn!("[{}]: Leaving out {command} from the figure", self.name);
+        }
+    }
+
     fn format_contour(&self, contour: Vec<Complex64>) -> Vec<String> {
         contour
             .into_iter()
@@ -342,6 +454,8 @@ progress_file=io.open(""#;
     }
 
     pub fn add_plot_all(&mut self, options: &[&str], contour: Vec<Complex64>) -> Result<()> {
+        self.draw_plot(options, &contour);
+
         let mut coordinates = self.format_contour(contour);
         coordinates.dedup();
 
@@ -369,6 +483,8 @@ progress_file=io.open(""#;
 
             contour.dedup();
 
+            self.draw_plot(&[options.as_str()], &contour);
+
             if contour.len() > 2 {
                 let points = contour
                     .into_iter()
@@ -420,6 +536,7 @@ progress_file=io.open(""#;
     }
 
     pub fn add_plot_custom(&mut self, options: &[&str], plot: &str) -> Result<()> {
+        self.skip_drawing(&format!("\\addplot {plot}"));
         writeln!(self.writer, "\\addplot [{}] {plot};", options.join(","),)?;
         writeln!(self.writer, r#"\directlua{{progress_file:write(".")}}"#)?;
         writeln!(self.writer, r#"\directlua{{progress_file:flush()}}"#)?;
@@ -428,8 +545,9 @@ progress_file=io.open(""#;
     }
 
     pub fn add_grid_line(&mut self, grid_line: &GridLine, options: &[&str]) -> Result<()> {
+        let theme = self.theme.clone();
         self.add_curve(
-            &[&["very thin", "lightgray"], options].concat(),
+            &[&role_options(&theme.grid), options].concat(),
             &grid_line.path,
         )?;
 
@@ -441,8 +559,9 @@ progress_file=io.open(""#;
             self.add_grid_line(contour, options)?;
         }
         if matches!(self.component, pxu::Component::Xp | pxu::Component::Xm) {
+            let theme = self.theme.clone();
             self.add_plot(
-                &[&["very thin", "lightgray"], options].concat(),
+                &[&role_options(&theme.grid), options].concat(),
                 &[Complex64::from(-10.0), Complex64::from(10.0)],
             )?;
         }
@@ -455,25 +574,25 @@ progress_file=io.open(""#;
         options: &[&str],
         consts: CouplingConstants,
     ) -> Result<()> {
-        let straight = "very thick";
-        let dashed = "very thick,densely dashed";
-        let zigzag = "decorate,decoration={zigzag, segment length=1.2mm, amplitude=0.15mm},thick";
-        let (color, style) = match cut.typ {
-            pxu::CutType::E => ("black", straight),
-            pxu::CutType::Log(pxu::Component::Xp) => ("Red", zigzag),
-            pxu::CutType::Log(pxu::Component::Xm) => ("Green", zigzag),
-            pxu::CutType::ULongPositive(pxu::Component::Xp) => ("Red", straight),
-            pxu::CutType::ULongNegative(pxu::Component::Xp) => ("Red", dashed),
-            pxu::CutType::ULongPositive(pxu::Component::Xm) => ("Green", straight),
-            pxu::CutType::ULongNegative(pxu::Component::Xm) => ("Green", dashed),
-            pxu::CutType::UShortScallion(pxu::Component::Xp) => ("Red", straight),
-            pxu::CutType::UShortKidney(pxu::Component::Xp) => ("Red", dashed),
-            pxu::CutType::UShortScallion(pxu::Component::Xm) => ("Green", straight),
-            pxu::CutType::UShortKidney(pxu::Component::Xm) => ("Green", dashed),
+        let theme = self.theme.clone();
+        let (cut_style, kidney) = match cut.typ {
+            pxu::CutType::E => (&theme.e_cut, false),
+            pxu::CutType::QLog => (&theme.q_log_cut, false),
+            pxu::CutType::Log(pxu::Component::Xp) => (&theme.xp_log_cut, false),
+            pxu::CutType::Log(pxu::Component::Xm) => (&theme.xm_log_cut, false),
+            pxu::CutType::ULongPositive(pxu::Component::Xp) => (&theme.xp_scallion, false),
+            pxu::CutType::ULongNegative(pxu::Component::Xp) => (&theme.xp_kidney, true),
+            pxu::CutType::ULongPositive(pxu::Component::Xm) => (&theme.xm_scallion, false),
+            pxu::CutType::ULongNegative(pxu::Component::Xm) => (&theme.xm_kidney, true),
+            pxu::CutType::UShortScallion(pxu::Component::Xp) => (&theme.xp_scallion, false),
+            pxu::CutType::UShortKidney(pxu::Component::Xp) => (&theme.xp_kidney, true),
+            pxu::CutType::UShortScallion(pxu::Component::Xm) => (&theme.xm_scallion, false),
+            pxu::CutType::UShortKidney(pxu::Component::Xm) => (&theme.xm_kidney, true),
             _ => {
                 return Ok(());
             }
         };
+        let color = cut_style.color.as_str();
 
         let shifts = if cut.component == pxu::Component::U && cut.periodic {
             let period = 2.0 * consts.k() as f64 / consts.h;
@@ -491,10 +610,10 @@ progress_file=io.open(""#;
         for shift in shifts {
             self.y_shift = shift;
 
-            if style == dashed && options.is_empty() {
-                self.add_curve(&["lightgray", "very thick"], &cut.path)?
+            if kidney && options.is_empty() {
+                self.add_curve(&role_options(&theme.kidney_background), &cut.path)?
             }
-            self.add_curve(&[&[color, style], options].concat(), &cut.path)?;
+            self.add_curve(&[&cut_style.options(), options].concat(), &cut.path)?;
 
             if let Some(branch_point) = cut.branch_point {
                 self.add_plot_all(
@@ -532,7 +651,7 @@ progress_file=io.open(""#;
                 ULongNegative(_) => false,
                 ULongPositive(_) => false,
                 UShortScallion(_) | UShortKidney(_) => true,
-                E => true,
+                E | QLog => true,
                 DebugPath => false,
             })
         {
@@ -542,7 +661,8 @@ progress_file=io.open(""#;
     }
 
     pub fn add_axis(&mut self) -> Result<()> {
-        let options = ["very thin", "black"];
+        let theme = self.theme.clone();
+        let options = role_options(&theme.axis);
         self.add_plot(
             &options,
             &[
@@ -560,7 +680,8 @@ progress_file=io.open(""#;
     }
 
     pub fn add_axis_origin(&mut self, origin: impl Into<Complex64>) -> Result<()> {
-        let options = ["very thin", "black"];
+        let theme = self.theme.clone();
+        let options = role_options(&theme.axis);
         let origin = origin.into();
         self.add_plot(
             &options,
@@ -624,7 +745,7 @@ progress_file=io.open(""#;
                 points = vec![];
             }
 
-            points.extend(segment.get(self.component));
+            points.extend(segment.get(self.component).iter());
             same_branch = segment_same_branch;
         }
 
@@ -634,15 +755,16 @@ progress_file=io.open(""#;
             dotted_segments.push(points);
         }
 
+        let theme = self.theme.clone();
         for points in dotted_segments {
             self.add_curve(
-                &[&["very thick", "Blue", "densely dotted"], options].concat(),
+                &[&role_options(&theme.path_other_sheet), options].concat(),
                 &points,
             )?;
         }
 
         for points in straight_segments {
-            self.add_curve(&[&["very thick", "Blue"], options].concat(), &points)?;
+            self.add_curve(&[&role_options(&theme.path), options].concat(), &points)?;
         }
 
         Ok(())
@@ -680,7 +802,11 @@ progress_file=io.open(""#;
     ) -> Result<()> {
         let start = path.first_coordinate(self.component, active_point).unwrap();
         let points = vec![start];
-        self.add_plot_all(&[&["only marks"], options].concat(), points)
+        let theme = self.theme.clone();
+        self.add_plot_all(
+            &[&["only marks"], &role_options(&theme.marker)[..], options].concat(),
+            points,
+        )
     }
 
     pub fn add_path_end_mark(&mut self, path: &pxu::path::Path, options: &[&str]) -> Result<()> {
@@ -706,7 +832,11 @@ progress_file=io.open(""#;
     ) -> Result<()> {
         let end = path.last_coordinate(self.component, active_point).unwrap();
         let points = vec![end];
-        self.add_plot_all(&[&["only marks"], options].concat(), points)
+        let theme = self.theme.clone();
+        self.add_plot_all(
+            &[&["only marks"], &role_options(&theme.marker)[..], options].concat(),
+            points,
+        )
     }
 
     pub fn add_path_arrows(
@@ -779,6 +909,8 @@ progress_file=io.open(""#;
     }
 
     pub fn add_node(&mut self, text: &str, pos: Complex64, options: &[&str]) -> Result<()> {
+        self.draw_node(text, self.coordinate(pos), options);
+
         let coord = self.format_coordinate(pos);
         writeln!(
             self.writer,
@@ -788,12 +920,17 @@ progress_file=io.open(""#;
     }
 
     pub fn draw(&mut self, path: &str, options: &[&str]) -> Result<()> {
+        self.skip_drawing(&format!("\\draw {path}"));
         writeln!(self.writer, "\\draw [{}] {path};", options.join(","))
     }
 
     pub fn add_point(&mut self, point: &pxu::Point, options: &[&str]) -> Result<()> {
         let points = vec![point.get(self.component)];
-        self.add_plot_all(&[&["only marks"], options].concat(), points)?;
+        let theme = self.theme.clone();
+        self.add_plot_all(
+            &[&["only marks"], &role_options(&theme.marker)[..], options].concat(),
+            points,
+        )?;
         Ok(())
     }
 
@@ -803,12 +940,19 @@ progress_file=io.open(""#;
             .iter()
             .map(|pt| pt.get(self.component))
             .collect::<Vec<_>>();
-        self.add_plot_all(&[&["only marks"], options].concat(), points)?;
+        let theme = self.theme.clone();
+        self.add_plot_all(
+            &[&["only marks"], &role_options(&theme.marker)[..], options].concat(),
+            points,
+        )?;
         Ok(())
     }
 
     pub fn extend_left(&mut self, value: f64) {
         self.extension.left += value;
+        if let Some(drawing) = self.drawing.as_mut() {
+            drawing.extend_left(value);
+        }
     }
 
     pub fn close_scope(&mut self) -> Result<()> {
@@ -846,6 +990,11 @@ progress_file=io.open(""#;
                     pxu::Component::Xp => "x^+",
                     pxu::Component::Xm => "x^-",
                     pxu::Component::U => "u",
+                    pxu::Component::E => "E",
+                    pxu::Component::X => "x",
+                    pxu::Component::MirrorP => r"\tilde{p}",
+                    pxu::Component::MirrorE => r"\tilde{E}",
+                    pxu::Component::Torus => "z",
                 }
                 .to_owned(),
             ),
@@ -853,7 +1002,7 @@ progress_file=io.open(""#;
             ComponentIndicator::None => None,
         };
 
-        if let Some(indicator) = indicator {
+        if let Some(indicator) = &indicator {
             writeln!(
                 self.writer,
                 "\\node at (current bounding box.north east) [anchor=north east,fill=white,outer sep=0.1cm,draw,thin] {{$\\scriptstyle {indicator}$}};"
@@ -875,6 +1024,13 @@ progress_file=io.open(""#;
         self.writer.write_all(Self::FILE_END.as_bytes())?;
         self.writer.flush()?;
 
+        if let Some(drawing) = &self.drawing {
+            let mut path = PathBuf::from(&settings.output_dir).join(&self.name);
+            path.set_extension(settings.format.extension());
+            log::info!("[{}]: Writing {}", self.name, path.to_string_lossy());
+            std::fs::write(path, drawing.finish(indicator.as_deref()))?;
+        }
+
         pb.set_message(format!("Compiling {}.tex", self.name));
         FigureCompiler::new(self, cache, settings)
     }
@@ -904,6 +1060,12 @@ progress_file=io.open(""#;
 
         scale_x.max(scale_y)
     }
+
+    // The plane that is drawn, which is not known for the figures with a
+    // custom axis
+    pub fn plane(&self) -> Option<pxu::Component> {
+        (!self.custom_axis).then_some(self.component)
+    }
 }
 
 pub trait Node {
@@ -950,6 +1112,17 @@ impl Node for PInterpolatorMut {
             _ => unreachable!(),
         };
 
+        figure.draw_node(
+            &format!("$\\scriptstyle {m}$"),
+            (self.p().re, self.p().im),
+            &[
+                "scale=0.5",
+                &format!("anchor={anchor}"),
+                &format!("rotate={rotation:.1}"),
+                color,
+            ],
+        );
+
         writeln!(figure.writer,"\\node[scale=0.5,anchor={anchor},inner sep=0.4pt,rotate={rotation:.1},{color}] at ({:.3}, {:.3}) {{$\\scriptstyle {}$}};",
                  self.p().re,
                  self.p().im,
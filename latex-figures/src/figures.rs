@@ -1,6 +1,7 @@
 use crate::cache;
 use crate::fig_compiler::FigureCompiler;
 use crate::fig_writer::FigureWriter;
+use crate::theme::role_options;
 use crate::utils::{error, Settings, Size};
 use indicatif::ProgressBar;
 
@@ -13,14 +14,14 @@ use pxu::{Component, CouplingConstants, Cut, CutType, GridLineComponent};
 use std::io::Result;
 use std::sync::Arc;
 
-fn load_state(s: &str) -> Result<pxu::State> {
-    ron::from_str(s).map_err(|_| error("Could not load state"))
+fn load_state(s: &str, consts: CouplingConstants) -> Result<pxu::State> {
+    pxu::State::parse(s, consts).map_err(|err| error(&format!("Could not load state: {err}")))
 }
 
-fn load_states(state_strings: &[&str]) -> Result<Vec<pxu::State>> {
+fn load_states(state_strings: &[&str], consts: CouplingConstants) -> Result<Vec<pxu::State>> {
     state_strings
         .iter()
-        .map(|s| load_state(s))
+        .map(|s| load_state(s, consts))
         .collect::<Result<Vec<_>>>()
 }
 
@@ -86,22 +87,15 @@ fn draw_xl_preimage(
             )
         })
     {
-        let options: &[&str] = match cut.typ {
-            CutType::Log(Component::Xp) => &["Red!50!white", "very thick"],
-            CutType::Log(Component::Xm) => &["Green!50!white", "very thick"],
-            CutType::ULongPositive(Component::Xp) => &[
-                "Red!50!white",
-                "decorate,decoration={coil,aspect=0, segment length=2.4mm, amplitude=0.15mm}",
-                "very thick",
-            ],
-            CutType::ULongPositive(Component::Xm) => &[
-                "Green!50!white",
-                "decorate,decoration={coil,aspect=0, segment length=2.4mm, amplitude=0.15mm}",
-                "very thick",
-            ],
-            _ => &[],
+        let theme = &settings.theme;
+        let options = match cut.typ {
+            CutType::Log(Component::Xp) => role_options(&theme.xp_preimage_log_cut),
+            CutType::Log(Component::Xm) => role_options(&theme.xm_preimage_log_cut),
+            CutType::ULongPositive(Component::Xp) => role_options(&theme.xp_preimage_long_cut),
+            CutType::ULongPositive(Component::Xm) => role_options(&theme.xm_preimage_long_cut),
+            _ => vec![],
         };
-        figure.add_cut(cut, options, consts)?;
+        figure.add_cut(cut, &options, consts)?;
     }
 
     for (z, dz, (xp_sign, xp_m), (xm_sign, xm_m)) in preimage_data {
@@ -402,7 +396,7 @@ fn fig_scallion_and_kidney_r(
         pb,
     )?;
     let state_string = r"(points:[(p:(-0.2498413622379303,0.000009991228580474854),xp:(-0.6478279611895327,0.6471633470693878),xm:(-0.6478494168942528,-0.6472232084111232),u:(-1.3503465619270798,-2.5000545006090906),sheet_data:(log_branch_p:0,log_branch_m:-1,e_branch:1,u_branch:(Between,Between),im_x_sign:(1,-1)))],unlocked:false)";
-    let state = load_state(state_string)?;
+    let state = load_state(state_string, consts)?;
     let pt = &state.points[0];
 
     figure.set_r();
@@ -468,7 +462,7 @@ fn fig_u_plane_between_between_r(
         pb,
     )?;
     let state_string = r"(points:[(p:(-0.2498413622379303,0.000009991228580474854),xp:(-0.6478279611895327,0.6471633470693878),xm:(-0.6478494168942528,-0.6472232084111232),u:(-1.3503465619270798,-2.5000545006090906),sheet_data:(log_branch_p:0,log_branch_m:-1,e_branch:1,u_branch:(Between,Between),im_x_sign:(1,-1)))],unlocked:false)";
-    let state = load_state(state_string)?;
+    let state = load_state(state_string, consts)?;
     let pt = &state.points[0];
     figure.set_r();
 
@@ -520,7 +514,7 @@ fn fig_p_plane_short_cuts_r(
         pb,
     )?;
     let state_string = r"(points:[(p:(-0.2498413622379303,0.000009991228580474854),xp:(-0.6478279611895327,0.6471633470693878),xm:(-0.6478494168942528,-0.6472232084111232),u:(-1.3503465619270798,-2.5000545006090906),sheet_data:(log_branch_p:0,log_branch_m:-1,e_branch:1,u_branch:(Between,Between),im_x_sign:(1,-1)))],unlocked:false)";
-    let state = load_state(state_string)?;
+    let state = load_state(state_string, consts)?;
     let pt = &state.points[0];
 
     figure.set_r();
@@ -553,22 +547,20 @@ fn fig_p_plane_short_cuts_r(
     figure.finish(cache, settings, pb)
 }
 
-fn get_cut_path(
-    contours: &pxu::Contours,
-    pt: &pxu::Point,
-    component: Component,
+// The lower half of the scallion or the kidney with Re(x) > -3.5, ordered by
+// increasing Re(x)
+fn bottom_x_cut_path(
+    curve: fn(f64, CouplingConstants) -> Complex64,
     consts: CouplingConstants,
-    cut_type: CutType,
 ) -> Vec<Complex64> {
-    let cut_paths = contours
-        .get_visible_cuts_from_point(pt, component, consts)
-        .filter(|cut| cut.typ == cut_type)
-        .collect::<Vec<_>>();
-
-    let mut path = cut_paths[0]
-        .path
-        .clone()
-        .into_iter()
+    let steps = 512;
+    let mut path = (1..steps)
+        .map(|i| {
+            curve(
+                std::f64::consts::PI * (2.0 * i as f64 / steps as f64 - 1.0),
+                consts,
+            )
+        })
         .filter(|x| x.im < 0.0 && x.re > -3.5)
         .collect::<Vec<_>>();
 
@@ -587,7 +579,6 @@ fn fig_x_integration_contour_1(
 ) -> Result<FigureCompiler> {
     let consts = CouplingConstants::new(2.0, 5);
     let contours = pxu_provider.get_contours(consts)?;
-    let pt = pxu::Point::new(0.5, consts);
 
     let mut figure = FigureWriter::new(
         "x-integration-contour-1",
@@ -606,15 +597,17 @@ fn fig_x_integration_contour_1(
     figure.add_grid_lines(&contours, &[])?;
     figure.add_axis()?;
 
-    let s = Complex64::from(consts.s());
+    let branch_point = |cut_type: CutType| {
+        pxu::branch_points(&cut_type, 0, consts)
+            .into_iter()
+            .flatten()
+            .find_map(|(component, z)| (component == Component::Xp).then_some(z))
+            .ok_or_else(|| error("Could not find branch point"))
+    };
+    let s = branch_point(CutType::UShortScallion(Component::Xp))?;
+    let kidney_tip = branch_point(CutType::UShortKidney(Component::Xp))?;
 
-    let mut bottom_scallion_path = get_cut_path(
-        &contours,
-        &pt,
-        Component::Xp,
-        consts,
-        CutType::UShortScallion(Component::Xp),
-    );
+    let mut bottom_scallion_path = bottom_x_cut_path(pxu::kinematics::scallion, consts);
     bottom_scallion_path.push(s);
     bottom_scallion_path.reverse();
 
@@ -623,14 +616,8 @@ fn fig_x_integration_contour_1(
         .map(|z| z.conj())
         .collect::<Vec<_>>();
 
-    let mut bottom_kidney_path = vec![-1.0 / s];
-    bottom_kidney_path.extend(get_cut_path(
-        &contours,
-        &pt,
-        Component::Xp,
-        consts,
-        CutType::UShortKidney(Component::Xp),
-    ));
+    let mut bottom_kidney_path = vec![kidney_tip];
+    bottom_kidney_path.extend(bottom_x_cut_path(pxu::kinematics::kidney, consts));
     bottom_kidney_path.reverse();
 
     let kidney_bottom = *bottom_kidney_path
@@ -645,10 +632,10 @@ fn fig_x_integration_contour_1(
         .collect::<Vec<_>>();
 
     let dy = Complex64::new(0.0, 0.03);
-    let log_path_1t = vec![-3.1 + dy, -1.0 / s + dy];
-    let log_path_1b = vec![-3.1 - dy, -1.0 / s + -dy];
-    let log_path_2t = vec![-1.0 / s + dy, dy];
-    let log_path_2b = vec![-1.0 / s + -dy, -dy];
+    let log_path_1t = vec![-3.1 + dy, kidney_tip + dy];
+    let log_path_1b = vec![-3.1 - dy, kidney_tip + -dy];
+    let log_path_2t = vec![kidney_tip + dy, dy];
+    let log_path_2b = vec![kidney_tip + -dy, -dy];
 
     figure.add_plot(
         &[
@@ -947,13 +934,7 @@ fn fig_x_regions_outside(
     figure.add_grid_lines(&contours, &[])?;
     figure.add_axis()?;
 
-    let scallion_path = get_cut_path(
-        &contours,
-        &pt,
-        Component::Xp,
-        consts,
-        CutType::UShortScallion(Component::Xp),
-    );
+    let scallion_path = bottom_x_cut_path(pxu::kinematics::scallion, consts);
 
     let (scallion_left, scallion_right) = scallion_path
         .split_at(scallion_path.partition_point(|x| pxu::kinematics::u_of_x(*x, consts).re < 0.0));
@@ -1035,21 +1016,9 @@ fn fig_x_regions_between(
     figure.add_grid_lines(&contours, &[])?;
     figure.add_axis()?;
 
-    let scallion_path = get_cut_path(
-        &contours,
-        &pt,
-        Component::Xp,
-        consts,
-        CutType::UShortScallion(Component::Xp),
-    );
+    let scallion_path = bottom_x_cut_path(pxu::kinematics::scallion, consts);
 
-    let kidney_path = get_cut_path(
-        &contours,
-        &pt,
-        Component::Xp,
-        consts,
-        CutType::UShortKidney(Component::Xp),
-    );
+    let kidney_path = bottom_x_cut_path(pxu::kinematics::kidney, consts);
 
     let (scallion_left, scallion_right) = scallion_path
         .split_at(scallion_path.partition_point(|x| pxu::kinematics::u_of_x(*x, consts).re < 0.0));
@@ -1126,13 +1095,7 @@ fn fig_x_regions_inside(
     figure.add_grid_lines(&contours, &[])?;
     figure.add_axis()?;
 
-    let kidney_path = get_cut_path(
-        &contours,
-        &pt,
-        Component::Xp,
-        consts,
-        CutType::UShortKidney(Component::Xp),
-    );
+    let kidney_path = bottom_x_cut_path(pxu::kinematics::kidney, consts);
 
     let (kidney_left, kidney_right) = kidney_path
         .split_at(kidney_path.partition_point(|x| pxu::kinematics::u_of_x(*x, consts).re < 0.0));
@@ -4534,7 +4497,7 @@ fn draw_state_figure(
     settings: &Settings,
     pb: &ProgressBar,
 ) -> Result<FigureCompiler> {
-    let states = load_states(state_strings)?;
+    let states = load_states(state_strings, consts)?;
     let contours = pxu_provider.get_contours(consts)?;
 
     figure.add_grid_lines(&contours, &[])?;
@@ -4612,10 +4575,7 @@ fn draw_x_bound_state_figure(
     let consts = CouplingConstants::new(2.0, 5);
     let contours = pxu_provider.get_contours(consts)?;
 
-    let states: Vec<pxu::State> = state_strings
-        .iter()
-        .map(|s| ron::from_str(s).map_err(|_| error("Could not load state")))
-        .collect::<Result<Vec<_>>>()?;
+    let states = load_states(state_strings, consts)?;
 
     figure.component_indicator(r"x^{\pm}");
     figure.add_grid_lines(&contours, &[])?;
@@ -4732,10 +4692,7 @@ fn fig_p_typical_bound_state(
         "(points:[(p:(0.0369899543404076,-0.029477676458957484),xp:(3.725975442509692,2.6128313499217866),xm:(3.5128286480709265,1.3995994557612454),u:(2.7000494004152316,1.5000010188076138),x:(3.6217633112309158,2.022895894514536),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1))),(p:(0.06034321575136616,-0.018323213928633217),xp:(3.512828648070947,1.3995994557612081),xm:(3.3701632658975504,0.000001507484578833207),u:(2.700049400415252,0.5000010188075885),x:(3.4147970768250535,0.7263861464447217),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1))),(p:(0.06034326215107557,0.018323155770842862),xp:(3.370163265897615,0.0000015074845481910515),xm:(3.5128282084799323,-1.3995968258500417),u:(2.700049400415295,-0.49999898119243236),x:(3.4147967471340466,-0.7263832822620354),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1))),(p:(0.03698999112227798,0.029477675660386345),xp:(3.5128282084799114,-1.3995968258500804),xm:(3.7259750341536533,-2.6128289961240028),u:(2.700049400415274,-1.4999989811924586),x:(3.621762872183573,-2.0228934323008243),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))])"
     ];
 
-    let states: Vec<pxu::State> = state_strings
-        .iter()
-        .map(|s| ron::from_str(s).map_err(|_| error("Could not load state")))
-        .collect::<Result<Vec<_>>>()?;
+    let states = load_states(&state_strings, consts)?;
 
     figure.add_grid_lines(&contours, &[])?;
 
@@ -4795,10 +4752,7 @@ fn fig_p_bound_state_region_1(
         "(points:[(p:(1.18723732607551,-0.017900744639078304),xp:(5.343571274474835,4.112533502713208),xm:(5.227614240073456,-2.996639019704647),u:(3.942370414738855,-1.9998999607629369),sheet_data:(log_branch_p:1,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(-1,1))),(p:(0.02155164482525027,0.017897155757077343),xp:(5.227614240073457,-2.996639019704648),xm:(5.343548529832183,-4.1123137550256015),u:(3.9423704147388543,-2.999899960762939),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))],unlocked:false)",
     ];
 
-    let states: Vec<pxu::State> = state_strings
-        .iter()
-        .map(|s| ron::from_str(s).map_err(|_| error("Could not load state")))
-        .collect::<Result<Vec<_>>>()?;
+    let states = load_states(&state_strings, consts)?;
 
     figure.add_grid_lines(&contours, &[])?;
 
@@ -4859,10 +4813,7 @@ fn fig_p_bound_state_regions_min_1_min_2(
         "(points:[(p:(-0.10396889396070738,-0.058571065344782174),xp:(-1.1673288038094392,0.8936432232901272),xm:(-1.0174826765753087,0.0001224475526552249),u:(-2.014092443020625,3.0000999381214077),sheet_data:(log_branch_p:-1,log_branch_m:1,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,1))),(p:(-0.10399507514856618,0.05855992759638331),xp:(-1.0174826765753078,0.00012244755265466978),xm:(-1.1673151913145814,-0.8934917573729062),u:(-2.014092443020624,2.0000999381214073),sheet_data:(log_branch_p:-1,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(1,1)))],unlocked:false)"
     ];
 
-    let states: Vec<pxu::State> = state_strings
-        .iter()
-        .map(|s| ron::from_str(s).map_err(|_| error("Could not load state")))
-        .collect::<Result<Vec<_>>>()?;
+    let states = load_states(&state_strings, consts)?;
 
     figure.add_grid_lines(&contours, &[])?;
 
@@ -5043,10 +4994,7 @@ fn fig_x_singlet_region_0(
         "(points:[(p:(-0.04915040522405487,-0.045791051935815626),xp:(-1.3220716930339478,1.6552562481272564),xm:(-1.3219227444059347,0.8813162555256742),u:(-2.214036050469592,4.000101180615412),sheet_data:(log_branch_p:-1,log_branch_m:1,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,-1))),(p:(-0.09357322668831639,-0.03991326998630673),xp:(-1.321922744405919,0.8813162555256757),xm:(-1.2363694671632584,0.00010225956113174561),u:(-2.214036050469572,3.000101180615414),sheet_data:(log_branch_p:-1,log_branch_m:-3,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,1))),(p:(-0.09358689247514664,0.03990349663451138),xp:(-1.2363694671632492,0.00010225956111992174),xm:(-1.3219116746778858,-0.8811569763752188),u:(-2.214036050469563,2.000101180615402),sheet_data:(log_branch_p:-1,log_branch_m:1,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,-1))),(p:(-0.049155153779756815,0.045792040962502355),xp:(-1.3219116746778863,-0.8811569763752252),xm:(-1.322081015696217,-1.6550991615231962),u:(-2.214036050469563,1.0001011806153943),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(1,1))),(p:(-0.7145343218327235,0.000008784325108582892),xp:(-1.3220810156962146,-1.6550991615231967),xm:(-1.3220716930339236,1.6552562481272393),u:(-2.2140360504695593,0.00010118061539343692),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:-1,u_branch:(Between,Between),im_x_sign:(-1,-1)))],unlocked:false)",
     ];
 
-    let states: Vec<pxu::State> = state_strings
-        .iter()
-        .map(|s| ron::from_str(s).map_err(|_| error("Could not load state")))
-        .collect::<Result<Vec<_>>>()?;
+    let states = load_states(&state_strings, consts)?;
 
     figure.component_indicator(r"x^{\pm}");
     figure.add_grid_lines(&contours, &[])?;
@@ -5737,7 +5685,7 @@ fn draw_singlet(
     state_string: &str,
     marked_indices: &[usize],
 ) -> Result<FigureCompiler> {
-    let state = load_state(state_string)?;
+    let state = load_state(state_string, consts)?;
     let pt = &state.points[0];
     let contours = pxu_provider.get_contours(consts)?;
 
@@ -8285,7 +8233,7 @@ fn fig_p_bs3_region_min_1(
 // (points:[(p:(0.008504583798772088,-0.035116319700573294),xp:(2.0310602321435156,4.601192336448928),xm:(1.8236860657460943,3.5979038213284),u:(0.8259153367490619,3.0001001254211848),x:(1.9343383671092051,4.098744023547635),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,-1))),(p:(0.010200969845901249,-0.04550261494750871),xp:(1.8236860657460947,3.5979038213284005),xm:(1.5405330621577877,2.609924132829659),u:(0.8259153367490621,2.000100125421185),x:(0.13351168692396484,-0.6208463450691568),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,-1))),(p:(0.007960192001555002,-0.06357382171119046),xp:(1.5405330621577886,2.6099241328296587),xm:(1.11944336273788,1.6966093669966547),u:(0.8259153367490633,1.0001001254211848),x:(1.352025969780485,2.135202745026528),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,-1))),(p:(-0.012722182657881366,-0.08134458093418913),xp:(1.1194433627378806,1.6966093669966533),xm:(0.5880727927249432,1.0680517330150374),u:(0.8259153367490646,0.00010012542118420509),x:(0.8493750305023915,-1.3318868074347914),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Between),im_x_sign:(1,-1))),(p:(-0.03140689089138959,-0.07227848066337073),xp:(0.5880727927249434,1.0680517330150365),xm:(0.2332084780767102,0.7382567722288272),u:(0.8259153367490658,-0.9998998745788164),x:(0.380789270523584,0.8815982816018764),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:-1,u_branch:(Outside,Between),im_x_sign:(1,-1))),(p:(-0.02777035244194526,-0.060942522213294804),xp:(0.23320847807671063,0.7382567722288274),xm:(0.06921300804414522,0.5233589956165808),u:(0.8259153367490667,-1.999899874578815),x:(-0.016428179656077273,0.16956485311996405),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:1,e_branch:-1,u_branch:(Between,Between),im_x_sign:(1,-1))),(p:(-0.018153512014311726,-0.05298576814605994),xp:(0.06921300804414529,0.5233589956165808),xm:(0.006592886744828705,0.37836736667440274),u:(0.825915336749067,-2.999899874578815),x:(1.9342970833496362,-4.098543038080382),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:-1,u_branch:(Between,Inside),im_x_sign:(1,-1))),(p:(-0.009985077989350393,-0.04484655870383054),xp:(0.00659288674482856,0.37836736667440285),xm:(-0.01293303863452533,0.28520580704222387),u:(0.8259153367490659,-3.999899874578815),x:(-0.006223493995138852,0.3265423856854216),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-1.485574288950221,-0.000008180452225970939),xp:(-0.012933038634525393,0.28520580704222437),xm:(-0.01293489175771858,-0.28519103385426203),u:(0.8259153367490643,-4.999899874578809),x:(2.746936330172484,-11.142155843007524),sheet_data:(log_branch_p:0,log_branch_m:-2,log_branch_x:1,e_branch:1,u_branch:(Inside,Inside),im_x_sign:(1,1))),(p:(-0.009983772843950994,0.04484493726643239),xp:(-0.012934891757718534,-0.28519103385426203),xm:(0.006586037849219721,-0.37834417705667645),u:(0.8259153367490649,-5.999899874578817),x:(-0.0062271846585451925,-0.32652389514860536),sheet_data:(log_branch_p:2,log_branch_m:-2,log_branch_x:-2,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(-1,1))),(p:(-0.01815159635002861,0.05298417576951007),xp:(0.006586037849219725,-0.3783441770566764),xm:(0.06919284241842479,-0.5233235398438041),u:(0.825915336749065,-6.999899874578816),x:(0.1335437484364576,0.6208891544601038),sheet_data:(log_branch_p:2,log_branch_m:-2,log_branch_x:-1,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(-1,1))),(p:(-0.027768641516948026,0.060940799026703206),xp:(0.06919284241842472,-0.5233235398438043),xm:(0.23315986602397537,-0.7382052120657752),u:(0.8259153367490644,-7.999899874578816),x:(0.13351168692396545,-0.6208463450691566),sheet_data:(log_branch_p:2,log_branch_m:-2,log_branch_x:-2,e_branch:-1,u_branch:(Inside,Between),im_x_sign:(-1,1))),(p:(-0.03140781971284981,0.07227558488286355),xp:(0.23315986602397598,-0.7382052120657749),xm:(0.5879773385079586,-1.0679642572576211),u:(0.8259153367490659,-8.999899874578817),x:(0.3807187988870824,-0.8815339636510102),sheet_data:(log_branch_p:2,log_branch_m:-2,log_branch_x:-2,e_branch:-1,u_branch:(Between,Between),im_x_sign:(-1,1))),(p:(-0.01272796844009084,0.0813454787332294),xp:(0.5879773385079594,-1.0679642572576202),xm:(1.1193410772171217,-1.6964455857607974),u:(0.8259153367490678,-9.999899874578816),x:(0.8493750305023934,-1.3318868074347894),sheet_data:(log_branch_p:2,log_branch_m:-2,log_branch_x:-2,e_branch:1,u_branch:(Between,Outside),im_x_sign:(-1,1))),(p:(0.007958457995764022,0.06357834537780237),xp:(1.1193410772171222,-1.6964455857607976),xm:(1.5404651704138481,-2.609730008600267),u:(0.8259153367490673,-10.999899874578817),x:(1.3519421219160495,-2.135017906474365),sheet_data:(log_branch_p:2,log_branch_m:-2,log_branch_x:-2,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(-1,1))),(p:(0.010201189133454311,0.04550531504147031),xp:(1.5404651704138483,-2.609730008600268),xm:(1.8236384201059015,-3.597703740169742),u:(0.8259153367490671,-11.99989987457882),x:(3.1358188803934888,-18.16733474231712),sheet_data:(log_branch_p:2,log_branch_m:-2,log_branch_x:1,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(-1,1))),(p:(0.00850495587109817,0.035117915121075954),xp:(1.823638420105902,-3.5977037401697416),xm:(2.031023836785722,-4.600990912521743),u:(0.8259153367490675,-12.999899874578817),x:(1.934297083349637,-4.0985430380803844),sheet_data:(log_branch_p:2,log_branch_m:-2,log_branch_x:-2,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(-1,1)))],unlocked:false)
 // (points:[(p:(0.00850451567122419,-0.0351163366428777),xp:(2.0310515783264487,4.601193769786978),xm:(1.8236774624129255,3.5979057375562773),u:(0.8259067559962097,3.000099999989294),x:(1.93432972901603,4.098745666617839),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,-1))),(p:(0.010200854291000724,-0.04550262603714801),xp:(1.8236774624129262,3.597905737556278),xm:(1.5405247013607068,2.6099269202774904),u:(0.8259067559962101,2.0000999999892946),x:(0.1335098726834099,-0.6208477455958303),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,-1))),(p:(0.007959997082149057,-0.06357375026038176),xp:(1.5405247013607066,2.60992692027749),xm:(1.1194362803928564,1.69661364718726),u:(0.8259067559962101,1.0000999999892937),x:(1.352017996815633,2.1352062219696677),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,-1))),(p:(-0.012722224785590501,-0.08134433357584302),xp:(1.1194362803928557,1.6966136471872597),xm:(0.5880689843184308,1.0680557686233254),u:(0.8259067559962098,0.0000999999892933312),x:(0.849369701941432,-1.331891622661128),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:1,u_branch:(Outside,Between),im_x_sign:(1,-1))),(p:(-0.03140676738687043,-0.07227843902770795),xp:(0.5880689843184305,1.0680557686233256),xm:(0.2332062381776739,0.7382588231196434),u:(0.8259067559962091,-0.9999000000107066),x:(0.38078647020266615,0.8816012612265064),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:-1,u_branch:(Outside,Between),im_x_sign:(1,-1))),(p:(-0.027770277529154154,-0.06094259444272281),xp:(0.23320623817767397,0.7382588231196433),xm:(0.06921147606791347,0.5233598375865766),u:(0.8259067559962093,-1.9999000000107068),x:(-0.016428422019962932,0.1695648360578815),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:1,e_branch:-1,u_branch:(Between,Between),im_x_sign:(1,-1))),(p:(-0.018153442581473014,-0.052985849236542905),xp:(0.06921147606791343,0.5233598375865767),xm:(0.006591888732602278,0.37836764565645814),u:(0.8259067559962092,-2.9999000000107072),x:(1.9342884969851755,-4.098544933029524),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:-1,u_branch:(Between,Inside),im_x_sign:(1,-1))),(p:(-0.00998500769250728,-0.044846613616642786),xp:(0.0065918887326022226,0.378367645656458),xm:(-0.01293367285536308,0.2852058772051723),u:(0.8259067559962093,-3.999900000010708),x:(-0.006224288663879103,0.326542532266881),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-0.00504454849795598,-0.037273352266220736),xp:(-0.012933672855363176,0.28520587720517243),xm:(-0.01737926285524494,0.22521931099646053),u:(0.8259067559962077,-4.999900000010707),x:(-0.01613831014380371,0.25206198159240306),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-0.002444001225971159,-0.0311580515212133),xp:(-0.01737926285524475,0.22521931099646045),xm:(-0.01713100290273024,0.18493402813542317),u:(0.8259067559962114,-5.999900000010708),x:(0.38078647020266637,0.881601261226505),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:-1,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-0.001107047302755978,-0.026473566594018966),xp:(-0.017131002902730157,0.18493402813542284),xm:(-0.015594721972887067,0.15648999637430783),u:(0.8259067559962135,-6.999900000010719),x:(-0.01642842201996279,0.16956483605788117),sheet_data:(log_branch_p:0,log_branch_m:0,log_branch_x:0,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-2.469490518301797,0.026467846111587222),xp:(-0.015594721972887044,0.15648999637430758),xm:(-0.01713076618032053,-0.18492734608997766),u:(0.8259067559962137,-7.9999000000107285),x:(2.9374736459666977,-14.154798684335006),sheet_data:(log_branch_p:0,log_branch_m:-3,log_branch_x:1,e_branch:1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-0.002443635689955469,0.031156981066727794),xp:(-0.017130766180320594,-0.18492734608997763),xm:(-0.017379500127251722,-0.22520962585015392),u:(0.8259067559962111,-8.999900000010724),x:(-0.013017598277623029,0.12694382857518613),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:0,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-0.005043836866204629,0.03727197299065966),xp:(-0.01737950012725175,-0.22520962585015392),xm:(-0.012935523601068057,-0.2851911225008308),u:(0.825906755996211,-9.999900000010722),x:(-0.016139122690666204,-0.2520500961959419),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:-3,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-0.009983704183199597,0.04484499420457478),xp:(-0.012935523601068168,-0.2851911225008307),xm:(0.006585048506230251,-0.3783444850201329),u:(0.8259067559962098,-10.999900000010722),x:(-0.006227974631957518,-0.32652406485302565),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:-3,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-0.018151529315943035,0.052984258851600366),xp:(0.006585048506230137,-0.3783444850201332),xm:(0.06919133582369916,-0.5233244260619485),u:(0.8259067559962082,-11.999900000010726),x:(-0.01642842201996293,0.16956483605788097),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:-1,e_branch:-1,u_branch:(Inside,Inside),im_x_sign:(1,-1))),(p:(-0.0277685687530608,0.060940873423300734),xp:(0.06919133582369891,-0.5233244260619482),xm:(0.23315768719594876,-0.7382073272200409),u:(0.8259067559962081,-12.999900000010722),x:(0.13350987268341222,-0.6208477455958343),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:-3,e_branch:-1,u_branch:(Inside,Between),im_x_sign:(1,-1))),(p:(-0.03140769505134425,0.07227554691810695),xp:(0.23315768719594848,-0.7382073272200408),xm:(0.5879736502152035,-1.0679684020490827),u:(0.8259067559962082,-13.999900000010722),x:(0.3807160871328493,-0.8815370234323279),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:-3,e_branch:-1,u_branch:(Between,Between),im_x_sign:(1,-1))),(p:(-0.012728003253993338,0.08134523025357689),xp:(0.5879736502152039,-1.0679684020490832),xm:(1.119334123519768,-1.6964500714143518),u:(0.8259067559962079,-14.999900000010722),x:(0.8493697019414395,-1.3318916226611388),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:-3,e_branch:1,u_branch:(Between,Outside),im_x_sign:(1,-1))),(p:(0.0079582652388391,0.06357826822367572),xp:(1.119334123519768,-1.6964500714143518),xm:(1.5404568947697497,-2.609733039471947),u:(0.8259067559962079,-15.999900000010722),x:(1.3519342542269488,-2.135021615283242),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:-3,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,-1))),(p:(0.010201073290449466,0.045505322744012554),xp:(1.5404568947697506,-2.6097330394719482),xm:(1.8236298764824674,-3.597705907174023),u:(0.8259067559962082,-16.999900000010726),x:(3.329264545743063,-23.178646433207852),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:1,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,-1))),(p:(0.008504887271023925,0.03511793006501657),xp:(1.8236298764824672,-3.5977059071740234),xm:(2.031015228567917,-4.6009925982671325),u:(0.8259067559962083,-17.999900000010726),x:(1.9342884969851784,-4.098544933029543),sheet_data:(log_branch_p:3,log_branch_m:-3,log_branch_x:-3,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,-1)))],unlocked:false)
 
-type FigureFunction = fn(
+pub type FigureFunction = fn(
     pxu_provider: Arc<PxuProvider>,
     cache: Arc<cache::Cache>,
     settings: &Settings,
@@ -1,19 +1,26 @@
-use make_paths::PxuProvider;
+use make_paths::{ProviderUsage, PxuProvider};
 use pxu::kinematics::CouplingConstants;
-use std::io::Result;
 use std::sync::Arc;
 
 use clap::Parser;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 
 mod cache;
+mod fig_asy;
 mod fig_compiler;
+mod fig_python;
+mod fig_style;
+mod fig_svg;
 mod fig_writer;
 mod figures;
+mod theme;
 mod utils;
 
-use crate::figures::ALL_FIGURES;
-use crate::utils::{error, Settings, Summary, SUMMARY_NAME};
+use crate::fig_compiler::FinishedFigure;
+use crate::figures::{FigureFunction, ALL_FIGURES};
+use crate::utils::{
+    error, print_figure_list, skipped_figure_name, OutputFormat, Settings, Summary, SUMMARY_NAME,
+};
 
 fn check_for_gs() -> bool {
     let mut cmd = std::process::Command::new("gs");
@@ -47,12 +54,81 @@ fn panic_on_thread_panic() {
     }));
 }
 
+// The name of a figure that failed, found by running it again in list mode,
+// where it stops as soon as it knows its name
+fn figure_name(
+    f: FigureFunction,
+    pxu_provider: Arc<PxuProvider>,
+    cache: Arc<cache::Cache>,
+    settings: &Settings,
+) -> Option<String> {
+    let settings = Settings {
+        list: true,
+        json: false,
+        check_paths: false,
+        ..settings.clone()
+    };
+    let err = f(pxu_provider, cache, &settings, &ProgressBar::hidden()).err()?;
+    skipped_figure_name(&err).map(str::to_owned)
+}
+
+// Report every path and start of a path used by a figure that is not the name
+// of a path
+fn check_paths(
+    pxu_provider: &Arc<PxuProvider>,
+    cache: &Arc<cache::Cache>,
+    settings: &Settings,
+    usages: Vec<(usize, Option<String>, ProviderUsage)>,
+) -> std::io::Result<()> {
+    let mut missing_paths = 0;
+    for (i, name, usage) in usages {
+        let used = usage.paths.iter().chain(usage.starts.iter());
+        let missing = pxu_provider.find_missing_paths(used.map(String::as_str));
+        if missing.is_empty() {
+            continue;
+        }
+
+        let name = name
+            .or_else(|| {
+                figure_name(
+                    ALL_FIGURES[i],
+                    pxu_provider.clone(),
+                    cache.clone(),
+                    settings,
+                )
+            })
+            .unwrap_or_else(|| format!("number {}", i + 1));
+        for path in missing {
+            eprintln!("Figure \"{name}\" uses the path \"{path}\" which does not exist");
+            missing_paths += 1;
+        }
+    }
+
+    if missing_paths > 0 {
+        return Err(error(&format!(
+            "{missing_paths} paths used by the figures do not exist"
+        )));
+    }
+    Ok(())
+}
+
 fn main() -> std::io::Result<()> {
     panic_on_thread_panic();
 
     let mut settings = Settings::parse();
     let verbose = settings.verbose > 0;
 
+    if let Some(theme_file) = &settings.theme_file {
+        settings.theme = Arc::new(theme::Theme::load(theme_file)?);
+    }
+
+    if let Some(preamble_file) = &settings.preamble_file {
+        settings.preamble = std::fs::read_to_string(preamble_file)?;
+        if !settings.preamble.ends_with('\n') {
+            settings.preamble.push('\n');
+        }
+    }
+
     let start = std::time::Instant::now();
 
     if verbose {
@@ -66,7 +142,7 @@ fn main() -> std::io::Result<()> {
         log::set_max_level(log::LevelFilter::Debug);
     }
 
-    if !settings.no_compress {
+    if !settings.no_compress && settings.format == OutputFormat::Tex {
         settings.no_compress = !check_for_gs();
     }
 
@@ -104,23 +180,38 @@ fn main() -> std::io::Result<()> {
 
     let mut pxu_provider = PxuProvider::new();
 
-    println!("[1/5] Generating figures");
-    pxu_provider.generate_contours(consts_list, verbose, &pool, &spinner_style);
-
-    println!("[2/5] Loading paths");
-    pxu_provider.load_paths(
-        make_paths::PLOT_PATHS,
-        verbose,
-        &pool,
-        &settings.output_dir,
-        &spinner_style,
-        &spinner_style_no_progress,
-    );
+    // Only the list is printed when listing the figures or checking the paths
+    let steps = !settings.dry_run();
+
+    if settings.list && !settings.json {
+        // The figures only need to know their names, which they get before
+        // they use any contours or paths
+        for consts in consts_list {
+            pxu_provider.add_contours(consts, pxu::Contours::new());
+        }
+    } else {
+        if steps {
+            println!("[1/5] Generating figures");
+        }
+        pxu_provider.generate_contours(consts_list, verbose, &pool, &spinner_style);
+
+        if steps {
+            println!("[2/5] Loading paths");
+        }
+        pxu_provider.load_paths(
+            make_paths::PLOT_PATHS,
+            verbose,
+            &pool,
+            &settings.output_dir,
+            &spinner_style,
+            &spinner_style_no_progress,
+        );
+    }
 
     let pxu_provider = Arc::new(pxu_provider);
     let cache = Arc::new(cache);
 
-    if !verbose {
+    if !verbose && steps {
         if settings.rebuild {
             println!("[3/5] Building figures (ignoring cache)");
         } else {
@@ -143,7 +234,12 @@ fn main() -> std::io::Result<()> {
     pb.enable_steady_tick(std::time::Duration::from_millis(250));
 
     for (i, f) in ALL_FIGURES.iter().enumerate() {
-        let pxu_provider = pxu_provider.clone();
+        // A separate provider for each figure records what it uses
+        let pxu_provider = if settings.dry_run() {
+            Arc::new(pxu_provider.recording())
+        } else {
+            pxu_provider.clone()
+        };
         let cache_ref = cache.clone();
         let spinner_style = spinner_style.clone();
         let settings = settings.clone();
@@ -157,72 +253,147 @@ fn main() -> std::io::Result<()> {
             };
             pb.set_style(spinner_style);
 
-            match f(pxu_provider, cache_ref, &settings, &pb) {
-                Ok(figure) => {
-                    let result = figure.wait(&pb, &settings);
-                    pb.finish_and_clear();
-                    tx.send(result.map(|r| (i, r))).unwrap();
-                }
-                Err(e) => {
-                    tx.send(Err(e)).unwrap();
-                }
-            }
+            // The usage is also kept for the figures that fail, which may be
+            // since they use a path that does not exist
+            let result = f(pxu_provider.clone(), cache_ref, &settings, &pb)
+                .and_then(|figure| figure.wait(&pb, &settings));
+            let usage = pxu_provider.get_usage();
+            let result = result.map(|finished_figure| FinishedFigure {
+                usage: usage.clone(),
+                ..finished_figure
+            });
+            pb.finish_and_clear();
+            tx.send((i, usage, result)).unwrap();
         });
     }
 
-    let mut finished_figures = rx
-        .into_iter()
-        .take(ALL_FIGURES.len())
-        .map(|r| {
-            pb.inc(1);
-            r
-        })
-        .collect::<Result<Vec<_>>>()?;
+    // All figures are built before reporting the errors, so that every missing
+    // path is listed at once
+    let mut finished_figures = vec![];
+    let mut skipped_figures = vec![];
+    let mut errors = vec![];
+    let mut usages = vec![];
+    for (i, usage, result) in rx.into_iter().take(ALL_FIGURES.len()) {
+        pb.inc(1);
+        let name = result
+            .as_ref()
+            .ok()
+            .map(|finished_figure| finished_figure.name.clone());
+        usages.push((i, name, usage));
+        match result {
+            Ok(finished_figure) => finished_figures.push((i, finished_figure)),
+            Err(err) => {
+                if let Some(name) = skipped_figure_name(&err) {
+                    skipped_figures.push((i, name.to_owned()));
+                } else {
+                    errors.push(err);
+                }
+            }
+        }
+    }
     pool.join();
     pb.finish_and_clear();
 
+    if settings.list && !settings.json {
+        skipped_figures.sort_by_key(|&(i, _)| i);
+        for (_, name) in skipped_figures {
+            println!("{name}");
+        }
+        return Ok(());
+    }
+
+    if settings.check_paths {
+        usages.sort_by_key(|&(i, ..)| i);
+        check_paths(&pxu_provider, &cache, &settings, usages)?;
+    }
+
+    if !errors.is_empty() {
+        for err in errors.iter() {
+            eprintln!("{err}");
+        }
+        return Err(error(&format!(
+            "{} figures could not be built",
+            errors.len()
+        )));
+    }
+
+    if finished_figures.is_empty() {
+        return Err(error(&format!(
+            "No figures matched {}",
+            settings.figures.join(", ")
+        )));
+    }
+
+    if settings.check_paths {
+        eprintln!("All paths used by the figures exist");
+        return Ok(());
+    }
+
     finished_figures.sort_by_key(|&(n, _)| n);
+
+    if settings.list {
+        let finished_figures = finished_figures
+            .into_iter()
+            .map(|(_, r)| r)
+            .collect::<Vec<_>>();
+        return print_figure_list(&finished_figures);
+    }
+
     let finished_figures = finished_figures.into_iter().map(|(_, r)| r);
 
     let mut new_cache = cache::Cache::new(&settings.output_dir);
+    for (_, name) in skipped_figures.iter() {
+        new_cache.keep(&cache, name);
+    }
     let mut summary = Summary::default();
 
     let mut lualatex_errors = vec![];
 
+    // The cache and the summary are only kept for the PDF figures
+    let pdf = settings.format == OutputFormat::Tex;
+
     for finished_figure in finished_figures {
         if finished_figure.lualatex_error {
             lualatex_errors.push(finished_figure.name.clone());
         }
-        new_cache.update(&finished_figure.name)?;
+        if pdf {
+            new_cache.update(&finished_figure.name)?;
+        }
         summary.add(finished_figure);
     }
 
-    if !verbose {
-        println!("[4/5] Saving cache");
+    if pdf {
+        if !verbose {
+            println!("[4/5] Saving cache");
+        }
+        new_cache.save()?;
     }
-    new_cache.save()?;
 
-    if !verbose {
-        println!("[5/5] Building summary");
-    }
+    // The summary of a subset of the figures would replace the summary of all
+    // figures
+    if pdf && settings.figures.is_empty() {
+        if !verbose {
+            println!("[5/5] Building summary");
+        }
 
-    let pb = if !verbose {
-        ProgressBar::new_spinner()
-    } else {
-        ProgressBar::hidden()
-    };
+        let pb = if !verbose {
+            ProgressBar::new_spinner()
+        } else {
+            ProgressBar::hidden()
+        };
 
-    pb.set_style(spinner_style_no_progress);
-    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+        pb.set_style(spinner_style_no_progress);
+        pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    if summary.finish(&settings, &pb)?.wait()?.success() {
-        log::info!("[{SUMMARY_NAME}] Done.");
-    } else {
-        log::error!("[{SUMMARY_NAME}] Error.");
-        return Err(error("Error compiling summary"));
-    }
+        if summary.finish(&settings, &pb)?.wait()?.success() {
+            log::info!("[{SUMMARY_NAME}] Done.");
+        } else {
+            log::error!("[{SUMMARY_NAME}] Error.");
+            return Err(error("Error compiling summary"));
+        }
 
-    pb.finish_and_clear();
+        pb.finish_and_clear();
+    }
 
     let end = std::time::Instant::now();
 
@@ -233,7 +404,7 @@ fn main() -> std::io::Result<()> {
 
     eprintln!(
         "\nBuilt {} figures in {minutes}:{seconds}",
-        ALL_FIGURES.len()
+        ALL_FIGURES.len() - skipped_figures.len()
     );
 
     eprintln!("{}", pxu_provider.get_statistics());
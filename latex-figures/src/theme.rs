@@ -0,0 +1,109 @@
+use serde::Deserialize;
+use std::io::Result;
+
+use crate::utils::error;
+
+// The color of a cut, which is also used for its branch point, and the other
+// options for drawing the cut
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CutStyle {
+    pub color: String,
+    pub style: Vec<String>,
+}
+
+impl CutStyle {
+    fn new(color: &str, style: &[&str]) -> Self {
+        Self {
+            color: color.to_owned(),
+            style: style.iter().map(|s| (*s).to_owned()).collect(),
+        }
+    }
+
+    pub fn options(&self) -> Vec<&str> {
+        [self.color.as_str()]
+            .into_iter()
+            .chain(self.style.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+// The TikZ options of the elements that appear in many figures, which can be
+// read from a .ron or .toml file to restyle all figures at once. A role that
+// is missing from the file keeps its default style.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub grid: Vec<String>,
+    pub axis: Vec<String>,
+    pub path: Vec<String>,
+    // The parts of a path that are not on the same sheet as the point
+    pub path_other_sheet: Vec<String>,
+    pub marker: Vec<String>,
+    pub e_cut: CutStyle,
+    pub q_log_cut: CutStyle,
+    pub xp_log_cut: CutStyle,
+    pub xm_log_cut: CutStyle,
+    // The scallion and kidney styles are also used for the long u cuts
+    pub xp_scallion: CutStyle,
+    pub xm_scallion: CutStyle,
+    pub xp_kidney: CutStyle,
+    pub xm_kidney: CutStyle,
+    // Drawn below a kidney cut unless other options are given for the cut
+    pub kidney_background: Vec<String>,
+    // The cuts of the x plane drawn in the p plane in the preimage figures
+    pub xp_preimage_log_cut: Vec<String>,
+    pub xm_preimage_log_cut: Vec<String>,
+    pub xp_preimage_long_cut: Vec<String>,
+    pub xm_preimage_long_cut: Vec<String>,
+}
+
+fn strings(options: &[&str]) -> Vec<String> {
+    options.iter().map(|s| (*s).to_owned()).collect()
+}
+
+pub fn role_options(role: &[String]) -> Vec<&str> {
+    role.iter().map(String::as_str).collect()
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let zigzag = [
+            "decorate",
+            "decoration={zigzag, segment length=1.2mm, amplitude=0.15mm}",
+            "thick",
+        ];
+        let coil = "decorate,decoration={coil,aspect=0, segment length=2.4mm, amplitude=0.15mm}";
+
+        Self {
+            grid: strings(&["very thin", "lightgray"]),
+            axis: strings(&["very thin", "black"]),
+            path: strings(&["very thick", "Blue"]),
+            path_other_sheet: strings(&["very thick", "Blue", "densely dotted"]),
+            marker: vec![],
+            e_cut: CutStyle::new("black", &["very thick"]),
+            q_log_cut: CutStyle::new("black", &zigzag),
+            xp_log_cut: CutStyle::new("Red", &zigzag),
+            xm_log_cut: CutStyle::new("Green", &zigzag),
+            xp_scallion: CutStyle::new("Red", &["very thick"]),
+            xm_scallion: CutStyle::new("Green", &["very thick"]),
+            xp_kidney: CutStyle::new("Red", &["very thick", "densely dashed"]),
+            xm_kidney: CutStyle::new("Green", &["very thick", "densely dashed"]),
+            kidney_background: strings(&["lightgray", "very thick"]),
+            xp_preimage_log_cut: strings(&["Red!50!white", "very thick"]),
+            xm_preimage_log_cut: strings(&["Green!50!white", "very thick"]),
+            xp_preimage_long_cut: strings(&["Red!50!white", coil, "very thick"]),
+            xm_preimage_long_cut: strings(&["Green!50!white", coil, "very thick"]),
+        }
+    }
+}
+
+impl Theme {
+    pub fn load(filename: &std::path::Path) -> Result<Self> {
+        let s = std::fs::read_to_string(filename)?;
+        match filename.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&s).map_err(|err| error(&format!("{err}"))),
+            _ => ron::from_str(&s).map_err(|err| error(&format!("{err}"))),
+        }
+    }
+}
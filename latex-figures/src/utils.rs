@@ -2,11 +2,13 @@ use std::fs::File;
 use std::io::{prelude::*, BufWriter, Result};
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
 
 use clap::Parser;
 use indicatif::ProgressBar;
 
 use crate::fig_compiler::FinishedFigure;
+use crate::theme::Theme;
 
 pub const TEX_EXT: &str = "tex";
 pub const PDF_EXT: &str = "pdf";
@@ -23,6 +25,29 @@ pub struct Size {
     pub height: f64,
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// PDF figures compiled with LuaLaTeX
+    Tex,
+    /// SVG figures, drawn without LaTeX
+    Svg,
+    /// Python scripts drawing the figures with matplotlib
+    Python,
+    /// Asymptote files
+    Asymptote,
+}
+
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Tex => TEX_EXT,
+            Self::Svg => "svg",
+            Self::Python => "py",
+            Self::Asymptote => "asy",
+        }
+    }
+}
+
 #[derive(Parser, Clone)]
 #[command(author, version, about, long_about = None)]
 pub struct Settings {
@@ -38,6 +63,80 @@ pub struct Settings {
     pub jobs: Option<usize>,
     #[arg(short, long)]
     pub no_compress: bool,
+    /// Only build the figures with names matching this pattern, where * matches
+    /// any text and ? any single character
+    #[arg(short, long = "figure", value_name = "NAME")]
+    pub figures: Vec<String>,
+    /// List the names of the figures instead of building them
+    #[arg(long)]
+    pub list: bool,
+    /// With --list, print the name, size, coupling constants and paths of the
+    /// figures as JSON, which runs the figures without writing any files
+    #[arg(long, requires = "list")]
+    pub json: bool,
+    /// Check that the paths used by the figures exist, which runs the figures
+    /// without writing any files
+    #[arg(long, conflicts_with = "list")]
+    pub check_paths: bool,
+    /// The format of the figures. Custom TikZ drawing commands are left out of
+    /// the figures that are not drawn by LaTeX.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Tex)]
+    pub format: OutputFormat,
+    /// Read the style of the cuts, paths and other recurring elements from a
+    /// .ron or .toml file
+    #[arg(long = "theme", value_name = "FILE")]
+    pub theme_file: Option<PathBuf>,
+    #[arg(skip)]
+    pub theme: Arc<Theme>,
+    /// The options and class of the figures as given to \documentclass, such
+    /// as "[11pt]{beamer}"
+    #[arg(long, default_value = "[10pt,a4paper]{article}")]
+    pub document_class: String,
+    /// Add the contents of a file, such as font packages and TikZ libraries,
+    /// to the end of the preamble of the figures
+    #[arg(long = "preamble", value_name = "FILE")]
+    pub preamble_file: Option<PathBuf>,
+    #[arg(skip)]
+    pub preamble: String,
+}
+
+impl Settings {
+    // The figures are run without writing or compiling anything
+    pub fn dry_run(&self) -> bool {
+        self.list || self.check_paths
+    }
+
+    pub fn is_selected(&self, name: &str) -> bool {
+        (!self.list || self.json)
+            && (self.figures.is_empty()
+                || self
+                    .figures
+                    .iter()
+                    .any(|pattern| make_paths::matches_pattern(pattern, name)))
+    }
+}
+
+// The error returned when a figure is not built since it was not selected
+#[derive(Debug)]
+pub struct SkippedFigure(pub String);
+
+impl std::fmt::Display for SkippedFigure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Skipped {}", self.0)
+    }
+}
+
+impl std::error::Error for SkippedFigure {}
+
+pub fn skipped(name: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, SkippedFigure(name.to_owned()))
+}
+
+// The name of the figure, if the error is that it was skipped
+pub fn skipped_figure_name(err: &std::io::Error) -> Option<&str> {
+    err.get_ref()?
+        .downcast_ref::<SkippedFigure>()
+        .map(|skipped| skipped.0.as_str())
 }
 
 #[derive(Debug, Default)]
@@ -126,3 +225,55 @@ impl Summary {
         cmd.spawn()
     }
 }
+
+#[derive(serde::Serialize)]
+struct ListedConsts {
+    h: f64,
+    k: i32,
+}
+
+// The description of a figure printed by --list --json. The component is
+// missing for the figures with a custom axis.
+#[derive(serde::Serialize)]
+struct ListedFigure<'a> {
+    name: &'a str,
+    caption: &'a str,
+    component: Option<pxu::Component>,
+    x_range: [f64; 2],
+    y_range: [f64; 2],
+    width: f64,
+    height: f64,
+    consts: Vec<ListedConsts>,
+    paths: &'a [String],
+    states: &'a [String],
+}
+
+pub fn print_figure_list(finished_figures: &[FinishedFigure]) -> Result<()> {
+    let figures = finished_figures
+        .iter()
+        .map(|figure| ListedFigure {
+            name: &figure.name,
+            caption: &figure.caption,
+            component: figure.component,
+            x_range: [figure.bounds.x_range.start, figure.bounds.x_range.end],
+            y_range: [figure.bounds.y_range.start, figure.bounds.y_range.end],
+            width: figure.size.width,
+            height: figure.size.height,
+            consts: figure
+                .usage
+                .consts
+                .iter()
+                .map(|consts| ListedConsts {
+                    h: consts.h,
+                    k: consts.k(),
+                })
+                .collect(),
+            paths: &figure.usage.paths,
+            states: &figure.usage.starts,
+        })
+        .collect::<Vec<_>>();
+
+    let json = serde_json::to_string_pretty(&figures).map_err(|err| error(&format!("{err}")))?;
+    println!("{json}");
+    Ok(())
+}
@@ -0,0 +1,38 @@
+// Compute a digest of the sources of pxu and make-paths, which is part of the
+// key of the path cache. Any change to the continuation or to the paths then
+// invalidates the cached paths.
+
+use std::path::{Path, PathBuf};
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+fn main() {
+    let dirs = [Path::new("../pxu/src"), Path::new("src")];
+
+    let mut files = vec![];
+    for dir in dirs {
+        println!("cargo:rerun-if-changed={}", dir.display());
+        collect_files(dir, &mut files);
+    }
+    files.sort();
+
+    let mut context = md5::Context::new();
+    for file in files {
+        context.consume(file.to_string_lossy().as_bytes());
+        context.consume(std::fs::read(&file).unwrap_or_default());
+    }
+
+    println!("cargo:rustc-env=SOURCE_DIGEST={:x}", context.compute());
+}
@@ -0,0 +1,262 @@
+use std::sync::Arc;
+
+use num::complex::Complex64;
+use pxu::kinematics::{dxp_dp, xp, CouplingConstants, SheetData};
+use pxu::path::SavedPath;
+
+use crate::paths::{load_state, Goto};
+use crate::ContourProvider;
+
+// Builds a saved path from a few steps. The start state is first moved into
+// place with goto, goto_points, goto_through and goto_m, and the path is then
+// drawn in its component with move_to, line_to, arc, circle and bezier_to.
+// Finally cross_cut can be used to keep only the part of the path on a given
+// sheet.
+pub struct PathBuilder {
+    name: String,
+    consts: CouplingConstants,
+    contours: Arc<pxu::Contours>,
+    state: pxu::State,
+    component: pxu::Component,
+    excitation: usize,
+    path: Vec<Complex64>,
+}
+
+// Find the real momentum p and the number of excitations m of the x⁺ grid line
+// passing through the given value of x⁺, starting from a single excitation
+// with the given momentum
+fn xp_grid_line(x: Complex64, p: f64, consts: CouplingConstants) -> Option<(f64, f64)> {
+    const DM: f64 = 1.0e-6;
+
+    let (mut p, mut m) = (p, 1.0);
+    for _ in 0..50 {
+        let f = xp(p, m, consts) - x;
+        if f.norm() < 1.0e-10 {
+            return Some((p, m));
+        }
+
+        // Solve the real two by two system for the changes in p and m
+        let df_dp = dxp_dp(p, m, consts);
+        let df_dm = (xp(p, m + DM, consts) - xp(p, m - DM, consts)) / (2.0 * DM);
+        let det = df_dp.re * df_dm.im - df_dm.re * df_dp.im;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        p -= (df_dm.im * f.re - df_dm.re * f.im) / det;
+        m -= (df_dp.re * f.im - df_dp.im * f.re) / det;
+    }
+    None
+}
+
+impl PathBuilder {
+    // A path of a single excitation in the given component, starting from the
+    // default state
+    pub fn new(
+        name: &str,
+        contour_provider: &ContourProvider,
+        consts: CouplingConstants,
+        component: pxu::Component,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            consts,
+            contours: contour_provider.get(consts).unwrap(),
+            state: pxu::State::new(1, consts),
+            component,
+            excitation: 0,
+            path: vec![],
+        }
+    }
+
+    pub fn with_state(mut self, state: pxu::State) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn with_state_str(self, s: &str) -> Self {
+        let state = load_state(s, self.consts).unwrap();
+        self.with_state(state)
+    }
+
+    // The excitation that is moved by goto and that follows the path
+    pub fn with_excitation(mut self, excitation: usize) -> Self {
+        self.excitation = excitation;
+        self
+    }
+
+    // The current value of the component of the path for the start state
+    pub fn current(&self) -> Complex64 {
+        self.state.points[self.excitation].get(self.component)
+    }
+
+    // Move the start state to the given value
+    pub fn goto(
+        mut self,
+        component: pxu::Component,
+        z: impl Into<Complex64>,
+        steps: usize,
+    ) -> Self {
+        self.state.goto(
+            self.excitation,
+            component,
+            z,
+            &self.contours,
+            self.consts,
+            steps,
+        );
+        self
+    }
+
+    // Move the start state through each of the points in turn
+    pub fn goto_points(self, component: pxu::Component, points: &[[f64; 2]]) -> Self {
+        let points = points.iter().map(|&[re, im]| Complex64::new(re, im));
+        self.goto_through(component, points, 15)
+    }
+
+    // Move the start state through each of the points in turn, with the given
+    // number of steps to each point
+    pub fn goto_through(
+        self,
+        component: pxu::Component,
+        points: impl IntoIterator<Item = Complex64>,
+        steps: usize,
+    ) -> Self {
+        points
+            .into_iter()
+            .fold(self, |builder, z| builder.goto(component, z, steps))
+    }
+
+    // Move x⁺ of the excitation along a line of constant real momentum, from
+    // the x⁺ grid line it is on to the grid line of bound states of m
+    // excitations, as goto_m of the interpolator used for the contours
+    pub fn goto_m(self, m: f64, steps: usize) -> Self {
+        let consts = self.consts;
+        let point = &self.state.points[self.excitation];
+        let Some((p, m0)) = xp_grid_line(point.xp, point.p.re, consts) else {
+            panic!("The start of {} is not on an x⁺ grid line", self.name);
+        };
+
+        let points = (1..=steps).map(|i| xp(p, m0 + (m - m0) * i as f64 / steps as f64, consts));
+        self.goto_through(pxu::Component::Xp, points, 1)
+    }
+
+    // Add a single point, which is where the next line or arc starts
+    pub fn move_to(mut self, z: impl Into<Complex64>) -> Self {
+        self.path.push(z.into());
+        self
+    }
+
+    fn last(&self) -> Complex64 {
+        *self
+            .path
+            .last()
+            .expect("The path should be started with move_to or circle")
+    }
+
+    pub fn line_to(mut self, z: impl Into<Complex64>, steps: usize) -> Self {
+        let start = self.last();
+        let end = z.into();
+        self.path
+            .extend((1..=steps).map(|i| start + (i as f64 / steps as f64) * (end - start)));
+        self
+    }
+
+    // Turn the given angle around the center, starting from the last point
+    pub fn arc(mut self, center: Complex64, angle: f64, steps: usize) -> Self {
+        let start = self.last() - center;
+        self.path.extend((1..=steps).map(|i| {
+            let theta = angle * i as f64 / steps as f64;
+            center + start * Complex64::from_polar(1.0, theta)
+        }));
+        self
+    }
+
+    // Add the points of a circle going from one angle to another, including
+    // both end points
+    pub fn circle(
+        mut self,
+        center: Complex64,
+        radius: f64,
+        from: f64,
+        to: f64,
+        steps: usize,
+    ) -> Self {
+        self.path.extend((0..=steps).map(|i| {
+            let theta = from + (to - from) * (i as f64 / steps as f64);
+            center + Complex64::from_polar(radius, theta)
+        }));
+        self
+    }
+
+    // A cubic Bezier curve from the last point, with points spaced by the
+    // given distance
+    pub fn bezier_to(
+        mut self,
+        control1: Complex64,
+        control2: Complex64,
+        end: Complex64,
+        distance: f64,
+        max_error: f64,
+    ) -> Self {
+        use flo_curves::{
+            bezier::{walk_curve_evenly, Curve},
+            BezierCurve, BezierCurveFactory, Coord2,
+        };
+
+        fn c64_to_coord2(z: Complex64) -> Coord2 {
+            Coord2(z.re, z.im)
+        }
+
+        fn coord2_to_c64(p: Coord2) -> Complex64 {
+            Complex64 { re: p.0, im: p.1 }
+        }
+
+        let curve = Curve::from_points(
+            c64_to_coord2(self.last()),
+            (c64_to_coord2(control1), c64_to_coord2(control2)),
+            c64_to_coord2(end),
+        );
+
+        self.path.extend(
+            walk_curve_evenly(&curve, distance, max_error).map(|z| coord2_to_c64(z.end_point())),
+        );
+        self
+    }
+
+    // Add points calculated in some other way
+    pub fn extend(mut self, points: impl IntoIterator<Item = Complex64>) -> Self {
+        self.path.extend(points);
+        self
+    }
+
+    // Move the start state along the path, and keep only the points where the
+    // sheet of the excitation passes the test. The path then starts from the
+    // first of these points.
+    pub fn cross_cut(mut self, steps: usize, keep: impl Fn(&SheetData) -> bool) -> Self {
+        let component = self.component;
+        let mut start = None;
+        let mut path = vec![];
+        for z in std::mem::take(&mut self.path) {
+            self = self.goto(component, z, steps);
+            if keep(&self.state.points[self.excitation].sheet_data) {
+                start.get_or_insert_with(|| self.state.clone());
+                path.push(z);
+            }
+        }
+
+        self.state = start.unwrap_or_else(|| panic!("No part of {} was kept", self.name));
+        self.path = path;
+        self
+    }
+
+    pub fn build(self) -> SavedPath {
+        SavedPath::new(
+            self.name,
+            self.path,
+            self.state,
+            self.component,
+            self.excitation,
+            self.consts,
+        )
+    }
+}
@@ -0,0 +1,241 @@
+use std::f64::consts::TAU;
+use std::io::Result;
+use std::sync::Arc;
+
+use num::complex::Complex64;
+use pxu::kinematics::CouplingConstants;
+use pxu::path::SavedPath;
+
+use crate::builder::PathBuilder;
+use crate::paths::error;
+use crate::{ContourProvider, PathDefinition};
+
+// Paths that are simple enough to be given in a file instead of in code. A file
+// in RON looks like
+//
+// (paths: [
+//     (
+//         name: "xp circle",
+//         h: 2.0,
+//         k: 5,
+//         approach: Some((component: P, points: [(0.03, 0.03), (-0.06, 0.0)])),
+//         component: Xp,
+//         shape: Circle(center: (-0.45, 0.2), radius: 0.93, from: -0.5, to: 1.5),
+//     ),
+// ])
+//
+// and the same in TOML is a [[paths]] table for each path.
+
+#[derive(serde::Deserialize)]
+struct DefinitionFile {
+    paths: Vec<PathSpec>,
+}
+
+#[derive(Clone, serde::Deserialize)]
+struct Approach {
+    component: pxu::Component,
+    points: Vec<[f64; 2]>,
+}
+
+#[derive(Clone, serde::Deserialize)]
+enum Shape {
+    // Straight lines between the points, split in steps of at most the given
+    // length
+    Polyline {
+        points: Vec<[f64; 2]>,
+        #[serde(default = "default_step")]
+        step: f64,
+    },
+    // An arc from and to the given angles, measured in turns
+    Circle {
+        center: [f64; 2],
+        radius: f64,
+        #[serde(default)]
+        from: f64,
+        #[serde(default = "default_to")]
+        to: f64,
+        #[serde(default = "default_steps_per_turn")]
+        steps_per_turn: usize,
+    },
+}
+
+fn default_step() -> f64 {
+    0.01
+}
+
+fn default_to() -> f64 {
+    1.0
+}
+
+fn default_steps_per_turn() -> usize {
+    256
+}
+
+fn default_m() -> usize {
+    1
+}
+
+#[derive(Clone, serde::Deserialize)]
+struct PathSpec {
+    name: String,
+    h: f64,
+    k: i32,
+    // The number of excitations, when no start state is given
+    #[serde(default = "default_m")]
+    m: usize,
+    // A state in the same format as the state exported from the app
+    #[serde(default)]
+    start: Option<String>,
+    // Points the start state is moved through before the path begins
+    #[serde(default)]
+    approach: Option<Approach>,
+    component: pxu::Component,
+    #[serde(default)]
+    excitation: usize,
+    shape: Shape,
+}
+
+pub struct DeclaredPath {
+    spec: PathSpec,
+    name: String,
+    consts: CouplingConstants,
+    start: pxu::State,
+}
+
+fn c64([re, im]: [f64; 2]) -> Complex64 {
+    Complex64::new(re, im)
+}
+
+impl Shape {
+    fn start(&self) -> Option<Complex64> {
+        match self {
+            Self::Polyline { points, .. } => points.first().map(|&z| c64(z)),
+            Self::Circle {
+                center,
+                radius,
+                from,
+                ..
+            } => Some(c64(*center) + Complex64::from_polar(*radius, TAU * from)),
+        }
+    }
+
+    fn draw(&self, builder: PathBuilder) -> PathBuilder {
+        match self {
+            Self::Polyline { points, step } => {
+                let builder = builder.move_to(c64(points[0]));
+                points.windows(2).fold(builder, |builder, pair| {
+                    let (z0, z1) = (c64(pair[0]), c64(pair[1]));
+                    let steps = ((z1 - z0).norm() / step).ceil().max(1.0) as usize;
+                    builder.line_to(z1, steps)
+                })
+            }
+            Self::Circle {
+                center,
+                radius,
+                from,
+                to,
+                steps_per_turn,
+            } => {
+                // The angles are given in turns, but the builder uses radians
+                let steps = (((to - from).abs() * *steps_per_turn as f64).ceil() as usize).max(1);
+                builder.circle(c64(*center), *radius, TAU * from, TAU * to, steps)
+            }
+        }
+    }
+}
+
+impl DeclaredPath {
+    fn new(spec: PathSpec, name: String, consts: CouplingConstants) -> Result<Self> {
+        let fail = |message: String| error(&format!("Path \"{name}\": {message}"));
+
+        if !(0.1..=10.0).contains(&consts.h) || !(0..=10).contains(&consts.k()) {
+            return Err(fail(format!(
+                "can not use the coupling constants h = {}, k = {}",
+                consts.h,
+                consts.k()
+            )));
+        }
+
+        let start = match &spec.start {
+            Some(s) => pxu::State::parse(s, consts)
+                .map_err(|err| fail(format!("could not load state: {err}")))?,
+            None if spec.m > 0 => pxu::State::new(spec.m, consts),
+            None => return Err(fail("the state has no excitations".to_owned())),
+        };
+        if spec.excitation >= start.points.len() {
+            return Err(fail(format!("there is no excitation {}", spec.excitation)));
+        }
+        if matches!(&spec.shape, Shape::Polyline { points, .. } if points.len() < 2) {
+            return Err(fail("the path needs at least two points".to_owned()));
+        }
+
+        Ok(Self {
+            spec,
+            name,
+            consts,
+            start,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn consts(&self) -> CouplingConstants {
+        self.consts
+    }
+
+    pub fn build(&self, contour_provider: Arc<ContourProvider>) -> SavedPath {
+        let spec = &self.spec;
+
+        let mut builder =
+            PathBuilder::new(&self.name, &contour_provider, self.consts, spec.component)
+                .with_state(self.start.clone())
+                .with_excitation(spec.excitation);
+
+        if let Some(approach) = &spec.approach {
+            builder = builder.goto_points(approach.component, &approach.points);
+        }
+        if let Some(start) = spec.shape.start() {
+            builder = builder.goto(spec.component, start, 15);
+        }
+
+        spec.shape.draw(builder).build()
+    }
+}
+
+// Read the paths in a .ron or .toml file. When a list of coupling constants is
+// given each path is built for all of them, instead of for the coupling
+// constants in the file.
+pub fn load_definitions(
+    filename: &std::path::Path,
+    consts_list: &[CouplingConstants],
+) -> Result<Vec<PathDefinition>> {
+    let s = std::fs::read_to_string(filename)?;
+    let file: DefinitionFile = match filename.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&s).map_err(|err| error(&format!("{err}")))?,
+        _ => ron::from_str(&s).map_err(|err| error(&format!("{err}")))?,
+    };
+
+    let mut definitions = vec![];
+    for spec in file.paths {
+        let variants = if consts_list.is_empty() {
+            vec![(spec.name.clone(), CouplingConstants::new(spec.h, spec.k))]
+        } else if consts_list.len() == 1 {
+            vec![(spec.name.clone(), consts_list[0])]
+        } else {
+            consts_list
+                .iter()
+                .map(|consts| {
+                    let name = format!("{} (h = {}, k = {})", spec.name, consts.h, consts.k());
+                    (name, *consts)
+                })
+                .collect()
+        };
+        for (name, consts) in variants {
+            let declared = DeclaredPath::new(spec.clone(), name, consts)?;
+            definitions.push(PathDefinition::Declared(Arc::new(declared)));
+        }
+    }
+    Ok(definitions)
+}
@@ -0,0 +1,98 @@
+use num::complex::Complex64;
+use pxu::kinematics::CouplingConstants;
+use pxu::Component;
+
+// The sampled values of the paths, for use outside of rust
+const COMPONENTS: [(&str, Component); 4] = [
+    ("p", Component::P),
+    ("xp", Component::Xp),
+    ("xm", Component::Xm),
+    ("u", Component::U),
+];
+
+fn quote_csv(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+// One row per sample of each component, with the real and imaginary parts in
+// separate columns. The components are simplified separately, so they do not
+// have the same number of samples.
+pub fn export_csv(paths: &[(CouplingConstants, &pxu::Path)]) -> String {
+    let header = [
+        "path",
+        "h",
+        "k",
+        "excitation",
+        "segment",
+        "component",
+        "sample",
+        "re",
+        "im",
+    ];
+
+    let mut lines = vec![header.join(",")];
+    for (consts, path) in paths {
+        for (excitation, segments) in path.segments.iter().enumerate() {
+            for (segment_index, segment) in segments.iter().enumerate() {
+                for (name, component) in COMPONENTS {
+                    for (sample, z) in segment.get(component).iter().enumerate() {
+                        let row = [
+                            quote_csv(&path.name),
+                            format!("{}", consts.h),
+                            format!("{}", consts.k()),
+                            format!("{excitation}"),
+                            format!("{segment_index}"),
+                            name.to_string(),
+                            format!("{sample}"),
+                            format!("{}", z.re),
+                            format!("{}", z.im),
+                        ];
+                        lines.push(row.join(","));
+                    }
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+fn json_points(points: &[Complex64]) -> serde_json::Value {
+    points
+        .iter()
+        .map(|z| serde_json::json!([z.re, z.im]))
+        .collect()
+}
+
+// A list of paths, each with a list of segments for each excitation. The
+// values are given as pairs of the real and imaginary parts.
+pub fn export_json(paths: &[(CouplingConstants, &pxu::Path)]) -> String {
+    let paths = paths
+        .iter()
+        .map(|(consts, path)| {
+            let excitations = path
+                .segments
+                .iter()
+                .map(|segments| {
+                    segments
+                        .iter()
+                        .map(|segment| {
+                            COMPONENTS
+                                .iter()
+                                .map(|(name, component)| {
+                                    (name.to_string(), json_points(&segment.get(*component)))
+                                })
+                                .collect::<serde_json::Map<_, _>>()
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({
+                "name": path.name,
+                "h": consts.h,
+                "k": consts.k(),
+                "excitations": excitations,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&paths).unwrap_or_default()
+}
@@ -1,10 +1,224 @@
+mod builder;
+mod declarative;
+mod export;
 mod path_provider;
 mod paths;
+mod preview;
 mod provider;
+mod variant;
 
+pub use builder::PathBuilder;
+pub use declarative::load_definitions;
+pub use declarative::DeclaredPath;
+pub use export::export_csv;
+pub use export::export_json;
+pub use path_provider::load_failed_paths;
+pub use path_provider::matches_pattern;
+pub use path_provider::FailedPath;
+pub use path_provider::Verification;
+pub use preview::Previewer;
 pub use provider::ContourProvider;
+pub use provider::ProviderUsage;
 pub use provider::PxuProvider;
+pub use variant::Variant;
 
-pub type PathFunction = fn(std::sync::Arc<ContourProvider>) -> pxu::path::SavedPath;
+use pxu::kinematics::CouplingConstants;
+
+pub type PathFunction =
+    fn(std::sync::Arc<ContourProvider>, CouplingConstants) -> pxu::path::SavedPath;
+// A path function continuing from the end state of another path, which is
+// built for the coupling constants of that path
+pub type ContinuedPathFunction =
+    fn(std::sync::Arc<ContourProvider>, CouplingConstants, pxu::State) -> pxu::path::SavedPath;
+// A path function for one member of a family, given its index and, for all but
+// the first member of a chained family, the end state of the previous member
+pub type FamilyPathFunction = fn(
+    std::sync::Arc<ContourProvider>,
+    CouplingConstants,
+    Option<pxu::State>,
+    i32,
+) -> pxu::path::SavedPath;
+
+// The coupling constants a built-in path is made for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathConsts {
+    // The path only works for these coupling constants, e.g. because it
+    // starts from a saved state
+    Fixed(CouplingConstants),
+    // The path can be made for any coupling constants, and uses these unless
+    // others are given
+    Default(CouplingConstants),
+}
+
+impl PathConsts {
+    pub fn get(&self) -> CouplingConstants {
+        match self {
+            Self::Fixed(consts) | Self::Default(consts) => *consts,
+        }
+    }
+}
+
+// A family of paths built by the same function for each of the indices
+pub struct PathFamily {
+    // The names of the paths, where {n} is replaced by the index and {n+1}
+    // and {n-1} by the indices next to it
+    pub name: &'static str,
+    pub indices: &'static [i32],
+    // Whether each path starts from the end of the previous path
+    pub chained: bool,
+    pub path_func: FamilyPathFunction,
+}
+
+impl PathFamily {
+    pub fn name(&self, index: i32) -> String {
+        let format = |n: i32| {
+            if n > 0 {
+                format!("+{n}")
+            } else {
+                format!("{n}")
+            }
+        };
+        self.name
+            .replace("{n+1}", &format(index + 1))
+            .replace("{n-1}", &format(index - 1))
+            .replace("{n}", &format(index))
+    }
+}
+
+#[derive(Clone)]
+pub enum PathDefinition {
+    Start(PathFunction, PathConsts),
+    // A path starting from the end state of the named path
+    Continue(&'static str, ContinuedPathFunction),
+    // A path read from a definition file
+    Declared(std::sync::Arc<DeclaredPath>),
+    // A variant of the named path
+    Variant(&'static str, Variant),
+    // A path for each index of the family
+    Family(&'static PathFamily, PathConsts),
+    // The path with the given position in the indices of the family, which is
+    // what a family is expanded into before the paths are generated
+    FamilyMember(&'static PathFamily, usize, CouplingConstants),
+    // The path with the suffix added to its name and to the name of the path
+    // it continues
+    Suffixed(Box<PathDefinition>, String),
+}
+
+impl PathDefinition {
+    pub fn after(&self) -> Option<String> {
+        match self {
+            Self::Start(_, _) | Self::Declared(_) | Self::Family(_, _) => None,
+            Self::Continue(name, _) | Self::Variant(name, _) => Some(name.to_string()),
+            Self::FamilyMember(family, position, _) => {
+                if family.chained && *position > 0 {
+                    Some(family.name(family.indices[position - 1]))
+                } else {
+                    None
+                }
+            }
+            Self::Suffixed(path_def, suffix) => path_def.after().map(|name| name + suffix),
+        }
+    }
+
+    // The name of the path, when it is known before the path is built
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Self::Start(_, _) | Self::Continue(_, _) | Self::Family(_, _) => None,
+            Self::Declared(declared) => Some(declared.name().to_owned()),
+            Self::Variant(name, variant) => Some(variant.name(name)),
+            Self::FamilyMember(family, position, _) => Some(family.name(family.indices[*position])),
+            Self::Suffixed(path_def, suffix) => path_def.name().map(|name| name + suffix),
+        }
+    }
+
+    // The coupling constants of the path, unless they are taken from the path
+    // it continues
+    pub fn consts(&self) -> Option<CouplingConstants> {
+        match self {
+            Self::Start(_, consts) | Self::Family(_, consts) => Some(consts.get()),
+            Self::FamilyMember(_, _, consts) => Some(*consts),
+            Self::Declared(declared) => Some(declared.consts()),
+            Self::Continue(_, _) | Self::Variant(_, _) => None,
+            Self::Suffixed(path_def, _) => path_def.consts(),
+        }
+    }
+
+    pub fn expand(&self) -> Vec<Self> {
+        match self {
+            Self::Family(family, consts) => (0..family.indices.len())
+                .map(|position| Self::FamilyMember(family, position, consts.get()))
+                .collect(),
+            Self::Suffixed(path_def, suffix) => path_def
+                .expand()
+                .into_iter()
+                .map(|path_def| Self::Suffixed(Box::new(path_def), suffix.clone()))
+                .collect(),
+            _ => vec![self.clone()],
+        }
+    }
+
+    // The path for each of the given coupling constants, with the coupling
+    // constants added to the names when there is more than one. Paths with
+    // fixed coupling constants, and those continuing them, are kept as they are.
+    pub fn for_consts(&self, consts_list: &[CouplingConstants]) -> Vec<Self> {
+        let with_consts = |consts: CouplingConstants| match self {
+            Self::Start(path_func, PathConsts::Default(_)) => {
+                Some(Self::Start(*path_func, PathConsts::Default(consts)))
+            }
+            Self::Family(family, PathConsts::Default(_)) => {
+                Some(Self::Family(family, PathConsts::Default(consts)))
+            }
+            _ => None,
+        };
+        if consts_list.is_empty() || with_consts(consts_list[0]).is_none() {
+            return vec![self.clone()];
+        }
+        consts_list
+            .iter()
+            .filter_map(|consts| {
+                let path_def = with_consts(*consts)?;
+                if consts_list.len() > 1 {
+                    let suffix = format!(" (h = {}, k = {})", consts.h, consts.k());
+                    Some(Self::Suffixed(Box::new(path_def), suffix))
+                } else {
+                    Some(path_def)
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn build(
+        self,
+        contour_provider: std::sync::Arc<ContourProvider>,
+        continue_from: Option<pxu::State>,
+        base_path: Option<pxu::path::SavedPath>,
+    ) -> pxu::path::SavedPath {
+        match self {
+            Self::Start(path_func, consts) => path_func(contour_provider, consts.get()),
+            Self::Continue(_, path_func) => path_func(
+                contour_provider,
+                base_path.unwrap().consts,
+                continue_from.unwrap(),
+            ),
+            Self::Declared(declared) => declared.build(contour_provider),
+            Self::Variant(_, variant) => variant.build(base_path.unwrap(), continue_from.unwrap()),
+            Self::FamilyMember(family, position, consts) => {
+                let index = family.indices[position];
+                let mut saved_path =
+                    (family.path_func)(contour_provider, consts, continue_from, index);
+                saved_path.name = family.name(index);
+                saved_path
+            }
+            Self::Family(_, _) => {
+                unreachable!("Families are expanded before the paths are generated")
+            }
+            Self::Suffixed(path_def, suffix) => {
+                let mut saved_path = path_def.build(contour_provider, continue_from, base_path);
+                saved_path.name += &suffix;
+                saved_path
+            }
+        }
+    }
+}
 pub use paths::INTERACTIVE_PATHS;
 pub use paths::PLOT_PATHS;
@@ -1,61 +1,399 @@
-use std::sync::Arc;
-
 use clap::Parser;
 use indicatif::ProgressStyle;
-use make_paths::ContourProvider;
+use make_paths::PxuProvider;
 use pxu::kinematics::CouplingConstants;
 
+const PATH_CACHE_DIR: &str = ".cache";
+
+#[derive(clap::ValueEnum, Clone, Copy)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+
+    fn export(&self, paths: &[(CouplingConstants, &pxu::Path)]) -> String {
+        match self {
+            Self::Csv => make_paths::export_csv(paths),
+            Self::Json => make_paths::export_json(paths),
+        }
+    }
+}
+
 #[derive(Parser, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Settings {
     #[arg(short, long)]
     compressed: bool,
+    #[arg(short, long, conflicts_with = "compressed")]
+    binary: bool,
+    /// Write each path to its own file in this directory instead of writing
+    /// all paths to stdout
+    #[arg(short, long = "output-dir", value_name = "DIR")]
+    output_dir: Option<std::path::PathBuf>,
+    /// Also draw each path in each plane to a small PNG image in this directory
+    #[arg(long, value_name = "DIR")]
+    preview: Option<std::path::PathBuf>,
+    /// Write the sampled values of p, x⁺, x⁻ and u of the paths instead of
+    /// the saved paths
+    #[arg(short, long, value_name = "FORMAT", conflicts_with_all = ["compressed", "binary"])]
+    export: Option<ExportFormat>,
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
-    path_number: Option<usize>,
+    /// The number of threads, by default one for each CPU
+    #[arg(short, long)]
+    jobs: Option<usize>,
+    /// Also build the paths defined in a .ron or .toml file
+    #[arg(short, long = "definitions", value_name = "FILE")]
+    definitions: Vec<std::path::PathBuf>,
+    /// Build the paths for these coupling constants, given as h,k, instead of
+    /// their own. Built-in paths starting from a saved state keep theirs.
+    #[arg(long = "consts", value_name = "H,K", value_parser = parse_consts)]
+    consts: Vec<CouplingConstants>,
+    /// List the names of the paths instead of generating them
+    #[arg(short, long)]
+    list: bool,
+    /// Regenerate the paths and compare them to the cached paths
+    #[arg(long, conflicts_with = "list")]
+    verify: bool,
+    /// The largest distance a point may move without being reported by --verify
+    #[arg(long, default_value_t = 1.0e-6, requires = "verify")]
+    tolerance: f64,
+    /// Follow the paths with a predictor-corrector method instead of
+    /// Newton-Raphson from nearby guesses
+    #[arg(long)]
+    predictor_corrector: bool,
+    /// Only generate the paths that failed in the last run
+    #[arg(long, conflicts_with_all = ["list", "patterns"])]
+    resume: bool,
+    /// Only generate the paths with names matching one of these patterns,
+    /// where * matches any text and ? any single character
+    #[arg(value_name = "PATTERN")]
+    patterns: Vec<String>,
+}
+
+fn parse_consts(s: &str) -> Result<CouplingConstants, String> {
+    let (h, k) = s
+        .split_once(',')
+        .ok_or_else(|| format!("Expected h,k but got {s}"))?;
+    let h = h
+        .trim()
+        .parse::<f64>()
+        .map_err(|err| format!("Could not read h: {err}"))?;
+    let k = k
+        .trim()
+        .parse::<i32>()
+        .map_err(|err| format!("Could not read k: {err}"))?;
+    if !(0.1..=10.0).contains(&h) || !(0..=10).contains(&k) {
+        return Err(format!(
+            "Can not use the coupling constants h = {h}, k = {k}"
+        ));
+    }
+    Ok(CouplingConstants::new(h, k))
+}
+
+// The name of the file for a single path, where the characters that are not
+// safe in file names are replaced by _
+fn path_filename(name: &str, extension: &str) -> String {
+    let stem = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("{stem}.{extension}")
+}
+
+fn save_paths_to_dir(
+    saved_paths: &[pxu::path::SavedPath],
+    dirname: &std::path::Path,
+    settings: &Settings,
+) -> std::io::Result<()> {
+    let error = |msg: &str| std::io::Error::new(std::io::ErrorKind::Other, msg);
+
+    std::fs::create_dir_all(dirname)?;
+
+    let extension = if settings.binary {
+        "bin"
+    } else if settings.compressed {
+        "ron.b64"
+    } else {
+        "ron"
+    };
+
+    let mut filenames = std::collections::HashMap::new();
+    for saved_path in saved_paths {
+        let filename = path_filename(&saved_path.name, extension);
+        if let Some(other) = filenames.insert(filename.clone(), &saved_path.name) {
+            return Err(error(&format!(
+                "\"{}\" and \"{other}\" would both be saved to {filename}",
+                saved_path.name
+            )));
+        }
+
+        let paths = vec![saved_path.clone()];
+        let bytes = if settings.binary {
+            pxu::path::SavedPath::save_binary(&paths)
+        } else if settings.compressed {
+            pxu::path::SavedPath::save_compressed(&paths).map(String::into_bytes)
+        } else {
+            pxu::path::SavedPath::save(&paths).map(String::into_bytes)
+        }
+        .ok_or_else(|| error(&format!("Could not save \"{}\"", saved_path.name)))?;
+
+        std::fs::write(dirname.join(filename), bytes)?;
+    }
+    Ok(())
+}
+
+fn export_paths(
+    pxu_provider: &PxuProvider,
+    saved_paths: &[pxu::path::SavedPath],
+    format: ExportFormat,
+    output_dir: Option<&std::path::Path>,
+) -> std::io::Result<()> {
+    let paths = saved_paths
+        .iter()
+        .map(|saved_path| {
+            let path = pxu_provider.get_path(&saved_path.name)?;
+            Ok((saved_path.consts, path))
+        })
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    if let Some(dirname) = output_dir {
+        std::fs::create_dir_all(dirname)?;
+        for (consts, path) in paths.iter() {
+            let filename = path_filename(&path.name, format.extension());
+            std::fs::write(dirname.join(filename), format.export(&[(*consts, path)]))?;
+        }
+    } else {
+        let paths = paths
+            .iter()
+            .map(|(consts, path)| (*consts, path.as_ref()))
+            .collect::<Vec<_>>();
+        println!("{}", format.export(&paths));
+    }
+    Ok(())
+}
+
+fn save_previews(
+    pxu_provider: &PxuProvider,
+    saved_paths: &[pxu::path::SavedPath],
+    dirname: &std::path::Path,
+) -> std::io::Result<()> {
+    const PLANES: [(&str, pxu::Component); 4] = [
+        ("p", pxu::Component::P),
+        ("xp", pxu::Component::Xp),
+        ("xm", pxu::Component::Xm),
+        ("u", pxu::Component::U),
+    ];
+
+    std::fs::create_dir_all(dirname)?;
+    let mut previewer = make_paths::Previewer::new(pxu_provider);
+    let components = PLANES.map(|(_, component)| component);
+    for saved_path in saved_paths {
+        let images = previewer.preview_png(saved_path, &components)?;
+        for ((name, _), bytes) in PLANES.iter().zip(images) {
+            let filename = path_filename(&format!("{} {name}", saved_path.name), "png");
+            std::fs::write(dirname.join(filename), bytes)?;
+        }
+    }
+    Ok(())
 }
 
 fn main() -> std::io::Result<()> {
     let settings = Settings::parse();
+    let verbose = settings.verbose > 0;
 
-    let pool = threadpool::ThreadPool::new(5);
+    let start = std::time::Instant::now();
+
+    let num_threads = if let Some(jobs) = settings.jobs {
+        jobs
+    } else {
+        num_cpus::get()
+    };
+
+    let pool = threadpool::ThreadPool::new(num_threads);
 
     let spinner_style = ProgressStyle::with_template(
         "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}",
     )
     .unwrap();
+    let spinner_style_no_progress =
+        ProgressStyle::with_template("[{elapsed_precise}] {spinner} {msg}")
+            .unwrap()
+            .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏");
 
-    let consts_list = vec![
-        CouplingConstants::new(2.0, 5),
-        CouplingConstants::new(1.0, 7),
-    ];
+    let mut paths = make_paths::INTERACTIVE_PATHS
+        .iter()
+        .flat_map(|path_def| path_def.for_consts(&settings.consts))
+        .collect::<Vec<_>>();
+    for filename in settings.definitions.iter() {
+        match make_paths::load_definitions(filename, &settings.consts) {
+            Ok(definitions) => paths.extend(definitions),
+            Err(err) => {
+                eprintln!("Could not load {}: {err}", filename.display());
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut consts_list = vec![];
+    for consts in paths.iter().filter_map(make_paths::PathDefinition::consts) {
+        if !consts_list.contains(&consts) {
+            consts_list.push(consts);
+        }
+    }
 
     eprintln!("[1/3] Generating contours");
-    let mut contour_provider = ContourProvider::default();
-    contour_provider.generate(consts_list, false, &pool, &spinner_style);
+    let mut pxu_provider = PxuProvider::new();
+    pxu_provider.generate_contours(consts_list, verbose, &pool, &spinner_style);
 
-    let contour_provider = Arc::new(contour_provider);
+    // The names are only known once the path functions have been run, so the
+    // paths are listed by generating none of them
+    if settings.list {
+        pxu_provider.select_paths(vec![]);
+    } else if settings.resume {
+        let failed = make_paths::load_failed_paths(PATH_CACHE_DIR).unwrap_or_default();
+        if failed.is_empty() {
+            eprintln!("No paths failed in the last run");
+            return Ok(());
+        }
+        pxu_provider.select_paths(failed);
+    } else if !settings.patterns.is_empty() {
+        pxu_provider.select_paths(settings.patterns.clone());
+    }
+    if settings.verify {
+        pxu_provider.verify_paths(settings.tolerance);
+    }
+    if settings.predictor_corrector {
+        pxu_provider.set_continuation_method(pxu::ContinuationMethod::PredictorCorrector);
+    }
 
     eprintln!("[2/3] Generating paths");
-    let saved_paths = make_paths::INTERACTIVE_PATHS
-        .iter()
-        .map(|f| f(contour_provider.clone()))
-        .collect::<Vec<_>>();
+    pxu_provider.load_paths(
+        &paths,
+        verbose,
+        &pool,
+        PATH_CACHE_DIR,
+        &spinner_style,
+        &spinner_style_no_progress,
+    );
+
+    if settings.list {
+        for name in pxu_provider.get_path_names() {
+            if settings.patterns.is_empty()
+                || settings
+                    .patterns
+                    .iter()
+                    .any(|pattern| make_paths::matches_pattern(pattern, name))
+            {
+                println!("{name}");
+            }
+        }
+        return Ok(());
+    }
+
+    if settings.verify {
+        let mut drifted = 0;
+        for (name, verification) in pxu_provider.get_verification() {
+            match verification {
+                make_paths::Verification::Unchanged => {}
+                make_paths::Verification::NotCached => {
+                    println!("\"{name}\" is not in the cache");
+                }
+                make_paths::Verification::Drifted(message) => {
+                    println!("\"{name}\" has changed: {message}");
+                    drifted += 1;
+                }
+            }
+        }
+        println!(
+            "{drifted} of {} paths have changed",
+            pxu_provider.get_verification().len()
+        );
+        if drifted > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let failures = pxu_provider.get_failures();
+    for failure in failures {
+        match &failure.name {
+            Some(name) => eprintln!("Could not generate \"{name}\": {}", failure.message),
+            None => eprintln!("Could not generate a path: {}", failure.message),
+        }
+    }
+
+    let saved_paths = pxu_provider.get_saved_paths().to_vec();
+    if saved_paths.is_empty() && failures.is_empty() {
+        eprintln!("No paths matched {}", settings.patterns.join(", "));
+        std::process::exit(1);
+    }
 
     eprintln!("[3/3] Saving paths");
 
-    let result = if settings.compressed {
-        pxu::path::SavedPath::save_compressed(&saved_paths)
+    if let Some(format) = settings.export {
+        export_paths(
+            &pxu_provider,
+            &saved_paths,
+            format,
+            settings.output_dir.as_deref(),
+        )?;
+    } else if let Some(dirname) = &settings.output_dir {
+        save_paths_to_dir(&saved_paths, dirname, &settings)?;
+    } else if settings.binary {
+        use std::io::Write;
+        let bytes = pxu::path::SavedPath::save_binary(&saved_paths).unwrap();
+        std::io::stdout().write_all(&bytes)?;
     } else {
-        pxu::path::SavedPath::save(&saved_paths)
+        let result = if settings.compressed {
+            pxu::path::SavedPath::save_compressed(&saved_paths)
+        } else {
+            pxu::path::SavedPath::save(&saved_paths)
+        }
+        .unwrap();
+        println!("{result}");
+    }
+
+    if let Some(dirname) = &settings.preview {
+        eprintln!("Drawing previews");
+        save_previews(&pxu_provider, &saved_paths, dirname)?;
     }
-    .unwrap();
-    println!("{result}");
 
     eprintln!();
-    eprintln!("Built {} paths", make_paths::INTERACTIVE_PATHS.len());
+    let seconds = start.elapsed().as_secs();
+    let minutes = seconds / 60;
+    let seconds = seconds - 60 * minutes;
+    eprintln!(
+        "Built {} paths in {minutes}:{seconds:02}",
+        saved_paths.len()
+    );
+    eprintln!();
+    eprintln!("{}", pxu_provider.get_path_statistics_table());
     eprintln!();
-    eprintln!("{}", contour_provider.get_statistics());
+    eprintln!("{}", pxu_provider.get_statistics());
+
+    if !failures.is_empty() {
+        eprintln!();
+        eprintln!(
+            "{} paths could not be generated, run again with --resume to generate only them",
+            failures.len()
+        );
+        std::process::exit(1);
+    }
 
     Ok(())
 }
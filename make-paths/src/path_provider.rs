@@ -12,6 +12,188 @@ pub struct PathProvider {
     paths: HashMap<String, Arc<pxu::Path>>,
     starts: HashMap<String, Arc<pxu::State>>,
     seen_paths: Arc<Mutex<HashSet<String>>>,
+    saved_paths: Vec<pxu::path::SavedPath>,
+    path_names: Vec<String>,
+    // Patterns for the names of the paths to generate, all paths are
+    // generated when this is None
+    selection: Option<Arc<Vec<String>>>,
+    // When set, the paths are always regenerated and compared to the cached
+    // paths with this tolerance
+    verify_tolerance: Option<f64>,
+    method: pxu::ContinuationMethod,
+    verification: Vec<(String, Verification)>,
+    statistics: Vec<PathStatistics>,
+    failures: Vec<FailedPath>,
+}
+
+// A path that could not be generated. The name is None when the path function
+// itself failed before giving the path a name.
+pub struct FailedPath {
+    pub name: Option<String>,
+    pub message: String,
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown error".to_owned()
+    }
+}
+
+// What a worker thread sends back for each path
+struct GeneratedPath {
+    index: usize,
+    // None when the path was neither selected nor needed by another path
+    path: Option<pxu::Path>,
+    saved_path: pxu::path::SavedPath,
+    start: pxu::State,
+    selected: bool,
+    verification: Option<Verification>,
+    seconds: Option<f64>,
+}
+
+// A row of the report printed after the paths have been generated
+struct PathStatistics {
+    name: String,
+    component: pxu::Component,
+    excitations: usize,
+    segments: usize,
+    samples: usize,
+    // The cuts crossed by the excitation that is moved, in order
+    crossings: Vec<&'static str>,
+    // The time to generate the path, or None when it was taken from the cache
+    seconds: Option<f64>,
+}
+
+// The cut crossed between two segments, named after what changes. Segments are
+// also split where no cut is crossed.
+fn crossed_cut(
+    before: &pxu::kinematics::SheetData,
+    after: &pxu::kinematics::SheetData,
+) -> Option<&'static str> {
+    if before.log_branch_p != after.log_branch_p || before.log_branch_m != after.log_branch_m {
+        Some("log")
+    } else if before.e_branch != after.e_branch {
+        Some("E")
+    } else if before.u_branch.0 != after.u_branch.0 {
+        Some("u+")
+    } else if before.u_branch.1 != after.u_branch.1 {
+        Some("u-")
+    } else if before.im_x_sign != after.im_x_sign {
+        Some("x")
+    } else {
+        None
+    }
+}
+
+impl PathStatistics {
+    fn new(path: &pxu::Path, saved_path: &pxu::path::SavedPath, seconds: Option<f64>) -> Self {
+        let crossings = path
+            .segments
+            .get(saved_path.excitation)
+            .map(|segments| {
+                segments
+                    .windows(2)
+                    .filter_map(|pair| crossed_cut(&pair[0].sheet_data, &pair[1].sheet_data))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            name: path.name.clone(),
+            component: saved_path.component,
+            excitations: path.segments.len(),
+            segments: path.segments.iter().map(|segments| segments.len()).sum(),
+            samples: path
+                .segments
+                .iter()
+                .flatten()
+                .map(|segment| segment.p.len())
+                .sum(),
+            crossings,
+            seconds,
+        }
+    }
+}
+
+pub enum Verification {
+    Unchanged,
+    NotCached,
+    Drifted(String),
+}
+
+// The largest distance between corresponding points of two paths, or how they
+// differ when they do not have the same number of segments and points
+fn path_distance(path: &pxu::Path, reference: &pxu::Path) -> std::result::Result<f64, String> {
+    if path.segments.len() != reference.segments.len() {
+        return Err(format!(
+            "{} excitations instead of {}",
+            path.segments.len(),
+            reference.segments.len()
+        ));
+    }
+
+    let mut distance: f64 = 0.0;
+    for (i, (segments, reference_segments)) in
+        path.segments.iter().zip(&reference.segments).enumerate()
+    {
+        if segments.len() != reference_segments.len() {
+            return Err(format!(
+                "excitation {i} has {} segments instead of {}",
+                segments.len(),
+                reference_segments.len()
+            ));
+        }
+        for (j, (segment, reference)) in segments.iter().zip(reference_segments).enumerate() {
+            if segment.sheet_data != reference.sheet_data {
+                return Err(format!(
+                    "segment {j} of excitation {i} is on a different sheet"
+                ));
+            }
+            for (zs, reference_zs) in [
+                (&segment.p, &reference.p),
+                (&segment.xp, &reference.xp),
+                (&segment.xm, &reference.xm),
+                (&segment.u, &reference.u),
+                (&segment.x, &reference.x),
+                (&segment.e, &reference.e),
+            ] {
+                if zs.len() != reference_zs.len() {
+                    return Err(format!(
+                        "segment {j} of excitation {i} has {} points instead of {}",
+                        zs.len(),
+                        reference_zs.len()
+                    ));
+                }
+                distance = zs
+                    .iter()
+                    .zip(reference_zs)
+                    .map(|(z, w)| (z - w).norm())
+                    .fold(distance, f64::max);
+            }
+        }
+    }
+    Ok(distance)
+}
+
+// Match a name against a pattern where * matches any text and ? any character
+fn glob_match(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| glob_match(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && glob_match(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && glob_match(rest, &name[1..]),
+    }
+}
+
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    glob_match(
+        &pattern.chars().collect::<Vec<_>>(),
+        &name.chars().collect::<Vec<_>>(),
+    )
 }
 
 impl PathProvider {
@@ -38,6 +220,71 @@ impl PathProvider {
             .ok_or_else(|| error(&format!("Could not find start for {name}")))
     }
 
+    // The saved paths in the order they were defined
+    pub fn get_saved_paths(&self) -> &[pxu::path::SavedPath] {
+        &self.saved_paths
+    }
+
+    // The names of all defined paths, including those that were not selected
+    pub fn get_path_names(&self) -> &[String] {
+        &self.path_names
+    }
+
+    pub fn select(&mut self, patterns: Vec<String>) {
+        self.selection = Some(Arc::new(patterns));
+    }
+
+    pub fn verify(&mut self, tolerance: f64) {
+        self.verify_tolerance = Some(tolerance);
+    }
+
+    pub fn set_method(&mut self, method: pxu::ContinuationMethod) {
+        self.method = method;
+    }
+
+    // A table of the generated paths, in the order they were defined
+    pub fn get_statistics_table(&self) -> String {
+        let width = self
+            .statistics
+            .iter()
+            .map(|row| row.name.chars().count())
+            .max()
+            .unwrap_or_default()
+            .max(4);
+
+        let mut lines = vec![format!(
+            "{:width$}  {:>9}  {:>3}  {:>8}  {:>8}  {:>7}  Crossed cuts",
+            "Path", "Component", "M", "Segments", "Samples", "Time"
+        )];
+        for row in self.statistics.iter() {
+            let time = row
+                .seconds
+                .map_or_else(|| "cached".to_owned(), |seconds| format!("{seconds:.1}s"));
+            lines.push(format!(
+                "{:width$}  {:>9}  {:>3}  {:>8}  {:>8}  {:>7}  {}",
+                row.name,
+                row.component.to_string(),
+                row.excitations,
+                row.segments,
+                row.samples,
+                time,
+                row.crossings.join(" ")
+            ));
+        }
+        lines.join("\n")
+    }
+
+    // How each generated path compares to the cached path, in the order they
+    // were defined
+    pub fn get_verification(&self) -> &[(String, Verification)] {
+        &self.verification
+    }
+
+    // The paths that could not be generated, in the order they were defined
+    pub fn get_failures(&self) -> &[FailedPath] {
+        &self.failures
+    }
+
     pub fn get_statistics(&self) -> String {
         let unused_paths = {
             let seen_paths = &self.seen_paths.lock().unwrap();
@@ -63,13 +310,26 @@ impl PathProvider {
     }
 }
 
+// The cache is keyed on a hash of everything the path depends on, so that an
+// entry is never used for a path generated with other contours or by other
+// sources of pxu and make-paths
 #[derive(serde::Serialize, serde::Deserialize)]
 struct CacheEntry {
+    name: String,
     path_string: String,
     saved_path_string: String,
 }
 
 const CACHE_FILENAME: &str = "path-cache";
+const FAILED_FILENAME: &str = "failed-paths";
+
+fn cache_key(saved_path_string: &str, contour_hash: &str) -> String {
+    let key = format!(
+        "{}\n{contour_hash}\n{saved_path_string}",
+        env!("SOURCE_DIGEST")
+    );
+    format!("{:x}", md5::compute(key))
+}
 
 fn load_cache(dirname: &str) -> Result<HashMap<String, CacheEntry>> {
     let path = std::path::PathBuf::from(dirname).join(CACHE_FILENAME);
@@ -84,11 +344,50 @@ fn save_cache(cache: HashMap<String, CacheEntry>, dirname: &str) -> Result<()> {
     std::fs::write(path, s)
 }
 
+// The paths that failed in the last run, as patterns to select them with. A
+// path without a name can only be selected by selecting all paths, which is
+// cheap for the paths that are in the cache.
+pub fn load_failed_paths(dirname: &str) -> Result<Vec<String>> {
+    let path = std::path::PathBuf::from(dirname).join(FAILED_FILENAME);
+    let s = std::fs::read_to_string(path)?;
+    ron::from_str(&s).map_err(|err| error(&format!("{err}")))
+}
+
+// The failures of the paths that were not selected this time are kept
+fn save_failed_paths(
+    failures: &[FailedPath],
+    selection: Option<&[String]>,
+    dirname: &str,
+) -> Result<()> {
+    let mut patterns = if let Some(selection) = selection {
+        load_failed_paths(dirname)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|name| {
+                !selection
+                    .iter()
+                    .any(|pattern| matches_pattern(pattern, name))
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+    patterns.extend(
+        failures
+            .iter()
+            .map(|failure| failure.name.clone().unwrap_or_else(|| "*".to_owned())),
+    );
+    patterns.dedup();
+    let s = ron::to_string(&patterns).map_err(|err| error(&format!("{err}")))?;
+    let path = std::path::PathBuf::from(dirname).join(FAILED_FILENAME);
+    std::fs::write(path, s)
+}
+
 #[allow(clippy::too_many_arguments)]
 impl PathProvider {
     pub fn load(
         &mut self,
-        paths: &[crate::PathFunction],
+        paths: &[crate::PathDefinition],
         contour_provider: Arc<ContourProvider>,
         verbose: bool,
         pool: &threadpool::ThreadPool,
@@ -114,92 +413,320 @@ impl PathProvider {
         };
 
         pb.set_style(spinner_style.clone());
+        let paths = paths
+            .iter()
+            .flat_map(crate::PathDefinition::expand)
+            .collect::<Vec<_>>();
         pb.set_length(paths.len() as u64);
 
         let (tx, rx) = std::sync::mpsc::channel();
+        // The latest entry for each name, which is the reference when verifying
+        let cache_by_name = cache
+            .iter()
+            .map(|(key, entry)| (entry.name.clone(), key.clone()))
+            .collect::<HashMap<_, _>>();
         let cache = Arc::new(cache);
+        let cache_by_name = Arc::new(cache_by_name);
+
+        // Paths that are continued by another path are generated even when they
+        // are not selected
+        let needed = Arc::new(
+            paths
+                .iter()
+                .filter_map(|path_def| path_def.after())
+                .collect::<HashSet<_>>(),
+        );
+
+        // A path continuing another path is generated in a later round than
+        // the path it continues, once the end state of that path is known
+        let mut pending = paths.into_iter().enumerate().collect::<Vec<_>>();
+        let mut end_states: HashMap<String, pxu::State> = HashMap::new();
+        let mut base_paths: HashMap<String, pxu::path::SavedPath> = HashMap::new();
+        let mut result = vec![];
+        let mut verifications = vec![];
+        let mut failures = vec![];
+
+        while !pending.is_empty() {
+            let (ready, waiting): (Vec<_>, Vec<_>) =
+                pending.into_iter().partition(|(_, path_def)| {
+                    path_def
+                        .after()
+                        .map_or(true, |name| end_states.contains_key(&name))
+                });
+
+            if ready.is_empty() {
+                for (index, path_def) in waiting {
+                    let message = format!(
+                        "could not find the path \"{}\" to continue",
+                        path_def.after().unwrap_or_default()
+                    );
+                    failures.push((
+                        index,
+                        FailedPath {
+                            name: path_def.name(),
+                            message,
+                        },
+                    ));
+                }
+                break;
+            }
+            pending = waiting;
+
+            let ready_len = ready.len();
+
+            for (index, path_def) in ready {
+                let tx = tx.clone();
+                let spinner_style = spinner_style_no_progress.clone();
+                let mb = mb.clone();
+                let contour_provider = contour_provider.clone();
+                let cache = cache.clone();
+                let cache_by_name = cache_by_name.clone();
+                let continue_from = path_def.after().map(|name| end_states[&name].clone());
+                let base_path = path_def.after().map(|name| base_paths[&name].clone());
+                let selection = self.selection.clone();
+                let needed = needed.clone();
+                let verify_tolerance = self.verify_tolerance;
+                let method = self.method;
+
+                pool.execute(move || {
+                    let pb = if !verbose {
+                        mb.add(ProgressBar::new(1))
+                    } else {
+                        ProgressBar::hidden()
+                    };
+                    pb.set_style(spinner_style);
+                    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+
+                    pb.set_message("Generating path");
+
+                    let name = path_def.name();
+                    let saved_path = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                        path_def.build(contour_provider.clone(), continue_from, base_path)
+                    }));
+                    let saved_path = match saved_path {
+                        Ok(saved_path) => saved_path,
+                        Err(payload) => {
+                            let message = panic_message(payload);
+                            tx.send(Err((index, FailedPath { name, message }))).unwrap();
+                            pb.finish_and_clear();
+                            return;
+                        }
+                    };
+                    let start = saved_path.start.clone();
+                    let consts = saved_path.consts;
+
+                    pb.set_message(saved_path.name.clone());
+                    pb.tick();
+
+                    let selected = selection.as_ref().map_or(true, |patterns| {
+                        patterns
+                            .iter()
+                            .any(|pattern| matches_pattern(pattern, &saved_path.name))
+                    });
+                    if !selected && !needed.contains(saved_path.name.as_str()) {
+                        tx.send(Ok(GeneratedPath {
+                            index,
+                            path: None,
+                            saved_path,
+                            start,
+                            selected: false,
+                            verification: None,
+                            seconds: None,
+                        }))
+                        .unwrap();
+                        pb.finish_and_clear();
+                        return;
+                    }
 
-        for path_func in paths {
-            let tx = tx.clone();
-            let spinner_style = spinner_style_no_progress.clone();
-            let mb = mb.clone();
-            let path_func = *path_func;
-            let contour_provider = contour_provider.clone();
-            let cache = cache.clone();
-
-            pool.execute(move || {
-                let pb = if !verbose {
-                    mb.add(ProgressBar::new(1))
-                } else {
-                    ProgressBar::hidden()
-                };
-                pb.set_style(spinner_style);
-                pb.enable_steady_tick(std::time::Duration::from_millis(100));
-
-                pb.set_message("Generating path");
-
-                let saved_path: pxu::path::SavedPath = path_func(contour_provider.clone());
-                let start = saved_path.start.clone();
-                let consts = saved_path.consts;
-
-                pb.set_message(saved_path.name.clone());
-                pb.tick();
-
-                let mut path = None;
+                    let mut cached_path: Option<pxu::Path> = None;
+
+                    // When verifying, the reference is the latest path with the same
+                    // name, even if it was generated by another version of the code
+                    let entry = if verify_tolerance.is_some() {
+                        cache_by_name
+                            .get(&saved_path.name)
+                            .and_then(|key| cache.get(key))
+                    } else {
+                        ron::to_string(&saved_path)
+                            .ok()
+                            .zip(contour_provider.get_hash(consts).ok())
+                            .and_then(|(s, hash)| cache.get(&cache_key(&s, hash)))
+                    };
+
+                    if let Some(entry) = entry {
+                        let cached_saved_path =
+                            ron::from_str::<pxu::path::SavedPath>(&entry.saved_path_string)
+                                .ok()
+                                .and_then(pxu::path::SavedPath::migrate);
+                        if cached_saved_path.as_ref() == Some(&saved_path) {
+                            cached_path = ron::from_str(&entry.path_string).ok()
+                        }
+                    }
 
-                if let Some(entry) = cache.get(&saved_path.name) {
-                    if let Ok(saved_path_string) = ron::to_string(&saved_path) {
-                        if saved_path_string == entry.saved_path_string {
-                            path = ron::from_str(&entry.path_string).ok()
+                    let mut path = if verify_tolerance.is_some() {
+                        None
+                    } else {
+                        cached_path.clone()
+                    };
+
+                    let mut seconds = None;
+                    if path.is_none() {
+                        let start_time = std::time::Instant::now();
+                        let generated = std::panic::catch_unwind(|| {
+                            pxu::path::Path::from_base_path_with_method(
+                                saved_path.clone().into(),
+                                &contour_provider.get(consts).unwrap(),
+                                consts,
+                                method,
+                            )
+                        });
+                        match generated {
+                            Ok(generated) => path = Some(generated),
+                            Err(payload) => {
+                                let failure = FailedPath {
+                                    name: Some(saved_path.name.clone()),
+                                    message: panic_message(payload),
+                                };
+                                tx.send(Err((index, failure))).unwrap();
+                                pb.finish_and_clear();
+                                return;
+                            }
+                        }
+                        let elapsed = start_time.elapsed().as_secs_f64();
+                        let message = format!("Generated \"{}\" in {elapsed:.1}s", saved_path.name);
+                        // Nothing is printed above hidden progress bars
+                        if mb.is_hidden() {
+                            eprintln!("{message}");
+                        } else {
+                            let _ = mb.println(message);
                         }
+                        seconds = Some(elapsed);
                     }
-                }
 
-                if path.is_none() {
-                    path = Some(pxu::path::Path::from_base_path(
-                        saved_path.clone().into(),
-                        &contour_provider.get(consts).unwrap(),
-                        consts,
+                    let verification = verify_tolerance.map(|tolerance| {
+                        let Some(cached_path) = cached_path else {
+                            return Verification::NotCached;
+                        };
+                        match path_distance(path.as_ref().unwrap(), &cached_path) {
+                            Ok(distance) if distance <= tolerance => Verification::Unchanged,
+                            Ok(distance) => Verification::Drifted(format!(
+                                "the points have moved by up to {distance:.3e}"
+                            )),
+                            Err(err) => Verification::Drifted(err),
+                        }
+                    });
+                    tx.send(Ok(GeneratedPath {
+                        index,
+                        path,
+                        saved_path,
+                        start,
+                        selected,
+                        verification,
+                        seconds,
+                    }))
+                    .unwrap();
+                    pb.finish_and_clear();
+                });
+            }
+
+            for message in rx.iter().take(ready_len) {
+                pb.inc(1);
+                let mut generated = match message {
+                    Ok(generated) => generated,
+                    Err(failure) => {
+                        failures.push(failure);
+                        continue;
+                    }
+                };
+                if let Some(verification) = generated.verification.take() {
+                    verifications.push((
+                        generated.index,
+                        generated.saved_path.name.clone(),
+                        verification,
                     ));
                 }
-                tx.send((path.unwrap(), saved_path, start)).unwrap();
-                pb.finish_and_clear();
-            });
+                let (Some(path), saved_path) = (&generated.path, &generated.saved_path) else {
+                    result.push(generated);
+                    continue;
+                };
+                if verbose {
+                    if let Some(monodromy) =
+                        path.monodromy(saved_path.component, saved_path.excitation)
+                    {
+                        eprintln!("Monodromy of \"{}\": {monodromy}", path.name);
+                    }
+                }
+                if let Some(end_state) = path.last_state() {
+                    end_states.insert(path.name.clone(), end_state);
+                    base_paths.insert(path.name.clone(), saved_path.clone());
+                }
+                result.push(generated);
+            }
         }
 
-        let result = rx
-            .into_iter()
-            .take(paths.len())
-            .map(|r: (pxu::Path, pxu::path::SavedPath, pxu::State)| {
-                pb.inc(1);
-                r
-            })
-            .collect::<Vec<_>>();
-
         pool.join();
         pb.finish_and_clear();
 
-        let mut cache: HashMap<String, CacheEntry> = Default::default();
+        result.sort_by_key(|generated| generated.index);
+        verifications.sort_by_key(|(index, _, _)| *index);
+        self.verification = verifications
+            .into_iter()
+            .map(|(_, name, verification)| (name, verification))
+            .collect();
+        failures.sort_by_key(|(index, _)| *index);
+        self.failures = failures.into_iter().map(|(_, failure)| failure).collect();
+
+        // Keep the cached paths that were not generated this time
+        drop(cache_by_name);
+        let mut cache: HashMap<String, CacheEntry> = if self.selection.is_some() {
+            Arc::try_unwrap(cache).unwrap_or_default()
+        } else {
+            Default::default()
+        };
 
-        for (path, saved_path, start) in result.iter() {
-            self.add(&path.name, path.clone(), start.clone());
+        for generated in result.iter() {
+            let saved_path = &generated.saved_path;
+            self.path_names.push(saved_path.name.clone());
+            let Some(path) = &generated.path else {
+                continue;
+            };
+            self.add(&path.name, path.clone(), generated.start.clone());
+            if generated.selected {
+                self.saved_paths.push(saved_path.clone());
+                self.statistics
+                    .push(PathStatistics::new(path, saved_path, generated.seconds));
+            }
             let Ok(path_string) = ron::to_string(&path) else {
                 continue;
             };
             let Ok(saved_path_string) = ron::to_string(&saved_path) else {
                 continue;
             };
+            let Ok(contour_hash) = contour_provider.get_hash(saved_path.consts) else {
+                continue;
+            };
+            cache.retain(|_, entry| entry.name != saved_path.name);
             cache.insert(
-                saved_path.name.clone(),
+                cache_key(&saved_path_string, contour_hash),
                 CacheEntry {
+                    name: saved_path.name.clone(),
                     path_string,
                     saved_path_string,
                 },
             );
         }
 
+        // The cache keeps the reference paths when verifying
+        if self.verify_tolerance.is_some() {
+            return;
+        }
+
         if let Err(err) = save_cache(cache, cache_dirname) {
             eprintln!("{err}");
         }
+        let selection = self.selection.as_ref().map(|patterns| patterns.as_slice());
+        if let Err(err) = save_failed_paths(&self.failures, selection, cache_dirname) {
+            eprintln!("{err}");
+        }
     }
 }
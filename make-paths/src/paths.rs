@@ -1,4 +1,7 @@
+use crate::builder::PathBuilder;
 use crate::ContourProvider;
+use crate::PathConsts;
+use crate::PathDefinition::{Continue, Family, Start, Variant};
 use num::complex::Complex64;
 use pxu::kinematics::UBranch;
 use pxu::{kinematics::CouplingConstants, path::SavedPath};
@@ -9,155 +12,96 @@ pub fn error(message: &str) -> std::io::Error {
     std::io::Error::new(std::io::ErrorKind::Other, message)
 }
 
-fn load_state(s: &str) -> Result<pxu::State> {
-    ron::from_str(s).map_err(|_| error("Could not load state"))
+pub(crate) fn load_state(s: &str, consts: CouplingConstants) -> Result<pxu::State> {
+    pxu::State::parse(s, consts).map_err(|err| error(&format!("Could not load state: {err}")))
 }
 
-trait Goto {
+pub(crate) trait Goto {
     fn goto(
         &mut self,
+        excitation: usize,
         component: pxu::Component,
         new_value: impl Into<Complex64>,
         contours: &pxu::Contours,
         consts: CouplingConstants,
         steps: usize,
     );
-
-    fn follow_path(
-        &mut self,
-        component: pxu::Component,
-        path: &[[f64; 2]],
-        contours: &pxu::Contours,
-        consts: CouplingConstants,
-    );
 }
 
 impl Goto for pxu::State {
     fn goto(
         &mut self,
+        excitation: usize,
         component: pxu::Component,
         new_value: impl Into<Complex64>,
         contours: &pxu::Contours,
         consts: CouplingConstants,
         steps: usize,
     ) {
-        let z0 = self.points[0].get(component);
+        let z0 = self.points[excitation].get(component);
         let z1 = new_value.into();
 
+        let mut first_error = None;
+
         for i in 0..=steps {
             let z = z0 + (i as f64 / steps as f64) * (z1 - z0);
-            self.update(0, component, z, contours, consts);
-        }
-
-        if (self.points[0].get(component) - z1).norm() > 1.0e-6 {
-            eprintln!(
-                "Could not goto ({})",
-                (self.points[0].get(component) - z1).norm()
-            );
+            if let Err(err) = self.update(excitation, component, z, contours, consts) {
+                first_error.get_or_insert(err);
+            }
         }
-    }
 
-    fn follow_path(
-        &mut self,
-        component: pxu::Component,
-        path: &[[f64; 2]],
-        contours: &pxu::Contours,
-        consts: CouplingConstants,
-    ) {
-        for &[re, im] in path {
-            self.goto(component, Complex64::new(re, im), contours, consts, 15);
+        let distance = (self.points[excitation].get(component) - z1).norm();
+        if distance > 1.0e-6 {
+            eprintln!("Could not goto ({distance})");
+            if let Some(err) = first_error {
+                eprintln!("{err}");
+            }
         }
     }
 }
 
-fn bezier_path(
-    start: Complex64,
-    control1: Complex64,
-    control2: Complex64,
-    end: Complex64,
-    distance: f64,
-    max_error: f64,
-) -> Vec<Complex64> {
-    use flo_curves::{
-        bezier::{walk_curve_evenly, Curve},
-        BezierCurve, BezierCurveFactory, Coord2,
-    };
-
-    fn c64_to_coord2(z: Complex64) -> Coord2 {
-        Coord2(z.re, z.im)
-    }
-
-    fn coord2_to_c64(p: Coord2) -> Complex64 {
-        Complex64 { re: p.0, im: p.1 }
-    }
-
-    let curve = Curve::from_points(
-        c64_to_coord2(start),
-        (c64_to_coord2(control1), c64_to_coord2(control2)),
-        c64_to_coord2(end),
-    );
-
-    let mut points = vec![coord2_to_c64(curve.start_point())];
-    points.extend(
-        walk_curve_evenly(&curve, distance, max_error).map(|z| coord2_to_c64(z.end_point())),
-    );
-
-    points
-}
-
-fn create_xp_circle_between_path(
-    name: &str,
-    mut start: pxu::State,
-    start_rev: f64,
-    end_rev: f64,
-    contours: &pxu::Contours,
-    consts: CouplingConstants,
-) -> SavedPath {
+// Move x⁺ around the circle through the x⁺ cuts between the scallops, first
+// approaching the start of the path along the circle
+fn create_xp_circle_between_path(builder: PathBuilder, start_rev: f64, end_rev: f64) -> SavedPath {
     let center = Complex64::new(-0.458742, 0.20995);
     let radius = 0.907159 * 1.03;
 
     let steps = 256.0;
 
-    let mut path = vec![];
-
-    for i in 0..=(start_rev.abs() * steps) as i32 {
+    let approach = (0..=(start_rev.abs() * steps) as i32).map(|i| {
         let theta = start_rev.signum() * TAU * (i as f64 / steps - 0.5);
-        let xp = center + Complex64::from_polar(radius, theta);
-        start.update(0, pxu::Component::Xp, xp, contours, consts);
-    }
-
-    let steps = 256.0;
-
-    for i in 0..=((end_rev - start_rev).abs() * steps) as i32 {
-        let theta = TAU * (start_rev + (end_rev - start_rev).signum() * i as f64 / steps - 0.5);
-        let xp = center + Complex64::from_polar(radius, theta);
-        path.push(xp);
-    }
-
-    pxu::path::SavedPath::new(name, path, start, pxu::Component::Xp, 0, consts)
+        center + Complex64::from_polar(radius, theta)
+    });
+
+    builder
+        .goto_through(pxu::Component::Xp, approach, 1)
+        .circle(
+            center,
+            radius,
+            TAU * (start_rev - 0.5),
+            TAU * (end_rev - 0.5),
+            ((end_rev - start_rev).abs() * steps) as usize,
+        )
+        .build()
 }
 
 // xp circle between/between
-fn path_xp_circle_between_between(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.follow_path(
+fn path_xp_circle_between_between(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
+    let builder = PathBuilder::new(
+        "xp circle between/between",
+        &contour_provider,
+        consts,
+        pxu::Component::Xp,
+    )
+    .goto_points(
         pxu::Component::P,
         &[[0.03, 0.03], [-0.03, 0.03], [-0.06, 0.0]],
-        &contours,
-        consts,
     );
 
-    create_xp_circle_between_path(
-        "xp circle between/between",
-        state,
-        -2.5,
-        3.5,
-        &contours,
-        consts,
-    )
+    create_xp_circle_between_path(builder, -2.5, 3.5)
 }
 
 fn create_x_circle_between_upper(
@@ -166,39 +110,28 @@ fn create_x_circle_between_upper(
     right: f64,
     start_angle: f64,
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.follow_path(
-        pxu::Component::P,
-        &[[0.03, -0.03], [-0.03, -0.03], [-0.06, 0.0]],
-        &contours,
-        consts,
-    );
     let center = (right + left) / 2.0;
     let radius = (right - left) / 2.0;
 
-    state.goto(
-        pxu::Component::Xp,
-        center + Complex64::from_polar(radius, start_angle + 0.001),
-        &contours,
-        consts,
-        2,
-    );
-
     let steps = 256;
 
-    let mut path = vec![];
-
-    for i in 1..steps {
-        let theta = start_angle * (1.0 - i as f64 / steps as f64);
-        let xp = center + Complex64::from_polar(radius, theta);
-        path.push(xp);
-    }
-
-    pxu::path::SavedPath::new(name, path, state, pxu::Component::Xp, 0, consts)
+    PathBuilder::new(name, &contour_provider, consts, pxu::Component::Xp)
+        .goto_points(
+            pxu::Component::P,
+            &[[0.03, -0.03], [-0.03, -0.03], [-0.06, 0.0]],
+        )
+        .goto(
+            pxu::Component::Xp,
+            center + Complex64::from_polar(radius, start_angle + 0.001),
+            2,
+        )
+        .extend((1..steps).map(|i| {
+            let theta = start_angle * (1.0 - i as f64 / steps as f64);
+            center + Complex64::from_polar(radius, theta)
+        }))
+        .build()
 }
 
 fn create_x_circle_between_lower(
@@ -207,781 +140,462 @@ fn create_x_circle_between_lower(
     right: f64,
     end_angle: f64,
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.follow_path(
-        pxu::Component::P,
-        &[[0.03, 0.03], [-0.03, 0.03], [-0.06, 0.0]],
-        &contours,
-        consts,
-    );
     let center = (right + left) / 2.0;
     let radius = (right - left) / 2.0;
 
-    state.goto(pxu::Component::Xp, left, &contours, consts, 2);
-
-    let steps = 16;
-
-    for i in 1..=steps {
-        let theta = PI * (1.0 - i as f64 / steps as f64);
-        let xp = center + Complex64::from_polar(radius, theta);
-        state.goto(pxu::Component::Xp, xp, &contours, consts, 2);
-    }
+    let approach_steps = 16;
+    let approach = (1..=approach_steps).map(|i| {
+        let theta = PI * (1.0 - i as f64 / approach_steps as f64);
+        center + Complex64::from_polar(radius, theta)
+    });
 
     let steps = 256;
 
-    state.goto(
-        pxu::Component::Xp,
-        center + Complex64::from_polar(radius, -PI / steps as f64),
-        &contours,
-        consts,
-        2,
-    );
-
-    let mut path = vec![];
-
-    for i in 1..steps {
-        let theta = end_angle * i as f64 / steps as f64;
-        let xp = center + Complex64::from_polar(radius, theta);
-        path.push(xp);
-    }
-
-    pxu::path::SavedPath::new(name, path, state, pxu::Component::Xp, 0, consts)
+    PathBuilder::new(name, &contour_provider, consts, pxu::Component::Xp)
+        .goto_points(
+            pxu::Component::P,
+            &[[0.03, 0.03], [-0.03, 0.03], [-0.06, 0.0]],
+        )
+        .goto(pxu::Component::Xp, left, 2)
+        .goto_through(pxu::Component::Xp, approach, 2)
+        .goto(
+            pxu::Component::Xp,
+            center + Complex64::from_polar(radius, -PI / steps as f64),
+            2,
+        )
+        .extend((1..steps).map(|i| {
+            let theta = end_angle * i as f64 / steps as f64;
+            center + Complex64::from_polar(radius, theta)
+        }))
+        .build()
 }
 
-fn path_x_half_circle_between_1(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
+fn path_x_half_circle_between_1(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     create_x_circle_between_upper(
         "x half circle between 1",
         -1.69,
         0.591,
         PI * 7.0 / 8.0,
         contour_provider,
+        consts,
     )
 }
 
-fn path_x_half_circle_between_2(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
+fn path_x_half_circle_between_2(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     create_x_circle_between_lower(
         "x half circle between 2",
         -1.96,
         0.591,
         -PI,
         contour_provider,
+        consts,
     )
 }
 
-fn path_x_half_circle_between_3(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
+fn path_x_half_circle_between_3(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     create_x_circle_between_upper(
         "x half circle between 3",
         -1.96,
         0.861,
         PI,
         contour_provider,
+        consts,
     )
 }
 
-fn path_x_half_circle_between_4(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
+fn path_x_half_circle_between_4(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     create_x_circle_between_lower(
         "x half circle between 4",
         -2.23,
         0.861,
         -PI * 7.0 / 8.0,
         contour_provider,
+        consts,
     )
 }
 
 // xp circle between/inside
 fn path_xp_circle_between_inside_left(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.follow_path(
+    let builder = PathBuilder::new(
+        "xp circle between/inside L",
+        &contour_provider,
+        consts,
+        pxu::Component::Xp,
+    )
+    .goto_points(
         pxu::Component::P,
         &[[0.03, 0.03], [-0.03, 0.03], [-0.06, 0.0], [-0.06, -0.2]],
-        &contours,
-        consts,
     );
 
-    create_xp_circle_between_path(
-        "xp circle between/inside L",
-        state,
-        0.0,
-        -2.5,
-        &contours,
-        consts,
-    )
+    create_xp_circle_between_path(builder, 0.0, -2.5)
 }
 
 // xp circle between/inside
 fn path_xp_circle_between_inside_right(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.follow_path(
+    let builder = PathBuilder::new(
+        "xp circle between/inside R",
+        &contour_provider,
+        consts,
+        pxu::Component::Xp,
+    )
+    .goto_points(
         pxu::Component::P,
         &[[0.03, 0.03], [-0.03, 0.03], [-0.06, 0.0], [-0.06, -0.2]],
-        &contours,
-        consts,
     );
 
-    create_xp_circle_between_path(
-        "xp circle between/inside R",
-        state,
-        0.0,
-        3.5,
-        &contours,
-        consts,
-    )
+    create_xp_circle_between_path(builder, 0.0, 3.5)
 }
 
 // xp circle between/outside
 fn path_xp_circle_between_outside_left(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.follow_path(
-        pxu::Component::P,
-        &[[0.2, 0.0], [0.2, 0.2], [0.78, 0.2]],
-        &contours,
-        consts,
-    );
-
-    create_xp_circle_between_path(
+    let builder = PathBuilder::new(
         "xp circle between/outside L",
-        state,
-        0.0,
-        -2.5,
-        &contours,
+        &contour_provider,
         consts,
+        pxu::Component::Xp,
     )
+    .goto_points(pxu::Component::P, &[[0.2, 0.0], [0.2, 0.2], [0.78, 0.2]]);
+
+    create_xp_circle_between_path(builder, 0.0, -2.5)
 }
 
 // xp circle between/outside
 fn path_xp_circle_between_outside_right(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.follow_path(
-        pxu::Component::P,
-        &[[0.2, 0.0], [0.2, 0.2], [0.78, 0.2]],
-        &contours,
-        consts,
-    );
-
-    create_xp_circle_between_path(
+    let builder = PathBuilder::new(
         "xp circle between/outside R",
-        state,
-        0.0,
-        3.5,
-        &contours,
+        &contour_provider,
         consts,
+        pxu::Component::Xp,
     )
+    .goto_points(pxu::Component::P, &[[0.2, 0.0], [0.2, 0.2], [0.78, 0.2]]);
+
+    create_xp_circle_between_path(builder, 0.0, 3.5)
 }
 
 // xp circle between/between single
 fn path_xp_circle_between_between_single(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.follow_path(
+    let builder = PathBuilder::new(
+        "xp circle between/between (single)",
+        &contour_provider,
+        consts,
+        pxu::Component::Xp,
+    )
+    .goto_points(
         pxu::Component::P,
         &[[0.03, 0.03], [-0.03, 0.03], [-0.06, 0.0]],
-        &contours,
-        consts,
     );
 
-    create_xp_circle_between_path(
-        "xp circle between/between (single)",
-        state,
-        0.0,
-        1.0,
-        &contours,
-        consts,
-    )
+    create_xp_circle_between_path(builder, 0.0, 1.0)
 }
 
 // p circle origin not through E cut
-fn path_p_circle_origin_not_e(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_p_circle_origin_not_e(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let center = Complex64::new(0.0, 0.0);
     let radius = 0.05;
     let steps = 128;
 
-    let mut state = pxu::State::new(1, consts);
-    state.goto(pxu::Component::P, center + radius, &contours, consts, 4);
-
-    let mut path = vec![];
-
-    for i in 0..=(steps) {
-        let theta = TAU * (i as f64 / steps as f64);
-        let z = center + Complex64::from_polar(radius, theta);
-        path.push(z);
-    }
-
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "p circle origin not through E cut",
-        path,
-        state,
-        pxu::Component::P,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::P,
     )
+    .goto(pxu::Component::P, center + radius, 4)
+    .circle(center, radius, 0.0, TAU, steps)
+    .build()
 }
 
 // P circle around origin through E cuts
-fn path_p_circle_origin_e(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_p_circle_origin_e(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let center = Complex64::new(0.0, 0.0);
     let radius = 0.10;
     let steps = 128;
 
-    let mut state = pxu::State::new(1, consts);
-    state.goto(pxu::Component::P, center + radius, &contours, consts, 4);
-
-    let mut path = vec![];
-
-    for i in 0..=(steps) {
-        let theta = TAU * (i as f64 / steps as f64);
-        let z = center + Complex64::from_polar(radius, theta);
-        path.push(z);
-    }
-
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "P circle around origin through E cuts",
-        path,
-        state,
-        pxu::Component::P,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::P,
     )
+    .goto(pxu::Component::P, center + radius, 4)
+    .circle(center, radius, 0.0, TAU, steps)
+    .build()
 }
 
-// U band between/outside
-fn path_u_band_between_outside(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
+// Zigzag down through three periods of the u plane, staying between the cuts
+fn u_band_approach(x0: f64, y0: f64, k: f64) -> Vec<[f64; 2]> {
+    (-2..=0)
+        .rev()
+        .flat_map(|y| {
+            let y = y as f64;
+            [
+                [-x0, y0 + k * y],
+                [-x0, y0 + k * (y - 0.5)],
+                [x0, y0 + k * (y - 0.5)],
+                [x0, y0 + k * (y - 1.0)],
+            ]
+        })
+        .collect()
+}
 
-    let mut state = pxu::State::new(1, consts);
+// Wind up through the given number of periods of the u plane, with a half
+// circle of radius r1 on the right and one of radius r2 on the left in each
+// period
+#[allow(clippy::too_many_arguments)]
+fn u_band(
+    builder: PathBuilder,
+    x0: f64,
+    y0: f64,
+    r1: f64,
+    r2: f64,
+    k: f64,
+    periods: i32,
+    steps: usize,
+) -> PathBuilder {
+    let start = builder.current();
+
+    (0..periods)
+        .fold(builder.move_to(start), |builder, y| {
+            let y = y as f64;
+            builder
+                .circle(
+                    Complex64::new(x0, y0 + k * y + r1),
+                    r1,
+                    -PI / 2.0,
+                    PI / 2.0,
+                    steps,
+                )
+                .circle(
+                    Complex64::new(-x0, y0 + k * y + 2.0 * r1 + r2),
+                    r2,
+                    -PI / 2.0,
+                    -3.0 * PI / 2.0,
+                    steps,
+                )
+        })
+        .move_to(Complex64::new(0.0, y0 + periods as f64 * k))
+}
 
+// U band between/outside
+fn path_u_band_between_outside(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let x0 = 2.7;
     let y0 = -1.55;
     let k = consts.k() as f64;
     let h = consts.h;
 
-    state.follow_path(
-        pxu::Component::U,
-        &[[0.0, 0.0], [0.0, y0]],
-        &contours,
-        consts,
-    );
-
-    for y in (-2..=0).rev() {
-        let y = y as f64;
-        let path = [
-            [-x0, y0 + k * y],
-            [-x0, y0 + k * (y - 0.5)],
-            [x0, y0 + k * (y - 0.5)],
-            [x0, y0 + k * (y - 1.0)],
-        ];
-
-        state.follow_path(pxu::Component::U, &path, &contours, consts);
-    }
-
-    let y0 = y0 - 3.0 * k;
-
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(0.0, y0),
-        &contours,
-        consts,
-        16,
-    );
-
     let r1 = 1.0;
     let r2 = k / h - r1;
-    let y0 = -r1 - 3.0 * k;
-
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(-x0, y0),
-        &contours,
-        consts,
-        16,
-    );
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(0.0, y0),
-        &contours,
-        consts,
-        16,
-    );
-
-    let mut path = vec![state.points[0].u];
-
-    let steps = 16;
-    let steps = (0..=steps)
-        .map(|n| PI * n as f64 / steps as f64)
-        .collect::<Vec<_>>();
-
-    for y in 0..=5 {
-        let y = y as f64;
+    let y1 = -r1 - 3.0 * k;
 
-        let c = Complex64::new(x0, y0 + k * y + r1);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r1, -PI / 2.0 + *theta));
-        }
-
-        let c = Complex64::new(-x0, y0 + k * y + 2.0 * r1 + r2);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r2, -PI / 2.0 - *theta));
-        }
-    }
-
-    path.push(Complex64::new(0.0, y0 + 6.0 * k));
-
-    pxu::path::SavedPath::new(
+    let builder = PathBuilder::new(
         "U band between/outside",
-        path,
-        state,
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .goto_points(pxu::Component::U, &[[0.0, 0.0], [0.0, y0]])
+    .goto_points(pxu::Component::U, &u_band_approach(x0, y0, k))
+    .goto(pxu::Component::U, Complex64::new(0.0, y0 - 3.0 * k), 16)
+    .goto(pxu::Component::U, Complex64::new(-x0, y1), 16)
+    .goto(pxu::Component::U, Complex64::new(0.0, y1), 16);
+
+    u_band(builder, x0, y1, r1, r2, k, 6, 16).build()
 }
 
 // U band between/outside (single)
 fn path_u_band_between_outside_single(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
     let x0 = 2.7;
     let y0 = -1.55;
     let k = consts.k() as f64;
     let h = consts.h;
 
-    state.follow_path(
-        pxu::Component::U,
-        &[[0.0, 0.0], [0.0, y0]],
-        &contours,
-        consts,
-    );
-
     let r1 = 1.0;
     let r2 = k / h - r1;
-    let y0 = -r1;
-
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(-x0, y0),
-        &contours,
-        consts,
-        16,
-    );
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(0.0, y0),
-        &contours,
-        consts,
-        16,
-    );
-
-    let mut path = vec![state.points[0].u];
-
-    let steps = 32;
-    let steps = (0..=steps)
-        .map(|n| PI * n as f64 / steps as f64)
-        .collect::<Vec<_>>();
-
-    for y in 0..=0 {
-        let y = y as f64;
-
-        let c = Complex64::new(x0, y0 + k * y + r1);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r1, -PI / 2.0 + *theta));
-        }
-
-        let c = Complex64::new(-x0, y0 + k * y + 2.0 * r1 + r2);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r2, -PI / 2.0 - *theta));
-        }
-    }
+    let y1 = -r1;
 
-    path.push(Complex64::new(0.0, y0 + 1.0 * k));
-
-    pxu::path::SavedPath::new(
+    let builder = PathBuilder::new(
         "U band between/outside (single)",
-        path,
-        state,
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .goto_points(pxu::Component::U, &[[0.0, 0.0], [0.0, y0]])
+    .goto(pxu::Component::U, Complex64::new(-x0, y1), 16)
+    .goto(pxu::Component::U, Complex64::new(0.0, y1), 16);
+
+    u_band(builder, x0, y1, r1, r2, k, 1, 32).build()
 }
 
 // U band between/inside
-fn path_u_band_between_inside(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
+fn path_u_band_between_inside(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let x0 = 2.7;
     let y0 = -1.75;
     let k = consts.k() as f64;
     let h = consts.h;
 
-    state.follow_path(
-        pxu::Component::U,
-        &[[4.8, 0.0], [4.8, 1.0], [0.0, 1.0], [0.0, -2.5], [-x0, -2.5]],
-        &contours,
-        consts,
-    );
-
-    for y in (-2..=0).rev() {
-        let y = y as f64;
-        let path = [
-            [-x0, y0 + k * y],
-            [-x0, y0 + k * (y - 0.5)],
-            [x0, y0 + k * (y - 0.5)],
-            [x0, y0 + k * (y - 1.0)],
-        ];
-
-        state.follow_path(pxu::Component::U, &path, &contours, consts);
-    }
-
-    let y0 = y0 - 3.0 * k;
-
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(-x0, y0),
-        &contours,
-        consts,
-        16,
-    );
-
     let r1 = 1.0;
     let r2 = k / h - r1;
-    let y0 = -r1 - 3.0 * k;
+    let y1 = -r1 - 3.0 * k;
 
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(-x0, y0),
-        &contours,
+    let builder = PathBuilder::new(
+        "U band between/inside",
+        &contour_provider,
         consts,
-        16,
-    );
-    state.goto(
         pxu::Component::U,
-        Complex64::new(0.0, y0),
-        &contours,
-        consts,
-        16,
-    );
+    )
+    .goto_points(
+        pxu::Component::U,
+        &[[4.8, 0.0], [4.8, 1.0], [0.0, 1.0], [0.0, -2.5], [-x0, -2.5]],
+    )
+    .goto_points(pxu::Component::U, &u_band_approach(x0, y0, k))
+    .goto(pxu::Component::U, Complex64::new(-x0, y0 - 3.0 * k), 16)
+    .goto(pxu::Component::U, Complex64::new(-x0, y1), 16)
+    .goto(pxu::Component::U, Complex64::new(0.0, y1), 16);
 
-    let mut path = vec![state.points[0].u];
-
-    let steps = 16;
-    let steps = (0..=steps)
-        .map(|n| PI * n as f64 / steps as f64)
-        .collect::<Vec<_>>();
-
-    for y in 0..=5 {
-        let y = y as f64;
-
-        let c = Complex64::new(x0, y0 + k * y + r1);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r1, -PI / 2.0 + *theta));
-        }
-
-        let c = Complex64::new(-x0, y0 + k * y + 2.0 * r1 + r2);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r2, -PI / 2.0 - *theta));
-        }
-    }
-
-    path.push(Complex64::new(0.0, y0 + 6.0 * k));
-
-    pxu::path::SavedPath::new(
-        "U band between/inside",
-        path,
-        state,
-        pxu::Component::U,
-        0,
-        consts,
-    )
-}
+    u_band(builder, x0, y1, r1, r2, k, 6, 16).build()
+}
 
 // U band between/inside (single)
 fn path_u_band_between_inside_single(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
     let x0 = 2.7;
     let k = consts.k() as f64;
     let h = consts.h;
 
-    state.follow_path(
-        pxu::Component::U,
-        &[[4.8, 0.0], [4.8, 1.0], [0.0, 1.0], [0.0, -2.5], [-x0, -2.5]],
-        &contours,
-        consts,
-    );
-
     let r1 = 1.0;
     let r2 = k / h - r1;
-    let y0 = -r1;
+    let y1 = -r1;
 
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(-x0, y0),
-        &contours,
+    let builder = PathBuilder::new(
+        "U band between/inside (single)",
+        &contour_provider,
         consts,
-        16,
-    );
-    state.goto(
         pxu::Component::U,
-        Complex64::new(0.0, y0),
-        &contours,
-        consts,
-        16,
-    );
-
-    let mut path = vec![state.points[0].u];
-
-    let steps = 32;
-    let steps = (0..=steps)
-        .map(|n| PI * n as f64 / steps as f64)
-        .collect::<Vec<_>>();
-
-    for y in 0..=0 {
-        let y = y as f64;
-
-        let c = Complex64::new(x0, y0 + k * y + r1);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r1, -PI / 2.0 + *theta));
-        }
-
-        let c = Complex64::new(-x0, y0 + k * y + 2.0 * r1 + r2);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r2, -PI / 2.0 - *theta));
-        }
-    }
-
-    path.push(Complex64::new(0.0, y0 + 1.0 * k));
-
-    pxu::path::SavedPath::new(
-        "U band between/inside (single)",
-        path,
-        state,
+    )
+    .goto_points(
         pxu::Component::U,
-        0,
-        consts,
+        &[[4.8, 0.0], [4.8, 1.0], [0.0, 1.0], [0.0, -2.5], [-x0, -2.5]],
     )
+    .goto(pxu::Component::U, Complex64::new(-x0, y1), 16)
+    .goto(pxu::Component::U, Complex64::new(0.0, y1), 16);
+
+    u_band(builder, x0, y1, r1, r2, k, 1, 32).build()
 }
 
 // U period between/between
-fn path_u_periodic_between_between(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
+fn path_u_periodic_between_between(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let x0 = 2.7;
     let y0 = -0.75;
     let k = consts.k() as f64;
     let h = consts.h;
 
-    state.follow_path(
-        pxu::Component::U,
-        &[[4.8, 0.0], [4.8, 1.0], [0.0, 1.0], [0.0, y0], [-x0, y0]],
-        &contours,
-        consts,
-    );
-
-    for y in (-2..=0).rev() {
-        let y = y as f64;
-        let path = [
-            [-x0, y0 + k * y],
-            [-x0, y0 + k * (y - 0.5)],
-            [x0, y0 + k * (y - 0.5)],
-            [x0, y0 + k * (y - 1.0)],
-        ];
-
-        state.follow_path(pxu::Component::U, &path, &contours, consts);
-    }
-
-    let y0 = y0 - 3.0 * k;
-
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(-x0, y0),
-        &contours,
-        consts,
-        16,
-    );
-
     let r1 = 0.75;
     let r2 = k / h - r1;
-    let y0 = -r1 - 3.0 * k;
-    let x0 = 1.8;
+    let x1 = 1.8;
+    let y1 = -r1 - 3.0 * k;
 
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(-x0, y0),
-        &contours,
+    let builder = PathBuilder::new(
+        "U period between/between",
+        &contour_provider,
         consts,
-        16,
-    );
-    state.goto(
         pxu::Component::U,
-        Complex64::new(0.0, y0),
-        &contours,
-        consts,
-        16,
-    );
-
-    let mut path = vec![state.points[0].u];
-
-    let steps = 16;
-    let steps = (0..=steps)
-        .map(|n| PI * n as f64 / steps as f64)
-        .collect::<Vec<_>>();
-
-    for y in 0..=5 {
-        let y = y as f64;
-
-        let c = Complex64::new(x0, y0 + k * y + r1);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r1, -PI / 2.0 + *theta));
-        }
-
-        let c = Complex64::new(-x0, y0 + k * y + 2.0 * r1 + r2);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r2, -PI / 2.0 - *theta));
-        }
-    }
-
-    path.push(Complex64::new(0.0, y0 + 6.0 * k));
-
-    pxu::path::SavedPath::new(
-        "U period between/between",
-        path,
-        state,
+    )
+    .goto_points(
         pxu::Component::U,
-        0,
-        consts,
+        &[[4.8, 0.0], [4.8, 1.0], [0.0, 1.0], [0.0, y0], [-x0, y0]],
     )
+    .goto_points(pxu::Component::U, &u_band_approach(x0, y0, k))
+    .goto(pxu::Component::U, Complex64::new(-x0, y0 - 3.0 * k), 16)
+    .goto(pxu::Component::U, Complex64::new(-x1, y1), 16)
+    .goto(pxu::Component::U, Complex64::new(0.0, y1), 16);
+
+    u_band(builder, x1, y1, r1, r2, k, 6, 16).build()
 }
 
 // U period between/between single
 fn path_u_periodic_between_between_single(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
     let x0 = 2.7;
     let y0 = -0.75;
     let k = consts.k() as f64;
     let h = consts.h;
 
-    state.follow_path(
-        pxu::Component::U,
-        &[[4.8, 0.0], [4.8, 1.0], [0.0, 1.0], [0.0, y0], [-x0, y0]],
-        &contours,
-        consts,
-    );
-
     let r1 = 1.0;
     let r2 = k / h - r1;
-    let y0 = -r1;
+    let y1 = -r1;
 
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(-x0, y0),
-        &contours,
+    let builder = PathBuilder::new(
+        "U period between/between (single)",
+        &contour_provider,
         consts,
-        16,
-    );
-    state.goto(
         pxu::Component::U,
-        Complex64::new(0.0, y0),
-        &contours,
-        consts,
-        16,
-    );
-
-    let mut path = vec![state.points[0].u];
-
-    let steps = 32;
-    let steps = (0..=steps)
-        .map(|n| PI * n as f64 / steps as f64)
-        .collect::<Vec<_>>();
-
-    for y in 0..=0 {
-        let y = y as f64;
-
-        let c = Complex64::new(x0, y0 + k * y + r1);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r1, -PI / 2.0 + *theta));
-        }
-
-        let c = Complex64::new(-x0, y0 + k * y + 2.0 * r1 + r2);
-        for theta in steps.iter() {
-            path.push(c + Complex64::from_polar(r2, -PI / 2.0 - *theta));
-        }
-    }
-
-    path.push(Complex64::new(0.0, y0 + 1.0 * k));
-
-    pxu::path::SavedPath::new(
-        "U period between/between (single)",
-        path,
-        state,
+    )
+    .goto_points(
         pxu::Component::U,
-        0,
-        consts,
+        &[[4.8, 0.0], [4.8, 1.0], [0.0, 1.0], [0.0, y0], [-x0, y0]],
     )
+    .goto(pxu::Component::U, Complex64::new(-x0, y1), 16)
+    .goto(pxu::Component::U, Complex64::new(0.0, y1), 16);
+
+    u_band(builder, x0, y1, r1, r2, k, 1, 32).build()
 }
 
 // U crossing from 0-2pi
-fn path_u_crossing_from_0_a(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
+fn path_u_crossing_from_0_a(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let h = consts.h;
     let x0 = -0.19224596334559135;
     let x1 = 2.6;
@@ -989,56 +603,32 @@ fn path_u_crossing_from_0_a(contour_provider: std::sync::Arc<ContourProvider>) -
     let y = 2.2 / h;
     let r = 1.0 / h;
 
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(x0, 0.0),
-        &contours,
-        consts,
-        16,
-    );
-
     let steps = 8;
-    let steps = (0..=steps)
-        .map(|n| PI / 2.0 * n as f64 / steps as f64)
-        .collect::<Vec<_>>();
-
-    let mut path = vec![state.points[0].u];
-
-    for theta in steps.iter() {
-        path.push(Complex64::new(x0 + r, -y + r) + Complex64::from_polar(r, -PI + theta));
-    }
-
-    for theta in steps.iter() {
-        path.push(Complex64::new(x1 - r, -y + r) + Complex64::from_polar(r, -PI / 2.0 + theta));
-    }
-
-    for theta in steps.iter() {
-        path.push(Complex64::new(x1 - r, y - r) + Complex64::from_polar(r, *theta));
-    }
-
-    for theta in steps.iter() {
-        path.push(Complex64::new(x2 + r, y - r) + Complex64::from_polar(r, PI / 2.0 + theta));
-    }
-
-    path.push(Complex64::new(x2, 0.0));
 
-    pxu::path::SavedPath::new(
+    let builder = PathBuilder::new(
         "U crossing from 0-2pi path A",
-        path,
-        state,
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .goto(pxu::Component::U, Complex64::new(x0, 0.0), 16);
+    let start = builder.current();
+
+    builder
+        .move_to(start)
+        .circle(Complex64::new(x0 + r, -y + r), r, -PI, -PI / 2.0, steps)
+        .circle(Complex64::new(x1 - r, -y + r), r, -PI / 2.0, 0.0, steps)
+        .circle(Complex64::new(x1 - r, y - r), r, 0.0, PI / 2.0, steps)
+        .circle(Complex64::new(x2 + r, y - r), r, PI / 2.0, PI, steps)
+        .move_to(Complex64::new(x2, 0.0))
+        .build()
 }
 
 // U crossing from 0-2pi
-fn path_u_crossing_from_0_b(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
+fn path_u_crossing_from_0_b(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let h = consts.h;
     let x0 = 1.9235122885022853;
     let x1 = 0.45;
@@ -1046,56 +636,32 @@ fn path_u_crossing_from_0_b(contour_provider: std::sync::Arc<ContourProvider>) -
     let y = 1.8 / h;
     let r = 1.0 / h;
 
-    state.goto(
-        pxu::Component::U,
-        Complex64::new(x0, 0.0),
-        &contours,
-        consts,
-        16,
-    );
-
     let steps = 8;
-    let steps = (0..=steps)
-        .map(|n| PI / 2.0 * n as f64 / steps as f64)
-        .collect::<Vec<_>>();
-
-    let mut path = vec![state.points[0].u];
 
-    for theta in steps.iter() {
-        path.push(Complex64::new(x0 - r, -y + r) + Complex64::from_polar(r, -theta));
-    }
-
-    for theta in steps.iter() {
-        path.push(Complex64::new(x1 + r, -y + r) + Complex64::from_polar(r, -PI / 2.0 - theta));
-    }
-
-    for theta in steps.iter() {
-        path.push(Complex64::new(x1 + r, y - r) + Complex64::from_polar(r, PI - theta));
-    }
-
-    for theta in steps.iter() {
-        path.push(Complex64::new(x2 - r, y - r) + Complex64::from_polar(r, PI / 2.0 - theta));
-    }
-
-    path.push(Complex64::new(x2, 0.0));
-
-    pxu::path::SavedPath::new(
+    let builder = PathBuilder::new(
         "U crossing from 0-2pi path B",
-        path,
-        state,
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .goto(pxu::Component::U, Complex64::new(x0, 0.0), 16);
+    let start = builder.current();
+
+    builder
+        .move_to(start)
+        .circle(Complex64::new(x0 - r, -y + r), r, 0.0, -PI / 2.0, steps)
+        .circle(Complex64::new(x1 + r, -y + r), r, -PI / 2.0, -PI, steps)
+        .circle(Complex64::new(x1 + r, y - r), r, PI, PI / 2.0, steps)
+        .circle(Complex64::new(x2 - r, y - r), r, PI / 2.0, 0.0, steps)
+        .move_to(Complex64::new(x2, 0.0))
+        .build()
 }
 
 // U crossing from -2pi to 0
-fn path_u_crossing_from_min_1(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
+fn path_u_crossing_from_min_1(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let k = consts.k() as f64;
     let h = consts.h;
     let x0 = -0.4319489724735624;
@@ -1104,7 +670,15 @@ fn path_u_crossing_from_min_1(contour_provider: std::sync::Arc<ContourProvider>)
     let y = 2.2 / h;
     let r = 1.0 / h;
 
-    state.follow_path(
+    let steps = 8;
+
+    let builder = PathBuilder::new(
+        "U crossing from -2pi to 0",
+        &contour_provider,
+        consts,
+        pxu::Component::U,
+    )
+    .goto_points(
         pxu::Component::U,
         &[
             [3.0, 0.0],
@@ -1113,230 +687,192 @@ fn path_u_crossing_from_min_1(contour_provider: std::sync::Arc<ContourProvider>)
             [-3.0, k / h],
             [x0, k / h],
         ],
-        &contours,
-        consts,
     );
-
-    let steps = 8;
-    let steps = (0..=steps)
-        .map(|n| PI / 2.0 * n as f64 / steps as f64)
-        .collect::<Vec<_>>();
-
-    let mut path = vec![state.points[0].u];
-
-    for theta in steps.iter() {
-        path.push(Complex64::new(x0 - r, k / h - y + r) + Complex64::from_polar(r, -theta));
-    }
-
-    for theta in steps.iter() {
-        path.push(
-            Complex64::new(x1 + r, k / h - y + r) + Complex64::from_polar(r, -PI / 2.0 - theta),
-        );
-    }
-
-    for theta in steps.iter() {
-        path.push(Complex64::new(x1 + r, k / h + y - r) + Complex64::from_polar(r, PI - theta));
-    }
-
-    for theta in steps.iter() {
-        path.push(
-            Complex64::new(x2 - r, k / h + y - r) + Complex64::from_polar(r, PI / 2.0 - theta),
-        );
-    }
-
-    path.push(Complex64::new(x2, k / h));
-
-    pxu::path::SavedPath::new(
-        "U crossing from -2pi to 0",
-        path,
-        state,
-        pxu::Component::U,
-        0,
-        consts,
-    )
+    let start = builder.current();
+
+    builder
+        .move_to(start)
+        .circle(
+            Complex64::new(x0 - r, k / h - y + r),
+            r,
+            0.0,
+            -PI / 2.0,
+            steps,
+        )
+        .circle(
+            Complex64::new(x1 + r, k / h - y + r),
+            r,
+            -PI / 2.0,
+            -PI,
+            steps,
+        )
+        .circle(
+            Complex64::new(x1 + r, k / h + y - r),
+            r,
+            PI,
+            PI / 2.0,
+            steps,
+        )
+        .circle(
+            Complex64::new(x2 - r, k / h + y - r),
+            r,
+            PI / 2.0,
+            0.0,
+            steps,
+        )
+        .move_to(Complex64::new(x2, k / h))
+        .build()
 }
 
 // p crossing a
-fn path_p_crossing_a(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_p_crossing_a(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let p0 = 0.15;
     let y0 = 0.08;
     let steps = 100;
 
-    let mut state = pxu::State::new(1, consts);
-    state.goto(pxu::Component::P, p0, &contours, consts, 4);
-
-    let mut path = vec![];
-    for i in 0..=steps {
-        let x = 1.0 - (i as f64 / steps as f64) * 2.0;
-        let y = y0 * (1.0 - x * x);
-        let p = Complex64::new(x * p0, y);
-
-        path.push(p);
-    }
-
-    pxu::path::SavedPath::new("p crossing a", path, state, pxu::Component::P, 0, consts)
+    PathBuilder::new("p crossing a", &contour_provider, consts, pxu::Component::P)
+        .goto(pxu::Component::P, p0, 4)
+        .extend((0..=steps).map(|i| {
+            let x = 1.0 - (i as f64 / steps as f64) * 2.0;
+            let y = y0 * (1.0 - x * x);
+            Complex64::new(x * p0, y)
+        }))
+        .build()
 }
 
 // p crossing b
-fn path_p_crossing_b(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_p_crossing_b(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let p0 = 0.15;
     let y0 = 0.08;
     let steps = 100;
 
-    let mut state = pxu::State::new(1, consts);
-    state.goto(pxu::Component::P, p0, &contours, consts, 4);
-
-    let mut path = vec![];
-    for i in 0..=steps {
-        let x = 1.0 - (i as f64 / steps as f64) * 2.0;
-        let y = -y0 * (1.0 - x * x);
-        let p = Complex64::new(x * p0, y);
-
-        path.push(p);
-    }
-
-    pxu::path::SavedPath::new("p crossing b", path, state, pxu::Component::P, 0, consts)
+    PathBuilder::new("p crossing b", &contour_provider, consts, pxu::Component::P)
+        .goto(pxu::Component::P, p0, 4)
+        .extend((0..=steps).map(|i| {
+            let x = 1.0 - (i as f64 / steps as f64) * 2.0;
+            let y = -y0 * (1.0 - x * x);
+            Complex64::new(x * p0, y)
+        }))
+        .build()
 }
 
 // p crossing c
-fn path_p_crossing_c(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_p_crossing_c(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let p0 = 0.15;
     let bp = Complex64::new(0.915, 0.370);
 
-    let mut state = pxu::State::new(1, consts);
-    state.goto(pxu::Component::P, p0, &contours, consts, 4);
-
     let dp = (bp / bp.norm()) * p0 * (bp.re / bp.norm());
 
-    let mut path = vec![Complex64::from(p0), bp + p0 - dp];
-
     let steps = 32;
 
-    for i in 1..(steps - 1) {
-        let theta = PI * i as f64 / steps as f64;
-        path.push(bp + (p0 - dp) * (Complex64::i() * theta).exp());
-    }
-
-    path.extend([bp - p0 + dp, Complex64::from(-p0)]);
-
-    pxu::path::SavedPath::new("p crossing c", path, state, pxu::Component::P, 0, consts)
+    PathBuilder::new("p crossing c", &contour_provider, consts, pxu::Component::P)
+        .goto(pxu::Component::P, p0, 4)
+        .move_to(p0)
+        .move_to(bp + p0 - dp)
+        .extend((1..(steps - 1)).map(|i| {
+            let theta = PI * i as f64 / steps as f64;
+            bp + (p0 - dp) * (Complex64::i() * theta).exp()
+        }))
+        .move_to(bp - p0 + dp)
+        .move_to(-p0)
+        .build()
 }
 
 // p crossing d
-fn path_p_crossing_d(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_p_crossing_d(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let p0 = 0.15;
     let bp = Complex64::new(-0.922, -0.265);
 
-    let mut state = pxu::State::new(1, consts);
-    state.goto(pxu::Component::P, p0, &contours, consts, 4);
-
     let dp = (bp / bp.norm()) * p0 * (bp.re / bp.norm());
 
-    let mut path = vec![Complex64::from(p0), bp + p0 - dp];
-
     let steps = 32;
 
-    for i in 1..(steps - 1) {
-        let theta = -PI * i as f64 / steps as f64;
-        path.push(bp + (p0 - dp) * (Complex64::i() * theta).exp());
-    }
-
-    path.extend([bp - p0 + dp, Complex64::from(-p0)]);
-
-    pxu::path::SavedPath::new("p crossing d", path, state, pxu::Component::P, 0, consts)
+    PathBuilder::new("p crossing d", &contour_provider, consts, pxu::Component::P)
+        .goto(pxu::Component::P, p0, 4)
+        .move_to(p0)
+        .move_to(bp + p0 - dp)
+        .extend((1..(steps - 1)).map(|i| {
+            let theta = -PI * i as f64 / steps as f64;
+            bp + (p0 - dp) * (Complex64::i() * theta).exp()
+        }))
+        .move_to(bp - p0 + dp)
+        .move_to(-p0)
+        .build()
 }
 
-fn path_u_vertical_outside(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
+fn path_u_vertical_outside(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let steps = 67;
     let y0 = -0.51;
     let y1 = -8.0;
 
-    state.follow_path(
-        pxu::Component::U,
-        &[[3.0, 0.0], [3.0, -2.0], [0.0, -2.0], [0.0, y0]],
-        &contours,
-        consts,
-    );
-
-    let p1 = Complex64::new(0.0, y0);
-    let p2 = Complex64::new(0.0, y1);
-
-    let path = (0..=steps)
-        .map(|i| p1 + (i as f64 / steps as f64) * (p2 - p1))
-        .collect::<Vec<_>>();
-
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "u vertical outside",
-        path,
-        state,
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
+    )
+    .goto_points(
+        pxu::Component::U,
+        &[[3.0, 0.0], [3.0, -2.0], [0.0, -2.0], [0.0, y0]],
     )
+    .move_to(Complex64::new(0.0, y0))
+    .line_to(Complex64::new(0.0, y1), steps)
+    .build()
 }
 
-fn path_u_vertical_between(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
+fn path_u_vertical_between(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let steps = 67;
     let y0 = -0.49;
     let y1 = 2.0;
 
-    state.follow_path(
-        pxu::Component::U,
-        &[[3.0, 0.0], [3.0, -2.0], [0.0, -2.0], [0.0, y0]],
-        &contours,
-        consts,
-    );
-
-    let p1 = Complex64::new(0.0, y0);
-    let p2 = Complex64::new(0.0, y1);
-
-    let path = (0..=steps)
-        .map(|i| p1 + (i as f64 / steps as f64) * (p2 - p1))
-        .collect::<Vec<_>>();
-
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "u vertical between",
-        path,
-        state,
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .goto_points(
+        pxu::Component::U,
+        &[[3.0, 0.0], [3.0, -2.0], [0.0, -2.0], [0.0, y0]],
+    )
+    .move_to(Complex64::new(0.0, y0))
+    .line_to(Complex64::new(0.0, y1), steps)
+    .build()
 }
 
-fn path_u_vertical_inside(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-
+fn path_u_vertical_inside(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let steps = 67;
     let y0 = 2.0;
     let y1 = 50.0;
 
-    state.follow_path(
+    PathBuilder::new(
+        "u vertical inside",
+        &contour_provider,
+        consts,
+        pxu::Component::U,
+    )
+    .goto_points(
         pxu::Component::U,
         &[
             [3.0, 0.0],
@@ -1346,164 +882,105 @@ fn path_u_vertical_inside(contour_provider: std::sync::Arc<ContourProvider>) ->
             [0.0, 0.0],
             [0.0, y0],
         ],
-        &contours,
-        consts,
-    );
-
-    let p1 = Complex64::new(0.0, y0);
-    let p2 = Complex64::new(0.0, y1);
-
-    let path = (0..=steps)
-        .map(|i| p1 + (i as f64 / steps as f64) * (p2 - p1))
-        .collect::<Vec<_>>();
-
-    pxu::path::SavedPath::new(
-        "u vertical inside",
-        path,
-        state,
-        pxu::Component::U,
-        0,
-        consts,
     )
+    .move_to(Complex64::new(0.0, y0))
+    .line_to(Complex64::new(0.0, y1), steps)
+    .build()
 }
 
-fn path_p_from_region_0_to_region_min_1(
+// A path from the middle of one region of the p plane to the middle of the
+// next, along a curve bulging out from the real line by the given angle
+fn path_p_between_regions(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+    state: Option<pxu::State>,
+    start: f64,
+    end: f64,
+    angle: f64,
+    step_size: f64,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let state_string = "(points:[(p:(0.5,0.0),xp:(0.00000000000000013494188523791627,2.2037682265918312),xm:(0.00000000000000013494188523791627,-2.2037682265918312),u:(-0.6287962926300276,0.0),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-    state.goto(pxu::Component::P, 0.5, &contours, consts, 4);
-
-    let start = Complex64::from(0.5);
-    let end = Complex64::from(-0.5);
-
-    let angle = PI / 14.0;
-
-    let dz1 = Complex64::from_polar(0.25, PI - angle);
-    let dz2 = Complex64::from_polar(0.25, angle);
+    let builder = PathBuilder::new("", &contour_provider, consts, pxu::Component::P);
+    let builder = match state {
+        Some(state) => builder.with_state(state),
+        None => builder.with_state_str("(points:[(p:(0.5,0.0),xp:(0.00000000000000013494188523791627,2.2037682265918312),xm:(0.00000000000000013494188523791627,-2.2037682265918312),u:(-0.6287962926300276,0.0),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))],unlocked:false)"),
+    };
 
-    let path = bezier_path(start, start + dz1, end + dz2, end, 0.001, 0.0001);
+    let start = Complex64::from(start);
+    let end = Complex64::from(end);
+
+    let (dz1, dz2) = if end.re < start.re {
+        (
+            Complex64::from_polar(0.25, PI - angle),
+            Complex64::from_polar(0.25, angle),
+        )
+    } else {
+        (
+            Complex64::from_polar(0.25, angle),
+            Complex64::from_polar(0.25, PI - angle),
+        )
+    };
 
-    pxu::path::SavedPath::new(
-        "p from region 0 to region -1",
-        path,
-        state,
-        pxu::Component::P,
-        0,
-        consts,
-    )
+    // The name is set from the name of the family
+    builder
+        .goto(pxu::Component::P, start, 4)
+        .move_to(start)
+        .bezier_to(start + dz1, end + dz2, end, step_size, 0.0001)
+        .build()
 }
 
-fn path_p_from_region_min_1_to_region_min_2(
+fn path_p_from_region_n_to_region_n_min_1(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+    state: Option<pxu::State>,
+    n: i32,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let state_string = "(points:[(p:(-0.5,0.0),xp:(-0.000000000000000042434040257275324,0.6930004681646913),xm:(-0.000000000000000042434040257275324,-0.6930004681646913),u:(0.29183016758322633,2.499999999999999),sheet_data:(log_branch_p:-1,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-    state.goto(pxu::Component::P, -0.5, &contours, consts, 4);
-
-    let start = Complex64::from(-0.5);
-    let end = Complex64::from(-1.5);
-
-    let angle = PI / 4.0;
-
-    let dz1 = Complex64::from_polar(0.25, PI - angle);
-    let dz2 = Complex64::from_polar(0.25, angle);
-
-    let path = bezier_path(start, start + dz1, end + dz2, end, 0.01, 0.0001);
-
-    pxu::path::SavedPath::new(
-        "p from region -1 to region -2",
-        path,
-        state,
-        pxu::Component::P,
-        0,
-        consts,
-    )
+    let start = n as f64 + 0.5;
+    let end = n as f64 - 0.5;
+    if n == 0 {
+        path_p_between_regions(
+            contour_provider,
+            consts,
+            state,
+            start,
+            end,
+            PI / 14.0,
+            0.001,
+        )
+    } else {
+        path_p_between_regions(contour_provider, consts, state, start, end, PI / 4.0, 0.01)
+    }
 }
 
-fn path_p_from_region_min_1_to_region_min_2_conj(
+fn path_p_from_region_n_to_region_n_plus_1(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+    state: Option<pxu::State>,
+    n: i32,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let state_string = "(points:[(p:(-0.5,0.0),xp:(-0.000000000000000042434040257275324,0.6930004681646913),xm:(-0.000000000000000042434040257275324,-0.6930004681646913),u:(0.29183016758322633,2.499999999999999),sheet_data:(log_branch_p:-1,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-    state.goto(pxu::Component::P, -0.5, &contours, consts, 4);
-
-    let start = Complex64::from(-0.5);
-    let end = Complex64::from(-1.5);
-
-    let angle = PI / 4.0;
-
-    let dz1 = Complex64::from_polar(0.25, PI - angle);
-    let dz2 = Complex64::from_polar(0.25, angle);
-
-    let path = bezier_path(
-        start,
-        start + dz1.conj(),
-        end + dz2.conj(),
-        end,
-        0.01,
-        0.0001,
-    );
-
-    pxu::path::SavedPath::new(
-        "p from region -1 to region -2 conj",
-        path,
-        state,
-        pxu::Component::P,
-        0,
-        consts,
-    )
+    let start = n as f64 + 0.5;
+    let end = n as f64 + 1.5;
+    path_p_between_regions(contour_provider, consts, state, start, end, PI / 4.0, 0.01)
 }
 
-fn path_p_from_region_min_2_to_region_min_3(
-    contour_provider: std::sync::Arc<ContourProvider>,
-) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
+const P_FROM_REGION_N_TO_REGION_N_MIN_1: crate::PathFamily = crate::PathFamily {
+    name: "p from region {n} to region {n-1}",
+    indices: &[0, -1, -2],
+    chained: true,
+    path_func: path_p_from_region_n_to_region_n_min_1,
+};
 
-    let state_string = "(points:[(p:(-1.5,0.0),xp:(-0.000000000000000051994006857876043,0.2830421903092184),xm:(-0.000000000000000051994006857876043,-0.2830421903092184),u:(1.0043944658480892,-0.0000000000000008881784197001252),sheet_data:(log_branch_p:-1,log_branch_m:-1,e_branch:1,u_branch:(Inside,Inside),im_x_sign:(-1,-1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-    state.goto(pxu::Component::P, -1.5, &contours, consts, 4);
-
-    let start = Complex64::from(-1.5);
-    let end = Complex64::from(-2.5);
-
-    let angle = PI / 4.0;
-
-    let dz1 = Complex64::from_polar(0.25, PI - angle);
-    let dz2 = Complex64::from_polar(0.25, angle);
-
-    let path = bezier_path(start, start + dz1, end + dz2, end, 0.01, 0.0001);
-
-    pxu::path::SavedPath::new(
-        "p from region -2 to region -3",
-        path,
-        state,
-        pxu::Component::P,
-        0,
-        consts,
-    )
-}
+const P_FROM_REGION_N_TO_REGION_N_PLUS_1: crate::PathFamily = crate::PathFamily {
+    name: "p from region {n} to region {n+1}",
+    indices: &[0, 1, 2],
+    chained: true,
+    path_func: path_p_from_region_n_to_region_n_plus_1,
+};
 
 fn path_p_from_region_min_2_to_region_min_3_conj(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+    state: pxu::State,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let state_string = "(points:[(p:(-1.5,0.0),xp:(-0.000000000000000051994006857876043,0.2830421903092184),xm:(-0.000000000000000051994006857876043,-0.2830421903092184),u:(1.0043944658480892,-0.0000000000000008881784197001252),sheet_data:(log_branch_p:-2,log_branch_m:-0,e_branch:1,u_branch:(Inside,Inside),im_x_sign:(-1,-1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-    state.goto(pxu::Component::P, -1.5, &contours, consts, 4);
-
     let start = Complex64::from(-1.5);
     let end = Complex64::from(-2.5);
 
@@ -1512,255 +989,101 @@ fn path_p_from_region_min_2_to_region_min_3_conj(
     let dz1 = Complex64::from_polar(0.25, PI - angle);
     let dz2 = Complex64::from_polar(0.25, angle);
 
-    let path = bezier_path(
-        start,
-        start + dz1.conj(),
-        end + dz2.conj(),
-        end,
-        0.01,
-        0.0001,
-    );
-
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "p from region -2 to region -3 conj",
-        path,
-        state,
-        pxu::Component::P,
-        0,
+        &contour_provider,
         consts,
-    )
-}
-
-fn path_p_from_region_0_to_region_plus_1(
-    contour_provider: std::sync::Arc<ContourProvider>,
-) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let state_string = "(points:[(p:(0.5,0.0),xp:(0.00000000000000013494188523791627,2.2037682265918312),xm:(0.00000000000000013494188523791627,-2.2037682265918312),u:(-0.6287962926300276,0.0),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-    state.goto(pxu::Component::P, 0.5, &contours, consts, 4);
-
-    let start = Complex64::from(0.5);
-    let end = Complex64::from(1.5);
-
-    let angle = PI / 4.0;
-
-    let dz1 = Complex64::from_polar(0.25, angle);
-    let dz2 = Complex64::from_polar(0.25, PI - angle);
-
-    let path = bezier_path(start, start + dz1, end + dz2, end, 0.01, 0.0001);
-
-    pxu::path::SavedPath::new(
-        "p from region 0 to region +1",
-        path,
-        state,
         pxu::Component::P,
-        0,
-        consts,
     )
+    .with_state(state)
+    .goto(pxu::Component::P, start, 4)
+    .move_to(start)
+    .bezier_to(start + dz1.conj(), end + dz2.conj(), end, 0.01, 0.0001)
+    .build()
 }
 
-fn path_p_from_region_plus_1_to_region_plus_2(
+fn path_p_period_1(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let state_string = "(points:[(p:(1.5,0.0),xp:(0.0000000000000008217753744999823,4.4735367785069915),xm:(0.0000000000000008217753744999823,-4.4735367785069915),u:(-1.19221322318503,-2.500000000000001),sheet_data:(log_branch_p:1,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(-1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-    state.goto(pxu::Component::P, 1.5, &contours, consts, 4);
-
-    let start = Complex64::from(1.5);
-    let end = Complex64::from(2.5);
-
-    let angle = PI / 4.0;
-
-    let dz1 = Complex64::from_polar(0.25, angle);
-    let dz2 = Complex64::from_polar(0.25, PI - angle);
-
-    let path = bezier_path(start, start + dz1, end + dz2, end, 0.01, 0.0001);
+    let state_str = "(points:[(p:(-0.105,0.0),xp:(-1.4091784817114132,0.48246963269997417),xm:(-1.4091784817114132,-0.48246963269997417),u:(-2.932123375880603,6.999999999999999),sheet_data:(log_branch_p:-1,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,1)))],unlocked:false)";
 
-    pxu::path::SavedPath::new(
-        "p from region +1 to region +2",
-        path,
-        state,
-        pxu::Component::P,
-        0,
-        consts,
-    )
+    PathBuilder::new("p period 1", &contour_provider, consts, pxu::Component::P)
+        .with_state_str(state_str)
+        .goto(pxu::Component::P, -0.105, 1)
+        .move_to(-0.105)
+        .bezier_to(
+            Complex64::new(-0.105, 0.125),
+            Complex64::new(-0.055, 0.125),
+            Complex64::from(-0.055),
+            0.001,
+            0.001,
+        )
+        .cross_cut(1, |sheet_data| sheet_data.e_branch > 0)
+        .build()
 }
 
-fn path_p_from_region_plus_2_to_region_plus_3(
+fn path_p_period_2(
     contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
 ) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let state_string = "(points:[(p:(2.5,0.0),xp:(0.0000000000000021109947049833357,6.89503196008218),xm:(0.0000000000000021109947049833357,-6.89503196008218),u:(-1.5364827329564084,-5.000000000000001),sheet_data:(log_branch_p:2,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-    state.goto(pxu::Component::P, 2.5, &contours, consts, 4);
-
-    let start = Complex64::from(2.5);
-    let end = Complex64::from(3.5);
-
-    let angle = PI / 4.0;
-
-    let dz1 = Complex64::from_polar(0.25, angle);
-    let dz2 = Complex64::from_polar(0.25, PI - angle);
-
-    let path = bezier_path(start, start + dz1, end + dz2, end, 0.01, 0.0001);
-
-    pxu::path::SavedPath::new(
-        "p from region +2 to region +3",
-        path,
-        state,
-        pxu::Component::P,
-        0,
-        consts,
-    )
-}
-
-fn path_p_period_1(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(1.0, 7);
-    let contours = contour_provider.get(consts).unwrap();
-
     let state_str = "(points:[(p:(-0.105,0.0),xp:(-1.4091784817114132,0.48246963269997417),xm:(-1.4091784817114132,-0.48246963269997417),u:(-2.932123375880603,6.999999999999999),sheet_data:(log_branch_p:-1,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,1)))],unlocked:false)";
-    let mut state: pxu::State = load_state(state_str).unwrap();
-    state.goto(pxu::Component::P, -0.105, &contours, consts, 1);
-
-    let full_path = bezier_path(
-        Complex64::from(-0.105),
-        Complex64::new(-0.105, 0.125),
-        Complex64::new(-0.055, 0.125),
-        Complex64::from(-0.055),
-        0.001,
-        0.001,
-    );
 
-    let start = state.clone();
-
-    let mut path = vec![];
-    for p in full_path.into_iter() {
-        state.goto(pxu::Component::P, p, &contours, consts, 1);
-        if state.points[0].sheet_data.e_branch < 0 {
-            break;
-        }
-        path.push(p);
-    }
-
-    pxu::path::SavedPath::new("p period 1", path, start, pxu::Component::P, 0, consts)
+    PathBuilder::new("p period 2", &contour_provider, consts, pxu::Component::P)
+        .with_state_str(state_str)
+        .goto(pxu::Component::P, -0.105, 1)
+        .move_to(-0.105)
+        .bezier_to(
+            Complex64::new(-0.105, 0.125),
+            Complex64::new(-0.055, 0.125),
+            Complex64::from(-0.055),
+            0.001,
+            0.001,
+        )
+        .cross_cut(1, |sheet_data| sheet_data.e_branch < 0)
+        .build()
 }
 
-fn path_p_period_2(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(1.0, 7);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let state_str = "(points:[(p:(-0.105,0.0),xp:(-1.4091784817114132,0.48246963269997417),xm:(-1.4091784817114132,-0.48246963269997417),u:(-2.932123375880603,6.999999999999999),sheet_data:(log_branch_p:-1,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,1)))],unlocked:false)";
-    let mut state: pxu::State = load_state(state_str).unwrap();
-    state.goto(pxu::Component::P, -0.105, &contours, consts, 1);
-
-    let full_path = bezier_path(
-        Complex64::from(-0.105),
-        Complex64::new(-0.105, 0.125),
-        Complex64::new(-0.055, 0.125),
-        Complex64::from(-0.055),
-        0.001,
-        0.001,
-    );
-
-    let mut start = None;
-
-    let mut path = vec![];
-    for p in full_path.into_iter() {
-        state.goto(pxu::Component::P, p, &contours, consts, 1);
-        if state.points[0].sheet_data.e_branch < 0 {
-            path.push(p);
-            if start.is_none() {
-                start = Some(state.clone());
-            }
-        }
-    }
-
-    pxu::path::SavedPath::new(
-        "p period 2",
-        path,
-        start.unwrap(),
-        pxu::Component::P,
-        0,
-        consts,
-    )
-}
-
-fn path_p_period_3(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(1.0, 7);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_p_period_3(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let state_str = "(points:[(p:(-0.055,0.0),xp:(0.2566971058663987,-0.0448008168070112),xm:(0.2566971058663987,0.0448008168070112),u:(7.033751628965735,0.0000000000000002220446049250313),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:-1,u_branch:(Between,Between),im_x_sign:(-1,-1)))],unlocked:false)";
-    let mut state: pxu::State = load_state(state_str).unwrap();
-    state.goto(pxu::Component::P, -0.055, &contours, consts, 1);
-
-    let full_path = bezier_path(
-        Complex64::from(-0.055),
-        Complex64::new(-0.055, -0.125),
-        Complex64::new(-0.105, -0.125),
-        Complex64::from(-0.105),
-        0.001,
-        0.001,
-    );
 
-    let start = state.clone();
-
-    let mut path = vec![];
-    for p in full_path.into_iter() {
-        state.goto(pxu::Component::P, p, &contours, consts, 1);
-        if state.points[0].sheet_data.e_branch > 0 {
-            break;
-        }
-        path.push(p);
-    }
-
-    pxu::path::SavedPath::new("p period 3", path, start, pxu::Component::P, 0, consts)
+    PathBuilder::new("p period 3", &contour_provider, consts, pxu::Component::P)
+        .with_state_str(state_str)
+        .goto(pxu::Component::P, -0.055, 1)
+        .move_to(-0.055)
+        .bezier_to(
+            Complex64::new(-0.055, -0.125),
+            Complex64::new(-0.105, -0.125),
+            Complex64::from(-0.105),
+            0.001,
+            0.001,
+        )
+        .cross_cut(1, |sheet_data| sheet_data.e_branch < 0)
+        .build()
 }
 
-fn path_p_period_4(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(1.0, 7);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_p_period_4(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let state_str = "(points:[(p:(-0.055,0.0),xp:(0.2566971058663987,-0.0448008168070112),xm:(0.2566971058663987,0.0448008168070112),u:(7.033751628965735,0.0000000000000002220446049250313),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:-1,u_branch:(Between,Between),im_x_sign:(-1,-1)))],unlocked:false)";
-    let mut state: pxu::State = load_state(state_str).unwrap();
-    state.goto(pxu::Component::P, -0.055, &contours, consts, 1);
-
-    let full_path = bezier_path(
-        Complex64::from(-0.055),
-        Complex64::new(-0.055, -0.125),
-        Complex64::new(-0.105, -0.125),
-        Complex64::from(-0.105),
-        0.001,
-        0.001,
-    );
-
-    let mut start = None;
 
-    let mut path = vec![];
-    for p in full_path.into_iter() {
-        state.goto(pxu::Component::P, p, &contours, consts, 1);
-        if state.points[0].sheet_data.e_branch > 0 {
-            path.push(p);
-            if start.is_none() {
-                start = Some(state.clone());
-            }
-        }
-    }
-
-    pxu::path::SavedPath::new(
-        "p period 4",
-        path,
-        start.unwrap(),
-        pxu::Component::P,
-        0,
-        consts,
-    )
+    PathBuilder::new("p period 4", &contour_provider, consts, pxu::Component::P)
+        .with_state_str(state_str)
+        .goto(pxu::Component::P, -0.055, 1)
+        .move_to(-0.055)
+        .bezier_to(
+            Complex64::new(-0.055, -0.125),
+            Complex64::new(-0.105, -0.125),
+            Complex64::from(-0.105),
+            0.001,
+            0.001,
+        )
+        .cross_cut(1, |sheet_data| sheet_data.e_branch > 0)
+        .build()
 }
 
 fn create_simple_path(consts: CouplingConstants) -> Vec<Complex64> {
@@ -1797,361 +1120,279 @@ fn create_simple_path(consts: CouplingConstants) -> Vec<Complex64> {
     path
 }
 
-fn path_u_simple_path_1(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_u_simple_path_1(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let state_string = "(points:[(p:(0.03183116464430967,0.000000000000000000022253468878373952),xp:(5.943002840303245,0.5962925607612622),xm:(5.943002840303245,-0.5962925607612622),u:(4.687364046788472,0.0),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-
-    let mut start: Option<pxu::State> = None;
-
-    let mut path = vec![];
-    for u in create_simple_path(consts) {
-        state.goto(pxu::Component::U, u, &contours, consts, 4);
-        if state.points[0].sheet_data.u_branch == (UBranch::Outside, UBranch::Outside) {
-            if start.is_none() {
-                start = Some(state.clone());
-            }
-            path.push(u);
-        }
-    }
 
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "u simple path 1",
-        path,
-        start.unwrap(),
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .with_state_str(state_string)
+    .extend(create_simple_path(consts))
+    .cross_cut(4, |sheet_data| {
+        sheet_data.u_branch == (UBranch::Outside, UBranch::Outside)
+    })
+    .build()
 }
 
-fn path_u_simple_path_2(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_u_simple_path_2(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let state_string = "(points:[(p:(0.03183116464430967,0.000000000000000000022253468878373952),xp:(5.943002840303245,0.5962925607612622),xm:(5.943002840303245,-0.5962925607612622),u:(4.687364046788472,0.0),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-
-    let mut start: Option<pxu::State> = None;
-
-    let mut path = vec![];
-    for u in create_simple_path(consts) {
-        state.goto(pxu::Component::U, u, &contours, consts, 4);
-        if state.points[0].sheet_data.u_branch == (UBranch::Between, UBranch::Outside) {
-            if start.is_none() {
-                start = Some(state.clone());
-            }
-            path.push(u);
-        }
-    }
 
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "u simple path 2",
-        path,
-        start.unwrap(),
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .with_state_str(state_string)
+    .extend(create_simple_path(consts))
+    .cross_cut(4, |sheet_data| {
+        sheet_data.u_branch == (UBranch::Between, UBranch::Outside)
+    })
+    .build()
 }
 
-fn path_u_simple_path_3(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_u_simple_path_3(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let state_string = "(points:[(p:(0.03183116464430967,0.000000000000000000022253468878373952),xp:(5.943002840303245,0.5962925607612622),xm:(5.943002840303245,-0.5962925607612622),u:(4.687364046788472,0.0),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-
-    let mut start: Option<pxu::State> = None;
-
-    let mut path = vec![];
-    for u in create_simple_path(consts) {
-        state.goto(pxu::Component::U, u, &contours, consts, 4);
-        let sheet_data = &state.points[0].sheet_data;
-        if sheet_data.u_branch == (UBranch::Between, UBranch::Between)
-            && sheet_data.log_branch_p == 0
-        {
-            if start.is_none() {
-                start = Some(state.clone());
-            }
-            path.push(u);
-        }
-    }
 
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "u simple path 3",
-        path,
-        start.unwrap(),
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .with_state_str(state_string)
+    .extend(create_simple_path(consts))
+    .cross_cut(4, |sheet_data| {
+        sheet_data.u_branch == (UBranch::Between, UBranch::Between) && sheet_data.log_branch_p == 0
+    })
+    .build()
 }
 
-fn path_u_simple_path_4(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
+fn path_u_simple_path_4(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let state_string = "(points:[(p:(0.03183116464430967,0.000000000000000000022253468878373952),xp:(5.943002840303245,0.5962925607612622),xm:(5.943002840303245,-0.5962925607612622),u:(4.687364046788472,0.0),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Outside,Outside),im_x_sign:(1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
-
-    let mut start: Option<pxu::State> = None;
-
-    let mut path = vec![];
-    for u in create_simple_path(consts) {
-        state.goto(pxu::Component::U, u, &contours, consts, 4);
-        let sheet_data = &state.points[0].sheet_data;
-        if sheet_data.u_branch == (UBranch::Between, UBranch::Between)
-            && sheet_data.log_branch_p == -1
-        {
-            if start.is_none() {
-                start = Some(state.clone());
-            }
-            path.push(u);
-        }
-    }
 
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "u simple path 4",
-        path,
-        start.unwrap(),
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .with_state_str(state_string)
+    .extend(create_simple_path(consts))
+    .cross_cut(4, |sheet_data| {
+        sheet_data.u_branch == (UBranch::Between, UBranch::Between) && sheet_data.log_branch_p == -1
+    })
+    .build()
 }
 
-fn path_xp_large_circle(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.goto(pxu::Component::P, 0.075, &contours, consts, 4);
-
-    let xp = state.points[0].xp;
-
-    let steps = 83;
-    let path = (0..=steps)
-        .map(|n| {
-            let angle = 2.0 * PI * n as f64 / steps as f64;
-            xp * Complex64::exp(Complex64::new(0.0, angle))
-        })
-        .collect::<Vec<_>>();
-
-    pxu::path::SavedPath::new(
+fn path_xp_large_circle(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
+    let builder = PathBuilder::new(
         "xp large circle",
-        path,
-        state,
-        pxu::Component::Xp,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::Xp,
     )
-}
-
-fn path_xp_smaller_circle(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(2.0, 5);
-    let contours = contour_provider.get(consts).unwrap();
-
-    let mut state = pxu::State::new(1, consts);
-    state.goto(pxu::Component::P, 0.32706, &contours, consts, 14);
-
-    let xp = state.points[0].xp;
+    .goto(pxu::Component::P, 0.075, 4);
+    let xp = builder.current();
 
     let steps = 83;
-    let path = (0..=steps)
-        .map(|n| {
-            let angle = 2.0 * PI * n as f64 / steps as f64;
-            xp * Complex64::exp(Complex64::new(0.0, angle))
-        })
-        .collect::<Vec<_>>();
+    builder
+        .circle(
+            Complex64::from(0.0),
+            xp.norm(),
+            xp.arg(),
+            xp.arg() + TAU,
+            steps,
+        )
+        .build()
+}
 
-    pxu::path::SavedPath::new(
+fn path_xp_smaller_circle(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
+    let builder = PathBuilder::new(
         "xp smaller circle",
-        path,
-        state,
-        pxu::Component::Xp,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::Xp,
     )
+    .goto(pxu::Component::P, 0.32706, 14);
+    let xp = builder.current();
+
+    let steps = 83;
+    builder
+        .circle(
+            Complex64::from(0.0),
+            xp.norm(),
+            xp.arg(),
+            xp.arg() + TAU,
+            steps,
+        )
+        .build()
 }
 
-fn path_bs3_region_min1_1(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(1.0, 7);
-    let contours = contour_provider.get(consts).unwrap();
+fn path_bs3_region_min1_1(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let state_string = "(points:[(p:(-0.04260723417329058,-0.008849643336329061),xp:(-5.030937834642458,2.1663201765896023),xm:(-5.131368108505251,0.7173396991721674),u:(-8.987960815429684,9.000099999999998),sheet_data:(log_branch_p:-1,log_branch_m:1,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,-1))),(p:(-0.04420722496321523,-0.0000004559509422977896),xp:(-5.131368108505251,0.7173396991721674),xm:(-5.13137320671517,-0.717196003526685),u:(-8.987960815429684,7.000099999999998),sheet_data:(log_branch_p:-1,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,1))),(p:(-0.0426075453771021,0.008848810802642947),xp:(-5.131373206715171,-0.7171960035266854),xm:(-5.030952664137852,-2.166173607614686),u:(-8.987960815429686,5.000100000000001),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
 
     let k = consts.k() as f64;
     let h = consts.h;
     let i = Complex64::i();
 
     let u0 = 4.0;
-    state.goto(
-        pxu::Component::U,
-        -u0 + i * (k + 2.0) / h,
-        &contours,
-        consts,
-        2,
-    );
-
-    let mut start = None;
-
-    let mut path = vec![];
     let steps = 511;
-    for n in 0..=steps {
-        let u = -u0 + i * (k + 2.0) / h + 2.0 * u0 * n as f64 / steps as f64;
-        state.goto(pxu::Component::U, u, &contours, consts, 2);
-        if state.points[0].sheet_data.e_branch > 0 {
-            if start.is_none() {
-                start = Some(state.clone());
-            }
-            path.push(u);
-        }
-    }
+    let path = (0..=steps).map(|n| -u0 + i * (k + 2.0) / h + 2.0 * u0 * n as f64 / steps as f64);
 
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "bs3 region -1 1",
-        path,
-        start.unwrap(),
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .with_state_str(state_string)
+    .goto(pxu::Component::U, -u0 + i * (k + 2.0) / h, 2)
+    .extend(path)
+    .cross_cut(2, |sheet_data| sheet_data.e_branch > 0)
+    .build()
 }
 
-fn path_bs3_region_min1_2(contour_provider: std::sync::Arc<ContourProvider>) -> SavedPath {
-    let consts = CouplingConstants::new(1.0, 7);
-    let contours = contour_provider.get(consts).unwrap();
+fn path_bs3_region_min1_2(
+    contour_provider: std::sync::Arc<ContourProvider>,
+    consts: CouplingConstants,
+) -> SavedPath {
     let state_string = "(points:[(p:(-0.04260723417329058,-0.008849643336329061),xp:(-5.030937834642458,2.1663201765896023),xm:(-5.131368108505251,0.7173396991721674),u:(-8.987960815429684,9.000099999999998),sheet_data:(log_branch_p:-1,log_branch_m:1,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,-1))),(p:(-0.04420722496321523,-0.0000004559509422977896),xp:(-5.131368108505251,0.7173396991721674),xm:(-5.13137320671517,-0.717196003526685),u:(-8.987960815429684,7.000099999999998),sheet_data:(log_branch_p:-1,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(-1,1))),(p:(-0.0426075453771021,0.008848810802642947),xp:(-5.131373206715171,-0.7171960035266854),xm:(-5.030952664137852,-2.166173607614686),u:(-8.987960815429686,5.000100000000001),sheet_data:(log_branch_p:0,log_branch_m:0,e_branch:1,u_branch:(Between,Between),im_x_sign:(1,1)))],unlocked:false)";
-    let mut state = load_state(state_string).unwrap();
 
     let k = consts.k() as f64;
     let h = consts.h;
     let i = Complex64::i();
 
     let u0 = 4.0;
-    state.goto(
-        pxu::Component::U,
-        -u0 + i * (k + 2.0) / h,
-        &contours,
-        consts,
-        2,
-    );
-
-    let mut start = None;
-
-    let mut path = vec![];
     let steps = 511;
-    for n in 0..=steps {
-        let u = -u0 + i * (k + 2.0) / h + 2.0 * u0 * n as f64 / steps as f64;
-        state.goto(pxu::Component::U, u, &contours, consts, 2);
-        if state.points[0].sheet_data.e_branch < 0 {
-            if start.is_none() {
-                start = Some(state.clone());
-            }
-            path.push(u);
-        }
-    }
+    let path = (0..=steps).map(|n| -u0 + i * (k + 2.0) / h + 2.0 * u0 * n as f64 / steps as f64);
 
-    pxu::path::SavedPath::new(
+    PathBuilder::new(
         "bs3 region -1 2",
-        path,
-        start.unwrap(),
-        pxu::Component::U,
-        0,
+        &contour_provider,
         consts,
+        pxu::Component::U,
     )
+    .with_state_str(state_string)
+    .goto(pxu::Component::U, -u0 + i * (k + 2.0) / h, 2)
+    .extend(path)
+    .cross_cut(2, |sheet_data| sheet_data.e_branch < 0)
+    .build()
 }
 
-pub const PLOT_PATHS: &[crate::PathFunction] = &[
-    path_xp_circle_between_between,
-    path_xp_circle_between_between_single,
-    path_xp_circle_between_inside_left,
-    path_xp_circle_between_inside_right,
-    path_xp_circle_between_outside_left,
-    path_xp_circle_between_outside_right,
-    path_x_half_circle_between_1,
-    path_x_half_circle_between_2,
-    path_x_half_circle_between_3,
-    path_x_half_circle_between_4,
-    path_p_circle_origin_e,
-    path_p_circle_origin_not_e,
-    path_u_band_between_inside,
-    path_u_band_between_inside_single,
-    path_u_band_between_outside,
-    path_u_band_between_outside_single,
-    path_u_periodic_between_between,
-    path_u_periodic_between_between_single,
-    path_u_crossing_from_0_a,
-    path_u_crossing_from_0_b,
-    path_u_crossing_from_min_1,
-    path_p_crossing_a,
-    path_p_crossing_b,
-    path_p_crossing_c,
-    path_p_crossing_d,
-    path_u_vertical_outside,
-    path_u_vertical_between,
-    path_u_vertical_inside,
-    path_p_from_region_0_to_region_min_1,
-    path_p_from_region_min_1_to_region_min_2,
-    path_p_from_region_min_2_to_region_min_3,
-    path_p_from_region_0_to_region_plus_1,
-    path_p_from_region_plus_1_to_region_plus_2,
-    path_p_from_region_plus_2_to_region_plus_3,
-    path_p_period_1,
-    path_p_period_2,
-    path_p_period_3,
-    path_p_period_4,
-    path_u_simple_path_1,
-    path_u_simple_path_2,
-    path_u_simple_path_3,
-    path_u_simple_path_4,
-    path_xp_large_circle,
-    path_xp_smaller_circle,
-    path_bs3_region_min1_1,
-    path_bs3_region_min1_2,
+// Most paths are drawn for h = 2, k = 5 but can be made for any coupling
+// constants. The ones starting from a saved state only work for the coupling
+// constants of that state.
+const H2_K5: PathConsts = PathConsts::Default(CouplingConstants::new(2.0, 5));
+const H2_K5_FIXED: PathConsts = PathConsts::Fixed(CouplingConstants::new(2.0, 5));
+const H1_K7_FIXED: PathConsts = PathConsts::Fixed(CouplingConstants::new(1.0, 7));
+
+pub const PLOT_PATHS: &[crate::PathDefinition] = &[
+    Start(path_xp_circle_between_between, H2_K5),
+    Start(path_xp_circle_between_between_single, H2_K5),
+    Start(path_xp_circle_between_inside_left, H2_K5),
+    Start(path_xp_circle_between_inside_right, H2_K5),
+    Start(path_xp_circle_between_outside_left, H2_K5),
+    Start(path_xp_circle_between_outside_right, H2_K5),
+    Start(path_x_half_circle_between_1, H2_K5),
+    Start(path_x_half_circle_between_2, H2_K5),
+    Start(path_x_half_circle_between_3, H2_K5),
+    Start(path_x_half_circle_between_4, H2_K5),
+    Start(path_p_circle_origin_e, H2_K5),
+    Start(path_p_circle_origin_not_e, H2_K5),
+    Start(path_u_band_between_inside, H2_K5),
+    Start(path_u_band_between_inside_single, H2_K5),
+    Start(path_u_band_between_outside, H2_K5),
+    Start(path_u_band_between_outside_single, H2_K5),
+    Start(path_u_periodic_between_between, H2_K5),
+    Start(path_u_periodic_between_between_single, H2_K5),
+    Start(path_u_crossing_from_0_a, H2_K5),
+    Start(path_u_crossing_from_0_b, H2_K5),
+    Start(path_u_crossing_from_min_1, H2_K5),
+    Start(path_p_crossing_a, H2_K5),
+    Start(path_p_crossing_b, H2_K5),
+    Start(path_p_crossing_c, H2_K5),
+    Start(path_p_crossing_d, H2_K5),
+    Start(path_u_vertical_outside, H2_K5),
+    Start(path_u_vertical_between, H2_K5),
+    Start(path_u_vertical_inside, H2_K5),
+    Family(&P_FROM_REGION_N_TO_REGION_N_MIN_1, H2_K5_FIXED),
+    Family(&P_FROM_REGION_N_TO_REGION_N_PLUS_1, H2_K5_FIXED),
+    Start(path_p_period_1, H1_K7_FIXED),
+    Start(path_p_period_2, H1_K7_FIXED),
+    Start(path_p_period_3, H1_K7_FIXED),
+    Start(path_p_period_4, H1_K7_FIXED),
+    Start(path_u_simple_path_1, H2_K5_FIXED),
+    Start(path_u_simple_path_2, H2_K5_FIXED),
+    Start(path_u_simple_path_3, H2_K5_FIXED),
+    Start(path_u_simple_path_4, H2_K5_FIXED),
+    Start(path_xp_large_circle, H2_K5),
+    Start(path_xp_smaller_circle, H2_K5),
+    Start(path_bs3_region_min1_1, H1_K7_FIXED),
+    Start(path_bs3_region_min1_2, H1_K7_FIXED),
 ];
 
-pub const INTERACTIVE_PATHS: &[crate::PathFunction] = &[
-    path_xp_circle_between_between,
-    path_xp_circle_between_between_single,
-    path_xp_circle_between_inside_left,
-    path_xp_circle_between_inside_right,
-    path_xp_circle_between_outside_left,
-    path_xp_circle_between_outside_right,
-    path_p_circle_origin_e,
-    path_p_circle_origin_not_e,
-    path_u_band_between_inside,
-    path_u_band_between_outside,
-    path_u_periodic_between_between,
-    path_u_crossing_from_0_b,
-    path_u_crossing_from_0_a,
-    path_u_crossing_from_min_1,
-    path_p_crossing_a,
-    path_p_crossing_b,
-    path_p_crossing_c,
-    path_p_crossing_d,
-    path_u_vertical_between,
-    path_x_half_circle_between_1,
-    path_x_half_circle_between_2,
-    path_x_half_circle_between_3,
-    path_x_half_circle_between_4,
-    path_p_from_region_0_to_region_min_1,
-    path_p_from_region_min_1_to_region_min_2,
-    path_p_from_region_min_2_to_region_min_3,
-    path_p_from_region_0_to_region_plus_1,
-    path_p_from_region_plus_1_to_region_plus_2,
-    path_p_from_region_plus_2_to_region_plus_3,
-    path_p_from_region_min_1_to_region_min_2_conj,
-    path_p_from_region_min_2_to_region_min_3_conj,
-    path_u_simple_path_1,
-    path_u_simple_path_2,
-    path_u_simple_path_3,
-    path_u_simple_path_4,
-    path_xp_large_circle,
-    path_xp_smaller_circle,
-    path_bs3_region_min1_1,
-    path_bs3_region_min1_2,
+pub const INTERACTIVE_PATHS: &[crate::PathDefinition] = &[
+    Start(path_xp_circle_between_between, H2_K5),
+    Start(path_xp_circle_between_between_single, H2_K5),
+    Start(path_xp_circle_between_inside_left, H2_K5),
+    Start(path_xp_circle_between_inside_right, H2_K5),
+    Start(path_xp_circle_between_outside_left, H2_K5),
+    Start(path_xp_circle_between_outside_right, H2_K5),
+    Start(path_p_circle_origin_e, H2_K5),
+    Start(path_p_circle_origin_not_e, H2_K5),
+    Start(path_u_band_between_inside, H2_K5),
+    Start(path_u_band_between_outside, H2_K5),
+    Start(path_u_periodic_between_between, H2_K5),
+    Start(path_u_crossing_from_0_b, H2_K5),
+    Start(path_u_crossing_from_0_a, H2_K5),
+    Start(path_u_crossing_from_min_1, H2_K5),
+    Start(path_p_crossing_a, H2_K5),
+    Start(path_p_crossing_b, H2_K5),
+    Start(path_p_crossing_c, H2_K5),
+    Start(path_p_crossing_d, H2_K5),
+    Start(path_u_vertical_between, H2_K5),
+    Start(path_x_half_circle_between_1, H2_K5),
+    Start(path_x_half_circle_between_2, H2_K5),
+    Start(path_x_half_circle_between_3, H2_K5),
+    Start(path_x_half_circle_between_4, H2_K5),
+    Family(&P_FROM_REGION_N_TO_REGION_N_MIN_1, H2_K5_FIXED),
+    Family(&P_FROM_REGION_N_TO_REGION_N_PLUS_1, H2_K5_FIXED),
+    Variant("p from region -1 to region -2", crate::Variant::Conj),
+    Continue(
+        "p from region -1 to region -2 conj",
+        path_p_from_region_min_2_to_region_min_3_conj,
+    ),
+    Start(path_u_simple_path_1, H2_K5_FIXED),
+    Start(path_u_simple_path_2, H2_K5_FIXED),
+    Start(path_u_simple_path_3, H2_K5_FIXED),
+    Start(path_u_simple_path_4, H2_K5_FIXED),
+    Start(path_xp_large_circle, H2_K5),
+    Start(path_xp_smaller_circle, H2_K5),
+    Start(path_bs3_region_min1_1, H1_K7_FIXED),
+    Start(path_bs3_region_min1_2, H1_K7_FIXED),
 ];
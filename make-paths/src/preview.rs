@@ -0,0 +1,111 @@
+use egui::{vec2, Pos2, Rect};
+use pxu::kinematics::CouplingConstants;
+use pxu::path::SavedPath;
+use std::io::Result;
+
+use crate::paths::error;
+use crate::PxuProvider;
+
+// The size of the previews in points
+const PREVIEW_SIZE: f32 = 240.0;
+
+// A plot showing all of the path, starting from the view used by the app
+fn preview_plot(component: pxu::Component, path: &pxu::Path) -> plot::Plot {
+    let width_factor = if component == pxu::Component::P {
+        1.5
+    } else {
+        1.0
+    };
+
+    let points = path
+        .segments
+        .iter()
+        .flatten()
+        .flat_map(|segment| segment.get(component).into_owned())
+        .map(|z| egui::pos2(z.re as f32, -z.im as f32))
+        .collect::<Vec<_>>();
+    let bounding_box = Rect::from_points(&points);
+
+    const MARGIN: f32 = 1.2;
+    const MIN_HEIGHT: f32 = 0.1;
+    let (origin, height) = if bounding_box.is_finite() {
+        let height = bounding_box
+            .height()
+            .max(bounding_box.width() / width_factor);
+        (bounding_box.center(), (MARGIN * height).max(MIN_HEIGHT))
+    } else {
+        (Pos2::ZERO, 1.0)
+    };
+
+    plot::Plot {
+        component,
+        height,
+        width_factor,
+        origin,
+    }
+}
+
+// Draws previews of the paths. The contours for each set of coupling constants
+// are only copied once, and the same renderer is used for all the images.
+pub struct Previewer<'a> {
+    pxu_provider: &'a PxuProvider,
+    renderer: plot::Renderer,
+    pxus: Vec<pxu::Pxu>,
+}
+
+impl<'a> Previewer<'a> {
+    pub fn new(pxu_provider: &'a PxuProvider) -> Self {
+        Self {
+            pxu_provider,
+            renderer: plot::Renderer::default(),
+            pxus: vec![],
+        }
+    }
+
+    // The index in pxus of the copy for the given coupling constants
+    fn pxu_index(&mut self, consts: CouplingConstants) -> Result<usize> {
+        match self.pxus.iter().position(|pxu| pxu.consts == consts) {
+            Some(index) => Ok(index),
+            None => {
+                let mut pxu = pxu::Pxu::new(consts);
+                pxu.contours = (*self.pxu_provider.get_contours(consts)?).clone();
+                self.pxus.push(pxu);
+                Ok(self.pxus.len() - 1)
+            }
+        }
+    }
+
+    // Draw the path in each of the given planes as PNG images, with the
+    // excitations at the start of the path
+    pub fn preview_png(
+        &mut self,
+        saved_path: &SavedPath,
+        components: &[pxu::Component],
+    ) -> Result<Vec<Vec<u8>>> {
+        let path = self.pxu_provider.get_path(&saved_path.name)?;
+
+        let index = self.pxu_index(saved_path.consts)?;
+        let pxu = &mut self.pxus[index];
+        pxu.state = path
+            .first_state()
+            .ok_or_else(|| error(&format!("The path \"{}\" is empty", saved_path.name)))?;
+        pxu.paths = vec![(*path).clone()];
+
+        let mut plot_state = plot::PlotState::default();
+        plot_state.active_point = saved_path.excitation;
+        plot_state.path_indices = vec![0];
+        let settings = plot::ExportSettings::default();
+        let size = vec2(PREVIEW_SIZE, PREVIEW_SIZE);
+
+        let pxu = &*pxu;
+        components
+            .iter()
+            .map(|&component| {
+                let plot = preview_plot(component, &path);
+                self.renderer
+                    .to_png(&plot, size, &settings, pxu, &plot_state)
+                    .ok_or_else(|| error(&format!("Could not draw \"{}\"", saved_path.name)))
+            })
+            .collect()
+    }
+}
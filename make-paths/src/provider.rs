@@ -43,19 +43,50 @@ impl From<CouplingConstants> for LossyHashCouplingConstants {
 pub struct PxuProvider {
     contours: Arc<ContourProvider>,
     paths: Arc<PathProvider>,
+    usage: Option<Arc<Mutex<ProviderUsage>>>,
+}
+
+// The coupling constants of the contours, and the names of the paths and start
+// states, that were used through a recording provider, in the order they were
+// first used
+#[derive(Debug, Default, Clone)]
+pub struct ProviderUsage {
+    pub consts: Vec<CouplingConstants>,
+    pub paths: Vec<String>,
+    pub starts: Vec<String>,
+}
+
+fn record<T: PartialEq>(list: &mut Vec<T>, item: T) {
+    if !list.contains(&item) {
+        list.push(item);
+    }
 }
 
 #[derive(Default)]
 pub struct ContourProvider {
     contours: HashMap<LossyHashCouplingConstants, Arc<pxu::Contours>>,
+    // A hash of the saved contours, which is part of the key of the cached
+    // paths
+    hashes: HashMap<LossyHashCouplingConstants, String>,
     seen_contours: Arc<Mutex<HashSet<LossyHashCouplingConstants>>>,
 }
 
 impl ContourProvider {
     pub fn add(&mut self, consts: pxu::CouplingConstants, contours: pxu::Contours) {
+        let hash = ron::to_string(&contours)
+            .map(|s| format!("{:x}", md5::compute(s)))
+            .unwrap_or_default();
+        self.hashes.insert(consts.into(), hash);
         self.contours.insert(consts.into(), Arc::new(contours));
     }
 
+    pub fn get_hash(&self, consts: pxu::CouplingConstants) -> Result<&str> {
+        self.hashes
+            .get(&consts.into())
+            .map(|hash| hash.as_str())
+            .ok_or_else(|| error(&format!("Could not find contour for {consts:?}")))
+    }
+
     pub fn get(&self, consts: pxu::CouplingConstants) -> Result<Arc<pxu::Contours>> {
         self.seen_contours.lock().unwrap().insert(consts.into());
 
@@ -102,6 +133,9 @@ impl PxuProvider {
     }
 
     pub fn get_contours(&self, consts: pxu::CouplingConstants) -> Result<Arc<pxu::Contours>> {
+        if let Some(usage) = &self.usage {
+            record(&mut usage.lock().unwrap().consts, consts);
+        }
         self.contours.get(consts)
     }
 
@@ -112,13 +146,81 @@ impl PxuProvider {
     }
 
     pub fn get_path(&self, name: &str) -> Result<Arc<pxu::Path>> {
+        if let Some(usage) = &self.usage {
+            record(&mut usage.lock().unwrap().paths, name.to_owned());
+        }
         self.paths.get_path(name)
     }
 
     pub fn get_start(&self, name: &str) -> Result<Arc<pxu::State>> {
+        if let Some(usage) = &self.usage {
+            record(&mut usage.lock().unwrap().starts, name.to_owned());
+        }
         self.paths.get_start(name)
     }
 
+    // A provider sharing the contours and paths of this one, which records
+    // what is used through it
+    pub fn recording(&self) -> Self {
+        Self {
+            contours: self.contours.clone(),
+            paths: self.paths.clone(),
+            usage: Some(Default::default()),
+        }
+    }
+
+    pub fn get_usage(&self) -> ProviderUsage {
+        self.usage
+            .as_ref()
+            .map(|usage| usage.lock().unwrap().clone())
+            .unwrap_or_default()
+    }
+
+    pub fn get_saved_paths(&self) -> &[pxu::path::SavedPath] {
+        self.paths.get_saved_paths()
+    }
+
+    pub fn get_path_names(&self) -> &[String] {
+        self.paths.get_path_names()
+    }
+
+    // The names that are not the name of any defined path, which also works
+    // when only the names of the paths were loaded
+    pub fn find_missing_paths<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+        let path_names = self.get_path_names();
+        names
+            .into_iter()
+            .filter(|name| !path_names.iter().any(|path_name| path_name == name))
+            .collect()
+    }
+
+    pub fn get_path_statistics_table(&self) -> String {
+        self.paths.get_statistics_table()
+    }
+
+    pub fn get_verification(&self) -> &[(String, crate::Verification)] {
+        self.paths.get_verification()
+    }
+
+    pub fn get_failures(&self) -> &[crate::FailedPath] {
+        self.paths.get_failures()
+    }
+
+    // Regenerate the paths and compare them to the cached paths instead of
+    // using the cache
+    pub fn verify_paths(&mut self, tolerance: f64) {
+        Arc::get_mut(&mut self.paths).unwrap().verify(tolerance);
+    }
+
+    pub fn set_continuation_method(&mut self, method: pxu::ContinuationMethod) {
+        Arc::get_mut(&mut self.paths).unwrap().set_method(method);
+    }
+
+    // Only generate the paths with names matching one of the patterns
+    pub fn select_paths(&mut self, patterns: Vec<String>) {
+        Arc::get_mut(&mut self.paths).unwrap().select(patterns);
+    }
+
     pub fn generate_contours(
         &mut self,
         consts_list: Vec<CouplingConstants>,
@@ -136,7 +238,7 @@ impl PxuProvider {
 
     pub fn load_paths(
         &mut self,
-        paths: &[crate::PathFunction],
+        paths: &[crate::PathDefinition],
         verbose: bool,
         pool: &threadpool::ThreadPool,
         cache_dir: &str,
@@ -0,0 +1,33 @@
+use pxu::path::SavedPath;
+
+// A path derived from another path, which is generated once the path it is
+// derived from is known
+#[derive(Clone, Copy, Debug)]
+pub enum Variant {
+    // The path reflected in the real axis, for a path that starts on the real
+    // axis
+    Conj,
+    // The path followed backwards from where it ends
+    Reversed,
+}
+
+impl Variant {
+    pub fn name(&self, base: &str) -> String {
+        match self {
+            Self::Conj => format!("{base} conj"),
+            Self::Reversed => format!("{base} reversed"),
+        }
+    }
+
+    pub(crate) fn build(&self, base: SavedPath, end_state: pxu::State) -> SavedPath {
+        let name = self.name(&base.name);
+        match self {
+            Self::Conj => {
+                let base_name = base.name.clone();
+                base.conj(name)
+                    .unwrap_or_else(|| panic!("{base_name} does not start on the real axis"))
+            }
+            Self::Reversed => base.reversed(name, end_state),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use egui::Color32;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum CutColorPreset {
+    #[default]
+    Standard,
+    ColorblindSafe,
+    Custom,
+}
+
+impl CutColorPreset {
+    pub const ALL: [Self; 3] = [Self::Standard, Self::ColorblindSafe, Self::Custom];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::ColorblindSafe => "Colorblind safe",
+            Self::Custom => "Custom",
+        }
+    }
+}
+
+// The colors of the cuts, used both on screen and in exported figures. The
+// first color of each pair is used for the cuts of x⁺ and the second for x⁻.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct CutColors {
+    pub preset: CutColorPreset,
+    pub e: Color32,
+    pub log: (Color32, Color32),
+    pub scallion: (Color32, Color32),
+    pub kidney: (Color32, Color32),
+    pub long: (Color32, Color32),
+}
+
+impl CutColors {
+    pub fn standard(dark: bool) -> Self {
+        let (xp, xm) = if dark {
+            (
+                Color32::from_rgb(255, 90, 90),
+                Color32::from_rgb(60, 220, 60),
+            )
+        } else {
+            (Color32::from_rgb(255, 0, 0), Color32::from_rgb(0, 192, 0))
+        };
+        Self {
+            preset: CutColorPreset::Standard,
+            e: if dark {
+                Color32::from_gray(230)
+            } else {
+                Color32::BLACK
+            },
+            log: if dark {
+                (
+                    Color32::from_rgb(200, 90, 90),
+                    Color32::from_rgb(90, 180, 90),
+                )
+            } else {
+                (
+                    Color32::from_rgb(255, 128, 128),
+                    Color32::from_rgb(128, 255, 128),
+                )
+            },
+            scallion: (xp, xm),
+            kidney: (xp, xm),
+            long: (xp, xm),
+        }
+    }
+
+    // Based on the Okabe-Ito palette, with vermillion and orange for x⁺ and
+    // blue and sky blue for x⁻
+    pub fn colorblind_safe(dark: bool) -> Self {
+        let (xp, xm) = if dark {
+            (
+                Color32::from_rgb(240, 120, 40),
+                Color32::from_rgb(86, 180, 233),
+            )
+        } else {
+            (
+                Color32::from_rgb(213, 94, 0),
+                Color32::from_rgb(0, 114, 178),
+            )
+        };
+        Self {
+            preset: CutColorPreset::ColorblindSafe,
+            e: if dark {
+                Color32::from_gray(230)
+            } else {
+                Color32::BLACK
+            },
+            log: if dark {
+                (
+                    Color32::from_rgb(230, 159, 0),
+                    Color32::from_rgb(160, 210, 240),
+                )
+            } else {
+                (
+                    Color32::from_rgb(230, 159, 0),
+                    Color32::from_rgb(86, 180, 233),
+                )
+            },
+            scallion: (xp, xm),
+            kidney: (xp, xm),
+            long: (xp, xm),
+        }
+    }
+
+    // The presets are adjusted to the background, while custom colors are kept
+    pub fn from_preset(preset: CutColorPreset, dark: bool) -> Option<Self> {
+        match preset {
+            CutColorPreset::Standard => Some(Self::standard(dark)),
+            CutColorPreset::ColorblindSafe => Some(Self::colorblind_safe(dark)),
+            CutColorPreset::Custom => None,
+        }
+    }
+
+    pub fn get(&self, typ: &pxu::CutType) -> Option<Color32> {
+        let pick = |(xp, xm): (Color32, Color32), component| {
+            if component == pxu::Component::Xp {
+                xp
+            } else {
+                xm
+            }
+        };
+
+        match *typ {
+            pxu::CutType::E => Some(self.e),
+            pxu::CutType::Log(component) => Some(pick(self.log, component)),
+            pxu::CutType::UShortScallion(component) => Some(pick(self.scallion, component)),
+            pxu::CutType::UShortKidney(component) => Some(pick(self.kidney, component)),
+            pxu::CutType::ULongPositive(component) | pxu::CutType::ULongNegative(component) => {
+                Some(pick(self.long, component))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for CutColors {
+    fn default() -> Self {
+        Self::standard(false)
+    }
+}
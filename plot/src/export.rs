@@ -0,0 +1,401 @@
+// Render a plot off screen, either as an SVG document or as a PNG image
+
+use egui::epaint::{Mesh, Tessellator, Vertex};
+use egui::{Color32, Pos2, Rect, Ui, Vec2};
+
+use crate::plot::{Plot, PlotState, Scale};
+
+// The settings of exported images. These do not depend on the zoom of the
+// screen or on the presentation mode, so that the same settings always give the
+// same figure.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ExportSettings {
+    // The number of pixels per point in PNG images
+    pub scale: f32,
+    // The widths of lines and the sizes of markers relative to the screen
+    pub line_width: f32,
+    pub marker_size: f32,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            scale: 2.0,
+            line_width: 1.0,
+            marker_size: 1.0,
+        }
+    }
+}
+
+impl Plot {
+    fn export_shapes(
+        &self,
+        ui: &Ui,
+        size: Vec2,
+        settings: &ExportSettings,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+    ) -> Vec<egui::Shape> {
+        let rect = Rect::from_min_size(Pos2::ZERO, size);
+        let scale = Scale {
+            line: settings.line_width,
+            marker: settings.marker_size,
+            text: 1.0,
+        };
+        let mut shapes = vec![egui::Shape::rect_filled(
+            rect,
+            egui::Rounding::ZERO,
+            plot_state.palette.background,
+        )];
+        shapes.extend(self.shapes(ui, rect, pxu, plot_state, scale));
+        shapes
+    }
+
+    // The size is given in points. The visible part of the plane has the same
+    // height as on screen, while the width follows the aspect ratio of the size.
+    pub fn to_svg(
+        &self,
+        ui: &Ui,
+        size: Vec2,
+        settings: &ExportSettings,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+    ) -> String {
+        let mut svg = Svg::default();
+        for shape in self.export_shapes(ui, size, settings, pxu, plot_state) {
+            svg.add(&shape);
+        }
+        svg.finish(size)
+    }
+
+    // Each point is drawn as scale x scale pixels
+    pub fn to_png(
+        &self,
+        ui: &Ui,
+        size: Vec2,
+        settings: &ExportSettings,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+    ) -> Option<Vec<u8>> {
+        let scale = settings.scale;
+        let shapes = self.export_shapes(ui, size, settings, pxu, plot_state);
+
+        let (font_image, font_image_size, prepared_discs) = ui.fonts(|fonts| {
+            (
+                fonts.image(),
+                fonts.font_image_size(),
+                fonts.texture_atlas().lock().prepared_discs(),
+            )
+        });
+        let options = ui.ctx().tessellation_options(|options| *options);
+
+        let width = (size.x * scale).round() as usize;
+        let height = (size.y * scale).round() as usize;
+        if width == 0 || height == 0 {
+            log::warn!("Can not export an empty image");
+            return None;
+        }
+
+        // Text has already been laid out for the screen, so it has to be
+        // tessellated at the screen resolution
+        let mut tessellator =
+            Tessellator::new(scale, options, font_image_size, prepared_discs.clone());
+        let mut text_tessellator = Tessellator::new(
+            ui.ctx().pixels_per_point(),
+            options,
+            font_image_size,
+            prepared_discs,
+        );
+
+        let texture = Texture {
+            size: font_image.size,
+            pixels: font_image
+                .srgba_pixels(None)
+                .map(|color| color.a() as f32 / 255.0)
+                .collect(),
+        };
+        let mut canvas = Canvas::new(width, height);
+        for shape in shapes {
+            let mut mesh = Mesh::default();
+            if matches!(shape, egui::Shape::Text(_)) {
+                text_tessellator.tessellate_shape(shape, &mut mesh);
+            } else {
+                tessellator.tessellate_shape(shape, &mut mesh);
+            }
+            for triangle in mesh.indices.chunks_exact(3) {
+                let vertices = [
+                    mesh.vertices[triangle[0] as usize],
+                    mesh.vertices[triangle[1] as usize],
+                    mesh.vertices[triangle[2] as usize],
+                ];
+                canvas.fill_triangle(vertices, scale, &texture);
+            }
+        }
+
+        canvas.encode_png()
+    }
+}
+
+#[derive(Default)]
+struct Svg {
+    body: String,
+}
+
+fn svg_color(color: Color32) -> (String, f32) {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    (format!("#{r:02x}{g:02x}{b:02x}"), a as f32 / 255.0)
+}
+
+fn svg_fill(color: Color32) -> String {
+    if color == Color32::TRANSPARENT {
+        return r#"fill="none""#.to_owned();
+    }
+    let (color, opacity) = svg_color(color);
+    if opacity < 1.0 {
+        format!(r#"fill="{color}" fill-opacity="{opacity:.3}""#)
+    } else {
+        format!(r#"fill="{color}""#)
+    }
+}
+
+fn svg_stroke(stroke: egui::Stroke) -> String {
+    if stroke.is_empty() {
+        return r#"stroke="none""#.to_owned();
+    }
+    let (color, opacity) = svg_color(stroke.color);
+    let width = stroke.width;
+    if opacity < 1.0 {
+        format!(r#"stroke="{color}" stroke-opacity="{opacity:.3}" stroke-width="{width}""#)
+    } else {
+        format!(r#"stroke="{color}" stroke-width="{width}""#)
+    }
+}
+
+fn svg_points(points: &[Pos2]) -> String {
+    points
+        .iter()
+        .map(|pos| format!("{:.2},{:.2}", pos.x, pos.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn svg_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl Svg {
+    fn add(&mut self, shape: &egui::Shape) {
+        let element = match shape {
+            egui::Shape::Noop => return,
+            egui::Shape::Vec(shapes) => {
+                for shape in shapes {
+                    self.add(shape);
+                }
+                return;
+            }
+            egui::Shape::Circle(circle) => format!(
+                r#"<circle cx="{:.2}" cy="{:.2}" r="{}" {} {}/>"#,
+                circle.center.x,
+                circle.center.y,
+                circle.radius,
+                svg_fill(circle.fill),
+                svg_stroke(circle.stroke)
+            ),
+            egui::Shape::LineSegment { points, stroke } => format!(
+                r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" {}/>"#,
+                points[0].x,
+                points[0].y,
+                points[1].x,
+                points[1].y,
+                svg_stroke(*stroke)
+            ),
+            egui::Shape::Path(path) => {
+                if path.points.len() < 2 {
+                    return;
+                }
+                let tag = if path.closed { "polygon" } else { "polyline" };
+                format!(
+                    r#"<{tag} points="{}" {} {} stroke-linejoin="round"/>"#,
+                    svg_points(&path.points),
+                    svg_fill(path.fill),
+                    svg_stroke(path.stroke)
+                )
+            }
+            egui::Shape::Rect(rect) => format!(
+                r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" rx="{}" {} {}/>"#,
+                rect.rect.left(),
+                rect.rect.top(),
+                rect.rect.width(),
+                rect.rect.height(),
+                rect.rounding.nw,
+                svg_fill(rect.fill),
+                svg_stroke(rect.stroke)
+            ),
+            egui::Shape::Text(text) => {
+                let mut elements = vec![];
+                for row in text.galley.rows.iter() {
+                    let Some(glyph) = row.glyphs.first() else {
+                        continue;
+                    };
+                    let format = &text.galley.job.sections[glyph.section_index as usize].format;
+                    let color = text.override_text_color.unwrap_or(format.color);
+                    let content = row.glyphs.iter().map(|glyph| glyph.chr).collect::<String>();
+                    let style = if format.italics {
+                        r#" font-style="italic""#
+                    } else {
+                        ""
+                    };
+                    elements.push(format!(
+                        r#"<text x="{:.2}" y="{:.2}" font-family="sans-serif" font-size="{}"{style} {}>{}</text>"#,
+                        text.pos.x + glyph.pos.x,
+                        text.pos.y + glyph.pos.y,
+                        format.font_id.size,
+                        svg_fill(color),
+                        svg_escape(&content)
+                    ));
+                }
+                elements.join("\n")
+            }
+            _ => {
+                log::warn!("Skipping unsupported shape in SVG export");
+                return;
+            }
+        };
+        self.body.push_str(&element);
+        self.body.push('\n');
+    }
+
+    fn finish(self, size: Vec2) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+<defs><clipPath id="plot"><rect width="{w}" height="{h}"/></clipPath></defs>
+<g clip-path="url(#plot)" stroke-linecap="round">
+{}</g>
+</svg>
+"#,
+            self.body,
+            w = size.x,
+            h = size.y
+        )
+    }
+}
+
+// The font texture, as coverage values between 0 and 1
+struct Texture {
+    size: [usize; 2],
+    pixels: Vec<f32>,
+}
+
+impl Texture {
+    fn sample(&self, uv: Pos2) -> f32 {
+        let x = ((uv.x * self.size[0] as f32) as usize).min(self.size[0] - 1);
+        let y = ((uv.y * self.size[1] as f32) as usize).min(self.size[1] - 1);
+        self.pixels[y * self.size[0] + x]
+    }
+}
+
+// A minimal software rasterizer for the meshes produced by the tessellator.
+// Anti-aliasing is already handled by the feathering of the meshes, so it is
+// enough to sample each pixel at its centre.
+struct Canvas {
+    width: usize,
+    height: usize,
+    // Premultiplied rgba
+    pixels: Vec<[f32; 4]>,
+}
+
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 4]; width * height],
+        }
+    }
+
+    fn fill_triangle(&mut self, vertices: [Vertex; 3], scale: f32, texture: &Texture) {
+        let [a, b, c] = vertices.map(|v| Pos2::new(v.pos.x * scale, v.pos.y * scale));
+        let area = edge(a, b, c);
+        if area == 0.0 {
+            return;
+        }
+
+        let min_x = a.x.min(b.x).min(c.x).floor().max(0.0) as usize;
+        let min_y = a.y.min(b.y).min(c.y).floor().max(0.0) as usize;
+        let max_x = (a.x.max(b.x).max(c.x).ceil().max(0.0) as usize).min(self.width);
+        let max_y = (a.y.max(b.y).max(c.y).ceil().max(0.0) as usize).min(self.height);
+
+        let colors = vertices.map(|v| v.color.to_array().map(|c| c as f32 / 255.0));
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let w = [
+                    edge(b, c, p) / area,
+                    edge(c, a, p) / area,
+                    edge(a, b, p) / area,
+                ];
+                if w.iter().any(|&w| w < 0.0) {
+                    continue;
+                }
+
+                let uv = Pos2::new(
+                    w[0] * vertices[0].uv.x + w[1] * vertices[1].uv.x + w[2] * vertices[2].uv.x,
+                    w[0] * vertices[0].uv.y + w[1] * vertices[1].uv.y + w[2] * vertices[2].uv.y,
+                );
+                let coverage = texture.sample(uv);
+
+                let pixel = &mut self.pixels[y * self.width + x];
+                let src = std::array::from_fn::<f32, 4, _>(|i| {
+                    coverage * (w[0] * colors[0][i] + w[1] * colors[1][i] + w[2] * colors[2][i])
+                });
+                for i in 0..4 {
+                    pixel[i] = src[i] + pixel[i] * (1.0 - src[3]);
+                }
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Option<Vec<u8>> {
+        let data = self
+            .pixels
+            .iter()
+            .flat_map(|&[r, g, b, a]| {
+                let unmultiply = |c: f32| {
+                    if a > 0.0 {
+                        (c / a * 255.0).round().clamp(0.0, 255.0) as u8
+                    } else {
+                        0
+                    }
+                };
+                [
+                    unmultiply(r),
+                    unmultiply(g),
+                    unmultiply(b),
+                    (a * 255.0).round().clamp(0.0, 255.0) as u8,
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let mut bytes = vec![];
+        let mut encoder = png::Encoder::new(&mut bytes, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let result = encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&data));
+        if let Err(err) = result {
+            log::warn!("Could not encode png: {err}");
+            return None;
+        }
+        Some(bytes)
+    }
+}
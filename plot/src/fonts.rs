@@ -0,0 +1,29 @@
+pub fn setup_custom_fonts(ctx: &egui::Context) {
+    // Start with the default fonts (we will be adding to them rather than replacing them).
+    let mut fonts = egui::FontDefinitions::default();
+
+    // Install my own font (maybe supporting non-latin characters).
+    // .ttf and .otf files supported.
+
+    fonts.font_data.insert(
+        "DejaVuSansMono".to_owned(),
+        egui::FontData::from_static(include_bytes!("../../fonts/DejaVuSansMono.ttf")),
+    );
+
+    // Put my font first (highest priority):
+    fonts
+        .families
+        .get_mut(&egui::FontFamily::Proportional)
+        .unwrap()
+        .insert(0, "DejaVuSansMono".to_owned());
+
+    // // Put my font as last fallback for monospace:
+    fonts
+        .families
+        .get_mut(&egui::FontFamily::Monospace)
+        .unwrap()
+        .insert(0, "DejaVuSansMono".to_owned());
+
+    // Tell egui to use these fonts:
+    ctx.set_fonts(fonts);
+}
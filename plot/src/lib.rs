@@ -1,2 +1,14 @@
+mod cut_colors;
+mod export;
+mod fonts;
+mod math_text;
+mod palette;
 mod plot;
-pub use plot::{CutFilter, Plot, PlotState, Theme};
+mod renderer;
+mod tikz;
+pub use cut_colors::{CutColorPreset, CutColors};
+pub use export::ExportSettings;
+pub use fonts::setup_custom_fonts;
+pub use palette::Palette;
+pub use plot::{Comparison, CutFilter, DragFailure, GridLabels, Overlay, Plot, PlotState, Theme};
+pub use renderer::Renderer;
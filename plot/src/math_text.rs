@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use egui::epaint::{text::LayoutJob, Fonts, Galley};
+use egui::{vec2, Align2, Color32, FontId, Pos2, Rect, Shape, TextFormat, Vec2};
+
+// Labels are written in a small subset of LaTeX. Single letters are set in
+// italics, while words such as the names in the legend are set upright, and
+// `^` and `_` start a superscript or subscript, which is either a single
+// character or a group in braces, so that x⁺_L is written as `x^+_L`.
+
+#[derive(Clone, Copy, PartialEq)]
+enum Position {
+    Normal,
+    Superscript,
+    Subscript,
+}
+
+impl Position {
+    // The size of the font and the shift of the baseline, relative to the size
+    // of the normal font
+    fn size(self) -> f32 {
+        match self {
+            Self::Normal => 1.0,
+            Self::Superscript | Self::Subscript => 0.7,
+        }
+    }
+
+    fn shift(self) -> f32 {
+        match self {
+            Self::Normal => 0.0,
+            Self::Superscript => -0.4,
+            Self::Subscript => 0.2,
+        }
+    }
+}
+
+struct Run {
+    text: String,
+    position: Position,
+    italics: bool,
+}
+
+fn is_variable(c: char) -> bool {
+    c.is_ascii_alphabetic() || ('α'..='ω').contains(&c)
+}
+
+fn push_char(runs: &mut Vec<Run>, c: char, position: Position) {
+    let c = if c == '-' { '−' } else { c };
+    let italics = is_variable(c);
+    match runs.last_mut() {
+        Some(run) if run.position == position && run.italics == italics => run.text.push(c),
+        _ => runs.push(Run {
+            text: c.to_string(),
+            position,
+            italics,
+        }),
+    }
+}
+
+fn parse(text: &str) -> Vec<Run> {
+    let mut runs = vec![];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        let position = match c {
+            '^' => Position::Superscript,
+            '_' => Position::Subscript,
+            _ => {
+                push_char(&mut runs, c, Position::Normal);
+                continue;
+            }
+        };
+        match chars.next() {
+            Some('{') => {
+                for c in chars.by_ref().take_while(|c| *c != '}') {
+                    push_char(&mut runs, c, position);
+                }
+            }
+            Some(c) => push_char(&mut runs, c, position),
+            None => {}
+        }
+    }
+    for run in runs.iter_mut() {
+        if run.italics && run.text.chars().count() > 1 {
+            run.italics = false;
+        }
+    }
+    runs
+}
+
+// The baseline of the first row of the galley, relative to its top
+fn baseline(galley: &Galley) -> f32 {
+    galley
+        .rows
+        .first()
+        .and_then(|row| row.glyphs.first())
+        .map_or(galley.size().y, |glyph| glyph.pos.y)
+}
+
+// Lay out the label with the given anchor at pos. Each run becomes its own text
+// shape, so that the label can be exported like any other text.
+pub fn math_text(
+    fonts: &Fonts,
+    pos: Pos2,
+    anchor: Align2,
+    text: &str,
+    font_id: FontId,
+    color: Color32,
+) -> Vec<Shape> {
+    let size = font_id.size;
+
+    let mut galleys: Vec<(Vec2, Arc<Galley>)> = vec![];
+    let mut x: f32 = 0.0;
+    // A subscript directly after a superscript, or the other way around, is
+    // placed below or above it
+    let mut last_script: Option<(Position, f32)> = None;
+    for run in parse(text) {
+        let format = TextFormat {
+            font_id: FontId::new(size * run.position.size(), font_id.family.clone()),
+            color,
+            italics: run.italics,
+            ..Default::default()
+        };
+        let galley = fonts.layout_job(LayoutJob::single_section(run.text, format));
+        let start = match last_script {
+            Some((position, start))
+                if run.position != Position::Normal && position != run.position =>
+            {
+                start
+            }
+            _ => x,
+        };
+        last_script = (run.position != Position::Normal).then_some((run.position, start));
+
+        let offset = vec2(start, size * run.position.shift() - baseline(&galley));
+        x = x.max(start + galley.size().x);
+        galleys.push((offset, galley));
+    }
+
+    let bounds = galleys
+        .iter()
+        .map(|(offset, galley)| Rect::from_min_size(Pos2::ZERO + *offset, galley.size()))
+        .fold(Rect::NOTHING, |a, b| a.union(b));
+    if !bounds.is_positive() {
+        return vec![];
+    }
+    let rect = anchor.anchor_rect(Rect::from_min_size(pos, bounds.size()));
+    let origin = rect.min - bounds.min.to_vec2();
+
+    galleys
+        .into_iter()
+        .map(|(offset, galley)| Shape::galley(origin + offset, galley, color))
+        .collect()
+}
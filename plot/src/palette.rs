@@ -0,0 +1,80 @@
+use egui::Color32;
+
+// The colors used to draw a plot, apart from the colors of the cuts. The light
+// palette is the one used for the figures, while the dark one keeps everything
+// visible on a dark background.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub background: Color32,
+    pub frame: Color32,
+    pub axis: Color32,
+    pub grid: Color32,
+    pub energy_grid: Color32,
+    pub qlog_cut: Color32,
+    pub other_cut: Color32,
+    pub active_point: Color32,
+    pub active_ring: Color32,
+    pub point: Color32,
+    pub other_sheet_point: Color32,
+    pub text: Color32,
+    pub label_fill: Color32,
+    pub label_stroke: Color32,
+    pub snap: Color32,
+    pub measure: Color32,
+}
+
+impl Palette {
+    pub const LIGHT: Self = Self {
+        background: Color32::WHITE,
+        frame: Color32::DARK_GRAY,
+        axis: Color32::DARK_GRAY,
+        grid: Color32::GRAY,
+        energy_grid: Color32::from_rgb(160, 160, 255),
+        qlog_cut: Color32::DARK_GRAY,
+        other_cut: Color32::from_rgb(255, 128, 0),
+        active_point: Color32::BLUE,
+        active_ring: Color32::LIGHT_BLUE,
+        point: Color32::BLACK,
+        other_sheet_point: Color32::GRAY,
+        text: Color32::BLACK,
+        label_fill: Color32::WHITE,
+        label_stroke: Color32::BLACK,
+        snap: Color32::from_rgb(230, 140, 0),
+        measure: Color32::from_rgb(200, 0, 160),
+    };
+
+    pub const DARK: Self = Self {
+        background: Color32::from_gray(27),
+        frame: Color32::from_gray(140),
+        axis: Color32::from_gray(170),
+        grid: Color32::from_gray(90),
+        energy_grid: Color32::from_rgb(90, 90, 170),
+        qlog_cut: Color32::from_gray(170),
+        other_cut: Color32::from_rgb(255, 160, 40),
+        active_point: Color32::from_rgb(100, 150, 255),
+        active_ring: Color32::from_rgb(170, 200, 255),
+        point: Color32::from_gray(230),
+        other_sheet_point: Color32::from_gray(120),
+        text: Color32::from_gray(230),
+        label_fill: Color32::from_gray(40),
+        label_stroke: Color32::from_gray(160),
+        snap: Color32::from_rgb(255, 180, 60),
+        measure: Color32::from_rgb(255, 110, 220),
+    };
+
+    // Use another color for the active excitation and the paths it follows
+    pub fn with_accent(self, accent: Color32) -> Self {
+        let [r, g, b, _] = accent.to_array();
+        Self {
+            active_point: accent,
+            active_ring: Color32::from_rgba_unmultiplied(r, g, b, 96),
+            ..self
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::LIGHT
+    }
+}
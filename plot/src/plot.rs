@@ -4,6 +4,9 @@ use num::complex::Complex64;
 
 use pxu::kinematics::UBranch;
 
+use crate::math_text::math_text;
+use crate::{CutColors, Palette};
+
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct Plot {
     pub component: pxu::Component,
@@ -19,6 +22,23 @@ pub enum Theme {
     Black,
 }
 
+// How much lines, markers and text are enlarged. On screen this follows the
+// presentation mode, while exported figures have their own settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Scale {
+    pub line: f32,
+    pub marker: f32,
+    pub text: f32,
+}
+
+// When the bound state number m of the grid lines of x⁺ and x⁻ is shown
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum GridLabels {
+    #[default]
+    Hover,
+    Always,
+}
+
 #[derive(Debug, Default, Clone, serde::Deserialize, serde::Serialize)]
 pub enum CutFilter {
     #[default]
@@ -27,6 +47,76 @@ pub enum CutFilter {
     Only(Vec<pxu::CutType>),
 }
 
+// A state shown alongside the editable state for comparison
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct Overlay {
+    pub name: String,
+    pub state: pxu::State,
+    pub color: Color32,
+    pub visible: bool,
+}
+
+impl Overlay {
+    const COLORS: [Color32; 6] = [
+        Color32::from_rgb(220, 50, 32),
+        Color32::from_rgb(0, 150, 60),
+        Color32::from_rgb(230, 140, 0),
+        Color32::from_rgb(140, 50, 170),
+        Color32::from_rgb(0, 160, 180),
+        Color32::from_rgb(140, 90, 40),
+    ];
+
+    pub fn new(name: &str, state: pxu::State, index: usize) -> Self {
+        Self {
+            name: name.to_owned(),
+            state,
+            color: Self::COLORS[index % Self::COLORS.len()],
+            visible: true,
+        }
+    }
+}
+
+// Where a dragged excitation was pulled to in snap mode
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Snapped {
+    component: pxu::Component,
+    value: Complex64,
+    along_line: bool,
+}
+
+// Two locations picked in one of the planes with the measurement tool. Until
+// the second click the end follows the pointer.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Measurement {
+    component: pxu::Component,
+    start: Complex64,
+    end: Option<Complex64>,
+}
+
+// Where the context menu of a plane was opened, and the excitation under the
+// pointer at that time if any
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ContextTarget {
+    component: pxu::Component,
+    value: Complex64,
+    point: Option<usize>,
+}
+
+// The contours for another choice of the coupling constants, drawn faded
+// behind the ones of the current coupling
+pub struct Comparison {
+    pub consts: pxu::CouplingConstants,
+    pub contours: pxu::Contours,
+}
+
+// An excitation that could not follow a drag. It is left at the last position
+// where the root finding converged.
+pub struct DragFailure {
+    pub index: usize,
+    pub component: pxu::Component,
+    pub message: String,
+}
+
 #[derive(Default, serde::Deserialize, serde::Serialize)]
 pub struct PlotState {
     pub active_point: usize,
@@ -46,12 +136,109 @@ pub struct PlotState {
     pub cut_filter: CutFilter,
     #[serde(skip)]
     pub theme: Theme,
+    #[serde(skip)]
+    pub hide_grid: bool,
+    #[serde(skip)]
+    pub overlays: Vec<Overlay>,
+    #[serde(skip)]
+    pub palette: Palette,
+    #[serde(default)]
+    pub cut_colors: CutColors,
+    #[serde(default)]
+    pub snap_mode: bool,
+    #[serde(default)]
+    pub grid_labels: GridLabels,
+    #[serde(default)]
+    pub mirror_images: bool,
+    #[serde(skip)]
+    pub(crate) snapped: Option<Snapped>,
+    #[serde(skip)]
+    touch_grab: Option<(usize, pxu::Component)>,
+    #[serde(skip)]
+    pub presentation: bool,
+    // Hides tooltips and measurements, for taking clean screen captures
+    #[serde(skip)]
+    pub clean: bool,
+    #[serde(skip)]
+    pub measure_mode: bool,
+    #[serde(skip)]
+    pub(crate) measurement: Option<Measurement>,
+    #[serde(skip)]
+    pub(crate) context_target: Option<ContextTarget>,
+    // Set from the context menu, and taken by the app to open the bound state
+    // dialog
+    #[serde(skip)]
+    pub bound_state_request: Option<(pxu::Component, Complex64)>,
+    // The index of the path that dragged excitations are kept on, and the
+    // position along it
+    #[serde(skip)]
+    pub path_constraint: Option<(usize, f64)>,
+    // The convergence of the root finding for each step of the last drag
+    #[serde(skip)]
+    pub drag_stats: Vec<pxu::ContinuationStats>,
+    // These are saved and loaded together with the state
+    #[serde(skip)]
+    pub point_styles: Vec<pxu::PointStyle>,
+    #[serde(skip)]
+    pub comparison: Option<Comparison>,
+    // The plane the arrow keys move the active excitation in, which is the
+    // last plane that was clicked
+    #[serde(skip)]
+    pub focused_component: Option<pxu::Component>,
+    #[serde(skip)]
+    pub drag_failure: Option<DragFailure>,
+    // The state when the last drag started, which a failed drag can be rolled
+    // back to
+    #[serde(skip)]
+    pub drag_start: Option<pxu::State>,
 }
 
 impl PlotState {
     pub fn reset(&mut self) {
         self.interaction_point = None;
         self.interaction_component = None;
+        self.snapped = None;
+    }
+
+    fn start_drag(&mut self, state: &pxu::State) {
+        self.drag_stats.clear();
+        self.drag_failure = None;
+        self.drag_start = Some(state.clone());
+    }
+
+    pub fn point_style(&self, index: usize) -> pxu::PointStyle {
+        self.point_styles.get(index).copied().unwrap_or_default()
+    }
+
+    pub fn set_point_style(&mut self, index: usize, style: pxu::PointStyle) {
+        if self.point_styles.len() <= index {
+            self.point_styles.resize(index + 1, Default::default());
+        }
+        self.point_styles[index] = style;
+    }
+
+    pub(crate) fn screen_scale(&self) -> Scale {
+        let scale = self.scale();
+        Scale {
+            line: scale,
+            marker: scale,
+            text: scale,
+        }
+    }
+
+    // Lines and markers are drawn larger in presentation mode, so that they are
+    // visible when projected
+    pub(crate) fn scale(&self) -> f32 {
+        if self.presentation {
+            2.0
+        } else {
+            1.0
+        }
+    }
+
+    pub fn toggle_measure_mode(&mut self) {
+        self.measure_mode = !self.measure_mode;
+        self.measurement = None;
     }
 
     pub fn toggle_fullscreen(&mut self, component: pxu::Component) {
@@ -75,8 +262,19 @@ impl PlotState {
 
 impl Plot {
     fn interact_with_grid(&mut self, ui: &mut Ui, rect: Rect, response: &egui::Response) {
-        if response.dragged() {
-            let delta = response.drag_delta();
+        // Pan with two fingers on touch screens. The pinch zoom is included in
+        // the zoom delta below.
+        let (multi_touch, modifiers) = ui.input(|i| (i.multi_touch(), i.modifiers));
+        if let Some(touch) = multi_touch {
+            if ui.rect_contains_pointer(rect) {
+                let delta = touch.translation_delta;
+                self.origin -= Vec2::new(
+                    delta.x * (self.height / rect.height()) * (self.width_factor),
+                    delta.y * (self.height / rect.height()),
+                );
+            }
+        } else if response.dragged() {
+            let delta = Self::lock_axis(response.drag_delta(), modifiers);
             self.origin -= Vec2::new(
                 delta.x * (self.height / rect.height()) * (self.width_factor),
                 delta.y * (self.height / rect.height()),
@@ -85,9 +283,15 @@ impl Plot {
 
         if ui.rect_contains_pointer(rect) {
             let zoom = ui.input(|i| i.zoom_delta());
-            self.zoom(zoom);
+            if modifiers.shift {
+                self.zoom_horizontally(zoom);
+            } else if modifiers.alt {
+                self.zoom_vertically(zoom);
+            } else {
+                self.zoom(zoom);
+            }
 
-            let scroll = ui.input(|i| i.smooth_scroll_delta);
+            let scroll = Self::lock_axis(ui.input(|i| i.smooth_scroll_delta), modifiers);
             self.origin -= Vec2::new(
                 scroll.x * (self.height / rect.height()) * (self.width_factor),
                 scroll.y * (self.height / rect.height()),
@@ -95,6 +299,18 @@ impl Plot {
         }
     }
 
+    // With Shift held down the view only moves horizontally, and with Alt only
+    // vertically. The scroll wheel already scrolls horizontally with Shift.
+    fn lock_axis(delta: Vec2, modifiers: egui::Modifiers) -> Vec2 {
+        if modifiers.shift {
+            Vec2::new(delta.x, 0.0)
+        } else if modifiers.alt {
+            Vec2::new(0.0, delta.y)
+        } else {
+            delta
+        }
+    }
+
     fn interact_with_points(
         &mut self,
         ui: &mut Ui,
@@ -105,18 +321,27 @@ impl Plot {
     ) {
         let to_screen = self.to_screen(rect);
 
-        let state = &mut pxu.state;
+        for j in 0..pxu.state.points.len() {
+            let z = pxu.state.points[j].get(self.component);
 
-        for j in 0..state.points.len() {
-            let z = state.points[j].get(self.component);
-
-            let size = egui::epaint::Vec2::splat(8.0);
+            let size = egui::epaint::Vec2::splat(8.0 * plot_state.scale());
             let center = to_screen * egui::pos2(z.re as f32, -z.im as f32);
             let point_rect = egui::Rect::from_center_size(center, size);
 
             let id = (usize::MAX, j);
             let point_id = response.id.with(id);
             let point_response = ui.interact(point_rect, point_id, egui::Sense::drag());
+            point_response.widget_info(|| {
+                egui::WidgetInfo::labeled(
+                    egui::WidgetType::Other,
+                    format!(
+                        "Excitation {j}, {} = {:.3} {:+.3} i",
+                        spoken_name(self.component),
+                        z.re,
+                        z.im
+                    ),
+                )
+            });
 
             if point_response.hovered() || point_response.dragged() {
                 plot_state.interaction_point = Some(j);
@@ -125,8 +350,18 @@ impl Plot {
                 plot_state.hovered = point_response.hovered();
             }
 
+            if point_response.drag_started() {
+                plot_state.start_drag(&pxu.state);
+            }
+
             if point_response.dragged() {
-                let delta = point_response.drag_delta();
+                // Follow the pointer itself when snapping, as otherwise a
+                // snapped point could never be dragged away again
+                let follow_pointer = plot_state.snap_mode || plot_state.path_constraint.is_some();
+                let delta = match point_response.interact_pointer_pos() {
+                    Some(pos) if follow_pointer => pos - center,
+                    _ => point_response.drag_delta(),
+                };
                 let delta = if ui.input(|i| i.key_down(egui::Key::E)) {
                     vec2(delta.x, 0.0)
                 } else if ui.input(|i| i.key_down(egui::Key::W)) {
@@ -134,29 +369,222 @@ impl Plot {
                 } else {
                     delta
                 };
-                let new_value = to_screen.inverse() * (center + delta);
-                let new_value = Complex64::new(new_value.x as f64, -new_value.y as f64);
-
-                let new_value = if ui.input(|i| i.key_pressed(egui::Key::R)) {
-                    match self.component {
-                        pxu::Component::P => Complex64::new(new_value.re, 0.00001),
-                        pxu::Component::U => {
-                            let re = new_value.re;
-                            let im = (pxu.consts.h * new_value.im).round() / pxu.consts.h;
-                            Complex64::new(re, im + 0.0001)
+                self.move_point(j, center + delta, rect, pxu, plot_state);
+
+                if ui.input(|i| i.key_pressed(egui::Key::R)) {
+                    let consts = pxu.kinematic_consts();
+                    let state = &mut pxu.state;
+                    let z = state.points[j].get(self.component);
+                    if let Some(snap) = pxu::Snap::nearest(self.component, z) {
+                        if let Err(err) = state.snap(j, snap, &pxu.contours, consts) {
+                            log::debug!("Could not snap point {j}: {err}");
                         }
-                        _ => new_value,
                     }
-                } else {
-                    new_value
+                }
+            }
+        }
+    }
+
+    // Move a point towards the given screen position
+    fn move_point(
+        &self,
+        j: usize,
+        pos: Pos2,
+        rect: Rect,
+        pxu: &mut pxu::Pxu,
+        plot_state: &mut PlotState,
+    ) {
+        let new_value = self.to_plane(rect, pos);
+
+        if let Some((index, _)) = plot_state.path_constraint {
+            if let Some(path) = pxu.paths.get(index) {
+                let t = path.nearest(self.component, j, new_value, pxu.consts);
+                let state = path.sample(t, pxu.consts);
+                if j < state.points.len() {
+                    pxu.state = pxu::State {
+                        unlocked: pxu.state.unlocked,
+                        ..state
+                    };
+                    plot_state.active_point = j;
+                    plot_state.path_constraint = Some((index, t));
+                }
+                return;
+            }
+        }
+
+        let snapped = if plot_state.snap_mode {
+            self.snap_target(j, new_value, rect, &pxu.state, pxu.kinematic_consts())
+        } else {
+            None
+        };
+        let new_value = snapped.map_or(new_value, |snapped| snapped.value);
+        plot_state.snapped = snapped;
+
+        plot_state.active_point = j;
+        pxu.state.take_stats();
+        // A failed update can leave the excitations half way, so the whole
+        // state is put back
+        let last_good = pxu.state.clone();
+        let result = pxu.update_state(j, self.component, new_value);
+        plot_state.drag_stats.push(pxu.state.take_stats());
+        if let Err(err) = result {
+            log::debug!("Could not move point {j}: {err}");
+            pxu.state = last_good;
+            plot_state.drag_failure = Some(DragFailure {
+                index: j,
+                component: self.component,
+                message: err.to_string(),
+            });
+        }
+    }
+
+    // The excitations are small targets for a finger, and dragging them would
+    // conflict with panning, so on touch screens they are picked up with a long
+    // press close to them
+    fn interact_with_touch(
+        &mut self,
+        ui: &mut Ui,
+        rect: Rect,
+        pxu: &mut pxu::Pxu,
+        plot_state: &mut PlotState,
+    ) {
+        const LONG_PRESS: f64 = 0.4;
+        const MAX_MOVEMENT: f32 = 10.0;
+        const GRAB_DISTANCE: f32 = 24.0;
+
+        let (touching, time, press_start, press_origin, pos) = ui.input(|i| {
+            (
+                i.any_touches() && i.pointer.primary_down(),
+                i.time,
+                i.pointer.press_start_time(),
+                i.pointer.press_origin(),
+                i.pointer.interact_pos(),
+            )
+        });
+
+        if !touching {
+            if plot_state
+                .touch_grab
+                .is_some_and(|(_, component)| component == self.component)
+            {
+                plot_state.touch_grab = None;
+            }
+            return;
+        }
+
+        match plot_state.touch_grab {
+            // Only the pane where the point was grabbed moves it, in case the
+            // plane is shown in more than one pane
+            Some((j, component))
+                if component == self.component
+                    && press_origin.is_some_and(|o| rect.contains(o)) =>
+            {
+                if let Some(pos) = pos {
+                    plot_state.interaction_point = Some(j);
+                    plot_state.interaction_component = Some(self.component);
+                    plot_state.dragged = true;
+                    self.move_point(j, pos, rect, pxu, plot_state);
+                }
+            }
+            Some(_) => {}
+            None => {
+                let (Some(start), Some(origin), Some(pos)) = (press_start, press_origin, pos)
+                else {
+                    return;
                 };
+                if !rect.contains(origin) || origin.distance(pos) > MAX_MOVEMENT {
+                    return;
+                }
+                if time - start < LONG_PRESS {
+                    ui.ctx()
+                        .request_repaint_after(std::time::Duration::from_secs_f64(
+                            LONG_PRESS - (time - start),
+                        ));
+                    return;
+                }
 
-                plot_state.active_point = j;
-                state.update(j, self.component, new_value, &pxu.contours, pxu.consts);
+                let to_screen = self.to_screen(rect);
+                let closest = pxu
+                    .state
+                    .points
+                    .iter()
+                    .enumerate()
+                    .map(|(j, pt)| {
+                        let z = pt.get(self.component);
+                        let center = to_screen * egui::pos2(z.re as f32, -z.im as f32);
+                        (j, center.distance(origin))
+                    })
+                    .filter(|(_, distance)| *distance < GRAB_DISTANCE)
+                    .min_by(|a, b| a.1.total_cmp(&b.1));
+                if let Some((j, _)) = closest {
+                    plot_state.touch_grab = Some((j, self.component));
+                    plot_state.start_drag(&pxu.state);
+                    plot_state.active_point = j;
+                }
             }
         }
     }
 
+    // Pull the value onto real p, onto a line with Im u a multiple of 1/2h, or
+    // for an unlocked state onto the position that an excitation would have in
+    // a bound state with one of its neighbours
+    fn snap_target(
+        &self,
+        j: usize,
+        z: Complex64,
+        rect: Rect,
+        state: &pxu::State,
+        consts: pxu::CouplingConstants,
+    ) -> Option<Snapped> {
+        const SNAP_DISTANCE: f32 = 8.0;
+
+        let to_screen = self.to_screen(rect);
+        let screen = |z: Complex64| to_screen * egui::pos2(z.re as f32, -z.im as f32);
+
+        let mut candidates = vec![];
+        match self.component {
+            pxu::Component::P => candidates.push((Complex64::from(z.re), true)),
+            pxu::Component::U => {
+                let h = consts.h;
+                candidates.push((
+                    Complex64::new(z.re, (2.0 * h * z.im).round() / (2.0 * h)),
+                    true,
+                ));
+            }
+            _ => {}
+        }
+
+        if state.unlocked {
+            let points = &state.points;
+            let shift = Complex64::new(0.0, 2.0 / consts.h);
+            let (before, after) = (j.checked_sub(1), Some(j + 1).filter(|&i| i < points.len()));
+            match self.component {
+                pxu::Component::Xp => {
+                    candidates.extend(before.map(|i| (points[i].xm, false)));
+                }
+                pxu::Component::Xm => {
+                    candidates.extend(after.map(|i| (points[i].xp, false)));
+                }
+                pxu::Component::U => {
+                    candidates.extend(before.map(|i| (points[i].u - shift, false)));
+                    candidates.extend(after.map(|i| (points[i].u + shift, false)));
+                }
+                _ => {}
+            }
+        }
+
+        candidates
+            .into_iter()
+            .map(|(value, along_line)| (screen(value).distance(screen(z)), value, along_line))
+            .filter(|(distance, _, _)| *distance < SNAP_DISTANCE)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, value, along_line)| Snapped {
+                component: self.component,
+                value,
+                along_line,
+            })
+    }
+
     fn do_interact(
         &mut self,
         ui: &mut Ui,
@@ -169,9 +597,42 @@ impl Plot {
             ui.id().with(format!("{:?}", self.component)),
             egui::Sense::click_and_drag(),
         );
-
-        self.interact_with_grid(ui, rect, &response);
+        response.widget_info(|| {
+            egui::WidgetInfo::labeled(
+                egui::WidgetType::Other,
+                format!("{} plane", spoken_name(self.component)),
+            )
+        });
+
+        let grabbed = plot_state
+            .touch_grab
+            .is_some_and(|(_, component)| component == self.component);
+        if !grabbed {
+            self.interact_with_grid(ui, rect, &response);
+        }
         self.interact_with_points(ui, rect, pxu, plot_state, &response);
+        self.interact_with_touch(ui, rect, pxu, plot_state);
+
+        if plot_state.measure_mode && response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                self.measure(self.to_plane(rect, pos), plot_state);
+            }
+        }
+
+        if response.secondary_clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                plot_state.context_target = Some(ContextTarget {
+                    component: self.component,
+                    value: self.to_plane(rect, pos),
+                    point: self.point_at(pos, rect, pxu),
+                });
+            }
+        }
+        response.context_menu(|ui| self.context_menu(ui, pxu, plot_state));
+
+        if response.clicked() || response.drag_started() {
+            plot_state.focused_component = Some(self.component);
+        }
 
         if response.double_clicked() {
             plot_state.toggle_fullscreen(self.component)
@@ -183,15 +644,139 @@ impl Plot {
         }
     }
 
+    // The excitation closest to a screen position, if it is close enough to be
+    // picked
+    fn point_at(&self, pos: Pos2, rect: Rect, pxu: &pxu::Pxu) -> Option<usize> {
+        const PICK_DISTANCE: f32 = 12.0;
+
+        let to_screen = self.to_screen(rect);
+        pxu.state
+            .points
+            .iter()
+            .enumerate()
+            .map(|(j, pt)| {
+                let z = pt.get(self.component);
+                let center = to_screen * egui::pos2(z.re as f32, -z.im as f32);
+                (j, center.distance(pos))
+            })
+            .filter(|(_, distance)| *distance < PICK_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(j, _)| j)
+    }
+
+    fn context_menu(&self, ui: &mut Ui, pxu: &mut pxu::Pxu, plot_state: &mut PlotState) {
+        let Some(target) = plot_state
+            .context_target
+            .filter(|target| target.component == self.component)
+        else {
+            ui.close_menu();
+            return;
+        };
+        let len = pxu.state.points.len();
+        let point = target.point.filter(|j| *j < len);
+
+        if ui
+            .button("Add excitation here")
+            .on_hover_text("Add a copy of the active excitation, moved to this location")
+            .clicked()
+        {
+            match pxu.state.add_point(
+                plot_state.active_point,
+                self.component,
+                target.value,
+                &pxu.contours,
+                pxu.consts,
+            ) {
+                Ok(j) => plot_state.active_point = j,
+                Err(err) => log::info!("Could not add an excitation at {}: {err}", target.value),
+            }
+            ui.close_menu();
+        }
+
+        if ui
+            .add_enabled(
+                point.is_some() && len > 1,
+                egui::Button::new("Remove excitation"),
+            )
+            .clicked()
+        {
+            if let Some(j) = point {
+                if pxu.state.remove_point(j) {
+                    let active_point = &mut plot_state.active_point;
+                    if *active_point > j || *active_point == len - 1 {
+                        *active_point = active_point.saturating_sub(1);
+                    }
+                }
+            }
+            ui.close_menu();
+        }
+
+        if ui
+            .add_enabled(point.is_some(), egui::Button::new("Set as active"))
+            .clicked()
+        {
+            if let Some(j) = point {
+                plot_state.active_point = j;
+            }
+            ui.close_menu();
+        }
+
+        if ui
+            .button("Create bound state here…")
+            .on_hover_text("Replace the state by a bound state centered at this location")
+            .clicked()
+        {
+            plot_state.bound_state_request = Some((self.component, target.value));
+            ui.close_menu();
+        }
+
+        ui.separator();
+
+        if ui
+            .button("Duplicate state")
+            .on_hover_text("Keep a copy of the current state in the plots")
+            .clicked()
+        {
+            let index = plot_state.overlays.len();
+            let name = format!("State {}", index + 1);
+            plot_state
+                .overlays
+                .push(Overlay::new(&name, pxu.state.clone(), index));
+            ui.close_menu();
+        }
+    }
+
+    // The first click starts a measurement, the second one ends it and the
+    // next one starts a new one
+    fn measure(&self, z: Complex64, plot_state: &mut PlotState) {
+        match &mut plot_state.measurement {
+            Some(measurement)
+                if measurement.component == self.component && measurement.end.is_none() =>
+            {
+                measurement.end = Some(z);
+            }
+            measurement => {
+                *measurement = Some(Measurement {
+                    component: self.component,
+                    start: z,
+                    end: None,
+                });
+            }
+        }
+    }
+
     fn draw_grid(
         &self,
         rect: Rect,
         pxu: &pxu::Pxu,
         plot_state: &PlotState,
+        scale: Scale,
         shapes: &mut Vec<egui::Shape>,
     ) {
         let to_screen = self.to_screen(rect);
         let visible_rect = self.visible_rect(rect);
+        let palette = &plot_state.palette;
+        let scale = scale.line;
         if self.component != pxu::Component::P {
             let origin = to_screen
                 * if (plot_state.theme == Theme::Black) && (self.component == pxu::Component::U) {
@@ -206,18 +791,22 @@ impl Plot {
                         egui::pos2(rect.left(), origin.y),
                         egui::pos2(rect.right(), origin.y),
                     ],
-                    Stroke::new(1.0, Color32::DARK_GRAY),
+                    Stroke::new(scale, palette.axis),
                 ),
                 egui::epaint::Shape::line(
                     vec![
                         egui::pos2(origin.x, rect.bottom()),
                         egui::pos2(origin.x, rect.top()),
                     ],
-                    Stroke::new(1.0, Color32::DARK_GRAY),
+                    Stroke::new(scale, palette.axis),
                 ),
             ]);
         }
 
+        if plot_state.hide_grid {
+            return;
+        }
+
         let grid_contours = pxu.contours.get_grid(self.component);
 
         for grid_line in grid_contours {
@@ -230,18 +819,97 @@ impl Plot {
                 .map(|z| to_screen * egui::pos2(z.re as f32, -z.im as f32))
                 .collect::<Vec<_>>();
 
+            let color = if matches!(grid_line.component, pxu::GridLineComponent::Energy(_)) {
+                palette.energy_grid
+            } else {
+                palette.grid
+            };
+
             shapes.push(egui::epaint::Shape::line(
                 points.clone(),
-                Stroke::new(0.75, Color32::GRAY),
+                Stroke::new(0.75 * scale, color),
             ));
         }
     }
 
+    // Label each visible grid line of x⁺ and x⁻ with its value of m, at the
+    // middle of its visible part. Labels that would overlap are left out.
+    fn draw_grid_labels(
+        &self,
+        ui: &Ui,
+        rect: Rect,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+        scale: Scale,
+        shapes: &mut Vec<egui::Shape>,
+    ) {
+        if plot_state.hide_grid {
+            return;
+        }
+
+        let to_screen = self.to_screen(rect);
+        let visible_rect = self.visible_rect(rect);
+        let inner_rect = rect.shrink(12.0);
+        let palette = &plot_state.palette;
+
+        let mut font_id = egui::TextStyle::Small.resolve(ui.style());
+        font_id.size *= scale.text;
+
+        let mut placed: Vec<Rect> = vec![];
+        ui.fonts(|f| {
+            for grid_line in pxu.contours.get_grid(self.component) {
+                let m = match grid_line.component {
+                    pxu::GridLineComponent::Xp(m) | pxu::GridLineComponent::Xm(m) => m,
+                    _ => continue,
+                };
+                if !grid_line.bounding_box.intersects(visible_rect) {
+                    continue;
+                }
+
+                let points = grid_line
+                    .path
+                    .iter()
+                    .map(|z| to_screen * egui::pos2(z.re as f32, -z.im as f32))
+                    .filter(|pos| inner_rect.contains(*pos))
+                    .collect::<Vec<_>>();
+                if points.len() < 2 {
+                    continue;
+                }
+
+                let text_shapes = math_text(
+                    f,
+                    points[points.len() / 2],
+                    egui::Align2::CENTER_CENTER,
+                    &format!("{m}"),
+                    font_id.clone(),
+                    palette.grid,
+                );
+                let label_rect = text_shapes
+                    .iter()
+                    .map(|shape| shape.visual_bounding_rect())
+                    .fold(Rect::NOTHING, |a, b| a.union(b))
+                    .expand(2.0);
+                if placed.iter().any(|other| other.intersects(label_rect)) {
+                    continue;
+                }
+                placed.push(label_rect);
+
+                shapes.push(egui::epaint::Shape::rect_filled(
+                    label_rect,
+                    egui::Rounding::same(2.0),
+                    palette.background,
+                ));
+                shapes.extend(text_shapes);
+            }
+        });
+    }
+
     fn draw_cuts(
         &self,
         rect: Rect,
         pxu: &pxu::Pxu,
         plot_state: &PlotState,
+        scale: Scale,
         shapes: &mut Vec<egui::Shape>,
     ) {
         let to_screen = self.to_screen(rect);
@@ -249,90 +917,25 @@ impl Plot {
         let mut branch_point_shapes = vec![];
 
         {
-            let shift = if self.component == pxu::Component::U {
-                2.0 * (pxu.state.points[plot_state.active_point]
-                    .sheet_data
-                    .log_branch_p
-                    * pxu.consts.k()) as f32
-                    / pxu.consts.h as f32
-            } else {
-                0.0
-            };
+            let shift = self.cut_shift(pxu, plot_state) as f32;
 
             let visible_cuts = pxu
                 .contours
                 .get_visible_cuts(pxu, self.component, plot_state.active_point)
-                .filter(|cut| match &plot_state.cut_filter {
-                    CutFilter::All => true,
-                    CutFilter::None => false,
-                    CutFilter::Only(v) => v.contains(&cut.typ),
-                })
+                .filter(|cut| self.is_cut_shown(cut, pxu, plot_state))
                 .collect::<Vec<_>>();
 
+            let palette = &plot_state.palette;
             for cut in visible_cuts {
-                let hide_log_cut = |comp| {
-                    comp != cut.component
-                        || (comp == pxu::Component::Xp
-                            && pxu.state.points[plot_state.active_point]
-                                .sheet_data
-                                .u_branch
-                                .1
-                                == UBranch::Between)
-                        || (comp == pxu::Component::Xm
-                            && pxu.state.points[plot_state.active_point]
-                                .sheet_data
-                                .u_branch
-                                .0
-                                == UBranch::Between)
-                };
-
                 let color = if plot_state.theme == Theme::Black {
-                    Color32::BLACK
+                    plot_state.cut_colors.e
+                } else if cut.typ == pxu::CutType::QLog {
+                    palette.qlog_cut
                 } else {
-                    match cut.typ {
-                        pxu::CutType::E => Color32::BLACK,
-
-                        pxu::CutType::Log(comp) => {
-                            if hide_log_cut(comp) {
-                                continue;
-                            } else if comp == pxu::Component::Xp {
-                                Color32::from_rgb(255, 128, 128)
-                            } else {
-                                Color32::from_rgb(128, 255, 128)
-                            }
-                        }
-
-                        pxu::CutType::ULongNegative(_) => {
-                            continue;
-                        }
-
-                        pxu::CutType::ULongPositive(comp) => {
-                            if hide_log_cut(comp) {
-                                continue;
-                            } else if comp == pxu::Component::Xp {
-                                Color32::from_rgb(255, 0, 0)
-                            } else {
-                                Color32::from_rgb(0, 192, 0)
-                            }
-                        }
-
-                        pxu::CutType::UShortScallion(comp) => {
-                            if comp == pxu::Component::Xp {
-                                Color32::from_rgb(255, 0, 0)
-                            } else {
-                                Color32::from_rgb(0, 192, 0)
-                            }
-                        }
-
-                        pxu::CutType::UShortKidney(comp) => {
-                            if comp == pxu::Component::Xp {
-                                Color32::from_rgb(255, 0, 0)
-                            } else {
-                                Color32::from_rgb(0, 192, 0)
-                            }
-                        }
-                        _ => Color32::from_rgb(255, 128, 0),
-                    }
+                    plot_state
+                        .cut_colors
+                        .get(&cut.typ)
+                        .unwrap_or(palette.other_cut)
                 };
 
                 let period_shifts = if cut.periodic {
@@ -356,16 +959,16 @@ impl Plot {
                         pxu::CutType::UShortKidney(_) | pxu::CutType::ULongNegative(_) => {
                             egui::epaint::Shape::dashed_line_many(
                                 &points.clone(),
-                                Stroke::new(3.0, color),
-                                4.0,
-                                4.0,
+                                Stroke::new(3.0 * scale.line, color),
+                                4.0 * scale.line,
+                                4.0 * scale.line,
                                 shapes,
                             );
                         }
                         _ => {
                             shapes.push(egui::epaint::Shape::line(
                                 points.clone(),
-                                Stroke::new(3.0, color),
+                                Stroke::new(3.0 * scale.line, color),
                             ));
                         }
                     }
@@ -376,7 +979,7 @@ impl Plot {
                         branch_point_shapes.push(egui::epaint::Shape::Circle(
                             egui::epaint::CircleShape {
                                 center,
-                                radius: 3.5,
+                                radius: 3.5 * scale.marker,
                                 fill: color,
                                 stroke: Stroke::NONE,
                             },
@@ -389,14 +992,379 @@ impl Plot {
         shapes.extend(branch_point_shapes);
     }
 
+    // The cuts of the comparison are the ones seen from the physical sheet, at
+    // the momentum of the active excitation
+    fn draw_comparison(
+        &self,
+        rect: Rect,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+        scale: Scale,
+        shapes: &mut Vec<egui::Shape>,
+    ) {
+        let Some(ref comparison) = plot_state.comparison else {
+            return;
+        };
+        let consts = comparison.consts;
+
+        let to_screen = self.to_screen(rect);
+        let pt = pxu::Point::new(pxu.state.points[plot_state.active_point].p, consts);
+        let stroke_width = 2.0 * scale.line;
+
+        let visible_cuts = comparison
+            .contours
+            .get_visible_cuts_from_point(&pt, self.component, consts)
+            .filter(|cut| {
+                let included = match &plot_state.cut_filter {
+                    CutFilter::All => true,
+                    CutFilter::None => false,
+                    CutFilter::Only(v) => v.contains(&cut.typ),
+                };
+                included && !matches!(cut.typ, pxu::CutType::ULongNegative(_))
+            });
+
+        for cut in visible_cuts {
+            let color = plot_state
+                .cut_colors
+                .get(&cut.typ)
+                .unwrap_or(plot_state.palette.other_cut)
+                .gamma_multiply(0.35);
+
+            let period_shifts = if cut.periodic {
+                let period = 2.0 * consts.k() as f64 / consts.h;
+                (-5..=5).map(|n| period as f32 * n as f32).collect()
+            } else {
+                vec![0.0]
+            };
+
+            for period_shift in period_shifts {
+                let points = cut
+                    .path
+                    .iter()
+                    .map(|z| to_screen * egui::pos2(z.re as f32, -(z.im as f32 + period_shift)))
+                    .collect::<Vec<_>>();
+
+                match cut.typ {
+                    pxu::CutType::UShortKidney(_) => {
+                        egui::epaint::Shape::dashed_line_many(
+                            &points,
+                            Stroke::new(stroke_width, color),
+                            4.0 * scale.line,
+                            4.0 * scale.line,
+                            shapes,
+                        );
+                    }
+                    _ => {
+                        shapes.push(egui::epaint::Shape::line(
+                            points,
+                            Stroke::new(stroke_width, color),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    // In the u plane the cuts follow the log branch of the active point
+    pub(crate) fn cut_shift(&self, pxu: &pxu::Pxu, plot_state: &PlotState) -> f64 {
+        if self.component == pxu::Component::U {
+            2.0 * (pxu.state.points[plot_state.active_point]
+                .sheet_data
+                .log_branch_p
+                * pxu.consts.k()) as f64
+                / pxu.consts.h
+        } else {
+            0.0
+        }
+    }
+
+    pub(crate) fn is_cut_shown(
+        &self,
+        cut: &pxu::Cut,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+    ) -> bool {
+        let included = match &plot_state.cut_filter {
+            CutFilter::All => true,
+            CutFilter::None => false,
+            CutFilter::Only(v) => v.contains(&cut.typ),
+        };
+        if !included {
+            return false;
+        }
+        if plot_state.theme == Theme::Black {
+            return true;
+        }
+
+        let hide_log_cut = |comp| {
+            let u_branch = &pxu.state.points[plot_state.active_point]
+                .sheet_data
+                .u_branch;
+            comp != cut.component
+                || (comp == pxu::Component::Xp && u_branch.1 == UBranch::Between)
+                || (comp == pxu::Component::Xm && u_branch.0 == UBranch::Between)
+        };
+
+        match cut.typ {
+            pxu::CutType::Log(comp) | pxu::CutType::ULongPositive(comp) => !hide_log_cut(comp),
+            pxu::CutType::ULongNegative(_) => false,
+            _ => true,
+        }
+    }
+
+    // Describe the cut or grid line closest to the given screen position, if any
+    // of them is close enough
+    fn curve_description(
+        &self,
+        rect: Rect,
+        pos: Pos2,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+    ) -> Option<String> {
+        const MAX_DISTANCE: f32 = 5.0;
+
+        let to_screen = self.to_screen(rect);
+        let distance = |path: &[Complex64], shift: f32| {
+            let points = path
+                .iter()
+                .map(|z| to_screen * egui::pos2(z.re as f32, -(z.im as f32 - shift)))
+                .collect::<Vec<_>>();
+            points
+                .windows(2)
+                .map(|w| distance_to_segment(pos, w[0], w[1]))
+                .fold(f32::INFINITY, f32::min)
+        };
+
+        let mut closest: Option<(f32, String)> = None;
+        let mut consider = |d: f32, describe: &dyn Fn() -> String| {
+            if d < MAX_DISTANCE && closest.as_ref().map_or(true, |(best, _)| d < *best) {
+                closest = Some((d, describe()));
+            }
+        };
+
+        let shift = self.cut_shift(pxu, plot_state) as f32;
+        for cut in pxu
+            .contours
+            .get_visible_cuts(pxu, self.component, plot_state.active_point)
+            .filter(|cut| self.is_cut_shown(cut, pxu, plot_state))
+        {
+            let period_shifts = if cut.periodic {
+                let period = 2.0 * pxu.consts.k() as f64 / pxu.consts.h;
+                (-5..=5).map(|n| period as f32 * n as f32).collect()
+            } else {
+                vec![0.0]
+            };
+            for period_shift in period_shifts {
+                let mut d = distance(&cut.path, shift - period_shift);
+                if let Some(z) = cut.branch_point {
+                    d = d.min(distance(&[z, z], shift - period_shift));
+                }
+                consider(d, &|| describe_cut(cut));
+            }
+        }
+
+        if !plot_state.hide_grid {
+            let tolerance = MAX_DISTANCE * self.height / rect.height();
+            let plane_pos = to_screen.inverse() * pos;
+            for grid_line in pxu.contours.get_grid(self.component) {
+                if grid_line.bounding_box.expand(tolerance).contains(plane_pos) {
+                    consider(distance(&grid_line.path, 0.0), &|| {
+                        describe_grid_line(grid_line)
+                    });
+                }
+            }
+        }
+
+        closest.map(|(_, description)| description)
+    }
+
+    fn show_curve_tooltip(&self, ui: &Ui, rect: Rect, pxu: &pxu::Pxu, plot_state: &PlotState) {
+        if plot_state.clean || plot_state.interaction_point.is_some() {
+            return;
+        }
+        let Some(pos) = ui.input(|i| {
+            if i.pointer.any_down() {
+                None
+            } else {
+                i.pointer.hover_pos()
+            }
+        }) else {
+            return;
+        };
+        if !rect.contains(pos) {
+            return;
+        }
+
+        if let Some(description) = self.curve_description(rect, pos, pxu, plot_state) {
+            let id = ui
+                .id()
+                .with(("curve_tooltip", format!("{:?}", self.component)));
+            egui::show_tooltip_at_pointer(ui.ctx(), id, |ui| {
+                ui.label(description);
+            });
+        }
+    }
+
+    fn draw_overlays(
+        &self,
+        rect: Rect,
+        plot_state: &PlotState,
+        scale: Scale,
+        shapes: &mut Vec<egui::Shape>,
+    ) {
+        let to_screen = self.to_screen(rect);
+        let scale = scale.marker;
+
+        for overlay in plot_state.overlays.iter().filter(|overlay| overlay.visible) {
+            for pt in overlay.state.points.iter() {
+                let z = pt.get(self.component);
+                shapes.push(egui::epaint::Shape::Circle(egui::epaint::CircleShape {
+                    center: to_screen * egui::pos2(z.re as f32, -z.im as f32),
+                    radius: 4.0 * scale,
+                    fill: overlay.color,
+                    stroke: egui::epaint::Stroke::new(scale, plot_state.palette.background),
+                }));
+            }
+        }
+    }
+
+    fn draw_legend(
+        &self,
+        ui: &Ui,
+        rect: Rect,
+        plot_state: &PlotState,
+        shapes: &mut Vec<egui::Shape>,
+    ) {
+        let overlays = plot_state
+            .overlays
+            .iter()
+            .filter(|overlay| overlay.visible)
+            .map(|overlay| (overlay.name.as_str(), overlay.color));
+        let palette = &plot_state.palette;
+        let entries = std::iter::once(("Editable", palette.active_point))
+            .chain(overlays)
+            .collect::<Vec<_>>();
+        if entries.len() < 2 {
+            return;
+        }
+
+        ui.fonts(|f| {
+            let font_id = egui::TextStyle::Small.resolve(ui.style());
+            let row_height = f.row_height(&font_id) + 4.0;
+            let top_left = rect.left_top() + vec2(10.0, 10.0);
+
+            let mut entry_shapes = vec![];
+            for (i, (name, color)) in entries.into_iter().enumerate() {
+                let pos = top_left + vec2(6.0, 6.0 + i as f32 * row_height);
+                entry_shapes.push(egui::epaint::Shape::circle_filled(
+                    pos + vec2(4.0, row_height / 2.0 - 2.0),
+                    4.0,
+                    color,
+                ));
+                entry_shapes.extend(math_text(
+                    f,
+                    pos + vec2(14.0, 0.0),
+                    egui::Align2::LEFT_TOP,
+                    name,
+                    font_id.clone(),
+                    palette.text,
+                ));
+            }
+
+            let bounding_rect = entry_shapes
+                .iter()
+                .map(|shape| shape.visual_bounding_rect())
+                .fold(Rect::NOTHING, |a, b| a.union(b));
+            shapes.push(egui::epaint::Shape::rect_filled(
+                bounding_rect.expand(6.0),
+                egui::Rounding::ZERO,
+                palette.label_fill,
+            ));
+            shapes.push(egui::epaint::Shape::rect_stroke(
+                bounding_rect.expand(4.0),
+                egui::Rounding::ZERO,
+                egui::Stroke::new(0.5, palette.label_stroke),
+            ));
+            shapes.extend(entry_shapes);
+        });
+    }
+
+    // The images 1/x_R^± of the excitations in the theory with k replaced by -k,
+    // drawn as hollow markers in the x planes
+    fn draw_mirror_images(
+        &self,
+        ui: &Ui,
+        rect: Rect,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+        scale: Scale,
+        shapes: &mut Vec<egui::Shape>,
+    ) {
+        let label = match self.component {
+            pxu::Component::Xp => "1/x^+_R",
+            pxu::Component::Xm => "1/x^-_R",
+            _ => return,
+        };
+
+        let to_screen = self.to_screen(rect);
+        let palette = &plot_state.palette;
+        let consts = pxu::CouplingConstants::new(pxu.consts.h, -pxu.consts.k());
+
+        let mut font_id = egui::TextStyle::Small.resolve(ui.style());
+        font_id.size *= scale.text;
+
+        for (i, pt) in pxu.state.points.iter().enumerate() {
+            let xr = if self.component == pxu::Component::Xp {
+                pxu::kinematics::xp_on_sheet(pt.p, 1.0, consts, &pt.sheet_data)
+            } else {
+                pxu::kinematics::xm_on_sheet(pt.p, 1.0, consts, &pt.sheet_data)
+            };
+            let z = 1.0 / xr;
+            if !z.is_finite() {
+                continue;
+            }
+            let center = to_screen * egui::pos2(z.re as f32, -z.im as f32);
+            let is_active = plot_state.active_point == i;
+            let color = if is_active {
+                palette.active_point
+            } else {
+                palette.point
+            };
+
+            shapes.push(egui::epaint::Shape::Circle(egui::epaint::CircleShape {
+                center,
+                radius: 4.0 * scale.marker,
+                fill: palette.background,
+                stroke: Stroke::new(1.5 * scale.marker, color),
+            }));
+
+            if is_active {
+                ui.fonts(|f| {
+                    shapes.extend(math_text(
+                        f,
+                        center + vec2(6.0, -6.0) * scale.marker,
+                        egui::Align2::LEFT_BOTTOM,
+                        label,
+                        font_id.clone(),
+                        palette.text,
+                    ));
+                });
+            }
+        }
+    }
+
     fn draw_points(
         &self,
         rect: Rect,
         pxu: &pxu::Pxu,
         plot_state: &PlotState,
+        scale: Scale,
         shapes: &mut Vec<egui::Shape>,
     ) {
         let to_screen = self.to_screen(rect);
+        let palette = &plot_state.palette;
+        let line_scale = scale.line;
+        let scale = scale.marker;
 
         for (i, pt) in pxu.state.points.iter().enumerate() {
             let is_interactive = plot_state.interaction_component == Some(self.component)
@@ -417,14 +1385,14 @@ impl Plot {
                 let center = to_screen * egui::pos2(z.re as f32, -z.im as f32);
 
                 let stroke = if is_active {
-                    egui::epaint::Stroke::new(2.0, Color32::BLUE)
+                    egui::epaint::Stroke::new(2.0 * scale, palette.active_point)
                 } else {
-                    egui::epaint::Stroke::new(2.0, Color32::GRAY)
+                    egui::epaint::Stroke::new(2.0 * scale, palette.other_sheet_point)
                 };
 
                 shapes.push(egui::epaint::Shape::Circle(egui::epaint::CircleShape {
                     center,
-                    radius: 7.0,
+                    radius: 7.0 * scale,
                     fill: Color32::TRANSPARENT,
                     stroke,
                 }));
@@ -433,46 +1401,74 @@ impl Plot {
             let z = pt.get(self.component);
             let center = to_screen * egui::pos2(z.re as f32, -z.im as f32);
 
-            let radius = if is_hovered || is_dragged {
-                6.0
-            } else if is_active {
-                5.0
-            } else {
-                4.0
-            };
+            let radius = scale
+                * if is_hovered || is_dragged {
+                    6.0
+                } else if is_active {
+                    5.0
+                } else {
+                    4.0
+                };
 
             let stroke = if is_active {
-                egui::epaint::Stroke::new(2.0, Color32::LIGHT_BLUE)
+                egui::epaint::Stroke::new(2.0 * scale, palette.active_ring)
             } else {
                 egui::epaint::Stroke::NONE
             };
 
-            let fill = if is_active {
-                Color32::BLUE
-            } else if pxu.state.points[i]
-                .same_sheet(&pxu.state.points[plot_state.active_point], self.component)
-            {
-                Color32::BLACK
-            } else {
-                Color32::GRAY
+            let same_sheet = pxu.state.points[i]
+                .same_sheet(&pxu.state.points[plot_state.active_point], self.component);
+            let style = plot_state.point_style(i);
+            let fill = match style.color {
+                Some([r, g, b]) if same_sheet || is_active => Color32::from_rgb(r, g, b),
+                Some([r, g, b]) => Color32::from_rgb(r, g, b).gamma_multiply(0.4),
+                None if is_active => palette.active_point,
+                None if same_sheet => palette.point,
+                None => palette.other_sheet_point,
             };
 
-            shapes.push(egui::epaint::Shape::Circle(egui::epaint::CircleShape {
-                center,
-                radius,
-                fill,
-                stroke,
-            }));
+            shapes.push(marker_shape(style.marker, center, radius, fill, stroke));
+        }
+
+        if let Some(snapped) = plot_state.snapped {
+            if snapped.component == self.component {
+                let center =
+                    to_screen * egui::pos2(snapped.value.re as f32, -snapped.value.im as f32);
+                let stroke = Stroke::new(1.5 * line_scale, palette.snap);
+                if snapped.along_line {
+                    shapes.extend(egui::Shape::dashed_line(
+                        &[
+                            egui::pos2(rect.left(), center.y),
+                            egui::pos2(rect.right(), center.y),
+                        ],
+                        stroke,
+                        6.0,
+                        4.0,
+                    ));
+                }
+                shapes.push(egui::Shape::circle_stroke(center, 9.0 * scale, stroke));
+            }
         }
     }
 
-    fn draw(&self, ui: &mut Ui, rect: Rect, pxu: &mut pxu::Pxu, plot_state: &PlotState) {
+    pub(crate) fn shapes(
+        &self,
+        ui: &Ui,
+        rect: Rect,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+        scale: Scale,
+    ) -> Vec<egui::Shape> {
         let to_screen = self.to_screen(rect);
 
         let mut shapes = vec![];
 
-        self.draw_grid(rect, pxu, plot_state, &mut shapes);
-        self.draw_cuts(rect, pxu, plot_state, &mut shapes);
+        self.draw_grid(rect, pxu, plot_state, scale, &mut shapes);
+        if plot_state.grid_labels == GridLabels::Always {
+            self.draw_grid_labels(ui, rect, pxu, plot_state, scale, &mut shapes);
+        }
+        self.draw_comparison(rect, pxu, plot_state, scale, &mut shapes);
+        self.draw_cuts(rect, pxu, plot_state, scale, &mut shapes);
 
         for &path_index in plot_state.path_indices.iter() {
             if path_index < pxu.paths.len() {
@@ -481,19 +1477,14 @@ impl Plot {
                     let mut same_branch = false;
 
                     let color = if active_point == plot_state.active_point {
-                        Color32::BLUE
+                        plot_state.palette.active_point
                     } else {
-                        Color32::GRAY
+                        plot_state.palette.other_sheet_point
                     };
-                    let width = 2.0;
+                    let width = 2.0 * scale.line;
 
                     for segment in segments.iter() {
-                        let contour = match self.component {
-                            pxu::Component::P => &segment.p,
-                            pxu::Component::Xp => &segment.xp,
-                            pxu::Component::Xm => &segment.xm,
-                            pxu::Component::U => &segment.u,
-                        };
+                        let contour = segment.get(self.component);
 
                         let segment_points = contour
                             .iter()
@@ -536,7 +1527,12 @@ impl Plot {
             }
         }
 
-        self.draw_points(rect, pxu, plot_state, &mut shapes);
+        self.draw_overlays(rect, plot_state, scale, &mut shapes);
+        if plot_state.mirror_images {
+            self.draw_mirror_images(ui, rect, pxu, plot_state, scale, &mut shapes);
+        }
+        self.draw_points(rect, pxu, plot_state, scale, &mut shapes);
+        self.draw_legend(ui, rect, plot_state, &mut shapes);
 
         {
             let text = match self.component {
@@ -546,44 +1542,136 @@ impl Plot {
                     if plot_state.theme == Theme::Black {
                         "x"
                     } else {
-                        "x⁺"
+                        "x^+"
                     }
                 }
-                pxu::Component::Xm => "x⁻",
+                pxu::Component::Xm => "x^-",
+                pxu::Component::E => "E",
+                pxu::Component::X => "x",
+                pxu::Component::MirrorP => "p̃",
+                pxu::Component::MirrorE => "Ẽ",
+                pxu::Component::Torus => "z",
             };
 
+            let mut font_id = egui::TextStyle::Body.resolve(ui.style());
+            font_id.size *= scale.text;
+
             ui.fonts(|f| {
-                let text_shape = egui::epaint::Shape::text(
+                let text_shapes = math_text(
                     f,
                     rect.right_top() + vec2(-10.0, 10.0),
                     egui::Align2::RIGHT_TOP,
                     text,
-                    egui::TextStyle::Body.resolve(ui.style()),
-                    Color32::BLACK,
+                    font_id,
+                    plot_state.palette.text,
                 );
+                let text_rect = text_shapes
+                    .iter()
+                    .map(|shape| shape.visual_bounding_rect())
+                    .fold(Rect::NOTHING, |a, b| a.union(b));
 
                 shapes.push(egui::epaint::Shape::rect_filled(
-                    text_shape.visual_bounding_rect().expand(6.0),
+                    text_rect.expand(6.0),
                     egui::Rounding::ZERO,
-                    Color32::WHITE,
+                    plot_state.palette.label_fill,
                 ));
                 shapes.push(egui::epaint::Shape::rect_stroke(
-                    text_shape.visual_bounding_rect().expand(4.0),
+                    text_rect.expand(4.0),
                     egui::Rounding::ZERO,
-                    egui::Stroke::new(0.5, Color32::BLACK),
+                    egui::Stroke::new(0.5, plot_state.palette.label_stroke),
                 ));
-                shapes.push(text_shape);
+                shapes.extend(text_shapes);
             });
         }
 
+        shapes
+    }
+
+    fn draw(&self, ui: &mut Ui, rect: Rect, pxu: &pxu::Pxu, plot_state: &PlotState) {
+        let shapes = self.shapes(ui, rect, pxu, plot_state, plot_state.screen_scale());
         ui.painter().extend(shapes);
+        self.draw_measurement(ui, rect, pxu, plot_state);
+    }
+
+    // The measurement is only shown on screen and is not included in exported
+    // figures
+    fn draw_measurement(&self, ui: &Ui, rect: Rect, pxu: &pxu::Pxu, plot_state: &PlotState) {
+        if plot_state.clean || !plot_state.measure_mode {
+            return;
+        }
+        let Some(measurement) = plot_state
+            .measurement
+            .filter(|measurement| measurement.component == self.component)
+        else {
+            return;
+        };
+
+        let to_screen = self.to_screen(rect);
+        let palette = &plot_state.palette;
+        let stroke = Stroke::new(1.5 * plot_state.scale(), palette.measure);
+        let screen_pos = |z: Complex64| to_screen * egui::pos2(z.re as f32, -z.im as f32);
+
+        let start = screen_pos(measurement.start);
+        ui.painter().circle_stroke(start, 4.0, stroke);
+
+        let end = measurement.end.or_else(|| {
+            ui.input(|i| i.pointer.hover_pos())
+                .filter(|pos| rect.contains(*pos))
+                .map(|pos| self.to_plane(rect, pos))
+        });
+        let Some(end) = end else {
+            return;
+        };
+        ui.painter().line_segment([start, screen_pos(end)], stroke);
+        ui.painter().circle_stroke(screen_pos(end), 4.0, stroke);
+
+        let dz = end - measurement.start;
+        let mut lines = vec![
+            format!("Δ{} = {dz:+.4}", plane_name(self.component)),
+            format!("|Δ{}| = {:.4}", plane_name(self.component), dz.norm()),
+        ];
+        // The excitations of a bound state are separated by 2i/h in the u
+        // plane, and the cuts are repeated with period 2ik/h
+        if self.component == pxu::Component::U {
+            let h = pxu.consts.h;
+            let k = pxu.consts.k() as f64;
+            lines.push(format!(
+                "Δu / (i/h) = {:+.4}",
+                dz / Complex64::new(0.0, 1.0 / h)
+            ));
+            if k > 0.0 {
+                lines.push(format!(
+                    "Δu / (2ik/h) = {:+.4}",
+                    dz / Complex64::new(0.0, 2.0 * k / h)
+                ));
+            }
+        }
+
+        let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+        let galley = ui.fonts(|f| f.layout_no_wrap(lines.join("\n"), font_id, palette.text));
+        let text_rect = egui::Align2::LEFT_BOTTOM.anchor_rect(Rect::from_min_size(
+            rect.left_bottom() + vec2(10.0, -10.0),
+            galley.size(),
+        ));
+        ui.painter().rect(
+            text_rect.expand(6.0),
+            egui::Rounding::ZERO,
+            palette.label_fill,
+            Stroke::new(0.5, palette.label_stroke),
+        );
+        ui.painter().galley(text_rect.min, galley, palette.text);
     }
 
     fn to_screen(&self, rect: Rect) -> RectTransform {
         RectTransform::from_to(self.visible_rect(rect), rect)
     }
 
-    fn visible_rect(&self, rect: Rect) -> Rect {
+    fn to_plane(&self, rect: Rect, pos: Pos2) -> Complex64 {
+        let z = self.to_screen(rect).inverse() * pos;
+        Complex64::new(z.x as f64, -z.y as f64)
+    }
+
+    pub(crate) fn visible_rect(&self, rect: Rect) -> Rect {
         Rect::from_center_size(
             self.origin,
             vec2(
@@ -609,7 +1697,7 @@ impl Plot {
         ui.painter().add(egui::epaint::Shape::rect_stroke(
             rect,
             egui::epaint::Rounding::same(4.0),
-            Stroke::new(1.0, Color32::DARK_GRAY),
+            self.frame_stroke(plot_state),
         ));
     }
 
@@ -624,16 +1712,169 @@ impl Plot {
         ui.set_clip_rect(rect);
 
         self.draw(ui, rect, pxu, plot_state);
+        self.show_curve_tooltip(ui, rect, pxu, plot_state);
 
         ui.set_clip_rect(old_clip_rect);
         ui.painter().add(egui::epaint::Shape::rect_stroke(
             rect,
             egui::epaint::Rounding::same(4.0),
-            Stroke::new(1.0, Color32::DARK_GRAY),
+            self.frame_stroke(plot_state),
         ));
     }
 
+    // The frame of the focused plane is drawn in the color of the active
+    // excitation, except in screenshots
+    fn frame_stroke(&self, plot_state: &PlotState) -> Stroke {
+        if plot_state.focused_component == Some(self.component) && !plot_state.clean {
+            Stroke::new(1.5, plot_state.palette.active_point)
+        } else {
+            Stroke::new(1.0, plot_state.palette.frame)
+        }
+    }
+
     fn zoom(&mut self, zoom: f32) {
         self.height /= zoom;
     }
+
+    // Stretch the plot along one axis only, which is useful for the very
+    // elongated cuts in the u plane
+    fn zoom_horizontally(&mut self, zoom: f32) {
+        self.width_factor /= zoom;
+    }
+
+    fn zoom_vertically(&mut self, zoom: f32) {
+        self.height /= zoom;
+        self.width_factor *= zoom;
+    }
+
+    // Center the view on the state and zoom so that all excitations are visible
+    pub fn zoom_to_fit(&mut self, rect: Rect, pxu: &pxu::Pxu) {
+        let points = pxu
+            .state
+            .points
+            .iter()
+            .map(|pt| {
+                let z = pt.get(self.component);
+                egui::pos2(z.re as f32, -z.im as f32)
+            })
+            .collect::<Vec<_>>();
+        let bounding_box = Rect::from_points(&points);
+        if !bounding_box.is_finite() {
+            return;
+        }
+
+        const MARGIN: f32 = 1.5;
+        const MIN_HEIGHT: f32 = 0.1;
+        let width = bounding_box.width() / (self.width_factor * rect.aspect_ratio());
+        self.origin = bounding_box.center();
+        self.height = (MARGIN * width.max(bounding_box.height())).max(MIN_HEIGHT);
+    }
+}
+
+// The names of the planes for screen readers
+fn spoken_name(component: pxu::Component) -> &'static str {
+    match component {
+        pxu::Component::P => "p",
+        pxu::Component::Xp => "x plus",
+        pxu::Component::Xm => "x minus",
+        pxu::Component::U => "u",
+        pxu::Component::E => "E",
+        pxu::Component::X => "x",
+        pxu::Component::MirrorP => "mirror p",
+        pxu::Component::MirrorE => "mirror E",
+        pxu::Component::Torus => "torus",
+    }
+}
+
+// The markers other than the circle are scaled to have about the same area
+fn marker_shape(
+    marker: pxu::Marker,
+    center: Pos2,
+    radius: f32,
+    fill: Color32,
+    stroke: Stroke,
+) -> egui::Shape {
+    let polygon = |corners: &[(f32, f32)], size: f32| {
+        let points = corners
+            .iter()
+            .map(|&(x, y)| center + size * vec2(x, y))
+            .collect();
+        egui::Shape::convex_polygon(points, fill, stroke)
+    };
+
+    match marker {
+        pxu::Marker::Circle => egui::Shape::Circle(egui::epaint::CircleShape {
+            center,
+            radius,
+            fill,
+            stroke,
+        }),
+        pxu::Marker::Square => polygon(
+            &[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)],
+            0.9 * radius,
+        ),
+        pxu::Marker::Diamond => polygon(
+            &[(0.0, -1.0), (1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)],
+            1.25 * radius,
+        ),
+        pxu::Marker::Triangle => polygon(&[(0.0, -1.0), (0.87, 0.5), (-0.87, 0.5)], 1.35 * radius),
+    }
+}
+
+fn distance_to_segment(pos: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_sq() > 0.0 {
+        ((pos - a).dot(ab) / ab.length_sq()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    pos.distance(a + t * ab)
+}
+
+fn plane_name(component: pxu::Component) -> &'static str {
+    match component {
+        pxu::Component::P => "p",
+        pxu::Component::Xp => "x⁺",
+        pxu::Component::Xm => "x⁻",
+        pxu::Component::U => "u",
+        pxu::Component::E => "E",
+        pxu::Component::X => "x",
+        pxu::Component::MirrorP => "p̃",
+        pxu::Component::MirrorE => "Ẽ",
+        pxu::Component::Torus => "z",
+    }
+}
+
+fn describe_cut(cut: &pxu::Cut) -> String {
+    let name = match cut.typ {
+        pxu::CutType::E => "E cut".to_owned(),
+        pxu::CutType::QLog => "Log cut".to_owned(),
+        pxu::CutType::DebugPath => "Debug path".to_owned(),
+        pxu::CutType::Log(component) => format!("Log cut of {}", plane_name(component)),
+        pxu::CutType::ULongPositive(component) => {
+            format!("Long cut of {} (positive)", plane_name(component))
+        }
+        pxu::CutType::ULongNegative(component) => {
+            format!("Long cut of {} (negative)", plane_name(component))
+        }
+        pxu::CutType::UShortScallion(component) => {
+            format!("Scallion of {}", plane_name(component))
+        }
+        pxu::CutType::UShortKidney(component) => format!("Kidney of {}", plane_name(component)),
+    };
+    format!("{name}\n{:?}", cut.typ)
+}
+
+fn describe_grid_line(grid_line: &pxu::GridLine) -> String {
+    match grid_line.component {
+        pxu::GridLineComponent::Real => "Real line".to_owned(),
+        pxu::GridLineComponent::Xp(m) => format!("Grid line of x⁺\nm = {m}"),
+        pxu::GridLineComponent::Xm(m) => format!("Grid line of x⁻\nm = {m}"),
+        pxu::GridLineComponent::Energy(pxu::EnergyLevel::Re(e)) => {
+            format!("Energy grid line\nRe E = {e}")
+        }
+        pxu::GridLineComponent::Energy(pxu::EnergyLevel::Im(e)) => {
+            format!("Energy grid line\nIm E = {e}")
+        }
+    }
 }
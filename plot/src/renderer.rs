@@ -0,0 +1,53 @@
+use egui::Vec2;
+
+use crate::{setup_custom_fonts, ExportSettings, Plot, PlotState};
+
+// Draws plots to images outside of the app. The plots are drawn into an egui
+// context that is never shown, which is only needed for laying out the text.
+// It has the same fonts as the app, and is kept for all the images drawn by
+// the renderer.
+pub struct Renderer {
+    ctx: egui::Context,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        let ctx = egui::Context::default();
+        setup_custom_fonts(&ctx);
+        Self { ctx }
+    }
+}
+
+impl Renderer {
+    fn with_ui<R>(&self, f: impl FnOnce(&mut egui::Ui) -> R) -> R {
+        let mut result = None;
+        let _ = self.ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                result = Some(f(ui));
+            });
+        });
+        result.expect("The central panel should always be shown")
+    }
+
+    pub fn to_png(
+        &self,
+        plot: &Plot,
+        size: Vec2,
+        settings: &ExportSettings,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+    ) -> Option<Vec<u8>> {
+        self.with_ui(|ui| plot.to_png(ui, size, settings, pxu, plot_state))
+    }
+
+    pub fn to_svg(
+        &self,
+        plot: &Plot,
+        size: Vec2,
+        settings: &ExportSettings,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+    ) -> String {
+        self.with_ui(|ui| plot.to_svg(ui, size, settings, pxu, plot_state))
+    }
+}
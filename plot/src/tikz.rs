@@ -0,0 +1,324 @@
+// Write what is shown in a plot as a pgfplots figure, using the same
+// conventions as the FigureWriter in latex-figures
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use egui::Rect;
+use num::complex::Complex64;
+
+use crate::plot::{Plot, PlotState};
+use crate::{CutColorPreset, CutColors};
+
+struct Tikz {
+    x_range: Range<f64>,
+    y_range: Range<f64>,
+    y_shift: f64,
+    body: String,
+}
+
+impl Tikz {
+    fn format_coordinate(&self, z: Complex64) -> String {
+        format!("({:.5},{:.5})", z.re, z.im + self.y_shift)
+    }
+
+    // Only keep the parts of the contour that are close to the visible region
+    fn crop(&self, contour: &[Complex64]) -> Vec<Complex64> {
+        let expand = |range: &Range<f64>| {
+            let d = 1.1 * (range.end - range.start);
+            (range.start - d)..(range.end + d)
+        };
+        let x_range = expand(&self.x_range);
+        let y_range = expand(&self.y_range);
+
+        let include = |z1: Complex64, z2: Complex64| {
+            let z1 = z1 + Complex64::new(0.0, self.y_shift);
+            let z2 = z2 + Complex64::new(0.0, self.y_shift);
+            let inside = |z: Complex64| x_range.contains(&z.re) && y_range.contains(&z.im);
+            let crosses = (z1.re < x_range.start && z2.re > x_range.end)
+                || (z2.re < x_range.start && z1.re > x_range.end)
+                || (z1.im < y_range.start && z2.im > y_range.end)
+                || (z2.im < y_range.start && z1.im > y_range.end);
+            inside(z1) || inside(z2) || crosses
+        };
+
+        if contour.len() < 2 {
+            return vec![];
+        }
+
+        let mut coordinates = vec![];
+        if include(contour[0], contour[1]) {
+            coordinates.push(contour[0]);
+        }
+        for window in contour.windows(3) {
+            if include(window[0], window[1]) || include(window[1], window[2]) {
+                coordinates.push(window[1]);
+            }
+        }
+        let n = contour.len();
+        if include(contour[n - 2], contour[n - 1]) {
+            coordinates.push(contour[n - 1]);
+        }
+        coordinates
+    }
+
+    fn add_plot(&mut self, options: &[&str], contour: &[Complex64]) {
+        self.add_plot_all(options, self.crop(contour));
+    }
+
+    fn add_plot_all(&mut self, options: &[&str], contour: Vec<Complex64>) {
+        let mut coordinates = contour
+            .into_iter()
+            .map(|z| self.format_coordinate(z))
+            .collect::<Vec<_>>();
+        coordinates.dedup();
+
+        if !coordinates.is_empty() {
+            let _ = writeln!(
+                self.body,
+                "\\addplot [{}] coordinates {{ {} }};",
+                options.join(","),
+                coordinates.join(" ")
+            );
+        }
+    }
+
+    fn add_cut(&mut self, cut: &pxu::Cut, shifts: &[f64], cut_colors: &CutColors) {
+        let straight = "very thick";
+        let dashed = "very thick,densely dashed";
+        let zigzag = "decorate,decoration={zigzag, segment length=1.2mm, amplitude=0.15mm},thick";
+        let (color, style) = match cut.typ {
+            pxu::CutType::E => ("black", straight),
+            pxu::CutType::QLog => ("black", zigzag),
+            pxu::CutType::Log(pxu::Component::Xp) => ("Red", zigzag),
+            pxu::CutType::Log(pxu::Component::Xm) => ("Green", zigzag),
+            pxu::CutType::ULongPositive(pxu::Component::Xp) => ("Red", straight),
+            pxu::CutType::ULongNegative(pxu::Component::Xp) => ("Red", dashed),
+            pxu::CutType::ULongPositive(pxu::Component::Xm) => ("Green", straight),
+            pxu::CutType::ULongNegative(pxu::Component::Xm) => ("Green", dashed),
+            pxu::CutType::UShortScallion(pxu::Component::Xp) => ("Red", straight),
+            pxu::CutType::UShortKidney(pxu::Component::Xp) => ("Red", dashed),
+            pxu::CutType::UShortScallion(pxu::Component::Xm) => ("Green", straight),
+            pxu::CutType::UShortKidney(pxu::Component::Xm) => ("Green", dashed),
+            _ => return,
+        };
+
+        // The standard colors are written with the names used in the paper
+        let custom_color;
+        let color = match cut_colors.get(&cut.typ) {
+            Some(custom) if cut_colors.preset != CutColorPreset::Standard => {
+                custom_color = rgb_color(custom);
+                custom_color.as_str()
+            }
+            _ => color,
+        };
+
+        for &shift in shifts {
+            self.y_shift = shift;
+            if style == dashed {
+                self.add_plot(&["lightgray", "very thick"], &cut.path);
+            }
+            self.add_plot(&[color, style], &cut.path);
+            if let Some(branch_point) = cut.branch_point {
+                self.add_plot_all(
+                    &[color, "only marks", "mark size=0.05cm"],
+                    vec![branch_point],
+                );
+            }
+        }
+        self.y_shift = 0.0;
+    }
+}
+
+fn rgb_color(color: egui::Color32) -> String {
+    let [r, g, b, _] = color.to_array();
+    format!("color={{rgb,255:red,{r};green,{g};blue,{b}}}")
+}
+
+impl Plot {
+    // The width is given in cm, and the height follows from the aspect ratio of
+    // the plot on screen
+    pub fn to_tikz(
+        &self,
+        rect: Rect,
+        width: f64,
+        pxu: &pxu::Pxu,
+        plot_state: &PlotState,
+    ) -> String {
+        let visible_rect = self.visible_rect(rect);
+        let x_range = visible_rect.left() as f64..visible_rect.right() as f64;
+        let y_range = -visible_rect.bottom() as f64..-visible_rect.top() as f64;
+        let height = width / rect.aspect_ratio() as f64;
+
+        let mut tikz = Tikz {
+            x_range: x_range.clone(),
+            y_range: y_range.clone(),
+            y_shift: 0.0,
+            body: String::new(),
+        };
+
+        if self.component != pxu::Component::P {
+            let origin =
+                if plot_state.theme == crate::Theme::Black && self.component == pxu::Component::U {
+                    Complex64::new(0.0, -1.0 / pxu.consts.h)
+                } else {
+                    Complex64::from(0.0)
+                };
+            let options = ["very thin", "black"];
+            tikz.add_plot(
+                &options,
+                &[
+                    Complex64::new(x_range.start - 1.0, origin.im),
+                    Complex64::new(x_range.end + 1.0, origin.im),
+                ],
+            );
+            tikz.add_plot(
+                &options,
+                &[
+                    Complex64::new(origin.re, y_range.start - 1.0),
+                    Complex64::new(origin.re, y_range.end + 1.0),
+                ],
+            );
+        }
+
+        for grid_line in pxu.contours.get_grid(self.component) {
+            let color = if matches!(grid_line.component, pxu::GridLineComponent::Energy(_)) {
+                "LightBlue"
+            } else {
+                "lightgray"
+            };
+            tikz.add_plot(&["very thin", color], &grid_line.path);
+        }
+
+        let shift = self.cut_shift(pxu, plot_state);
+        for cut in pxu
+            .contours
+            .get_visible_cuts(pxu, self.component, plot_state.active_point)
+            .filter(|cut| self.is_cut_shown(cut, pxu, plot_state))
+        {
+            let shifts = if cut.periodic {
+                let period = 2.0 * pxu.consts.k() as f64 / pxu.consts.h;
+                (-5..=5).map(|n| period * n as f64 - shift).collect()
+            } else {
+                vec![-shift]
+            };
+            tikz.add_cut(cut, &shifts, &plot_state.cut_colors);
+        }
+
+        let active_point = &pxu.state.points[plot_state.active_point];
+        for &path_index in plot_state.path_indices.iter() {
+            let Some(path) = pxu.paths.get(path_index) else {
+                continue;
+            };
+            for (index, segments) in path.segments.iter().enumerate() {
+                let color = if index == plot_state.active_point {
+                    "Blue"
+                } else {
+                    "Gray"
+                };
+                for segment in segments {
+                    let style = if active_point
+                        .sheet_data
+                        .is_same(&segment.sheet_data, self.component)
+                    {
+                        "solid"
+                    } else {
+                        "densely dotted"
+                    };
+                    tikz.add_plot(&["very thick", color, style], &segment.get(self.component));
+                }
+            }
+        }
+
+        if pxu.state.unlocked && matches!(self.component, pxu::Component::Xp | pxu::Component::Xm) {
+            let conj = self.component.conj();
+            for (index, point) in pxu.state.points.iter().enumerate() {
+                let color = if index == plot_state.active_point {
+                    "Blue"
+                } else {
+                    "Gray"
+                };
+                tikz.add_plot_all(
+                    &["only marks", "mark=o", color, "mark size=0.1cm"],
+                    vec![point.get(conj)],
+                );
+            }
+        }
+
+        for overlay in plot_state.overlays.iter().filter(|overlay| overlay.visible) {
+            let color = rgb_color(overlay.color);
+            let points = overlay
+                .state
+                .points
+                .iter()
+                .map(|point| point.get(self.component))
+                .collect();
+            tikz.add_plot_all(&["only marks", &color, "mark size=0.05cm"], points);
+        }
+
+        let mut marks = [("Blue", vec![]), ("black", vec![]), ("Gray", vec![])];
+        for (index, point) in pxu.state.points.iter().enumerate() {
+            let group = if index == plot_state.active_point {
+                0
+            } else if point.same_sheet(active_point, self.component) {
+                1
+            } else {
+                2
+            };
+
+            let style = plot_state.point_style(index);
+            if style == pxu::PointStyle::default() {
+                marks[group].1.push(point.get(self.component));
+                continue;
+            }
+
+            let color = style.color.map_or_else(
+                || marks[group].0.to_owned(),
+                |[r, g, b]| rgb_color(egui::Color32::from_rgb(r, g, b)),
+            );
+            let mark = match style.marker {
+                pxu::Marker::Circle => "mark=*",
+                pxu::Marker::Square => "mark=square*",
+                pxu::Marker::Diamond => "mark=diamond*",
+                pxu::Marker::Triangle => "mark=triangle*",
+            };
+            tikz.add_plot_all(
+                &["only marks", mark, &color, "mark size=0.05cm"],
+                vec![point.get(self.component)],
+            );
+        }
+        for (color, points) in marks {
+            tikz.add_plot_all(&["only marks", color, "mark size=0.05cm"], points);
+        }
+
+        let indicator = match self.component {
+            pxu::Component::P => "p",
+            pxu::Component::Xp => "x^+",
+            pxu::Component::Xm => "x^-",
+            pxu::Component::U => "u",
+            pxu::Component::E => "E",
+            pxu::Component::X => "x",
+            pxu::Component::MirrorP => r"\tilde{p}",
+            pxu::Component::MirrorE => r"\tilde{E}",
+            pxu::Component::Torus => "z",
+        };
+
+        let (x_min, x_max) = (x_range.start, x_range.end);
+        let (y_min, y_max) = (y_range.start, y_range.end);
+
+        let mut output = String::new();
+        let _ = writeln!(output, "\\begin{{tikzpicture}}");
+        let _ = writeln!(output, "\\begin{{axis}}[hide axis,scale only axis,ticks=none,xmin={x_min:.5},xmax={x_max:.5},ymin={y_min:.5},ymax={y_max:.5},clip,clip mode=individual,width={width}cm,height={height:.3}cm]");
+        let _ = writeln!(output, "\\begin{{scope}}");
+        let _ = writeln!(
+            output,
+            "\\clip ({x_min:.5},{y_min:.5}) rectangle ({x_max:.5},{y_max:.5});"
+        );
+        output.push_str(&tikz.body);
+        let _ = writeln!(output, "\\end{{scope}}");
+        let _ = writeln!(output, "\\end{{axis}}\n");
+        let _ = writeln!(output, "\\node at (current bounding box.north east) [anchor=north east,fill=white,outer sep=0.1cm,draw,thin] {{$\\scriptstyle {indicator}$}};");
+        let _ = writeln!(output, "\\end{{tikzpicture}}");
+        output
+    }
+}
@@ -15,6 +15,7 @@ struct PlotData {
     xp_plot: Plot,
     xm_plot: Plot,
     u_plot: Plot,
+    e_plot: Plot,
     #[serde(skip)]
     plot_state: PlotState,
 }
@@ -94,6 +95,11 @@ impl Frame {
                 pxu::Component::Xp => &mut plot_data.xp_plot,
                 pxu::Component::Xm => &mut plot_data.xm_plot,
                 pxu::Component::U => &mut plot_data.u_plot,
+                pxu::Component::E => &mut plot_data.e_plot,
+                _ => {
+                    log::warn!("Presentations have no {component} plot");
+                    continue;
+                }
             };
 
             if let Some(Value::Const(origin)) = descr.origin {
@@ -169,6 +175,12 @@ impl Default for PlotData {
                 width_factor: 1.0,
                 origin: Pos2::ZERO,
             },
+            e_plot: Plot {
+                component: pxu::Component::E,
+                height: (8.0 * consts.h) as f32,
+                width_factor: 1.0,
+                origin: Pos2::ZERO,
+            },
             plot_state: Default::default(),
         }
     }
@@ -271,14 +283,11 @@ impl eframe::App for PresentationApp {
             } else {
                 log::info!("Pxu {:?} not found", self.plot_data.consts);
                 let mut pxu = pxu::Pxu::new(self.plot_data.consts);
-                pxu.state = pxu::State::new(1, pxu.consts);
+                pxu.state = pxu::State::new(1, pxu.kinematic_consts());
                 self.plot_data.plot_state.active_point = 0;
 
-                pxu.state
-                    .update(0, pxu::Component::P, 0.1.into(), &pxu.contours, pxu.consts);
-
-                pxu.state
-                    .update(0, pxu::Component::P, 0.15.into(), &pxu.contours, pxu.consts);
+                let _ = pxu.update_state(0, pxu::Component::P, 0.1.into());
+                let _ = pxu.update_state(0, pxu::Component::P, 0.15.into());
 
                 self.pxu.push(pxu);
                 self.pxu.last_mut().unwrap()
@@ -316,10 +325,7 @@ impl eframe::App for PresentationApp {
                 while (chrono::Utc::now() - start).num_milliseconds()
                     < (1000.0 / 20.0f64).floor() as i64
                 {
-                    if pxu
-                        .contours
-                        .update(pxu.state.points[0].p.re.floor() as i32, pxu.consts)
-                    {
+                    if pxu.update_contours(pxu.state.points[0].p.re.floor() as i32) {
                         break;
                     }
                     ctx.request_repaint();
@@ -347,6 +353,8 @@ impl eframe::App for PresentationApp {
                             pxu::Component::Xp => &mut self.plot_data.xp_plot,
                             pxu::Component::Xm => &mut self.plot_data.xm_plot,
                             pxu::Component::U => &mut self.plot_data.u_plot,
+                            pxu::Component::E => &mut self.plot_data.e_plot,
+                            _ => continue,
                         };
 
                         if let Some(ref height) = descr.height {
@@ -514,25 +522,12 @@ impl PresentationApp {
                     if !self.pxu.iter().any(|p| p.consts == consts) {
                         log::info!("Generating contours for ({},{})", consts.h, consts.k());
                         let mut pxu = pxu::Pxu::new(consts);
-                        pxu.state = pxu::State::new(1, pxu.consts);
-
-                        pxu.state.update(
-                            0,
-                            pxu::Component::P,
-                            0.1.into(),
-                            &pxu.contours,
-                            pxu.consts,
-                        );
+                        pxu.state = pxu::State::new(1, pxu.kinematic_consts());
 
-                        pxu.state.update(
-                            0,
-                            pxu::Component::P,
-                            0.15.into(),
-                            &pxu.contours,
-                            pxu.consts,
-                        );
+                        let _ = pxu.update_state(0, pxu::Component::P, 0.1.into());
+                        let _ = pxu.update_state(0, pxu::Component::P, 0.15.into());
 
-                        pxu.contours.update(0, pxu.consts);
+                        pxu.update_contours(0);
                         self.pxu.push(pxu);
                     }
                 }
@@ -542,7 +537,7 @@ impl PresentationApp {
                 while (chrono::Utc::now() - start).num_milliseconds()
                     < (1000.0 / 10.0f64).floor() as i64
                 {
-                    if pxu.contours.update(0, pxu.consts) {
+                    if pxu.update_contours(0) {
                         break;
                     }
                 }
@@ -1,11 +1,20 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 use egui::{vec2, Pos2};
+use num::complex::Complex64;
 use pxu::kinematics::CouplingConstants;
 use pxu::{CutType, Pxu};
 
 use crate::arguments::Arguments;
-use crate::ui_state::UiState;
+use crate::console::{Console, Request};
+use crate::contour_job::ContourJob;
+use crate::event_log::{EventLog, EventTime};
+use crate::path_recorder::PathRecorder;
+use crate::shortcuts::Action;
+use crate::sweep::{Sweep, SweepParameter};
+use crate::ui_state::{
+    PaneLayout, PlotView, Session, SharedView, StateBookmark, UiState, ViewPreset, VisualTheme,
+};
 use plot::Plot;
 
 use std::sync::mpsc;
@@ -19,10 +28,17 @@ pub struct PxuGuiApp {
     xp_plot: Plot,
     xm_plot: Plot,
     u_plot: Plot,
+    e_plot: Plot,
+    x_plot: Plot,
+    mirror_p_plot: Plot,
+    mirror_e_plot: Plot,
+    torus_plot: Plot,
     ui_state: UiState,
     #[serde(skip)]
     frame_history: crate::frame_history::FrameHistory,
     #[serde(skip)]
+    undo_history: crate::undo_history::UndoHistory,
+    #[serde(skip)]
     path_dialog_text: Option<String>,
     #[serde(skip)]
     state_dialog_text: Option<String>,
@@ -42,6 +58,128 @@ pub struct PxuGuiApp {
     figure_index: Option<usize>,
     #[serde(skip)]
     figure_response_channel: ResponseChannel,
+    #[serde(skip)]
+    figure_bundle: HashMap<String, Vec<u8>>,
+    #[serde(skip)]
+    figure_bundle_channel: FigureBundleChannel,
+    #[serde(skip)]
+    figure_bundle_message: Option<String>,
+    #[serde(skip)]
+    export_dialog: Option<ExportDialog>,
+    #[serde(skip)]
+    plot_rects: Vec<(pxu::Component, egui::Rect)>,
+    #[serde(skip)]
+    state_text_channel: StateTextChannel,
+    #[serde(skip)]
+    state_message: Option<String>,
+    #[serde(skip)]
+    recording: bool,
+    #[serde(skip)]
+    recorder_start: Option<pxu::State>,
+    #[serde(skip)]
+    recorder: Option<PathRecorder>,
+    #[serde(skip)]
+    recorded_paths: Vec<pxu::path::SavedPath>,
+    #[serde(skip)]
+    playback: Playback,
+    #[serde(skip)]
+    coordinate_text: [String; 4],
+    #[serde(skip)]
+    coordinate_error: Option<String>,
+    #[serde(skip)]
+    sheet_data_watch: Vec<SheetDataWatch>,
+    #[serde(skip)]
+    event_log: EventLog,
+    #[serde(skip)]
+    contour_job: Option<ContourJob>,
+    #[serde(skip)]
+    comparison_consts: Option<CouplingConstants>,
+    // The job generating the contours of the comparison, with its range of p
+    #[serde(skip)]
+    comparison_job: Option<(i32, ContourJob)>,
+    #[serde(skip)]
+    comparison_p_range: i32,
+    #[serde(skip)]
+    sweep: Sweep,
+    // The frame of the sweep that is waiting to be exported
+    #[serde(skip)]
+    sweep_export: Option<usize>,
+    #[serde(skip)]
+    console: Console,
+    #[serde(skip)]
+    view_preset_name: String,
+    #[serde(skip)]
+    view_preset_component: pxu::Component,
+    #[serde(skip)]
+    bookmark_name: String,
+    #[serde(skip)]
+    pending_session: Option<Session>,
+    #[serde(skip)]
+    bound_state_dialog: Option<BoundStateDialog>,
+}
+
+// The sheet data of an excitation together with the time each of its five
+// entries last changed
+struct SheetDataWatch {
+    sheet_data: pxu::kinematics::SheetData,
+    changed: [f64; 5],
+}
+
+impl SheetDataWatch {
+    // How long a changed entry stays highlighted, in seconds
+    const HIGHLIGHT: f64 = 2.0;
+
+    fn new(sheet_data: &pxu::kinematics::SheetData) -> Self {
+        Self {
+            sheet_data: sheet_data.clone(),
+            changed: [f64::NEG_INFINITY; 5],
+        }
+    }
+
+    fn update(&mut self, sheet_data: &pxu::kinematics::SheetData, now: f64) {
+        let old = &self.sheet_data;
+        let changes = [
+            old.log_branch_p != sheet_data.log_branch_p,
+            old.log_branch_m != sheet_data.log_branch_m,
+            old.e_branch != sheet_data.e_branch,
+            old.u_branch != sheet_data.u_branch,
+            old.im_x_sign != sheet_data.im_x_sign,
+        ];
+        for (changed, time) in changes.into_iter().zip(self.changed.iter_mut()) {
+            if changed {
+                *time = now;
+            }
+        }
+        self.sheet_data = sheet_data.clone();
+    }
+
+    fn is_highlighted(&self, index: usize, now: f64) -> bool {
+        now - self.changed[index] < Self::HIGHLIGHT
+    }
+}
+
+// Moves the state along one of the loaded paths
+struct Playback {
+    path_index: usize,
+    t: f64,
+    playing: bool,
+    speed: f64,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            path_index: 0,
+            t: 0.0,
+            playing: false,
+            speed: 1.0,
+        }
+    }
+}
+
+impl Playback {
+    // Seconds to play a whole path at normal speed
+    const DURATION: f64 = 5.0;
 }
 
 type ResponseChannelTuple = (
@@ -62,6 +200,74 @@ impl From<ResponseChannelTuple> for ResponseChannel {
     }
 }
 
+// Opening a file or reading the clipboard is asynchronous on the web, so the
+// text of a state to load is passed back to the app through a channel
+type StateTextChannelTuple = (
+    mpsc::Sender<Result<String, String>>,
+    mpsc::Receiver<Result<String, String>>,
+);
+struct StateTextChannel {
+    tx: mpsc::Sender<Result<String, String>>,
+    rx: mpsc::Receiver<Result<String, String>>,
+}
+
+impl From<StateTextChannelTuple> for StateTextChannel {
+    fn from(value: StateTextChannelTuple) -> Self {
+        Self {
+            tx: value.0,
+            rx: value.1,
+        }
+    }
+}
+
+// The files of a figure bundle written by interactive-figures, as pairs of file
+// names and contents
+type FigureBundle = Vec<(String, Vec<u8>)>;
+
+type FigureBundleChannelTuple = (
+    mpsc::Sender<Result<FigureBundle, String>>,
+    mpsc::Receiver<Result<FigureBundle, String>>,
+);
+struct FigureBundleChannel {
+    tx: mpsc::Sender<Result<FigureBundle, String>>,
+    rx: mpsc::Receiver<Result<FigureBundle, String>>,
+}
+
+impl From<FigureBundleChannelTuple> for FigureBundleChannel {
+    fn from(value: FigureBundleChannelTuple) -> Self {
+        Self {
+            tx: value.0,
+            rx: value.1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Svg,
+    Png,
+    Tikz,
+}
+
+struct ExportDialog {
+    component: pxu::Component,
+    format: ExportFormat,
+    size: egui::Vec2,
+    tikz_width: f64,
+    message: Option<String>,
+}
+
+// Replaces the state by a locked bound state. When the coupling constants are
+// changed the bound state is only built once the new contours are ready.
+struct BoundStateDialog {
+    m: usize,
+    p_range: i32,
+    consts: CouplingConstants,
+    center: Option<(pxu::Component, Complex64)>,
+    waiting: bool,
+    message: Option<String>,
+}
+
 impl Default for PxuGuiApp {
     fn default() -> Self {
         let bound_state_number = 1;
@@ -74,31 +280,17 @@ impl Default for PxuGuiApp {
 
         Self {
             pxu,
-            p_plot: Plot {
-                component: pxu::Component::P,
-                height: 0.75,
-                width_factor: 1.5,
-                origin: Pos2::new(0.5, 0.0),
-            },
-            xp_plot: Plot {
-                component: pxu::Component::Xp,
-                height: (8.0 * consts.s()) as f32,
-                width_factor: 1.0,
-                origin: Pos2::ZERO,
-            },
-            xm_plot: Plot {
-                component: pxu::Component::Xm,
-                height: (8.0 * consts.s()) as f32,
-                width_factor: 1.0,
-                origin: Pos2::ZERO,
-            },
-            u_plot: Plot {
-                component: pxu::Component::U,
-                height: ((4 * consts.k() + 1) as f64 / consts.h) as f32,
-                width_factor: 1.0,
-                origin: Pos2::ZERO,
-            },
+            p_plot: default_plot(pxu::Component::P, consts),
+            xp_plot: default_plot(pxu::Component::Xp, consts),
+            xm_plot: default_plot(pxu::Component::Xm, consts),
+            u_plot: default_plot(pxu::Component::U, consts),
+            e_plot: default_plot(pxu::Component::E, consts),
+            x_plot: default_plot(pxu::Component::X, consts),
+            mirror_p_plot: default_plot(pxu::Component::MirrorP, consts),
+            mirror_e_plot: default_plot(pxu::Component::MirrorE, consts),
+            torus_plot: default_plot(pxu::Component::Torus, consts),
             frame_history: Default::default(),
+            undo_history: Default::default(),
             ui_state: Default::default(),
             path_dialog_text: None,
             state_dialog_text: None,
@@ -110,38 +302,75 @@ impl Default for PxuGuiApp {
             figures: vec![],
             figure_index: None,
             figure_response_channel: mpsc::channel().into(),
+            figure_bundle: HashMap::new(),
+            figure_bundle_channel: mpsc::channel().into(),
+            figure_bundle_message: None,
+            export_dialog: None,
+            plot_rects: vec![],
+            state_text_channel: mpsc::channel().into(),
+            state_message: None,
+            recording: false,
+            recorder_start: None,
+            recorder: None,
+            recorded_paths: vec![],
+            playback: Default::default(),
+            coordinate_text: Default::default(),
+            coordinate_error: None,
+            sheet_data_watch: vec![],
+            event_log: Default::default(),
+            contour_job: None,
+            comparison_consts: None,
+            comparison_job: None,
+            comparison_p_range: 0,
+            sweep: Sweep::default(),
+            sweep_export: None,
+            console: Console::default(),
+            view_preset_name: String::new(),
+            view_preset_component: pxu::Component::P,
+            bookmark_name: String::new(),
+            pending_session: None,
+            bound_state_dialog: None,
         }
     }
 }
 
-fn setup_custom_fonts(ctx: &egui::Context) {
-    // Start with the default fonts (we will be adding to them rather than replacing them).
-    let mut fonts = egui::FontDefinitions::default();
-
-    // Install my own font (maybe supporting non-latin characters).
-    // .ttf and .otf files supported.
-
-    fonts.font_data.insert(
-        "DejaVuSansMono".to_owned(),
-        egui::FontData::from_static(include_bytes!("../../fonts/DejaVuSansMono.ttf")),
-    );
-
-    // Put my font first (highest priority):
-    fonts
-        .families
-        .get_mut(&egui::FontFamily::Proportional)
-        .unwrap()
-        .insert(0, "DejaVuSansMono".to_owned());
-
-    // // Put my font as last fallback for monospace:
-    fonts
-        .families
-        .get_mut(&egui::FontFamily::Monospace)
-        .unwrap()
-        .insert(0, "DejaVuSansMono".to_owned());
-
-    // Tell egui to use these fonts:
-    ctx.set_fonts(fonts);
+// The branch point of the energy of an excitation with bound state number m
+// that is closest to p = 0 in the upper half plane
+// The view of each plane when the app is first started
+pub fn default_plot(component: pxu::Component, consts: CouplingConstants) -> Plot {
+    let (height, width_factor, origin) = match component {
+        pxu::Component::P => (0.75, 1.5, Pos2::new(0.5, 0.0)),
+        pxu::Component::Xp | pxu::Component::Xm => (8.0 * consts.s(), 1.0, Pos2::ZERO),
+        pxu::Component::U => ((4 * consts.k() + 1) as f64 / consts.h, 1.0, Pos2::ZERO),
+        pxu::Component::E => (8.0 * consts.h, 1.0, Pos2::ZERO),
+        pxu::Component::X => (8.0 * consts.s(), 1.0, Pos2::ZERO),
+        pxu::Component::MirrorP => (1.5 * consts.h, 1.0, Pos2::ZERO),
+        pxu::Component::MirrorE => (4.0 * std::f64::consts::TAU, 1.0, Pos2::ZERO),
+        pxu::Component::Torus => (4.0, 1.0, Pos2::ZERO),
+    };
+    Plot {
+        component,
+        height: height as f32,
+        width_factor,
+        origin,
+    }
+}
+
+fn energy_branch_point(m: f64, consts: CouplingConstants) -> Complex64 {
+    use std::f64::consts::PI;
+
+    let k = consts.k() as f64;
+    let h = consts.h;
+    let i = Complex64::i();
+
+    // Start from the solution for small p
+    let mut p = -m / (k - 2.0 * PI * i * h);
+    for _ in 0..16 {
+        let f = m + k * p + 2.0 * i * h * (PI * p).sin();
+        let df = k + 2.0 * PI * i * h * (PI * p).cos();
+        p -= f / df;
+    }
+    p
 }
 
 impl PxuGuiApp {
@@ -150,13 +379,19 @@ impl PxuGuiApp {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
-        setup_custom_fonts(&cc.egui_ctx);
+        plot::setup_custom_fonts(&cc.egui_ctx);
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
         if let Some(storage) = cc.storage {
             let mut app: Self = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            let consts = app.pxu.kinematic_consts();
+            app.pxu.state = std::mem::take(&mut app.pxu.state).migrate(consts);
             app.ui_state.set(settings);
+            // A state or view given in the url takes precedence over the session
+            if app.ui_state.inital_saved_state.is_none() && app.ui_state.initial_view.is_none() {
+                app.pending_session = Session::load(storage);
+            }
             return app;
         }
 
@@ -174,34 +409,44 @@ impl PxuGuiApp {
         Ok(())
     }
 
-    fn load_figure(&mut self, name: &String, body: &str) -> Result<(), String> {
-        let figure = ron::from_str::<interactive_figures::Figure>(body)
-            .map_err(|err| format!("Could not parse figure {name}: {err}"))?;
+    fn load_figure(&mut self, name: &str, bytes: &[u8]) -> Result<(), String> {
+        let figure = pxu::binary::load::<interactive_figures::Figure>(bytes)
+            .ok_or_else(|| format!("Could not parse figure {name}"))?;
 
         log::info!("Loaded figure {name}");
 
         self.ui_state.plot_state.path_indices = (0..figure.paths.len()).collect();
+        self.contour_job = None;
         if self.pxu.consts != figure.consts {
             self.pxu.consts = figure.consts;
             self.pxu.contours.clear();
+            self.ui_state.plot_state.overlays.clear();
         }
-        self.pxu.state = figure.state;
+        self.pxu.state = figure.state.migrate(self.pxu.kinematic_consts());
         self.pxu.paths = figure.paths;
         self.ui_state.plot_state.active_point = 0;
         Ok(())
     }
 
     fn load_file(&mut self, name: &String, bytes: Vec<u8>) -> Result<(), String> {
-        let body = std::str::from_utf8(&bytes)
-            .map_err(|err| format!("Could not parse response body: {err}"))?;
-
         if name == "figures" {
+            let body = std::str::from_utf8(&bytes)
+                .map_err(|err| format!("Could not parse response body: {err}"))?;
             self.load_figure_descriptions(body)
         } else {
-            self.load_figure(name, body)
+            self.load_figure(name, &bytes)
         }
     }
 
+    // Figures are saved either as RON or in the binary format
+    fn file_name(&self, name: &str) -> String {
+        let binary = self
+            .figures
+            .iter()
+            .any(|fig| fig.binary && fig.filename == name);
+        format!("{name}.{}", if binary { "bin" } else { "ron" })
+    }
+
     fn parse_figure_download_response(
         &mut self,
         name: &String,
@@ -245,15 +490,15 @@ impl PxuGuiApp {
         Some("http://olofos.github.io/pxu-gui/".to_owned())
     }
 
-    fn download_file(&mut self, ctx: &egui::Context, name: &String) -> Result<(), String> {
+    fn download_file(&mut self, ctx: &egui::Context, name: &str) -> Result<(), String> {
         let base_url = self.get_base_url().ok_or("No base URL set".to_owned())?;
 
-        let url = format!("{base_url}data/{name}.ron");
+        let url = format!("{base_url}data/{}", self.file_name(name));
         let request = ehttp::Request::get(url);
 
         let ctx = ctx.clone();
         let tx = self.figure_response_channel.tx.clone();
-        let name = name.clone();
+        let name = name.to_owned();
         ehttp::fetch(request, move |response| {
             if tx.send((name, response)).is_err() {
                 log::info!("Could not send response!");
@@ -281,8 +526,7 @@ impl PxuGuiApp {
 
     #[cfg(not(target_arch = "wasm32"))]
     fn load_local_file(&mut self, name: &String) -> Result<(), String> {
-        let mut path = std::path::Path::new("./pxu-gui/dist/data/").join(name);
-        path.set_extension("ron");
+        let path = std::path::Path::new("./pxu-gui/dist/data/").join(self.file_name(name));
 
         let bytes =
             std::fs::read(&path).map_err(|err| format!("Could not read {path:?}: {err}"))?;
@@ -294,6 +538,12 @@ impl PxuGuiApp {
         let Some(name) = self.fetch_queue.pop_front() else {
             return;
         };
+        if let Some(bytes) = self.figure_bundle.get(&self.file_name(&name)).cloned() {
+            if let Err(err) = self.load_file(&name, bytes) {
+                log::warn!("Error: {err}");
+            }
+            return;
+        }
         #[cfg(not(target_arch = "wasm32"))]
         {
             match self.load_local_file(&name) {
@@ -317,6 +567,7 @@ impl PxuGuiApp {
     }
 
     fn set_ux_mode(&mut self) {
+        self.ui_state.hidden_cut_filter = None;
         self.ui_state.plot_state.theme = plot::Theme::Black;
         self.ui_state.plot_state.cut_filter = plot::CutFilter::Only(vec![
             CutType::UShortScallion(pxu::Component::Xp),
@@ -326,272 +577,2108 @@ impl PxuGuiApp {
     }
 
     fn set_normal_mode(&mut self) {
+        self.ui_state.hidden_cut_filter = None;
         self.ui_state.plot_state.theme = plot::Theme::Normal;
         self.ui_state.plot_state.cut_filter = plot::CutFilter::All;
     }
-}
 
-impl eframe::App for PxuGuiApp {
-    fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, self);
+    fn saved_state(&self) -> pxu::SavedState {
+        pxu::SavedState::new(self.pxu.state.clone(), self.pxu.consts)
+            .with_point_styles(&self.ui_state.plot_state.point_styles)
     }
 
-    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        if self.ui_state.show_fps {
-            self.frame_history
-                .on_new_frame(ctx.input(|i| i.time), frame.info().cpu_usage);
+    fn load_saved_state(&mut self, saved_state: pxu::SavedState) {
+        self.contour_job = None;
+        if self.pxu.consts != saved_state.consts {
+            self.pxu.consts = saved_state.consts;
+            self.pxu.contours.clear();
+            self.ui_state.plot_state.overlays.clear();
         }
+        self.pxu.state = saved_state.state;
+        self.ui_state.plot_state.point_styles = saved_state.point_styles;
 
-        self.load_files(ctx);
-        self.receive_download();
+        let last = self.pxu.state.points.len().saturating_sub(1);
+        let active_point = &mut self.ui_state.plot_state.active_point;
+        *active_point = (*active_point).min(last);
+    }
 
-        if self.ui_state.continuous_mode {
-            ctx.request_repaint();
+    fn load_singlet(&mut self, singlet: &crate::singlets::Singlet) {
+        match singlet.state(self.pxu.consts) {
+            Ok(state) => {
+                self.pxu.state = state;
+                let last = self.pxu.state.points.len() - 1;
+                let active_point = &mut self.ui_state.plot_state.active_point;
+                *active_point = (*active_point).min(last);
+                self.state_message = Some(format!("Loaded the {} singlet", singlet.name));
+            }
+            Err(err) => {
+                log::warn!("{err}");
+                self.state_message = Some(err);
+            }
         }
+    }
 
-        if ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
-            self.ui_state.hide_side_panel = !self.ui_state.hide_side_panel;
+    fn save_state(&mut self) {
+        let saved_state = self.saved_state();
+        let result = ron::ser::to_string_pretty(&saved_state, Default::default())
+            .map_err(|err| format!("Could not serialise state: {err}"))
+            .and_then(|s| save_ron_file("state.ron", &s));
+
+        match result {
+            Ok(Some(message)) => self.state_message = Some(message),
+            Ok(None) => {}
+            Err(err) => {
+                log::warn!("{err}");
+                self.state_message = Some(err);
+            }
         }
+    }
 
-        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-            self.ui_state.plot_state.close_fullscreen();
-            self.ui_state.hide_side_panel = false;
+    // The rows of planes in the current mode. Without a custom layout the extra
+    // plots are split between the rows, starting with the top one.
+    fn plot_rows(&self) -> Vec<Vec<pxu::Component>> {
+        use pxu::Component::*;
+        if self.is_ux_mode() {
+            return vec![vec![Xp, U]];
+        }
+        if let Some(layout) = &self.ui_state.pane_layout {
+            return layout.rows();
         }
 
-        ctx.input(|i| {
-            for (key, num) in [
-                (egui::Key::Backspace, self.pxu.state.points.len()),
-                (egui::Key::Num1, 1),
-                (egui::Key::Num2, 2),
-                (egui::Key::Num3, 3),
-                (egui::Key::Num4, 4),
-                (egui::Key::Num5, 5),
-                (egui::Key::Num6, 6),
-                (egui::Key::Num7, 7),
-                (egui::Key::Num8, 8),
-                (egui::Key::Num9, 9),
-            ] {
-                if i.key_pressed(key) {
-                    self.pxu.state = pxu::State::new(num, self.pxu.consts);
-                    self.ui_state.plot_state.active_point =
-                        self.ui_state.plot_state.active_point.min(num - 1);
-                }
-            }
+        let extra = &self.ui_state.extra_plots;
+        let (top, bottom) = extra.split_at((extra.len() + 1) / 2);
+        vec![
+            [P, U].into_iter().chain(top.iter().copied()).collect(),
+            [Xp, Xm].into_iter().chain(bottom.iter().copied()).collect(),
+        ]
+    }
 
-            if i.key_pressed(egui::Key::Space) {
-                self.pxu.state.unlocked = !self.pxu.state.unlocked;
+    // The planes in the order they are laid out in the current mode. A plane
+    // shown in more than one pane is only included once.
+    fn visible_components(&self) -> Vec<pxu::Component> {
+        let mut components = vec![];
+        for component in self.plot_rows().into_iter().flatten() {
+            if !components.contains(&component) {
+                components.push(component);
             }
-        });
+        }
+        components
+    }
 
-        if self.pxu.state.unlocked && ctx.input(|i| i.key_pressed(egui::Key::Plus)) {
-            self.pxu
-                .state
-                .points
-                .push(pxu::Point::new(0.1, self.pxu.consts));
+    fn plot_layout(&self, rect: egui::Rect) -> Vec<(pxu::Component, egui::Rect)> {
+        if let Some(component) = self.ui_state.plot_state.fullscreen_component {
+            return vec![(component, rect)];
         }
 
-        if self.pxu.state.unlocked
-            && self.pxu.state.points.len() > 1
-            && ctx.input(|i| i.key_pressed(egui::Key::Minus))
-        {
-            self.pxu
-                .state
-                .points
-                .remove(self.ui_state.plot_state.active_point);
-            self.ui_state.plot_state.active_point = self
-                .ui_state
-                .plot_state
-                .active_point
-                .min(self.pxu.state.points.len() - 1);
+        const GAP: f32 = 8.0;
+        let rows = self.plot_rows();
+        let h = (rect.height() - GAP * (rows.len() - 1) as f32) / rows.len() as f32;
+
+        let mut layout = vec![];
+        for (i, row) in rows.iter().enumerate() {
+            let w = (rect.width() - GAP * (row.len() - 1) as f32) / row.len() as f32;
+            for (j, component) in row.iter().enumerate() {
+                let top_left = rect.left_top() + vec2(j as f32 * (w + GAP), i as f32 * (h + GAP));
+                layout.push((*component, egui::Rect::from_min_size(top_left, vec2(w, h))));
+            }
         }
+        layout
+    }
 
-        if self.pxu.state.unlocked
-            && self.pxu.state.points.len() > 1
-            && self.ui_state.plot_state.active_point < self.pxu.state.points.len() - 1
-            && ctx.input(|i| i.key_pressed(egui::Key::ArrowUp))
-        {
-            let i = self.ui_state.plot_state.active_point;
-            self.pxu.state.points.swap(i, i + 1);
-            self.ui_state.plot_state.active_point += 1;
+    fn toggle_extra_plot(&mut self, component: pxu::Component, show: bool) {
+        let extra = &mut self.ui_state.extra_plots;
+        extra.retain(|c| *c != component);
+        if show {
+            extra.push(component);
         }
+    }
 
-        if self.pxu.state.unlocked
-            && self.pxu.state.points.len() > 1
-            && self.ui_state.plot_state.active_point > 0
-            && ctx.input(|i| i.key_pressed(egui::Key::ArrowDown))
-        {
-            let i = self.ui_state.plot_state.active_point;
-            self.pxu.state.points.swap(i, i - 1);
-            self.ui_state.plot_state.active_point -= 1;
+    // Cycle through showing each plane in full screen, and then all of them. In
+    // presentation mode there is always a single plane.
+    fn cycle_component(&mut self, forward: bool) {
+        let mut cycle = vec![];
+        if !self.ui_state.plot_state.presentation {
+            cycle.push(None);
         }
+        cycle.extend(self.visible_components().into_iter().map(Some));
 
-        if self.pxu.state.points.len() > 1
-            && self.ui_state.plot_state.active_point < self.pxu.state.points.len() - 1
-            && ctx.input(|i| i.key_pressed(egui::Key::ArrowRight))
-        {
-            self.ui_state.plot_state.active_point += 1;
+        let current = &mut self.ui_state.plot_state.fullscreen_component;
+        let index = cycle.iter().position(|c| c == current).unwrap_or(0);
+        let index = if forward {
+            (index + 1) % cycle.len()
+        } else {
+            (index + cycle.len() - 1) % cycle.len()
+        };
+        *current = cycle[index];
+    }
+
+    fn do_action(&mut self, ctx: &egui::Context, action: Action) {
+        let plot_state = &mut self.ui_state.plot_state;
+        let len = self.pxu.state.points.len();
+
+        match action {
+            Action::NextComponent => self.cycle_component(true),
+            Action::PreviousComponent => self.cycle_component(false),
+            Action::NextExcitation => {
+                plot_state.active_point = (plot_state.active_point + 1) % len;
+            }
+            Action::PreviousExcitation => {
+                plot_state.active_point = (plot_state.active_point + len - 1) % len;
+            }
+            Action::ToggleCuts => {
+                if let Some(cut_filter) = self.ui_state.hidden_cut_filter.take() {
+                    plot_state.cut_filter = cut_filter;
+                } else {
+                    self.ui_state.hidden_cut_filter = Some(std::mem::replace(
+                        &mut plot_state.cut_filter,
+                        plot::CutFilter::None,
+                    ));
+                }
+            }
+            Action::ToggleGrid => plot_state.hide_grid = !plot_state.hide_grid,
+            Action::ToggleSnap => plot_state.snap_mode = !plot_state.snap_mode,
+            Action::TogglePresentation => self.toggle_presentation(ctx),
+            Action::ToggleMeasure => plot_state.toggle_measure_mode(),
+            Action::ToggleScreenshot => plot_state.clean = !plot_state.clean,
+            Action::FocusNextPlane => self.cycle_focus(true),
+            Action::FocusPreviousPlane => self.cycle_focus(false),
+            Action::ZoomToFit => {
+                for (component, rect) in self.plot_rects.iter() {
+                    let plot = match component {
+                        pxu::Component::P => &mut self.p_plot,
+                        pxu::Component::Xp => &mut self.xp_plot,
+                        pxu::Component::Xm => &mut self.xm_plot,
+                        pxu::Component::U => &mut self.u_plot,
+                        pxu::Component::E => &mut self.e_plot,
+                        pxu::Component::X => &mut self.x_plot,
+                        pxu::Component::MirrorP => &mut self.mirror_p_plot,
+                        pxu::Component::MirrorE => &mut self.mirror_e_plot,
+                        pxu::Component::Torus => &mut self.torus_plot,
+                    };
+                    plot.zoom_to_fit(*rect, &self.pxu);
+                }
+            }
         }
+    }
 
-        if self.pxu.state.points.len() > 1
-            && self.ui_state.plot_state.active_point > 0
-            && ctx.input(|i| i.key_pressed(egui::Key::ArrowLeft))
-        {
-            self.ui_state.plot_state.active_point -= 1;
+    // Move the focus through the visible planes, and then back to no plane
+    fn cycle_focus(&mut self, forward: bool) {
+        let mut components = vec![];
+        for (component, _) in self.plot_rects.iter() {
+            if !components.contains(component) {
+                components.push(*component);
+            }
         }
 
-        if !self.ui_state.hide_side_panel {
-            self.draw_side_panel(ctx);
+        let plot_state = &mut self.ui_state.plot_state;
+        let index = plot_state
+            .focused_component
+            .and_then(|focused| components.iter().position(|c| *c == focused));
+        plot_state.focused_component = match (index, forward) {
+            (None, true) => components.first().copied(),
+            (None, false) => components.last().copied(),
+            (Some(i), true) => components.get(i + 1).copied(),
+            (Some(i), false) => i.checked_sub(1).map(|i| components[i]),
+        };
+    }
+
+    // Show a single plane, the one under the pointer if any, with everything
+    // else hidden and larger markers and lines
+    fn toggle_presentation(&mut self, ctx: &egui::Context) {
+        if self.ui_state.plot_state.presentation {
+            self.ui_state.plot_state.presentation = false;
+            self.ui_state.plot_state.close_fullscreen();
+            return;
         }
 
-        if let Some(saved_state) = self.ui_state.inital_saved_state.take() {
-            self.pxu.consts = saved_state.consts;
-            self.pxu.state = saved_state.state;
-            self.ui_state.plot_state.active_point = 0;
+        if self.ui_state.plot_state.fullscreen_component.is_none() {
+            let hovered = ctx.input(|i| i.pointer.hover_pos()).and_then(|pos| {
+                self.plot_rects
+                    .iter()
+                    .find(|(_, rect)| rect.contains(pos))
+                    .map(|(component, _)| *component)
+            });
+            self.ui_state.plot_state.fullscreen_component =
+                hovered.or_else(|| self.visible_components().first().copied());
         }
+        self.ui_state.plot_state.presentation = true;
+    }
 
-        {
-            let start = chrono::Utc::now();
-            while (chrono::Utc::now() - start).num_milliseconds()
-                < (1000.0 / 20.0f64).floor() as i64
-            {
-                if self.pxu.contours.update(
-                    self.pxu.state.points[self.ui_state.plot_state.active_point]
-                        .p
-                        .re
-                        .floor() as i32,
-                    self.pxu.consts,
-                ) {
-                    if let Some(ref mut saved_paths) = self.ui_state.saved_paths_to_load {
-                        if let Some(saved_path) = saved_paths.pop() {
-                            let path = pxu::Path::from_base_path(
-                                saved_path.into(),
-                                &self.pxu.contours,
-                                self.pxu.consts,
-                            );
+    fn rebind_shortcut(&mut self, ctx: &egui::Context, action: Action) {
+        let pressed = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some((*key, *modifiers)),
+                _ => None,
+            })
+        });
 
-                            self.pxu.paths.push(path);
+        match pressed {
+            Some((egui::Key::Escape, _)) => self.ui_state.rebinding = None,
+            Some((key, modifiers)) => {
+                let shortcut = egui::KeyboardShortcut::new(modifiers, key);
+                self.ui_state.shortcuts.set(action, shortcut);
+                self.ui_state.rebinding = None;
+            }
+            None => {}
+        }
+    }
 
-                            let progress = self.ui_state.path_load_progress.unwrap();
-                            self.ui_state.path_load_progress = Some((progress.0 + 1, progress.1));
-                        } else {
-                            self.ui_state.saved_paths_to_load = None;
-                            self.ui_state.path_load_progress = None;
-                            if !self.pxu.paths.is_empty() {
-                                self.ui_state.plot_state.path_indices = vec![];
+    fn show_shortcuts_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.ui_state.show_shortcuts;
+        egui::Window::new("Keyboard shortcuts")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("shortcuts_grid")
+                    .num_columns(2)
+                    .spacing([10.0, 4.0])
+                    .show(ui, |ui| {
+                        for action in Action::ALL {
+                            ui.label(action.description());
+                            let text = if self.ui_state.rebinding == Some(action) {
+                                "Press a key…".to_owned()
+                            } else {
+                                ctx.format_shortcut(&self.ui_state.shortcuts.get(action))
+                            };
+                            if ui.button(text).clicked() {
+                                self.ui_state.rebinding = Some(action);
                             }
+                            ui.end_row();
                         }
-                    }
-                    break;
+                    });
+
+                ui.add_space(10.0);
+                ui.label("Click a shortcut and press the new key, or Escape to cancel.");
+                if ui.button("Reset to defaults").clicked() {
+                    self.ui_state.shortcuts = Default::default();
+                    self.ui_state.rebinding = None;
                 }
-                ctx.request_repaint();
-            }
+            });
+
+        if !open {
+            self.ui_state.rebinding = None;
         }
+        self.ui_state.show_shortcuts = open;
+    }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let rect = ui.available_rect_before_wrap();
+    fn record_path(&mut self) {
+        if !self.recording {
+            return;
+        }
 
-            let mut plots = if let Some(component) = self.ui_state.plot_state.fullscreen_component {
-                let plot = match component {
-                    pxu::Component::P => &mut self.p_plot,
-                    pxu::Component::Xp => &mut self.xp_plot,
-                    pxu::Component::Xm => &mut self.xm_plot,
-                    pxu::Component::U => &mut self.u_plot,
-                };
+        let plot_state = &self.ui_state.plot_state;
+        let dragged = plot_state
+            .interaction_component
+            .zip(plot_state.interaction_point)
+            .filter(|_| plot_state.dragged);
 
-                vec![(plot, rect)]
-            } else if self.is_ux_mode() {
-                use egui::Rect;
-                const GAP: f32 = 8.0;
-                let w = (rect.width() - GAP) / 2.0;
-                let size = vec2(w, rect.height());
-
-                let top_left = rect.left_top();
-
-                vec![
-                    (
-                        &mut self.u_plot,
-                        Rect::from_min_size(top_left + vec2(w + GAP, 0.0), size),
-                    ),
-                    (
-                        &mut self.xp_plot,
-                        Rect::from_min_size(top_left + vec2(0.0, 0.0), size),
-                    ),
-                ]
-            } else {
-                use egui::Rect;
-                const GAP: f32 = 8.0;
-                let w = (rect.width() - GAP) / 2.0;
-                let h = (rect.height() - GAP) / 2.0;
-                let size = vec2(w, h);
-
-                let top_left = rect.left_top();
-
-                vec![
-                    (&mut self.p_plot, Rect::from_min_size(top_left, size)),
-                    (
-                        &mut self.u_plot,
-                        Rect::from_min_size(top_left + vec2(w + GAP, 0.0), size),
-                    ),
-                    (
-                        &mut self.xp_plot,
-                        Rect::from_min_size(top_left + vec2(0.0, h + GAP), size),
-                    ),
-                    (
-                        &mut self.xm_plot,
-                        Rect::from_min_size(top_left + vec2(w + GAP, h + GAP), size),
-                    ),
-                ]
+        if let Some((component, excitation)) = dragged {
+            let Some(start) = &self.recorder_start else {
+                return;
+            };
+            let consts = self.pxu.consts;
+            self.recorder
+                .get_or_insert_with(|| {
+                    PathRecorder::new(start.clone(), component, excitation, consts)
+                })
+                .push(&self.pxu.state);
+        } else if let Some(recorder) = self.recorder.take() {
+            let name = format!("Recorded path {}", self.recorded_paths.len() + 1);
+            let Some(saved_path) = recorder.finish(&name) else {
+                return;
             };
 
-            self.ui_state.plot_state.reset();
+            let path = pxu::Path::from_base_path(
+                saved_path.clone().into(),
+                &self.pxu.contours,
+                self.pxu.consts,
+            );
+            self.ui_state
+                .plot_state
+                .path_indices
+                .push(self.pxu.paths.len());
+            self.pxu.paths.push(path);
+            self.recorded_paths.push(saved_path);
+        }
+    }
 
-            for (plot, rect) in plots.iter_mut() {
-                plot.interact(ui, *rect, &mut self.pxu, &mut self.ui_state.plot_state);
-            }
+    fn set_playback_position(&mut self, t: f64) {
+        self.playback.t = t.clamp(0.0, 1.0);
+        if let Some((_, t)) = &mut self.ui_state.plot_state.path_constraint {
+            *t = self.playback.t;
+        }
+        let Some(path) = self.pxu.paths.get(self.playback.path_index) else {
+            self.playback.playing = false;
+            return;
+        };
 
-            for (plot, rect) in plots {
-                plot.show(ui, rect, &mut self.pxu, &mut self.ui_state.plot_state);
-            }
-        });
+        let state = path.sample(self.playback.t, self.pxu.consts);
+        if state.points.is_empty() {
+            return;
+        }
+        self.pxu.state = pxu::State {
+            unlocked: self.pxu.state.unlocked,
+            ..state
+        };
+        let last = self.pxu.state.points.len() - 1;
+        let active_point = &mut self.ui_state.plot_state.active_point;
+        *active_point = (*active_point).min(last);
+    }
 
+    // Plays a path that moves the active excitation from p to -p, relative to
+    // the start of its range of p, along an arc above the branch point of the
+    // energy. This is the first of the crossing paths of the paper, and it
+    // leaves the state on the crossed sheet.
+    fn cross_active_point(&mut self) {
+        const NAME: &str = "Crossing";
+        const STEPS: usize = 100;
+        const HEIGHT: f64 = 0.08;
+        const MARGIN: f64 = 0.02;
+
+        let active_point = self.ui_state.plot_state.active_point;
+        let point = &self.pxu.state.points[active_point];
+        let p = point.p;
+        let start = p.re.floor();
+        let d = p - start;
+
+        // The arc is widened and raised if needed so that it passes above the
+        // branch point
+        let branch_point = energy_branch_point(point.m(self.pxu.consts).re, self.pxu.consts);
+        let width = d.re.max(branch_point.re.abs() + MARGIN);
+        let height =
+            HEIGHT.max((branch_point.im + MARGIN) / (1.0 - (branch_point.re / width).powi(2)));
+
+        let mut path = vec![p];
+        path.extend((0..=STEPS).map(|i| {
+            let x = 1.0 - 2.0 * i as f64 / STEPS as f64;
+            start + width * x + Complex64::i() * height * (1.0 - x * x)
+        }));
+        path.push(start - d);
+        // A segment of zero length can not be followed
+        path.dedup_by(|a, b| (*a - *b).norm() < 1.0e-9);
+
+        let saved_path = pxu::path::SavedPath::new(
+            NAME,
+            path,
+            self.pxu.state.clone(),
+            pxu::Component::P,
+            active_point,
+            self.pxu.consts,
+        );
+        let path = match pxu::Path::try_from_base_path(
+            saved_path.into(),
+            &self.pxu.contours,
+            self.pxu.consts,
+        ) {
+            Ok(path) => path,
+            Err(err) => {
+                log::warn!("Could not cross excitation {active_point}: {err}");
+                self.state_message = Some(format!("Could not cross the excitation: {err}"));
+                return;
+            }
+        };
+
+        // Replace the path of an earlier crossing
+        let index = match self.pxu.paths.iter().position(|path| path.name == NAME) {
+            Some(index) => {
+                self.pxu.paths[index] = path;
+                index
+            }
+            None => {
+                self.pxu.paths.push(path);
+                self.pxu.paths.len() - 1
+            }
+        };
+        let path_indices = &mut self.ui_state.plot_state.path_indices;
+        if !path_indices.contains(&index) {
+            path_indices.push(index);
+        }
+
+        self.playback.path_index = index;
+        self.playback.playing = true;
+        self.set_playback_position(0.0);
+    }
+
+    fn advance_playback(&mut self, ctx: &egui::Context) {
+        if !self.playback.playing {
+            return;
+        }
+
+        // With a delayed repaint egui only predicts the frame time, so use the
+        // time that has actually passed
+        let dt = if self.ui_state.power_saving {
+            ctx.input(|i| i.unstable_dt).min(0.25) as f64
+        } else {
+            ctx.input(|i| i.stable_dt) as f64
+        };
+        let t = self.playback.t + dt * self.playback.speed / Playback::DURATION;
+        if t >= 1.0 {
+            self.playback.playing = false;
+        }
+        self.set_playback_position(t);
+        self.request_animation_frame(ctx);
+    }
+
+    // Asks for the next frame of an animation or progress display. In power
+    // saving mode these are drawn at a lower frame rate.
+    fn request_animation_frame(&self, ctx: &egui::Context) {
+        const POWER_SAVING_FRAME_TIME: f64 = 1.0 / 15.0;
+
+        if self.ui_state.power_saving {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(POWER_SAVING_FRAME_TIME));
+        } else {
+            ctx.request_repaint();
+        }
+    }
+
+    fn apply_view_preset(&mut self, index: usize) {
+        let Some(preset) = self.ui_state.view_presets.get(index) else {
+            return;
+        };
+        let view = preset.view;
+        let plot = self.plot_mut(view.component);
+        plot.height = view.height;
+        plot.origin = view.origin;
+        plot.width_factor = view.width_factor;
+    }
+
+    fn draw_layout_controls(&mut self, ui: &mut egui::Ui) {
+        // The payload of a pane that is dragged onto another one
+        struct DraggedPane(usize);
+
+        egui::CollapsingHeader::new("Layout")
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut custom = self.ui_state.pane_layout.is_some();
+                if ui.checkbox(&mut custom, "Custom layout").changed() {
+                    self.ui_state.pane_layout = if custom {
+                        let rows = self.plot_rows();
+                        Some(PaneLayout {
+                            columns: rows[0].len(),
+                            panes: rows.concat(),
+                        })
+                    } else {
+                        None
+                    };
+                }
+
+                let Some(layout) = &mut self.ui_state.pane_layout else {
+                    return;
+                };
+
+                let mut count = layout.panes.len();
+                if ui
+                    .add(egui::Slider::new(&mut count, 1..=PaneLayout::MAX_PANES).text("Panes"))
+                    .changed()
+                {
+                    layout.set_pane_count(count);
+                }
+                let count = layout.panes.len();
+                ui.add(egui::Slider::new(&mut layout.columns, 1..=count).text("Columns"));
+
+                let mut swap = None;
+                egui::Grid::new("pane_layout").show(ui, |ui| {
+                    let columns = layout.columns.max(1);
+                    for (index, component) in layout.panes.iter_mut().enumerate() {
+                        let cell = ui
+                            .horizontal(|ui| {
+                                ui.dnd_drag_source(
+                                    egui::Id::new(("pane", index)),
+                                    DraggedPane(index),
+                                    |ui| ui.label("☰"),
+                                )
+                                .response
+                                .on_hover_text("Drag onto another pane to swap them");
+                                egui::ComboBox::from_id_source(("pane_component", index))
+                                    .width(40.0)
+                                    .selected_text(component_name(*component))
+                                    .show_ui(ui, |ui| {
+                                        for c in ALL_COMPONENTS {
+                                            ui.selectable_value(component, c, component_name(c));
+                                        }
+                                    });
+                            })
+                            .response;
+
+                        if cell.dnd_hover_payload::<DraggedPane>().is_some() {
+                            ui.painter().rect_stroke(
+                                cell.rect.expand(2.0),
+                                2.0,
+                                ui.visuals().selection.stroke,
+                            );
+                        }
+                        if let Some(dragged) = cell.dnd_release_payload::<DraggedPane>() {
+                            swap = Some((dragged.0, index));
+                        }
+
+                        if (index + 1) % columns == 0 {
+                            ui.end_row();
+                        }
+                    }
+                });
+                if let Some((from, to)) = swap {
+                    layout.panes.swap(from, to);
+                }
+            });
+    }
+
+    fn draw_view_presets(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Views")
+            .default_open(false)
+            .show(ui, |ui| {
+                if ui
+                    .button("Fit state")
+                    .on_hover_text("Zoom each plane to fit the whole state")
+                    .clicked()
+                {
+                    self.do_action(ui.ctx(), Action::ZoomToFit);
+                }
+
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("view_preset_component")
+                        .width(40.0)
+                        .selected_text(component_name(self.view_preset_component))
+                        .show_ui(ui, |ui| {
+                            for component in self.visible_components() {
+                                ui.selectable_value(
+                                    &mut self.view_preset_component,
+                                    component,
+                                    component_name(component),
+                                );
+                            }
+                        });
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.view_preset_name)
+                            .hint_text("Name")
+                            .desired_width(100.0),
+                    );
+                    if ui
+                        .button("Save view")
+                        .on_hover_text("Save the center and zoom of the plane")
+                        .clicked()
+                    {
+                        let component = self.view_preset_component;
+                        let name = if self.view_preset_name.is_empty() {
+                            format!("View {}", self.ui_state.view_presets.len() + 1)
+                        } else {
+                            std::mem::take(&mut self.view_preset_name)
+                        };
+                        let plot = self.plot(component);
+                        let view = PlotView {
+                            component,
+                            height: plot.height,
+                            origin: plot.origin,
+                            width_factor: plot.width_factor,
+                        };
+                        self.ui_state.view_presets.push(ViewPreset { name, view });
+                    }
+                });
+
+                let mut apply = None;
+                let mut remove = None;
+                egui::Grid::new("view_presets")
+                    .num_columns(4)
+                    .show(ui, |ui| {
+                        for (index, preset) in self.ui_state.view_presets.iter_mut().enumerate() {
+                            let shortcut = VIEW_PRESET_KEYS
+                                .get(index)
+                                .map(|key| format!("Alt+{}", key.name()))
+                                .unwrap_or_default();
+                            ui.label(egui::RichText::new(shortcut).small());
+                            ui.label(component_name(preset.view.component));
+                            ui.add(
+                                egui::TextEdit::singleline(&mut preset.name).desired_width(100.0),
+                            );
+                            ui.horizontal(|ui| {
+                                if ui.button("Go").clicked() {
+                                    apply = Some(index);
+                                }
+                                if ui.small_button("✖").clicked() {
+                                    remove = Some(index);
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(index) = apply {
+                    self.apply_view_preset(index);
+                }
+                if let Some(index) = remove {
+                    self.ui_state.view_presets.remove(index);
+                }
+            });
+    }
+
+    fn draw_bookmarks(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Bookmarks")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.bookmark_name)
+                            .hint_text("Name")
+                            .desired_width(140.0),
+                    );
+                    if ui
+                        .button("Bookmark")
+                        .on_hover_text("Keep the state and coupling constants under this name")
+                        .clicked()
+                    {
+                        let name = if self.bookmark_name.is_empty() {
+                            format!("State {}", self.ui_state.bookmarks.len() + 1)
+                        } else {
+                            std::mem::take(&mut self.bookmark_name)
+                        };
+                        let saved_state = self.saved_state();
+                        self.ui_state
+                            .bookmarks
+                            .push(StateBookmark { name, saved_state });
+                    }
+                });
+
+                let mut load = None;
+                let mut remove = None;
+                egui::Grid::new("bookmarks").num_columns(3).show(ui, |ui| {
+                    for (index, bookmark) in self.ui_state.bookmarks.iter_mut().enumerate() {
+                        ui.add(egui::TextEdit::singleline(&mut bookmark.name).desired_width(100.0));
+
+                        let saved_state = &bookmark.saved_state;
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "h = {}, k = {}, M = {}",
+                                saved_state.consts.h,
+                                saved_state.consts.k(),
+                                saved_state.state.points.len()
+                            ))
+                            .small(),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button("Go").clicked() {
+                                load = Some(index);
+                            }
+                            if ui.small_button("✖").clicked() {
+                                remove = Some(index);
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+
+                if let Some(index) = load {
+                    let saved_state = self.ui_state.bookmarks[index].saved_state.clone();
+                    self.load_saved_state(saved_state);
+                }
+                if let Some(index) = remove {
+                    self.ui_state.bookmarks.remove(index);
+                }
+            });
+    }
+
+    fn draw_playback_controls(&mut self, ui: &mut egui::Ui) {
+        if self.pxu.paths.is_empty() {
+            return;
+        }
+
+        ui.separator();
+        ui.label(egui::RichText::new("Path playback").strong());
+
+        // Keep the slider at the position an excitation was dragged to
+        if let Some((index, t)) = self.ui_state.plot_state.path_constraint {
+            if index == self.playback.path_index && !self.playback.playing {
+                self.playback.t = t;
+            }
+        }
+
+        let mut path_index = self.playback.path_index.min(self.pxu.paths.len() - 1);
+        egui::ComboBox::from_id_source("playback_path")
+            .selected_text(&self.pxu.paths[path_index].name)
+            .show_ui(ui, |ui| {
+                for (index, path) in self.pxu.paths.iter().enumerate() {
+                    ui.selectable_value(&mut path_index, index, &path.name);
+                }
+            });
+        if path_index != self.playback.path_index {
+            self.playback.path_index = path_index;
+            if let Some((index, _)) = &mut self.ui_state.plot_state.path_constraint {
+                *index = path_index;
+            }
+            self.playback.playing = false;
+            self.set_playback_position(0.0);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("⏮").on_hover_text("Go to the start").clicked() {
+                self.playback.playing = false;
+                self.set_playback_position(0.0);
+            }
+
+            let text = if self.playback.playing { "⏸" } else { "▶" };
+            if ui.button(text).clicked() {
+                self.playback.playing = !self.playback.playing;
+                if self.playback.playing && self.playback.t >= 1.0 {
+                    self.playback.t = 0.0;
+                }
+            }
+
+            let mut t = self.playback.t;
+            if ui
+                .add(egui::Slider::new(&mut t, 0.0..=1.0).show_value(false))
+                .changed()
+            {
+                self.playback.playing = false;
+                self.set_playback_position(t);
+            }
+        });
+
+        ui.add(
+            egui::Slider::new(&mut self.playback.speed, 0.1..=10.0)
+                .logarithmic(true)
+                .text("Speed"),
+        );
+
+        let mut constrained = self.ui_state.plot_state.path_constraint.is_some();
+        if ui
+            .checkbox(&mut constrained, "Drag along path")
+            .on_hover_text("Keep dragged excitations on the selected path")
+            .changed()
+        {
+            self.ui_state.plot_state.path_constraint =
+                constrained.then_some((path_index, self.playback.t));
+        }
+    }
+
+    fn save_recorded_paths(&mut self) {
+        let result = pxu::path::SavedPath::save(&self.recorded_paths)
+            .ok_or_else(|| "Could not serialise paths".to_owned())
+            .and_then(|s| save_ron_file("paths.ron", &s));
+
+        match result {
+            Ok(Some(message)) => self.state_message = Some(message),
+            Ok(None) => {}
+            Err(err) => {
+                log::warn!("{err}");
+                self.state_message = Some(err);
+            }
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(state) = self.undo_history.undo() {
+            self.pxu.state = state;
+            let last = self.pxu.state.points.len() - 1;
+            let active_point = &mut self.ui_state.plot_state.active_point;
+            *active_point = (*active_point).min(last);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(state) = self.undo_history.redo() {
+            self.pxu.state = state;
+            let last = self.pxu.state.points.len() - 1;
+            let active_point = &mut self.ui_state.plot_state.active_point;
+            *active_point = (*active_point).min(last);
+        }
+    }
+
+    fn copy_state(&mut self, ctx: &egui::Context) {
+        let saved_state = self.saved_state();
+        match ron::to_string(&saved_state) {
+            Ok(s) => {
+                ctx.output_mut(|writer| writer.copied_text = s);
+                self.state_message = Some("Copied state".to_owned());
+            }
+            Err(err) => {
+                let err = format!("Could not serialise state: {err}");
+                log::warn!("{err}");
+                self.state_message = Some(err);
+            }
+        }
+    }
+
+    // A state can be given as a full SavedState, as a bare State in RON using
+    // the current coupling constants, or as a Mathematica style list
+    fn load_state_text(&mut self, text: Result<String, String>) {
+        match text.and_then(|s| parse_state_text(&s, self.pxu.consts)) {
+            Ok(saved_state) => {
+                self.load_saved_state(saved_state);
+                self.state_message = Some("Loaded state".to_owned());
+            }
+            Err(err) => {
+                log::warn!("{err}");
+                self.state_message = Some(err);
+            }
+        }
+    }
+
+    fn receive_state_text(&mut self) {
+        if let Ok(text) = self.state_text_channel.rx.try_recv() {
+            self.load_state_text(text);
+        }
+    }
+
+    // The figures of an opened bundle replace the figures of the paper, and are
+    // loaded from the bundle instead of being downloaded
+    fn load_figure_bundle(&mut self, bundle: Result<FigureBundle, String>) {
+        let result = bundle.and_then(|files| {
+            let (_, descriptions) = files
+                .iter()
+                .find(|(name, _)| name == "figures.ron")
+                .ok_or("The bundle has no figures.ron".to_owned())?;
+            let body = std::str::from_utf8(descriptions)
+                .map_err(|err| format!("Could not read figures.ron: {err}"))?;
+            self.load_figure_descriptions(body)?;
+            self.figure_bundle = files.into_iter().collect();
+            Ok(format!("Opened {} figures", self.figures.len()))
+        });
+
+        match result {
+            Ok(message) => self.figure_bundle_message = Some(message),
+            Err(err) => {
+                log::warn!("{err}");
+                self.figure_bundle_message = Some(err);
+            }
+        }
+    }
+
+    fn receive_figure_bundle(&mut self) {
+        if let Ok(bundle) = self.figure_bundle_channel.rx.try_recv() {
+            self.load_figure_bundle(bundle);
+        }
+    }
+}
+
+// View presets are picked with Alt and a number key
+const VIEW_PRESET_KEYS: [egui::Key; 9] = [
+    egui::Key::Num1,
+    egui::Key::Num2,
+    egui::Key::Num3,
+    egui::Key::Num4,
+    egui::Key::Num5,
+    egui::Key::Num6,
+    egui::Key::Num7,
+    egui::Key::Num8,
+    egui::Key::Num9,
+];
+
+const ALL_COMPONENTS: [pxu::Component; 9] = [
+    pxu::Component::P,
+    pxu::Component::Xp,
+    pxu::Component::Xm,
+    pxu::Component::U,
+    pxu::Component::E,
+    pxu::Component::X,
+    pxu::Component::MirrorP,
+    pxu::Component::MirrorE,
+    pxu::Component::Torus,
+];
+
+// The planes that can be added to the four standard ones
+const OPTIONAL_PLOTS: [(pxu::Component, &str); 5] = [
+    (pxu::Component::E, "energy plane"),
+    (pxu::Component::X, "single x plane"),
+    (pxu::Component::MirrorP, "mirror momentum plane"),
+    (pxu::Component::MirrorE, "mirror energy plane"),
+    (pxu::Component::Torus, "torus plane"),
+];
+
+fn component_name(component: pxu::Component) -> &'static str {
+    match component {
+        pxu::Component::P => "p",
+        pxu::Component::Xp => "x⁺",
+        pxu::Component::Xm => "x⁻",
+        pxu::Component::U => "u",
+        pxu::Component::E => "E",
+        pxu::Component::X => "x",
+        pxu::Component::MirrorP => "p̃",
+        pxu::Component::MirrorE => "Ẽ",
+        pxu::Component::Torus => "z",
+    }
+}
+
+// A state is either a saved state, as written by `Save state…` or shared, or
+// just the excitations, which then use the given coupling constants
+pub fn parse_state_text(s: &str, consts: CouplingConstants) -> Result<pxu::SavedState, String> {
+    if let Some(saved_state) = pxu::SavedState::decode(s) {
+        return Ok(saved_state);
+    }
+
+    let s = s.trim();
+    if s.is_empty() {
+        return Err("No state to load".to_owned());
+    }
+
+    if s.starts_with('(') {
+        if s.contains("consts") {
+            return Err(match ron::from_str::<pxu::SavedState>(s) {
+                Ok(saved_state) => format!("Unknown state version {}", saved_state.version),
+                Err(err) => format!("Invalid state: {err}"),
+            });
+        }
+        return pxu::State::parse(s, consts)
+            .map(|state| pxu::SavedState::new(state, consts))
+            .map_err(|err| format!("Invalid state: {err}"));
+    }
+
+    pxu::State::from_mathematica(s, consts)
+        .map(|state| pxu::SavedState::new(state, consts))
+        .ok_or_else(|| "Could not parse state".to_owned())
+}
+
+// Natively the file is written to the current directory, while on the web it
+// is offered as a download
+#[cfg(not(target_arch = "wasm32"))]
+fn save_file(name: &str, _mime_type: &str, bytes: &[u8]) -> Result<String, String> {
+    std::fs::write(name, bytes).map_err(|err| format!("Could not write {name}: {err}"))?;
+    Ok(format!("Saved {name}"))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_file(name: &str, mime_type: &str, bytes: &[u8]) -> Result<String, String> {
+    use wasm_bindgen::JsCast;
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let mut options = web_sys::BlobPropertyBag::new();
+    options.type_(mime_type);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .map_err(|_| "Could not create blob".to_owned())?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)
+        .map_err(|_| "Could not create object url".to_owned())?;
+
+    let anchor = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("a").ok())
+        .and_then(|element| element.dyn_into::<web_sys::HtmlAnchorElement>().ok())
+        .ok_or("Could not create download link".to_owned())?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    if web_sys::Url::revoke_object_url(&url).is_err() {
+        log::info!("Could not revoke object url");
+    }
+    Ok(format!("Downloaded {name}"))
+}
+
+// Keep the address bar in sync with the last shared view on the web
+#[cfg(not(target_arch = "wasm32"))]
+fn set_location_fragment(_fragment: &str) {}
+
+#[cfg(target_arch = "wasm32")]
+fn set_location_fragment(fragment: &str) {
+    let Some(location) = web_sys::window().map(|window| window.location()) else {
+        return;
+    };
+    if location.set_hash(fragment).is_err() {
+        log::info!("Could not set url fragment");
+    }
+}
+
+// Natively states and paths are saved and loaded through the system file
+// dialogs, while on the web they are downloaded and uploaded by the browser
+#[cfg(not(target_arch = "wasm32"))]
+fn save_ron_file(name: &str, contents: &str) -> Result<Option<String>, String> {
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter("RON", &["ron"])
+        .set_file_name(name)
+        .save_file()
+    else {
+        return Ok(None);
+    };
+
+    std::fs::write(&path, contents)
+        .map_err(|err| format!("Could not write {}: {err}", path.display()))?;
+    Ok(Some(format!("Saved {}", path.display())))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_ron_file(name: &str, contents: &str) -> Result<Option<String>, String> {
+    save_file(name, "application/ron", contents.as_bytes()).map(Some)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn open_state_file(_ctx: &egui::Context, tx: mpsc::Sender<Result<String, String>>) {
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter("RON", &["ron"])
+        .pick_file()
+    else {
+        return;
+    };
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Could not read {}: {err}", path.display()));
+    if tx.send(contents).is_err() {
+        log::info!("Could not send file contents");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn open_state_file(ctx: &egui::Context, tx: mpsc::Sender<Result<String, String>>) {
+    use wasm_bindgen::JsCast;
+
+    let Some(input) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("input").ok())
+        .and_then(|element| element.dyn_into::<web_sys::HtmlInputElement>().ok())
+    else {
+        log::warn!("Could not create file input");
+        return;
+    };
+    input.set_type("file");
+    input.set_accept(".ron");
+
+    let ctx = ctx.clone();
+    let target = input.clone();
+    let onchange = wasm_bindgen::closure::Closure::once(move || {
+        let Some(file) = target.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        let name = file.name();
+        wasm_bindgen_futures::spawn_local(async move {
+            let contents = wasm_bindgen_futures::JsFuture::from(file.text())
+                .await
+                .ok()
+                .and_then(|text| text.as_string())
+                .ok_or_else(|| format!("Could not read {name}"));
+            if tx.send(contents).is_err() {
+                log::info!("Could not send file contents");
+            }
+            ctx.request_repaint(); // Wake up UI thread
+        });
+    });
+    input.set_onchange(Some(onchange.as_ref().unchecked_ref()));
+    onchange.forget();
+    input.click();
+}
+
+// A bundle is opened natively by picking the directory written by
+// interactive-figures, and on the web by picking all of its files
+#[cfg(not(target_arch = "wasm32"))]
+fn open_figure_bundle(_ctx: &egui::Context, tx: mpsc::Sender<Result<FigureBundle, String>>) {
+    let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+        return;
+    };
+
+    let read_dir = || -> Result<FigureBundle, String> {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|err| format!("Could not read {}: {err}", dir.display()))?;
+        let mut files = vec![];
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("ron" | "bin")
+            ) {
+                continue;
+            }
+            let bytes = std::fs::read(&path)
+                .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+            files.push((entry.file_name().to_string_lossy().into_owned(), bytes));
+        }
+        Ok(files)
+    };
+
+    if tx.send(read_dir()).is_err() {
+        log::info!("Could not send figure bundle");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn open_figure_bundle(ctx: &egui::Context, tx: mpsc::Sender<Result<FigureBundle, String>>) {
+    use wasm_bindgen::JsCast;
+
+    let Some(input) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("input").ok())
+        .and_then(|element| element.dyn_into::<web_sys::HtmlInputElement>().ok())
+    else {
+        log::warn!("Could not create file input");
+        return;
+    };
+    input.set_type("file");
+    input.set_accept(".ron,.bin");
+    input.set_multiple(true);
+
+    let ctx = ctx.clone();
+    let target = input.clone();
+    let onchange = wasm_bindgen::closure::Closure::once(move || {
+        let Some(file_list) = target.files() else {
+            return;
+        };
+        let files = (0..file_list.length())
+            .filter_map(|index| file_list.get(index))
+            .collect::<Vec<_>>();
+        wasm_bindgen_futures::spawn_local(async move {
+            let mut bundle = vec![];
+            for file in files {
+                let name = file.name();
+                let Ok(buffer) = wasm_bindgen_futures::JsFuture::from(file.array_buffer()).await
+                else {
+                    let _ = tx.send(Err(format!("Could not read {name}")));
+                    ctx.request_repaint(); // Wake up UI thread
+                    return;
+                };
+                bundle.push((name, js_sys::Uint8Array::new(&buffer).to_vec()));
+            }
+            if tx.send(Ok(bundle)).is_err() {
+                log::info!("Could not send figure bundle");
+            }
+            ctx.request_repaint(); // Wake up UI thread
+        });
+    });
+    input.set_onchange(Some(onchange.as_ref().unchecked_ref()));
+    onchange.forget();
+    input.click();
+}
+
+// egui can only write to the clipboard, so reading it for the paste button goes
+// through arboard natively and the asynchronous clipboard API on the web
+#[cfg(not(target_arch = "wasm32"))]
+fn read_clipboard(_ctx: &egui::Context, tx: mpsc::Sender<Result<String, String>>) {
+    let text = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|err| format!("Could not read the clipboard: {err}"));
+    if tx.send(text).is_err() {
+        log::info!("Could not send clipboard contents");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_clipboard(ctx: &egui::Context, tx: mpsc::Sender<Result<String, String>>) {
+    let Some(clipboard) = web_sys::window().and_then(|window| window.navigator().clipboard())
+    else {
+        if tx
+            .send(Err("The clipboard is not available".to_owned()))
+            .is_err()
+        {
+            log::info!("Could not send clipboard contents");
+        }
+        return;
+    };
+
+    let ctx = ctx.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        let text = wasm_bindgen_futures::JsFuture::from(clipboard.read_text())
+            .await
+            .ok()
+            .and_then(|text| text.as_string())
+            .ok_or_else(|| "Could not read the clipboard".to_owned());
+        if tx.send(text).is_err() {
+            log::info!("Could not send clipboard contents");
+        }
+        ctx.request_repaint(); // Wake up UI thread
+    });
+}
+
+impl eframe::App for PxuGuiApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+        // Keep the previous session until it has been restored or discarded
+        match &self.pending_session {
+            Some(session) => session.store(storage),
+            None => self.session().store(storage),
+        }
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if self.ui_state.show_fps {
+            self.frame_history
+                .on_new_frame(ctx.input(|i| i.time), frame.info().cpu_usage);
+        }
+
+        self.load_files(ctx);
+        self.receive_download();
+        self.receive_state_text();
+        self.receive_figure_bundle();
+
+        if self.ui_state.continuous_mode && !self.ui_state.power_saving {
+            ctx.request_repaint();
+        }
+
+        // Plain keys go to the text field that has focus, if any
+        let typing = ctx.wants_keyboard_input();
+
+        if !typing && ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
+            self.ui_state.hide_side_panel = !self.ui_state.hide_side_panel;
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.ui_state.plot_state.close_fullscreen();
+            self.ui_state.plot_state.presentation = false;
+            self.ui_state.plot_state.clean = false;
+            self.ui_state.plot_state.focused_component = None;
+            self.ui_state.hide_side_panel = false;
+        }
+
+        if let Some(action) = self.ui_state.rebinding {
+            self.rebind_shortcut(ctx, action);
+        } else if !typing {
+            let actions = ctx.input_mut(|i| self.ui_state.shortcuts.pressed(i));
+            for action in actions {
+                self.do_action(ctx, action);
+            }
+
+            let preset = ctx.input_mut(|i| {
+                VIEW_PRESET_KEYS
+                    .iter()
+                    .position(|key| i.consume_key(egui::Modifiers::ALT, *key))
+            });
+            if let Some(index) = preset {
+                self.apply_view_preset(index);
+            }
+        }
+
+        // Copy and paste act on the whole state unless a text field has focus
+        if !typing {
+            let (undo, redo) = ctx.input(|i| {
+                let pressed = i.modifiers.command && i.key_pressed(egui::Key::Z);
+                (pressed && !i.modifiers.shift, pressed && i.modifiers.shift)
+            });
+            if undo {
+                self.undo();
+            }
+            if redo {
+                self.redo();
+            }
+
+            let events = ctx.input(|i| i.events.clone());
+            for event in events {
+                match event {
+                    egui::Event::Copy => self.copy_state(ctx),
+                    egui::Event::Paste(text) => self.load_state_text(Ok(text)),
+                    _ => {}
+                }
+            }
+        }
+
+        // Ignore the number keys when they are used together with modifiers,
+        // e.g. to pick a view preset
+        if !typing && ctx.input(|i| i.modifiers.is_none()) {
+            ctx.input(|i| {
+                for (key, num) in [
+                    (egui::Key::Backspace, self.pxu.state.points.len()),
+                    (egui::Key::Num1, 1),
+                    (egui::Key::Num2, 2),
+                    (egui::Key::Num3, 3),
+                    (egui::Key::Num4, 4),
+                    (egui::Key::Num5, 5),
+                    (egui::Key::Num6, 6),
+                    (egui::Key::Num7, 7),
+                    (egui::Key::Num8, 8),
+                    (egui::Key::Num9, 9),
+                ] {
+                    if i.key_pressed(key) {
+                        self.pxu.state = pxu::State::new(num, self.pxu.kinematic_consts());
+                        self.ui_state.plot_state.active_point =
+                            self.ui_state.plot_state.active_point.min(num - 1);
+                    }
+                }
+
+                if i.key_pressed(egui::Key::Space) {
+                    self.pxu.state.unlocked = !self.pxu.state.unlocked;
+                }
+            });
+        }
+
+        if !typing && self.pxu.state.unlocked && ctx.input(|i| i.key_pressed(egui::Key::Plus)) {
+            self.pxu
+                .state
+                .points
+                .push(pxu::Point::new(0.1, self.pxu.kinematic_consts()));
+        }
+
+        if !typing
+            && self.pxu.state.unlocked
+            && self.pxu.state.points.len() > 1
+            && ctx.input(|i| i.key_pressed(egui::Key::Minus))
+        {
+            self.pxu
+                .state
+                .points
+                .remove(self.ui_state.plot_state.active_point);
+            self.ui_state.plot_state.active_point = self
+                .ui_state
+                .plot_state
+                .active_point
+                .min(self.pxu.state.points.len() - 1);
+        }
+
+        // With a focused plane the arrow keys move the active excitation instead
+        let nudging = self.ui_state.plot_state.focused_component.is_some();
+        if let Some(component) = self.ui_state.plot_state.focused_component {
+            if !typing {
+                self.interact_with_focused_plane(ctx, component);
+            }
+        }
+
+        if !typing
+            && self.pxu.state.unlocked
+            && self.pxu.state.points.len() > 1
+            && self.ui_state.plot_state.active_point < self.pxu.state.points.len() - 1
+            && !nudging
+            && ctx.input(|i| i.key_pressed(egui::Key::ArrowUp))
+        {
+            let i = self.ui_state.plot_state.active_point;
+            self.pxu.state.points.swap(i, i + 1);
+            self.ui_state.plot_state.active_point += 1;
+        }
+
+        if !typing
+            && self.pxu.state.unlocked
+            && self.pxu.state.points.len() > 1
+            && self.ui_state.plot_state.active_point > 0
+            && !nudging
+            && ctx.input(|i| i.key_pressed(egui::Key::ArrowDown))
+        {
+            let i = self.ui_state.plot_state.active_point;
+            self.pxu.state.points.swap(i, i - 1);
+            self.ui_state.plot_state.active_point -= 1;
+        }
+
+        if !typing
+            && self.pxu.state.points.len() > 1
+            && self.ui_state.plot_state.active_point < self.pxu.state.points.len() - 1
+            && !nudging
+            && ctx.input(|i| i.key_pressed(egui::Key::ArrowRight))
+        {
+            self.ui_state.plot_state.active_point += 1;
+        }
+
+        if !typing
+            && self.pxu.state.points.len() > 1
+            && self.ui_state.plot_state.active_point > 0
+            && !nudging
+            && ctx.input(|i| i.key_pressed(egui::Key::ArrowLeft))
+        {
+            self.ui_state.plot_state.active_point -= 1;
+        }
+
+        let clean = self.ui_state.plot_state.clean;
+        if !self.ui_state.hide_side_panel && !self.ui_state.plot_state.presentation && !clean {
+            self.draw_side_panel(ctx);
+        }
+
+        if let Some(saved_state) = self.ui_state.inital_saved_state.take() {
+            self.load_saved_state(saved_state);
+            self.ui_state.plot_state.active_point = 0;
+        }
+
+        if let Some(view) = self.ui_state.initial_view.take() {
+            self.apply_shared_view(view);
+        }
+
+        self.apply_visual_theme(ctx);
+        self.poll_contour_job(ctx);
+        self.update_comparison(ctx);
+        self.advance_sweep(ctx);
+        #[cfg(target_arch = "wasm32")]
+        self.start_contour_job_in_place(ctx);
+
+        if !self.is_generating_in_place() {
+            let start = chrono::Utc::now();
+            while (chrono::Utc::now() - start).num_milliseconds()
+                < (1000.0 / 20.0f64).floor() as i64
+            {
+                if self.pxu.update_contours(
+                    self.pxu.state.points[self.ui_state.plot_state.active_point]
+                        .p
+                        .re
+                        .floor() as i32,
+                ) {
+                    if let Some(ref mut saved_paths) = self.ui_state.saved_paths_to_load {
+                        if let Some(saved_path) = saved_paths.pop() {
+                            let path = pxu::Path::from_base_path(
+                                saved_path.into(),
+                                &self.pxu.contours,
+                                self.pxu.consts,
+                            );
+
+                            self.pxu.paths.push(path);
+
+                            let progress = self.ui_state.path_load_progress.unwrap();
+                            self.ui_state.path_load_progress = Some((progress.0 + 1, progress.1));
+                        } else {
+                            self.ui_state.saved_paths_to_load = None;
+                            self.ui_state.path_load_progress = None;
+                            if !self.pxu.paths.is_empty() {
+                                self.ui_state.plot_state.path_indices = vec![];
+                            }
+                        }
+                    }
+                    break;
+                }
+                self.request_animation_frame(ctx);
+            }
+        }
+
+        // The state at the start of a drag is the start of a recorded path
+        if self.recording && !ctx.input(|i| i.pointer.any_down()) {
+            self.recorder_start = Some(self.pxu.state.clone());
+        }
+
+        // The optional frame puts a margin in the background color of the plots
+        // around them
+        let mut central_frame = egui::Frame::central_panel(&ctx.style());
+        if clean && self.ui_state.screenshot_frame {
+            central_frame = central_frame
+                .fill(self.ui_state.plot_state.palette.background)
+                .inner_margin(24.0);
+        }
+
+        egui::CentralPanel::default()
+            .frame(central_frame)
+            .show(ctx, |ui| {
+                let rect = ui.available_rect_before_wrap();
+
+                let layout = self.plot_layout(rect);
+
+                self.ui_state.plot_state.reset();
+
+                // A plane can be shown in more than one pane, so each pane gets its
+                // own id
+                for (index, (component, rect)) in layout.iter().enumerate() {
+                    let plot = match component {
+                        pxu::Component::P => &mut self.p_plot,
+                        pxu::Component::Xp => &mut self.xp_plot,
+                        pxu::Component::Xm => &mut self.xm_plot,
+                        pxu::Component::U => &mut self.u_plot,
+                        pxu::Component::E => &mut self.e_plot,
+                        pxu::Component::X => &mut self.x_plot,
+                        pxu::Component::MirrorP => &mut self.mirror_p_plot,
+                        pxu::Component::MirrorE => &mut self.mirror_e_plot,
+                        pxu::Component::Torus => &mut self.torus_plot,
+                    };
+                    ui.push_id(index, |ui| {
+                        plot.interact(ui, *rect, &mut self.pxu, &mut self.ui_state.plot_state);
+                    });
+                }
+
+                for (index, (component, rect)) in layout.iter().enumerate() {
+                    let plot = match component {
+                        pxu::Component::P => &mut self.p_plot,
+                        pxu::Component::Xp => &mut self.xp_plot,
+                        pxu::Component::Xm => &mut self.xm_plot,
+                        pxu::Component::U => &mut self.u_plot,
+                        pxu::Component::E => &mut self.e_plot,
+                        pxu::Component::X => &mut self.x_plot,
+                        pxu::Component::MirrorP => &mut self.mirror_p_plot,
+                        pxu::Component::MirrorE => &mut self.mirror_e_plot,
+                        pxu::Component::Torus => &mut self.torus_plot,
+                    };
+                    ui.push_id(index, |ui| {
+                        plot.show(ui, *rect, &mut self.pxu, &mut self.ui_state.plot_state);
+                    });
+                }
+
+                self.plot_rects = layout;
+                self.export_sweep_frame(ui);
+            });
+
+        if let Some(center) = self.ui_state.plot_state.bound_state_request.take() {
+            self.open_bound_state_dialog(Some(center));
+        }
+
+        self.record_path();
+        self.advance_playback(ctx);
+        self.watch_sheet_data(ctx);
+
+        // Only record the state once a drag has finished
+        if !self.playback.playing && !ctx.input(|i| i.pointer.any_down()) {
+            self.undo_history.update(&self.pxu.state, self.pxu.consts);
+        }
+
+        if clean {
+            ctx.set_cursor_icon(egui::CursorIcon::None);
+            return;
+        }
+
+        self.show_drag_failure(ctx);
         self.show_load_path_window(ctx);
         self.show_load_save_state_window(ctx);
         self.show_share_state_window(ctx);
         self.show_about_window(ctx);
         self.show_help_window(ctx);
         self.show_figure_window(ctx);
+        self.show_session_window(ctx);
+        self.show_bound_state_window(ctx);
+        self.show_export_window(ctx);
+        self.show_shortcuts_window(ctx);
+        crate::nr_diagnostics::show(
+            ctx,
+            &mut self.ui_state.show_nr_diagnostics,
+            &self.ui_state.plot_state.drag_stats,
+        );
+        if self.console.show(ctx, &mut self.ui_state.show_console) {
+            self.run_script(ctx);
+        }
+    }
+}
+
+impl PxuGuiApp {
+    // Shown when an excitation could not follow a drag, until it is dismissed or
+    // another drag starts
+    fn show_drag_failure(&mut self, ctx: &egui::Context) {
+        let plot_state = &mut self.ui_state.plot_state;
+        let Some(failure) = &plot_state.drag_failure else {
+            return;
+        };
+        let mut roll_back = false;
+        let mut dismiss = false;
+
+        egui::Window::new("Drag failed")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, vec2(0.0, 10.0))
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "⚠ Could not move excitation {} in the {} plane: {}",
+                        failure.index,
+                        component_name(failure.component),
+                        failure.message
+                    ))
+                    .color(ui.visuals().warn_fg_color),
+                );
+                ui.label("It was left at the last position where the solver converged.");
+                ui.horizontal(|ui| {
+                    roll_back = ui
+                        .add_enabled(
+                            plot_state.drag_start.is_some(),
+                            egui::Button::new("Roll back drag"),
+                        )
+                        .on_hover_text("Go back to the state before the drag")
+                        .clicked();
+                    dismiss = ui.button("Dismiss").clicked();
+                });
+            });
+
+        if roll_back {
+            if let Some(state) = plot_state.drag_start.take() {
+                self.pxu.state = state;
+                plot_state.active_point =
+                    plot_state.active_point.min(self.pxu.state.points.len() - 1);
+            }
+        }
+        if roll_back || dismiss {
+            plot_state.drag_failure = None;
+        }
+    }
+
+    fn show_share_state_window(&mut self, ctx: &egui::Context) {
+        if let Some(s) = &mut self.shared_state_text {
+            let mut close_dialog = false;
+            let mut open = true;
+
+            egui::Window::new("Share url")
+                .open(&mut open)
+                .show(ctx, |ui| {
+                    ui.label("Direct link to the current view:");
+                    ui.hyperlink(s.clone());
+
+                    ui.add_space(10.0);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::LEFT), |ui| {
+                        ui.add_space(10.0);
+                        if ui.button("Close").clicked() {
+                            close_dialog = true;
+                        }
+
+                        if ui.button("Copy Link").clicked() {
+                            ctx.output_mut(|writer| writer.copied_text.clone_from(s));
+                            close_dialog = true;
+                        }
+                    });
+                });
+            if close_dialog || !open {
+                self.shared_state_text = None;
+            }
+        }
+    }
+
+    fn plot(&self, component: pxu::Component) -> &Plot {
+        match component {
+            pxu::Component::P => &self.p_plot,
+            pxu::Component::Xp => &self.xp_plot,
+            pxu::Component::Xm => &self.xm_plot,
+            pxu::Component::U => &self.u_plot,
+            pxu::Component::E => &self.e_plot,
+            pxu::Component::X => &self.x_plot,
+            pxu::Component::MirrorP => &self.mirror_p_plot,
+            pxu::Component::MirrorE => &self.mirror_e_plot,
+            pxu::Component::Torus => &self.torus_plot,
+        }
+    }
+
+    fn plot_mut(&mut self, component: pxu::Component) -> &mut Plot {
+        match component {
+            pxu::Component::P => &mut self.p_plot,
+            pxu::Component::Xp => &mut self.xp_plot,
+            pxu::Component::Xm => &mut self.xm_plot,
+            pxu::Component::U => &mut self.u_plot,
+            pxu::Component::E => &mut self.e_plot,
+            pxu::Component::X => &mut self.x_plot,
+            pxu::Component::MirrorP => &mut self.mirror_p_plot,
+            pxu::Component::MirrorE => &mut self.mirror_e_plot,
+            pxu::Component::Torus => &mut self.torus_plot,
+        }
+    }
+
+    fn shared_view(&self) -> SharedView {
+        let plots = ALL_COMPONENTS
+            .into_iter()
+            .map(|component| {
+                let plot = self.plot(component);
+                PlotView {
+                    component,
+                    height: plot.height,
+                    origin: plot.origin,
+                    width_factor: plot.width_factor,
+                }
+            })
+            .collect();
+
+        SharedView {
+            saved_state: self.saved_state(),
+            active_point: self.ui_state.plot_state.active_point,
+            ux_mode: self.is_ux_mode(),
+            show_energy_plane: self.ui_state.extra_plots.contains(&pxu::Component::E),
+            extra_plots: self.ui_state.extra_plots.clone(),
+            fullscreen_component: self.ui_state.plot_state.fullscreen_component,
+            plots,
+        }
+    }
+
+    fn session(&self) -> Session {
+        Session {
+            view: self.shared_view(),
+            paths: self.pxu.paths.clone(),
+            path_indices: self.ui_state.plot_state.path_indices.clone(),
+            overlays: self.ui_state.plot_state.overlays.clone(),
+            recorded_paths: self.recorded_paths.clone(),
+        }
+    }
+
+    fn restore_session(&mut self, session: Session) {
+        self.apply_shared_view(session.view);
+        self.figure_index = None;
+        self.pxu.paths = session.paths;
+        self.ui_state.plot_state.path_indices = session.path_indices;
+        self.ui_state.plot_state.overlays = session.overlays;
+        self.recorded_paths = session.recorded_paths;
+    }
+
+    fn show_session_window(&mut self, ctx: &egui::Context) {
+        let Some(session) = &self.pending_session else {
+            return;
+        };
+
+        let mut restore = false;
+        let mut discard = false;
+        egui::Window::new("Restore session")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("Restore the session from the last time the app was used?");
+                ui.label(egui::RichText::new(session.summary()).small());
+                ui.horizontal(|ui| {
+                    restore = ui.button("Restore").clicked();
+                    discard = ui.button("Discard").clicked();
+                });
+            });
+
+        if restore {
+            if let Some(session) = self.pending_session.take() {
+                self.restore_session(session);
+            }
+        } else if discard {
+            self.pending_session = None;
+        }
+    }
+
+    fn apply_shared_view(&mut self, view: SharedView) {
+        self.load_saved_state(view.saved_state);
+        let last = self.pxu.state.points.len().saturating_sub(1);
+        self.ui_state.plot_state.active_point = view.active_point.min(last);
+
+        if view.ux_mode {
+            self.set_ux_mode();
+        } else {
+            self.set_normal_mode();
+        }
+        self.ui_state.extra_plots = view.extra_plots;
+        if view.show_energy_plane {
+            self.toggle_extra_plot(pxu::Component::E, true);
+        }
+        self.ui_state.plot_state.fullscreen_component = view.fullscreen_component;
+
+        for plot_view in view.plots {
+            let plot = self.plot_mut(plot_view.component);
+            plot.height = plot_view.height;
+            plot.origin = plot_view.origin;
+            plot.width_factor = plot_view.width_factor;
+        }
+    }
+
+    fn open_export_dialog(&mut self) {
+        let component = self
+            .ui_state
+            .plot_state
+            .fullscreen_component
+            .or_else(|| self.plot_rects.first().map(|(component, _)| *component))
+            .unwrap_or(pxu::Component::P);
+        let size = self
+            .plot_rects
+            .iter()
+            .find(|(c, _)| *c == component)
+            .map(|(_, rect)| rect.size())
+            .unwrap_or(vec2(640.0, 480.0));
+
+        self.export_dialog = Some(ExportDialog {
+            component,
+            format: ExportFormat::Svg,
+            size,
+            tikz_width: 6.0,
+            message: None,
+        });
+    }
+
+    fn export_view(&self, ui: &egui::Ui, dialog: &ExportDialog) -> Result<String, String> {
+        let plot = self.plot(dialog.component);
+        let plot_state = &self.ui_state.plot_state;
+        let settings = &self.ui_state.export_settings;
+        let name = format!("pxu-{}", dialog.component).to_lowercase();
+
+        match dialog.format {
+            ExportFormat::Svg => {
+                let svg = plot.to_svg(ui, dialog.size, settings, &self.pxu, plot_state);
+                save_file(&format!("{name}.svg"), "image/svg+xml", svg.as_bytes())
+            }
+            ExportFormat::Png => {
+                let png = plot
+                    .to_png(ui, dialog.size, settings, &self.pxu, plot_state)
+                    .ok_or("Could not render the plot")?;
+                save_file(&format!("{name}.png"), "image/png", &png)
+            }
+            ExportFormat::Tikz => {
+                let tikz = self.export_tikz(dialog);
+                save_file(&format!("{name}.tex"), "application/x-tex", tikz.as_bytes())
+            }
+        }
     }
-}
 
-impl PxuGuiApp {
-    fn show_share_state_window(&mut self, ctx: &egui::Context) {
-        if let Some(s) = &mut self.shared_state_text {
-            let mut close_dialog = false;
-            let mut open = true;
+    // The size of the dialog only sets the aspect ratio of the figure
+    fn export_tikz(&self, dialog: &ExportDialog) -> String {
+        let rect = egui::Rect::from_min_size(Pos2::ZERO, dialog.size);
+        self.plot(dialog.component).to_tikz(
+            rect,
+            dialog.tikz_width,
+            &self.pxu,
+            &self.ui_state.plot_state,
+        )
+    }
 
-            egui::Window::new("Share url")
-                .open(&mut open)
-                .show(ctx, |ui| {
-                    ui.label("Direct link to the current state:");
-                    ui.hyperlink(s.clone());
+    fn show_export_window(&mut self, ctx: &egui::Context) {
+        let Some(mut dialog) = self.export_dialog.take() else {
+            return;
+        };
+        let mut open = true;
+        let mut close_dialog = false;
 
-                    ui.add_space(10.0);
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::LEFT), |ui| {
-                        ui.add_space(10.0);
-                        if ui.button("Close").clicked() {
-                            close_dialog = true;
+        egui::Window::new("Export view")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("export_grid")
+                    .num_columns(2)
+                    .spacing([10.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label("Plot:");
+                        ui.horizontal(|ui| {
+                            for (component, rect) in self.plot_rects.iter() {
+                                if ui
+                                    .radio_value(
+                                        &mut dialog.component,
+                                        *component,
+                                        component.to_string(),
+                                    )
+                                    .clicked()
+                                {
+                                    dialog.size = rect.size();
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Format:");
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut dialog.format, ExportFormat::Svg, "SVG");
+                            ui.radio_value(&mut dialog.format, ExportFormat::Png, "PNG");
+                            ui.radio_value(&mut dialog.format, ExportFormat::Tikz, "TikZ");
+                        });
+                        ui.end_row();
+
+                        ui.label("Size:");
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut dialog.size.x)
+                                    .clamp_range(16.0..=4096.0)
+                                    .speed(1.0),
+                            );
+                            ui.label("×");
+                            ui.add(
+                                egui::DragValue::new(&mut dialog.size.y)
+                                    .clamp_range(16.0..=4096.0)
+                                    .speed(1.0),
+                            );
+                        });
+                        ui.end_row();
+
+                        let settings = &mut self.ui_state.export_settings;
+                        if dialog.format == ExportFormat::Png {
+                            ui.label("Scale:");
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::DragValue::new(&mut settings.scale)
+                                        .clamp_range(0.5..=8.0)
+                                        .speed(0.1),
+                                );
+                                ui.label(format!(
+                                    "({:.0} × {:.0} pixels)",
+                                    dialog.size.x * settings.scale,
+                                    dialog.size.y * settings.scale
+                                ));
+                            });
+                            ui.end_row();
                         }
 
-                        if ui.button("Copy Link").clicked() {
-                            ctx.output_mut(|writer| writer.copied_text.clone_from(s));
-                            close_dialog = true;
+                        if dialog.format != ExportFormat::Tikz {
+                            ui.label("Line width:");
+                            ui.add(
+                                egui::DragValue::new(&mut settings.line_width)
+                                    .clamp_range(0.25..=4.0)
+                                    .speed(0.05)
+                                    .suffix("×"),
+                            );
+                            ui.end_row();
+
+                            ui.label("Marker size:");
+                            ui.add(
+                                egui::DragValue::new(&mut settings.marker_size)
+                                    .clamp_range(0.25..=4.0)
+                                    .speed(0.05)
+                                    .suffix("×"),
+                            );
+                            ui.end_row();
+                        }
+
+                        if dialog.format == ExportFormat::Tikz {
+                            ui.label("Width:");
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::DragValue::new(&mut dialog.tikz_width)
+                                        .clamp_range(1.0..=30.0)
+                                        .speed(0.1)
+                                        .suffix(" cm"),
+                                );
+                                ui.label(format!(
+                                    "(height {:.2} cm)",
+                                    dialog.tikz_width * (dialog.size.y / dialog.size.x) as f64
+                                ));
+                            });
+                            ui.end_row();
                         }
                     });
+
+                if let Some(message) = &dialog.message {
+                    ui.label(message);
+                }
+
+                ui.add_space(10.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::LEFT), |ui| {
+                    ui.add_space(10.0);
+                    if ui.button("Close").clicked() {
+                        close_dialog = true;
+                    }
+
+                    if ui.button("Export").clicked() {
+                        dialog.message = Some(match self.export_view(ui, &dialog) {
+                            Ok(message) => message,
+                            Err(err) => {
+                                log::warn!("{err}");
+                                err
+                            }
+                        });
+                    }
+
+                    if dialog.format == ExportFormat::Tikz && ui.button("Copy").clicked() {
+                        let tikz = self.export_tikz(&dialog);
+                        ui.output_mut(|writer| writer.copied_text = tikz);
+                        dialog.message = Some("Copied TikZ code".to_owned());
+                    }
                 });
-            if close_dialog || !open {
-                self.shared_state_text = None;
+            });
+
+        if open && !close_dialog {
+            self.export_dialog = Some(dialog);
+        }
+    }
+
+    fn open_bound_state_dialog(&mut self, center: Option<(pxu::Component, Complex64)>) {
+        let p_range = self.pxu.state.points[self.ui_state.plot_state.active_point]
+            .p
+            .re
+            .floor() as i32;
+        self.bound_state_dialog = Some(BoundStateDialog {
+            m: self.pxu.state.points.len(),
+            p_range: if center.is_some() { 0 } else { p_range },
+            consts: self.pxu.consts,
+            center,
+            waiting: false,
+            message: None,
+        });
+    }
+
+    fn show_bound_state_window(&mut self, ctx: &egui::Context) {
+        let Some(mut dialog) = self.bound_state_dialog.take() else {
+            return;
+        };
+        let mut open = true;
+        let mut close_dialog = false;
+        let mut create = false;
+
+        egui::Window::new("Create bound state")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.add_enabled_ui(!dialog.waiting, |ui| {
+                    egui::Grid::new("bound_state_grid")
+                        .num_columns(2)
+                        .spacing([10.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label("M:");
+                            ui.add(egui::Slider::new(&mut dialog.m, 1..=20));
+                            ui.end_row();
+
+                            ui.label("h:");
+                            ui.add(
+                                egui::Slider::new(&mut dialog.consts.h, 0.1..=10.0)
+                                    .logarithmic(true),
+                            );
+                            ui.end_row();
+
+                            ui.label("k:");
+                            ui.add(
+                                egui::Slider::from_get_set(0.0..=10.0, |v| {
+                                    dialog.consts.get_set_k(v)
+                                })
+                                .integer(),
+                            );
+                            ui.end_row();
+
+                            ui.label("Center:");
+                            if let Some((component, value)) = dialog.center {
+                                ui.horizontal(|ui| {
+                                    ui.label(format!(
+                                        "{component} = {:.3} {:+.3}i",
+                                        value.re, value.im
+                                    ));
+                                    if ui.small_button("✖").on_hover_text("Clear").clicked() {
+                                        dialog.center = None;
+                                    }
+                                });
+                                ui.end_row();
+                            } else {
+                                ui.label("Default");
+                                ui.end_row();
+
+                                ui.label("p range:");
+                                ui.add(egui::Slider::new(&mut dialog.p_range, -3..=3));
+                                ui.end_row();
+                            }
+                        });
+                });
+
+                if dialog.waiting {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Generating contours…");
+                    });
+                } else if let Some(message) = &dialog.message {
+                    ui.label(message);
+                }
+
+                ui.add_space(10.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::LEFT), |ui| {
+                    ui.add_space(10.0);
+                    if ui.button("Close").clicked() {
+                        close_dialog = true;
+                    }
+
+                    if ui
+                        .add_enabled(!dialog.waiting, egui::Button::new("Create"))
+                        .clicked()
+                    {
+                        create = true;
+                    }
+                });
+            });
+
+        if !open || close_dialog {
+            return;
+        }
+
+        if create {
+            if dialog.consts != self.pxu.consts {
+                self.start_contour_job(ctx, dialog.consts);
+            }
+            dialog.waiting = true;
+            dialog.message = None;
+        }
+
+        // The coupling constants may have been changed from the side panel in
+        // the meantime
+        let target = self
+            .contour_job
+            .as_ref()
+            .map_or(self.pxu.consts, |job| job.consts());
+        if dialog.waiting && target != dialog.consts {
+            dialog.waiting = false;
+            dialog.message = Some("The coupling constants were changed".to_owned());
+        }
+
+        if dialog.waiting && self.contour_job.is_none() && self.pxu.contours.is_loaded() {
+            dialog.waiting = false;
+            match self.build_bound_state(&dialog) {
+                Ok(()) => return,
+                Err(err) => {
+                    log::warn!("{err}");
+                    dialog.message = Some(err);
+                }
             }
         }
+
+        self.bound_state_dialog = Some(dialog);
+    }
+
+    fn build_bound_state(&mut self, dialog: &BoundStateDialog) -> Result<(), String> {
+        let mut builder = pxu::BoundStateBuilder::new(dialog.m);
+        if let Some((component, value)) = dialog.center {
+            builder = builder.centered_at(component, value);
+        } else {
+            builder = builder.p_range(dialog.p_range);
+        }
+
+        let state = builder
+            .build(&self.pxu.contours, self.pxu.consts)
+            .map_err(|err| format!("Could not create the bound state: {err}"))?;
+        self.ui_state.plot_state.active_point = state.points.len() / 2;
+        self.pxu.state = state;
+        Ok(())
     }
 
     fn show_load_path_window(&mut self, ctx: &egui::Context) {
@@ -645,6 +2732,7 @@ impl PxuGuiApp {
     }
 
     fn show_load_save_state_window(&mut self, ctx: &egui::Context) {
+        let mut text_to_load = None;
         if let Some(ref mut s) = self.state_dialog_text {
             let mut close_dialog = false;
             egui::Window::new("Save state")
@@ -672,13 +2760,7 @@ impl PxuGuiApp {
 
                         if ui.button("Load").clicked() {
                             close_dialog = true;
-
-                            if let Some(saved_state) = pxu::SavedState::decode(s) {
-                                self.pxu.consts = saved_state.consts;
-                                self.pxu.state = saved_state.state;
-                            } else if let Ok(state) = ron::from_str::<pxu::State>(s) {
-                                self.pxu.state = state;
-                            }
+                            text_to_load = Some(s.clone());
                         }
 
                         if ui.button("Compress").clicked() {
@@ -703,6 +2785,10 @@ impl PxuGuiApp {
                 self.state_dialog_text = None;
             }
         }
+
+        if let Some(text) = text_to_load {
+            self.load_state_text(Ok(text));
+        }
     }
 
     fn show_about_window(&mut self, ctx: &egui::Context) {
@@ -779,32 +2865,519 @@ impl PxuGuiApp {
 
     fn show_figure_window(&mut self, ctx: &egui::Context) {
         let mut close = false;
+        let mut open = self.show_figure_picker;
         egui::Window::new("Figures")
-            .open(&mut self.show_figure_picker)
+            .open(&mut open)
             .resizable(false)
             .collapsible(true)
             .show(ctx, |ui| {
-                for (index, fig) in self.figures.iter().enumerate() {
-                    let title = format!("Figure {}: {}", fig.paper_ref.join("/"), fig.name);
-                    let response = ui.selectable_label(Some(index) == self.figure_index, &title);
-                    if (response.clicked() || response.double_clicked())
-                        && Some(index) != self.figure_index
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Open bundle…")
+                        .on_hover_text(
+                            "Open figures.ron and the figure files written by interactive-figures",
+                        )
+                        .clicked()
                     {
-                        self.fetch_queue.push_back(fig.filename.clone());
-                        self.figure_index = Some(index);
-                    };
+                        self.figure_bundle_message = None;
+                        open_figure_bundle(ctx, self.figure_bundle_channel.tx.clone());
+                    }
+                    if let Some(message) = &self.figure_bundle_message {
+                        ui.label(egui::RichText::new(message).small());
+                    }
+                });
+
+                if self.figures.is_empty() {
+                    ui.label("No figures loaded");
+                    return;
+                }
+
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for (index, fig) in self.figures.iter().enumerate() {
+                            let title = if fig.paper_ref.is_empty() {
+                                fig.name.clone()
+                            } else {
+                                format!("Figure {}: {}", fig.paper_ref.join("/"), fig.name)
+                            };
+                            let response =
+                                ui.selectable_label(Some(index) == self.figure_index, &title);
+                            if (response.clicked() || response.double_clicked())
+                                && Some(index) != self.figure_index
+                            {
+                                self.fetch_queue.push_back(fig.filename.clone());
+                                self.figure_index = Some(index);
+                            };
+
+                            if response.double_clicked() {
+                                close = true;
+                            }
+
+                            let details = format!("h = {}, k = {}", fig.consts.h, fig.consts.k());
+                            ui.indent(("figure", index), |ui| {
+                                if !fig.description.is_empty() {
+                                    ui.label(egui::RichText::new(&fig.description).small());
+                                }
+                                ui.label(egui::RichText::new(details).small().weak());
+                            });
+                        }
+                    });
+            });
+        self.show_figure_picker = open && !close;
+    }
+
+    // The old contours and state stay in place until the contours for the new
+    // coupling constants are ready
+    fn start_contour_job(&mut self, ctx: &egui::Context, consts: CouplingConstants) {
+        if consts == self.pxu.consts {
+            self.contour_job = None;
+            return;
+        }
+
+        // Without finished contours there is nothing to keep on screen
+        if self.is_generating_in_place() {
+            self.contour_job = None;
+            self.pxu.consts = consts;
+            self.pxu.state =
+                pxu::State::new(self.pxu.state.points.len(), self.pxu.kinematic_consts());
+            self.pxu.contours.clear();
+            self.ui_state.plot_state.overlays.clear();
+            return;
+        }
+
+        let state = pxu::State::new(
+            self.pxu.state.points.len(),
+            consts.with_model(self.pxu.model()),
+        );
+        let p_range = state.points[self.ui_state.plot_state.active_point]
+            .p
+            .re
+            .floor() as i32;
+        self.contour_job = Some(ContourJob::start(
+            ctx,
+            &self.pxu.contours,
+            p_range,
+            consts,
+            self.pxu.model(),
+        ));
+    }
+
+    fn poll_contour_job(&mut self, ctx: &egui::Context) {
+        let Some(job) = self.contour_job.as_mut() else {
+            return;
+        };
+        let in_place = job.consts() == self.pxu.consts;
+
+        if in_place {
+            if let Some(mut partial) = job.take_partial() {
+                partial.set_cut_visibility(self.pxu.contours.cut_visibility().clone());
+                self.pxu.contours = partial;
+            }
+        }
+
+        let Some(mut contours) = job.poll() else {
+            self.request_animation_frame(ctx);
+            return;
+        };
+        let consts = job.consts();
+        self.contour_job = None;
+
+        // The settings may have been changed while the job was running
+        contours.set_energy_grid(self.pxu.contours.energy_grid());
+        contours.set_cut_visibility(self.pxu.contours.cut_visibility().clone());
+
+        self.pxu.contours = contours;
+        if !in_place {
+            self.pxu.consts = consts;
+            self.pxu.state =
+                pxu::State::new(self.pxu.state.points.len(), self.pxu.kinematic_consts());
+            self.ui_state.plot_state.overlays.clear();
+        }
+    }
+
+    // The contours of the comparison follow the range of p of the active
+    // excitation, just like the ones for the current coupling constants
+    fn update_comparison(&mut self, ctx: &egui::Context) {
+        let plot_state = &mut self.ui_state.plot_state;
+        let Some(consts) = self.comparison_consts else {
+            plot_state.comparison = None;
+            self.comparison_job = None;
+            return;
+        };
+        let p_range = self.pxu.state.points[plot_state.active_point].p.re.floor() as i32;
+
+        let is_current = plot_state
+            .comparison
+            .as_ref()
+            .is_some_and(|comparison| comparison.consts == consts)
+            && self.comparison_p_range == p_range;
+        if is_current {
+            self.comparison_job = None;
+            return;
+        }
+
+        match self.comparison_job {
+            Some((job_p_range, ref job)) if job_p_range == p_range && job.consts() == consts => {}
+            _ => {
+                let job =
+                    ContourJob::start(ctx, &self.pxu.contours, p_range, consts, self.pxu.model());
+                self.comparison_job = Some((p_range, job));
+            }
+        }
+
+        let Some((_, ref mut job)) = self.comparison_job else {
+            return;
+        };
+        if let Some(contours) = job.poll() {
+            plot_state.comparison = Some(plot::Comparison { consts, contours });
+            self.comparison_p_range = p_range;
+            self.comparison_job = None;
+        } else {
+            self.request_animation_frame(ctx);
+        }
+    }
+
+    // Whatever the script could not do by itself is done once it has finished
+    fn run_script(&mut self, ctx: &egui::Context) {
+        for request in self.console.run(&mut self.pxu) {
+            match request {
+                Request::SetCoupling(consts) => {
+                    self.sweep.stop();
+                    self.start_contour_job(ctx, consts);
+                }
+                Request::SweepH { from, to, frames } => {
+                    self.sweep.parameter = SweepParameter::H;
+                    (self.sweep.from, self.sweep.to, self.sweep.frames) = (from, to, frames);
+                    self.sweep.start();
+                }
+                Request::SweepK { from, to } => {
+                    self.sweep.parameter = SweepParameter::K;
+                    (self.sweep.from, self.sweep.to) = (from, to);
+                    self.sweep.start();
+                }
+                Request::SetActive(index) => {
+                    self.ui_state.plot_state.active_point = index;
+                }
+                Request::View {
+                    component,
+                    origin,
+                    height,
+                } => {
+                    let plot = self.plot_mut(component);
+                    plot.origin = egui::pos2(origin.re as f32, -origin.im as f32);
+                    plot.height = height;
+                }
+                Request::Fullscreen(component) => {
+                    self.ui_state.plot_state.fullscreen_component = Some(component);
+                }
+            }
+        }
+
+        // The script may have changed the number of excitations
+        let plot_state = &mut self.ui_state.plot_state;
+        plot_state.active_point = plot_state.active_point.min(self.pxu.state.points.len() - 1);
+    }
+
+    // Each frame of a sweep replaces the coupling constants, the contours and
+    // the state, which is either continued from the previous frame or reset
+    fn advance_sweep(&mut self, ctx: &egui::Context) {
+        let Some(frame) = self.sweep.frame() else {
+            return;
+        };
+        let p_range = self.pxu.state.points[self.ui_state.plot_state.active_point]
+            .p
+            .re
+            .floor() as i32;
+        let Some((consts, mut contours)) = self.sweep.poll(
+            ctx,
+            &self.pxu.contours,
+            p_range,
+            self.pxu.consts,
+            self.pxu.model(),
+        ) else {
+            self.request_animation_frame(ctx);
+            return;
+        };
+
+        let state = if self.sweep.carry_state && self.sweep.parameter == SweepParameter::H {
+            let mut state = self.pxu.state.clone();
+            if let Err(err) = state.continue_in_h(self.pxu.consts, consts.h) {
+                let message = format!("Could not continue the state to h = {:.3}: {err}", consts.h);
+                log::warn!("{message}");
+                self.state_message = Some(message);
+                self.sweep.stop();
+                return;
+            }
+            state
+        } else {
+            pxu::State::new(
+                self.pxu.state.points.len(),
+                consts.with_model(self.pxu.model()),
+            )
+        };
+
+        contours.set_energy_grid(self.pxu.contours.energy_grid());
+        contours.set_cut_visibility(self.pxu.contours.cut_visibility().clone());
+
+        self.contour_job = None;
+        self.pxu.contours = contours;
+        self.pxu.consts = consts;
+        self.pxu.state = state;
+        self.ui_state.plot_state.overlays.clear();
+
+        self.sweep_export = self.sweep.export_frames.then_some(frame);
+        self.sweep.advance();
+        self.request_animation_frame(ctx);
+    }
+
+    // The frames are exported as SVG files of each visible plane, once they
+    // have been drawn with the new contours
+    fn export_sweep_frame(&mut self, ui: &egui::Ui) {
+        let Some(frame) = self.sweep_export.take() else {
+            return;
+        };
+        for (component, rect) in self.plot_rects.iter() {
+            let name = format!("sweep-{frame:03}-{component}.svg").to_lowercase();
+            let svg = self.plot(*component).to_svg(
+                ui,
+                rect.size(),
+                &self.ui_state.export_settings,
+                &self.pxu,
+                &self.ui_state.plot_state,
+            );
+            if let Err(err) = save_file(&name, "image/svg+xml", svg.as_bytes()) {
+                log::warn!("{err}");
+                self.state_message = Some(err);
+                self.sweep.stop();
+                return;
+            }
+        }
+    }
+
+    fn draw_sweep_controls(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Sweep")
+            .default_open(false)
+            .show(ui, |ui| {
+                let running = self.sweep.is_running();
+                ui.add_enabled_ui(!running, |ui| {
+                    let sweep = &mut self.sweep;
+                    ui.horizontal(|ui| {
+                        let old_parameter = sweep.parameter;
+                        ui.radio_value(&mut sweep.parameter, SweepParameter::H, "h");
+                        ui.radio_value(&mut sweep.parameter, SweepParameter::K, "k");
+                        if sweep.parameter != old_parameter {
+                            (sweep.from, sweep.to) = match sweep.parameter {
+                                SweepParameter::H => (0.5, 4.0),
+                                SweepParameter::K => (0.0, 5.0),
+                            };
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        match sweep.parameter {
+                            SweepParameter::H => {
+                                for value in [&mut sweep.from, &mut sweep.to] {
+                                    ui.add(
+                                        egui::DragValue::new(value)
+                                            .speed(0.05)
+                                            .clamp_range(0.1..=10.0),
+                                    );
+                                }
+                                ui.label("in");
+                                ui.add(
+                                    egui::DragValue::new(&mut sweep.frames)
+                                        .clamp_range(2..=500)
+                                        .suffix(" frames"),
+                                );
+                            }
+                            SweepParameter::K => {
+                                for value in [&mut sweep.from, &mut sweep.to] {
+                                    ui.add(
+                                        egui::DragValue::new(value)
+                                            .speed(0.1)
+                                            .fixed_decimals(0)
+                                            .clamp_range(0.0..=10.0),
+                                    );
+                                }
+                            }
+                        };
+                    });
+
+                    ui.add_enabled(
+                        sweep.parameter == SweepParameter::H,
+                        egui::Checkbox::new(&mut sweep.carry_state, "Carry the state along"),
+                    )
+                    .on_hover_text(
+                        "Continue the excitations from one frame to the next at fixed h u",
+                    )
+                    .on_disabled_hover_text("The state is reset for each value of k");
+                    ui.checkbox(&mut sweep.export_frames, "Export frames")
+                        .on_hover_text("Save each visible plane as an SVG file for each frame");
+                });
+
+                ui.horizontal(|ui| {
+                    if running {
+                        if ui.button("Stop").clicked() {
+                            self.sweep.stop();
+                        }
+                        if let Some(frame) = self.sweep.frame() {
+                            ui.spinner();
+                            ui.label(format!(
+                                "Frame {} of {}",
+                                frame + 1,
+                                self.sweep.frame_count()
+                            ));
+                        }
+                    } else if ui.button("▶ Sweep").clicked() {
+                        self.state_message = None;
+                        self.sweep.start();
+                    }
+                });
+            });
+    }
+
+    // On the web all contours are generated by a worker, including the ones for
+    // the current coupling constants, which are shown while they are generated
+    #[cfg(target_arch = "wasm32")]
+    fn start_contour_job_in_place(&mut self, ctx: &egui::Context) {
+        if self.contour_job.is_some()
+            || self.pxu.contours.is_loaded()
+            || self.pxu.contours.progress() != (0, 1)
+        {
+            return;
+        }
+
+        let p_range = self.pxu.state.points[self.ui_state.plot_state.active_point]
+            .p
+            .re
+            .floor() as i32;
+        self.contour_job = Some(ContourJob::start(
+            ctx,
+            &self.pxu.contours,
+            p_range,
+            self.pxu.consts,
+            self.pxu.model(),
+        ));
+    }
+
+    // While the contours for the current coupling constants are being generated
+    // in place, they must not be updated from the UI thread
+    fn is_generating_in_place(&self) -> bool {
+        self.contour_job
+            .as_ref()
+            .is_some_and(|job| job.consts() == self.pxu.consts)
+    }
+
+    fn apply_visual_theme(&mut self, ctx: &egui::Context) {
+        let theme = self.ui_state.visual_theme;
+        let accent = self.ui_state.accent;
+        let visuals = theme.visuals(accent);
+        if ctx.style().visuals != visuals {
+            ctx.set_visuals(visuals);
+        }
+        self.ui_state.plot_state.palette = theme.palette(accent);
+
+        let cut_colors = &mut self.ui_state.plot_state.cut_colors;
+        if let Some(colors) =
+            plot::CutColors::from_preset(cut_colors.preset, theme == VisualTheme::Dark)
+        {
+            *cut_colors = colors;
+        }
+    }
+
+    fn draw_theme_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Theme");
+            ui.radio_value(&mut self.ui_state.visual_theme, VisualTheme::Light, "Light");
+            ui.radio_value(&mut self.ui_state.visual_theme, VisualTheme::Dark, "Dark");
+
+            let mut custom_accent = self.ui_state.accent.is_some();
+            if ui
+                .checkbox(&mut custom_accent, "Accent")
+                .on_hover_text("Use a custom color for the active excitation and selections")
+                .changed()
+            {
+                self.ui_state.accent =
+                    custom_accent.then_some(self.ui_state.plot_state.palette.active_point);
+            }
+            if let Some(ref mut accent) = self.ui_state.accent {
+                ui.color_edit_button_srgba(accent);
+            }
+        });
+
+        ui.checkbox(&mut self.ui_state.power_saving, "Power saving")
+            .on_hover_text("Only redraw on input, and animate at a lower frame rate");
+
+        ui.horizontal(|ui| {
+            if ui
+                .button("Screenshot mode")
+                .on_hover_text("Hide everything but the plots. Press Escape to leave.")
+                .clicked()
+            {
+                self.ui_state.plot_state.clean = true;
+            }
+            ui.checkbox(&mut self.ui_state.screenshot_frame, "Frame")
+                .on_hover_text("Draw a margin in the background color around the plots");
+        });
+
+        self.draw_cut_color_controls(ui);
+    }
+
+    fn draw_cut_color_controls(&mut self, ui: &mut egui::Ui) {
+        let cut_colors = &mut self.ui_state.plot_state.cut_colors;
+
+        egui::CollapsingHeader::new("Cut colors")
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::ComboBox::from_label("Preset")
+                    .selected_text(cut_colors.preset.name())
+                    .show_ui(ui, |ui| {
+                        for preset in plot::CutColorPreset::ALL {
+                            ui.selectable_value(&mut cut_colors.preset, preset, preset.name());
+                        }
+                    });
 
-                    if response.double_clicked() {
-                        close = true;
-                    }
+                // Editing any of the colors turns the preset into a custom one
+                let mut changed = false;
+                egui::Grid::new("cut_colors")
+                    .num_columns(3)
+                    .spacing([10.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.label("");
+                        ui.label("x⁺");
+                        ui.label("x⁻");
+                        ui.end_row();
+
+                        ui.label("E");
+                        changed |= ui.color_edit_button_srgba(&mut cut_colors.e).changed();
+                        ui.end_row();
+
+                        for (name, (xp, xm)) in [
+                            ("Log", &mut cut_colors.log),
+                            ("Scallion", &mut cut_colors.scallion),
+                            ("Kidney", &mut cut_colors.kidney),
+                            ("Long", &mut cut_colors.long),
+                        ] {
+                            ui.label(name);
+                            changed |= ui.color_edit_button_srgba(xp).changed();
+                            changed |= ui.color_edit_button_srgba(xm).changed();
+                            ui.end_row();
+                        }
+                    });
+                if changed {
+                    cut_colors.preset = plot::CutColorPreset::Custom;
                 }
             });
-        self.show_figure_picker ^= close;
     }
 
     fn draw_coupling_controls(&mut self, ui: &mut egui::Ui) {
-        let old_consts = self.pxu.consts;
-        let mut new_consts = self.pxu.consts;
+        let old_consts = self
+            .contour_job
+            .as_ref()
+            .map_or(self.pxu.consts, |job| job.consts());
+        let mut new_consts = old_consts;
 
         ui.add_space(6.0);
         ui.label(egui::RichText::new("Mode").strong());
@@ -842,11 +3415,40 @@ impl PxuGuiApp {
                 .integer()
                 .text("k"),
         );
+
+        // The q-deformed model only depends on q through log q, up to a sign, so
+        // q > 1 covers all of them
+        let mut model = self.pxu.model();
+        ui.horizontal(|ui| {
+            let mut deformed = !model.is_standard();
+            if ui
+                .checkbox(&mut deformed, "q-deformed")
+                .on_hover_text("Use the dispersion relation [E]_q = E, with its QLog cuts")
+                .changed()
+            {
+                model = if deformed {
+                    pxu::KinematicsModel::q_deformed(1.5)
+                } else {
+                    pxu::KinematicsModel::Standard
+                };
+            }
+            if let pxu::KinematicsModel::QDeformed { ref mut q } = model {
+                ui.add(egui::Slider::new(q, 1.01..=4.0).logarithmic(true).text("q"));
+            }
+        });
+        if model != self.pxu.model() {
+            self.sweep.stop();
+            self.contour_job = None;
+            self.pxu.set_model(model);
+            self.pxu.state =
+                pxu::State::new(self.pxu.state.points.len(), self.pxu.kinematic_consts());
+            self.ui_state.plot_state.overlays.clear();
+        }
         ui.add(
             egui::Slider::from_get_set(1.0..=20.0, |n| {
                 if let Some(n) = n {
                     let n = n as usize;
-                    self.pxu.state = pxu::State::new(n, self.pxu.consts);
+                    self.pxu.state = pxu::State::new(n, self.pxu.kinematic_consts());
                     self.ui_state.plot_state.active_point = n / 2;
                 }
                 self.pxu.state.points.len() as f64
@@ -856,9 +3458,85 @@ impl PxuGuiApp {
         );
 
         if old_consts != new_consts {
-            self.pxu.consts = new_consts;
-            self.pxu.state = pxu::State::new(self.pxu.state.points.len(), new_consts);
-            self.pxu.contours.clear();
+            self.sweep.stop();
+            self.start_contour_job(ui.ctx(), new_consts);
+        }
+
+        ui.horizontal(|ui| {
+            let mut compare = self.comparison_consts.is_some();
+            if ui
+                .checkbox(&mut compare, "Compare with")
+                .on_hover_text("Draw the cuts for other coupling constants faded in the background")
+                .changed()
+            {
+                self.comparison_consts = compare.then_some(CouplingConstants::new(7.0, 3));
+            }
+            if let Some(ref mut consts) = self.comparison_consts {
+                ui.add(
+                    egui::DragValue::new(&mut consts.h)
+                        .speed(0.05)
+                        .clamp_range(0.1..=10.0)
+                        .prefix("h = "),
+                );
+                ui.add(
+                    egui::DragValue::from_get_set(|v| consts.get_set_k(v))
+                        .clamp_range(0..=10)
+                        .prefix("k = "),
+                );
+            }
+            if self.comparison_job.is_some() {
+                ui.spinner();
+            }
+        });
+    }
+
+    fn draw_overlay_controls(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Compare states").strong());
+            if ui
+                .button("Pin")
+                .on_hover_text("Keep a copy of the current state in the plots")
+                .clicked()
+            {
+                let overlays = &mut self.ui_state.plot_state.overlays;
+                let index = overlays.len();
+                let name = format!("State {}", index + 1);
+                overlays.push(plot::Overlay::new(&name, self.pxu.state.clone(), index));
+            }
+        });
+
+        let mut edit = None;
+        let mut remove = None;
+        for (index, overlay) in self.ui_state.plot_state.overlays.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgba(&mut overlay.color);
+                ui.checkbox(&mut overlay.visible, "");
+                ui.add(egui::TextEdit::singleline(&mut overlay.name).desired_width(80.0));
+                if ui
+                    .button("Edit")
+                    .on_hover_text("Make this the editable state")
+                    .clicked()
+                {
+                    edit = Some(index);
+                }
+                if ui.button("✖").on_hover_text("Remove").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+
+        // The editable state takes the place of the overlay that is edited
+        if let Some(index) = edit {
+            let overlay = &mut self.ui_state.plot_state.overlays[index];
+            std::mem::swap(&mut overlay.state, &mut self.pxu.state);
+            let last = self.pxu.state.points.len() - 1;
+            let active_point = &mut self.ui_state.plot_state.active_point;
+            *active_point = (*active_point).min(last);
+        }
+
+        if let Some(index) = remove {
+            self.ui_state.plot_state.overlays.remove(index);
         }
     }
 
@@ -871,10 +3549,7 @@ impl PxuGuiApp {
         }
 
         if ui.button("Load/save state").clicked() {
-            let saved_state = pxu::SavedState {
-                state: self.pxu.state.clone(),
-                consts: self.pxu.consts,
-            };
+            let saved_state = self.saved_state();
             if let Ok(s) = ron::to_string(&saved_state) {
                 self.state_dialog_text = Some(s);
             } else {
@@ -882,13 +3557,22 @@ impl PxuGuiApp {
             }
         }
 
+        ui.checkbox(
+            &mut self.ui_state.show_nr_diagnostics,
+            "Newton-Raphson diagnostics",
+        )
+        .on_hover_text("Show the convergence of the root finding during the last drag");
+
+        ui.checkbox(&mut self.ui_state.show_console, "Script console")
+            .on_hover_text("Run rhai scripts on the state, paths and plots");
+
         if !self.pxu.paths.is_empty() {
             ui.add_space(5.0);
             ui.label("Paths");
             egui::Frame::none()
                 .inner_margin(4.0)
                 .stroke(egui::Stroke::new(2.0, egui::Color32::GRAY))
-                .fill(egui::Color32::WHITE)
+                .fill(ui.visuals().extreme_bg_color)
                 .show(ui, |ui| {
                     egui::ScrollArea::vertical()
                         .max_height(200.0)
@@ -919,8 +3603,56 @@ impl PxuGuiApp {
         }
     }
 
+    fn draw_constraint_settings(&mut self, ui: &mut egui::Ui) {
+        let mut conserved = self.pxu.state.constraint.map(|c| c.conserved);
+        let mut compensation = self
+            .pxu
+            .state
+            .constraint
+            .map_or(pxu::Compensation::Others, |c| c.compensation);
+
+        ui.horizontal(|ui| {
+            ui.label("Keep fixed:");
+            ui.radio_value(&mut conserved, None, "Nothing");
+            ui.radio_value(&mut conserved, Some(pxu::Conserved::Momentum), "Total p");
+            ui.radio_value(&mut conserved, Some(pxu::Conserved::Energy), "Total E");
+        });
+
+        if conserved.is_some() {
+            let last = self.pxu.state.points.len() - 1;
+            ui.horizontal(|ui| {
+                ui.label("By moving:");
+                ui.radio_value(&mut compensation, pxu::Compensation::Others, "All others");
+
+                let mut index = match compensation {
+                    pxu::Compensation::Point(index) => index.min(last),
+                    pxu::Compensation::Others => {
+                        (self.ui_state.plot_state.active_point + 1) % (last + 1)
+                    }
+                };
+                let selected = matches!(compensation, pxu::Compensation::Point(_));
+                if ui.radio(selected, "Excitation").clicked() {
+                    compensation = pxu::Compensation::Point(index);
+                }
+                if ui
+                    .add_enabled(
+                        selected,
+                        egui::DragValue::new(&mut index).clamp_range(0..=last),
+                    )
+                    .changed()
+                {
+                    compensation = pxu::Compensation::Point(index);
+                }
+            });
+        }
+
+        self.pxu.state.constraint = conserved.map(|conserved| pxu::Constraint {
+            conserved,
+            compensation,
+        });
+    }
+
     fn draw_state_information(&mut self, ui: &mut egui::Ui) {
-        let active_point = &self.pxu.state.points[self.ui_state.plot_state.active_point];
         ui.separator();
         {
             ui.label(egui::RichText::new("State").strong());
@@ -935,6 +3667,26 @@ impl PxuGuiApp {
                 self.pxu.state.points.len() as f64
                     + self.pxu.consts.k() as f64 * self.pxu.state.p()
             ));
+
+            ui.horizontal(|ui| {
+                if ui.button("Copy").clicked() {
+                    let text = self.pxu.state.display(self.pxu.consts).to_string();
+                    ui.output_mut(|writer| writer.copied_text = text);
+                }
+                if ui.button("Copy as LaTeX").clicked() {
+                    let text = self.pxu.state.to_latex_table(self.pxu.consts);
+                    ui.output_mut(|writer| writer.copied_text = text);
+                }
+                if ui.button("Copy as Mathematica").clicked() {
+                    let text = self.pxu.state.to_mathematica();
+                    ui.output_mut(|writer| writer.copied_text = text);
+                }
+            });
+
+            self.draw_excitation_table(ui);
+            self.draw_point_styles(ui);
+            self.draw_sheet_data_inspector(ui);
+            self.draw_event_log(ui);
         }
 
         ui.separator();
@@ -948,6 +3700,7 @@ impl PxuGuiApp {
                 .strong(),
             );
 
+            let active_point = &self.pxu.state.points[self.ui_state.plot_state.active_point];
             ui.label(format!("Momentum: {:+.3}", active_point.p));
 
             ui.label(format!(
@@ -956,9 +3709,9 @@ impl PxuGuiApp {
             ));
 
             ui.add_space(10.0);
-            ui.label(format!("x⁺: {:+.3}", active_point.xp));
-            ui.label(format!("x⁻: {:+.3}", active_point.xm));
-            ui.label(format!("u:  {:+.3}", active_point.u));
+            self.draw_coordinate_inputs(ui);
+            let active_point = &self.pxu.state.points[self.ui_state.plot_state.active_point];
+            ui.label(format!("E:  {:+.3}", active_point.e));
 
             ui.add_space(10.0);
             ui.label(egui::RichText::new("Branch info").strong());
@@ -1018,6 +3771,367 @@ impl PxuGuiApp {
         }
     }
 
+    // Move the active excitation to a typed in value in small steps, so that the
+    // solver can follow it across any cuts on the way
+    fn set_coordinate(&mut self, component: pxu::Component, value: Complex64) {
+        const STEPS: usize = 32;
+
+        let active_point = self.ui_state.plot_state.active_point;
+        let start = self.pxu.state.points[active_point].get(component);
+        let mut state = self.pxu.state.clone();
+        let consts = self.pxu.kinematic_consts();
+
+        for step in 1..=STEPS {
+            let z = start + (value - start) * (step as f64 / STEPS as f64);
+            if let Err(err) = state.update(active_point, component, z, &self.pxu.contours, consts) {
+                self.coordinate_error = Some(format!("Could not set {component}: {err}"));
+                return;
+            }
+        }
+
+        self.pxu.state = state;
+        self.coordinate_error = None;
+    }
+
+    // The arrow keys move the active excitation by a fraction of the height of
+    // the view of the focused plane, or a tenth of that while holding Shift.
+    // With Alt they pan the view instead, and Page Up and Page Down zoom.
+    fn interact_with_focused_plane(&mut self, ctx: &egui::Context, component: pxu::Component) {
+        const PAN: f32 = 0.1;
+        const ZOOM: f32 = 1.25;
+
+        let (direction, fine, pan, zoom) = ctx.input(|i| {
+            let direction = [
+                (egui::Key::ArrowRight, Complex64::new(1.0, 0.0)),
+                (egui::Key::ArrowLeft, Complex64::new(-1.0, 0.0)),
+                (egui::Key::ArrowUp, Complex64::new(0.0, 1.0)),
+                (egui::Key::ArrowDown, Complex64::new(0.0, -1.0)),
+            ]
+            .into_iter()
+            .filter(|(key, _)| i.key_pressed(*key))
+            .map(|(_, direction)| direction)
+            .sum::<Complex64>();
+            let zoom = if i.key_pressed(egui::Key::PageUp) {
+                ZOOM
+            } else if i.key_pressed(egui::Key::PageDown) {
+                1.0 / ZOOM
+            } else {
+                1.0
+            };
+            (direction, i.modifiers.shift, i.modifiers.alt, zoom)
+        });
+
+        let plot = self.plot_mut(component);
+        plot.height /= zoom;
+        if pan {
+            let height = plot.height;
+            plot.origin += PAN * height * vec2(direction.re as f32, -direction.im as f32);
+            return;
+        }
+
+        if direction == Complex64::from(0.0) {
+            return;
+        }
+
+        let mut step = self.ui_state.nudge_step * self.plot(component).height as f64;
+        if fine {
+            step /= 10.0;
+        }
+        let active_point = self.ui_state.plot_state.active_point;
+        let z = self.pxu.state.points[active_point].get(component);
+        self.set_coordinate(component, z + step * direction);
+    }
+
+    fn draw_coordinate_inputs(&mut self, ui: &mut egui::Ui) {
+        let components = [
+            ("p: ", pxu::Component::P),
+            ("x⁺:", pxu::Component::Xp),
+            ("x⁻:", pxu::Component::Xm),
+            ("u: ", pxu::Component::U),
+        ];
+        let active_point = self.ui_state.plot_state.active_point;
+        let mut new_value = None;
+
+        for (index, (label, component)) in components.into_iter().enumerate() {
+            let id = ui.id().with(("coordinate", component));
+            let text = &mut self.coordinate_text[index];
+
+            // Only show the current value when the user is not typing
+            if !ui.memory(|memory| memory.has_focus(id)) {
+                let z = self.pxu.state.points[active_point].get(component);
+                *text = format!("{:+.6}{:+.6}i", z.re, z.im);
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(label);
+                let response = ui.add(
+                    egui::TextEdit::singleline(text)
+                        .id(id)
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(180.0),
+                );
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    match text.replace(' ', "").parse::<Complex64>() {
+                        Ok(z) => new_value = Some((component, z)),
+                        Err(_) => {
+                            self.coordinate_error = Some(format!("Could not parse \"{text}\""))
+                        }
+                    }
+                }
+            });
+        }
+
+        if let Some((component, z)) = new_value {
+            self.set_coordinate(component, z);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Arrow key step:");
+            ui.add(
+                egui::DragValue::new(&mut self.ui_state.nudge_step)
+                    .clamp_range(0.0001..=0.1)
+                    .speed(0.0005)
+                    .custom_formatter(|step, _| format!("{:.2}%", 100.0 * step))
+                    .custom_parser(|text| {
+                        text.trim_end_matches('%').trim().parse::<f64>().ok().map(|step| step / 100.0)
+                    }),
+            )
+            .on_hover_text("The step of the arrow keys in a focused plane, relative to the height of the view. Shift gives steps ten times smaller.");
+        });
+
+        if let Some(err) = &self.coordinate_error {
+            ui.label(
+                egui::RichText::new(err)
+                    .small()
+                    .color(ui.visuals().warn_fg_color),
+            );
+        }
+    }
+
+    fn watch_sheet_data(&mut self, ctx: &egui::Context) {
+        let points = &self.pxu.state.points;
+        if self.sheet_data_watch.len() != points.len() {
+            self.sheet_data_watch = points
+                .iter()
+                .map(|pt| SheetDataWatch::new(&pt.sheet_data))
+                .collect();
+            return;
+        }
+
+        // Only the cuts crossed by moving the excitations are logged, and not
+        // the changes from loading a state or undoing
+        let now = ctx.input(|i| i.time);
+        let time = if self.playback.playing {
+            Some(EventTime::Playback(self.playback.t))
+        } else if ctx.input(|i| i.pointer.any_down()) {
+            Some(EventTime::Drag(now))
+        } else {
+            None
+        };
+        for (i, (watch, pt)) in self
+            .sheet_data_watch
+            .iter_mut()
+            .zip(points.iter())
+            .enumerate()
+        {
+            if let Some(time) = time {
+                self.event_log
+                    .record(i, &watch.sheet_data, &pt.sheet_data, time);
+            }
+            watch.update(&pt.sheet_data, now);
+        }
+
+        // Keep redrawing until the highlights have faded. In power saving mode
+        // they simply disappear with the next redraw.
+        if !self.ui_state.power_saving
+            && self
+                .sheet_data_watch
+                .iter()
+                .any(|watch| (0..5).any(|index| watch.is_highlighted(index, now)))
+        {
+            ctx.request_repaint();
+        }
+    }
+
+    fn draw_sheet_data_inspector(&self, ui: &mut egui::Ui) {
+        let now = ui.input(|i| i.time);
+        let active_point = self.ui_state.plot_state.active_point;
+
+        egui::CollapsingHeader::new("Sheet data")
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::Grid::new("sheet_data_inspector")
+                    .num_columns(6)
+                    .striped(true)
+                    .spacing([10.0, 2.0])
+                    .show(ui, |ui| {
+                        for heading in ["#", "log p", "log m", "E", "u", "Im x"] {
+                            ui.label(egui::RichText::new(heading).strong());
+                        }
+                        ui.end_row();
+
+                        for (i, watch) in self.sheet_data_watch.iter().enumerate() {
+                            let sd = &watch.sheet_data;
+                            let entries = [
+                                format!("{:+}", sd.log_branch_p),
+                                format!("{:+}", sd.log_branch_m),
+                                format!("{:+}", sd.e_branch),
+                                format!("({},{})", sd.u_branch.0, sd.u_branch.1),
+                                format!("({:+},{:+})", sd.im_x_sign.0, sd.im_x_sign.1),
+                            ];
+
+                            let mut index_text = egui::RichText::new(format!("{i}")).monospace();
+                            if i == active_point {
+                                index_text =
+                                    index_text.color(self.ui_state.plot_state.palette.active_point);
+                            }
+                            ui.label(index_text);
+
+                            for (index, entry) in entries.into_iter().enumerate() {
+                                let mut text = egui::RichText::new(entry).monospace();
+                                if watch.is_highlighted(index, now) {
+                                    text = text
+                                        .strong()
+                                        .color(egui::Color32::BLACK)
+                                        .background_color(egui::Color32::from_rgb(255, 230, 120));
+                                }
+                                ui.label(text);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn draw_event_log(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Crossed cuts")
+            .default_open(false)
+            .show(ui, |ui| {
+                self.event_log.show(ui);
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(!self.event_log.is_empty(), |ui| {
+                        if ui.button("Copy").clicked() {
+                            let text = self.event_log.to_text();
+                            ui.output_mut(|writer| writer.copied_text = text);
+                        }
+                        if ui.button("Clear").clicked() {
+                            self.event_log.clear();
+                        }
+                    });
+                });
+            });
+    }
+
+    // Momentum is given in units of 2π, as everywhere else
+    fn draw_excitation_table(&self, ui: &mut egui::Ui) {
+        let consts = self.pxu.consts;
+        let active_point = self.ui_state.plot_state.active_point;
+
+        egui::CollapsingHeader::new("Excitations")
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new("excitation_table")
+                    .num_columns(4)
+                    .striped(true)
+                    .spacing([10.0, 2.0])
+                    .show(ui, |ui| {
+                        for heading in ["#", "p", "m", "E"] {
+                            ui.label(egui::RichText::new(heading).strong());
+                        }
+                        ui.end_row();
+
+                        for (i, pt) in self.pxu.state.points.iter().enumerate() {
+                            let text = |s: String| {
+                                let text = egui::RichText::new(s).monospace();
+                                if i == active_point {
+                                    text.color(self.ui_state.plot_state.palette.active_point)
+                                } else {
+                                    text
+                                }
+                            };
+                            ui.label(text(format!("{i}")));
+                            ui.label(text(format!("{:+.3}", pt.p)));
+                            ui.label(text(format!("{:+.3}", pt.m(consts))));
+                            ui.label(text(format!("{:+.3}", pt.en(consts))));
+                            ui.end_row();
+                        }
+
+                        let m = self
+                            .pxu
+                            .state
+                            .points
+                            .iter()
+                            .map(|pt| pt.m(consts))
+                            .sum::<num::complex::Complex64>();
+                        let total = |s: String| egui::RichText::new(s).monospace().strong();
+                        ui.label(total("Σ".to_owned()));
+                        ui.label(total(format!("{:+.3}", self.pxu.state.p())));
+                        ui.label(total(format!("{:+.3}", m)));
+                        ui.label(total(format!("{:+.3}", self.pxu.state.en(consts))));
+                        ui.end_row();
+                    });
+            });
+    }
+
+    // A color and marker can be chosen for each excitation, so that it can be
+    // followed between the planes
+    fn draw_point_styles(&mut self, ui: &mut egui::Ui) {
+        let plot_state = &mut self.ui_state.plot_state;
+        let len = self.pxu.state.points.len();
+
+        egui::CollapsingHeader::new("Excitation styles")
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::Grid::new("point_styles")
+                    .num_columns(3)
+                    .spacing([10.0, 2.0])
+                    .show(ui, |ui| {
+                        for i in 0..len {
+                            let mut style = plot_state.point_style(i);
+                            ui.label(egui::RichText::new(format!("{i}")).monospace());
+
+                            ui.horizontal(|ui| {
+                                let mut custom_color = style.color.is_some();
+                                if ui.checkbox(&mut custom_color, "Color").changed() {
+                                    let [r, g, b, _] = plot_state.palette.active_point.to_array();
+                                    style.color = custom_color.then_some([r, g, b]);
+                                }
+                                if let Some(ref mut color) = style.color {
+                                    ui.color_edit_button_srgb(color);
+                                }
+                            });
+
+                            egui::ComboBox::from_id_source(("point_marker", i))
+                                .selected_text(style.marker.name())
+                                .show_ui(ui, |ui| {
+                                    for marker in pxu::Marker::ALL {
+                                        ui.selectable_value(
+                                            &mut style.marker,
+                                            marker,
+                                            marker.name(),
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+
+                            if style != plot_state.point_style(i) {
+                                plot_state.set_point_style(i, style);
+                            }
+                        }
+                    });
+
+                if ui
+                    .add_enabled(
+                        !plot_state.point_styles.is_empty(),
+                        egui::Button::new("Reset styles"),
+                    )
+                    .clicked()
+                {
+                    plot_state.point_styles.clear();
+                }
+            });
+    }
+
     fn draw_state_information_ux(&mut self, ui: &mut egui::Ui) {
         let active_point = &self.pxu.state.points[self.ui_state.plot_state.active_point];
         ui.separator();
@@ -1051,46 +4165,168 @@ impl PxuGuiApp {
     fn draw_side_panel(&mut self, ctx: &egui::Context) {
         egui::SidePanel::right("side_panel").show(ctx, |ui| {
             self.draw_coupling_controls(ui);
+            self.draw_sweep_controls(ui);
 
             ui.horizontal(|ui| {
                 if ui.add(egui::Button::new("Reset State")).clicked() {
-                    self.pxu.state = pxu::State::new(self.pxu.state.points.len(), self.pxu.consts);
+                    self.pxu.state = pxu::State::new(
+                        self.pxu.state.points.len(),
+                        self.pxu.kinematic_consts(),
+                    );
                 }
 
-                if ui.add(egui::Button::new("Share")).clicked() {
-                    let saved_state = pxu::SavedState {
-                        state: self.pxu.state.clone(),
-                        consts: self.pxu.consts,
-                    };
-                    if let Ok(mut s) = ron::to_string(&saved_state) {
-                        use base64::Engine;
-                        use std::io::Write;
+                if ui
+                    .button("Bound state…")
+                    .on_hover_text("Create a bound state with chosen M, p range and coupling")
+                    .clicked()
+                {
+                    self.open_bound_state_dialog(None);
+                }
 
-                        let mut enc = flate2::write::DeflateEncoder::new(
-                            Vec::new(),
-                            flate2::Compression::best(),
-                        );
-                        if enc.write_all(s.as_bytes()).is_ok() {
-                            if let Ok(data) = enc.finish() {
-                                s = base64::engine::general_purpose::URL_SAFE.encode(data);
-                                if let Some(url) = self.get_base_url() {
-                                    self.shared_state_text = Some(format!("{url}?state={s}",));
-                                } else {
-                                    log::info!("No base url");
-                                }
-                            } else {
-                                log::info!("Could not url decode state");
-                            }
+                if ui
+                    .add_enabled(self.undo_history.can_undo(), egui::Button::new("Undo"))
+                    .clicked()
+                {
+                    self.undo();
+                }
+
+                if ui
+                    .add_enabled(self.undo_history.can_redo(), egui::Button::new("Redo"))
+                    .clicked()
+                {
+                    self.redo();
+                }
+
+                if ui.add(egui::Button::new("Share")).clicked() {
+                    if let Some(s) = self.shared_view().encode() {
+                        if let Some(url) = self.get_base_url() {
+                            self.shared_state_text = Some(format!("{url}#view={s}"));
                         } else {
-                            log::info!("Could not compress state");
+                            log::info!("No base url");
                         }
+                        set_location_fragment(&format!("view={s}"));
                     } else {
-                        log::info!("Could not serialise state");
+                        log::info!("Could not encode view");
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Save state…").clicked() {
+                    self.save_state();
+                }
+
+                if ui.button("Load state…").clicked() {
+                    self.state_message = None;
+                    open_state_file(ctx, self.state_text_channel.tx.clone());
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Copy state").clicked() {
+                    self.copy_state(ctx);
+                }
+
+                if ui.button("Paste state").clicked() {
+                    self.state_message = None;
+                    read_clipboard(ctx, self.state_text_channel.tx.clone());
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui
+                    .toggle_value(&mut self.recording, "⏺ Record path")
+                    .on_hover_text("Record the trajectory of each dragged excitation")
+                    .clicked()
+                {
+                    self.recorder = None;
+                }
+
+                if !self.recorded_paths.is_empty() {
+                    if ui.button("Save paths…").clicked() {
+                        self.save_recorded_paths();
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.recorded_paths.clear();
+                    }
+                }
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Singlet:");
+                for singlet in crate::singlets::ALL.iter() {
+                    if ui
+                        .button(singlet.name)
+                        .on_hover_text("Replace the state by this singlet state")
+                        .clicked()
+                    {
+                        self.load_singlet(singlet);
                     }
                 }
             });
 
+            if ui
+                .add_enabled(
+                    self.pxu.contours.is_loaded() && !self.is_generating_in_place(),
+                    egui::Button::new("Cross excitation"),
+                )
+                .on_hover_text("Move the active excitation from p to -p through the crossing path")
+                .clicked()
+            {
+                self.cross_active_point();
+            }
+
+            if let Some(message) = &self.state_message {
+                ui.label(egui::RichText::new(message).small());
+            }
+
             ui.checkbox(&mut self.pxu.state.unlocked, "Unlock bound state");
+            ui.checkbox(
+                &mut self.ui_state.plot_state.snap_mode,
+                "Snap while dragging",
+            )
+            .on_hover_text("Pull dragged excitations onto real p, onto lines of constant Im u and onto bound state positions");
+            let mut measure_mode = self.ui_state.plot_state.measure_mode;
+            if ui
+                .checkbox(&mut measure_mode, "Measure")
+                .on_hover_text("Click two locations in a plane to show their separation")
+                .changed()
+            {
+                self.ui_state.plot_state.toggle_measure_mode();
+            }
+            if self.pxu.state.unlocked && self.pxu.state.points.len() > 1 {
+                self.draw_constraint_settings(ui);
+            }
+            if !self.is_ux_mode() {
+                if self.ui_state.pane_layout.is_none() {
+                    for (component, name) in OPTIONAL_PLOTS {
+                        let mut show = self.ui_state.extra_plots.contains(&component);
+                        if ui.checkbox(&mut show, format!("Show {name}")).changed() {
+                            self.toggle_extra_plot(component, show);
+                        }
+                    }
+                }
+
+                let mut energy_grid = self.pxu.contours.energy_grid();
+                if ui.checkbox(&mut energy_grid, "Energy grid lines").changed() {
+                    self.pxu.contours.set_energy_grid(energy_grid);
+                }
+            }
+
+            ui.horizontal(|ui| {
+                let grid_labels = &mut self.ui_state.plot_state.grid_labels;
+                ui.label("Grid m labels:");
+                ui.radio_value(grid_labels, plot::GridLabels::Hover, "On hover")
+                    .on_hover_text("Show m in the tooltip of a grid line");
+                ui.radio_value(grid_labels, plot::GridLabels::Always, "Always")
+                    .on_hover_text("Label every visible grid line of x⁺ and x⁻");
+            });
+
+            ui.checkbox(
+                &mut self.ui_state.plot_state.mirror_images,
+                "Mirror theory images",
+            )
+            .on_hover_text("Show 1/x_R of the excitations in the theory with k replaced by -k");
 
             if self.is_ux_mode() {
                 self.draw_state_information_ux(ui);
@@ -1098,17 +4334,35 @@ impl PxuGuiApp {
                 self.draw_state_information(ui);
             }
 
+            self.draw_overlay_controls(ui);
+            self.draw_playback_controls(ui);
+
+            ui.separator();
+            self.draw_view_presets(ui);
+            self.draw_bookmarks(ui);
+            if !self.is_ux_mode() {
+                self.draw_layout_controls(ui);
+            }
+
             ui.separator();
+            self.draw_theme_controls(ui);
             ui.horizontal_wrapped(|ui| {
                 if ui
-                    .add_enabled(!self.figures.is_empty(), egui::Button::new("Figures"))
-                    .on_disabled_hover_text("No figures loaded")
-                    .on_hover_text("Pick a figure")
+                    .button("Figures")
+                    .on_hover_text("Browse the figures of the paper or of a bundle")
                     .clicked()
                 {
                     self.show_figure_picker = true;
                 }
 
+                if ui.button("Export view…").clicked() {
+                    self.open_export_dialog();
+                }
+
+                if ui.button("Shortcuts…").clicked() {
+                    self.ui_state.show_shortcuts = true;
+                }
+
                 if ui.button("Help").clicked() {
                     self.show_help = true;
                 }
@@ -1157,7 +4411,7 @@ impl PxuGuiApp {
                         1000.0 * self.frame_history.mean_frame_time()
                     ));
 
-                    if !self.ui_state.continuous_mode {
+                    if !self.ui_state.continuous_mode || self.ui_state.power_saving {
                         ui.label(
                             egui::RichText::new("⚠ Not running in continuous mode ⚠")
                                 .small()
@@ -1170,7 +4424,29 @@ impl PxuGuiApp {
 
                 ui.add_space(10.0);
                 let (current, total) = self.pxu.contours.progress();
-                if total > 1 && current != total {
+                if self.is_generating_in_place() {
+                    let (current, total) = self.contour_job.as_ref().unwrap().progress();
+                    let progress = current as f32 / total.max(1) as f32;
+                    ui.add(
+                        egui::ProgressBar::new(progress)
+                            .text(format!("Generating contours {:.0}%", 100.0 * progress)),
+                    );
+                } else if let Some(ref job) = self.contour_job {
+                    let (current, total) = job.progress();
+                    let progress = current as f32 / total.max(1) as f32;
+                    let consts = job.consts();
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            self.contour_job = None;
+                        }
+                        ui.add(egui::ProgressBar::new(progress).text(format!(
+                            "Generating contours for h = {:.3}, k = {} {:.0}%",
+                            consts.h,
+                            consts.k(),
+                            100.0 * progress
+                        )));
+                    });
+                } else if total > 1 && current != total {
                     let progress = current as f32 / total as f32;
                     ui.add(
                         egui::ProgressBar::new(progress)
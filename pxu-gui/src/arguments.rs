@@ -6,17 +6,40 @@ pub struct Arguments {
     pub continuous_mode: bool,
     pub paths: Option<String>,
     pub state: Option<String>,
+    #[serde(skip)]
+    pub view: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    pub render: Option<RenderArguments>,
+}
+
+// Draw one plane of a state to a file without opening a window
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct RenderArguments {
+    pub state: String,
+    pub component: String,
+    pub out: std::path::PathBuf,
+    pub width: f32,
+    pub height: f32,
+    pub scale: f32,
+    pub fit: bool,
 }
 
 #[cfg(target_arch = "wasm32")]
 impl From<url::Url> for Arguments {
     fn from(url: url::Url) -> Self {
-        let Some(query) = url.query() else {
-            return Default::default();
-        };
-        let Ok(settings) = serde_urlencoded::from_str(query) else {
-            return Default::default();
-        };
+        let mut settings: Self = url
+            .query()
+            .and_then(|query| serde_urlencoded::from_str(query).ok())
+            .unwrap_or_default();
+
+        // A shared view is kept in the fragment as #view=...
+        settings.view = url
+            .fragment()
+            .and_then(|fragment| fragment.strip_prefix("view="))
+            .map(|view| view.to_owned());
+
         settings
     }
 }
@@ -71,14 +94,89 @@ impl Arguments {
                     .help("Load state")
                     .required(false),
             )
+            .arg(
+                clap::Arg::new("view")
+                    .long("view")
+                    .help("Load a shared view")
+                    .required(false),
+            )
+            .subcommand(
+                clap::Command::new("render")
+                    .about("Draw a plane of a state to a PNG, SVG or TikZ file without a window")
+                    .arg(
+                        clap::Arg::new("state")
+                            .long("state")
+                            .help("The state to draw, as saved by the app")
+                            .required(true),
+                    )
+                    .arg(
+                        clap::Arg::new("component")
+                            .long("component")
+                            .help(
+                                "The plane to draw: p, xp, xm, u, e, x, mirrorp, mirrore or torus",
+                            )
+                            .default_value("p"),
+                    )
+                    .arg(
+                        clap::Arg::new("out")
+                            .long("out")
+                            .help("The file to write, in the format given by its extension")
+                            .value_parser(clap::value_parser!(std::path::PathBuf))
+                            .required(true),
+                    )
+                    .arg(
+                        clap::Arg::new("width")
+                            .long("width")
+                            .help("The width in points")
+                            .value_parser(clap::value_parser!(f32))
+                            .default_value("640"),
+                    )
+                    .arg(
+                        clap::Arg::new("height")
+                            .long("height")
+                            .help("The height in points")
+                            .value_parser(clap::value_parser!(f32))
+                            .default_value("480"),
+                    )
+                    .arg(
+                        clap::Arg::new("scale")
+                            .long("scale")
+                            .help("The number of pixels per point in PNG images")
+                            .value_parser(clap::value_parser!(f32))
+                            .default_value("2"),
+                    )
+                    .arg(
+                        clap::Arg::new("fit")
+                            .long("fit")
+                            .help("Zoom to fit the excitations instead of using the default view")
+                            .action(clap::ArgAction::SetTrue),
+                    ),
+            )
             .get_matches();
 
+        let render = matches
+            .subcommand_matches("render")
+            .map(|matches| RenderArguments {
+                state: matches.get_one::<String>("state").unwrap().clone(),
+                component: matches.get_one::<String>("component").unwrap().clone(),
+                out: matches
+                    .get_one::<std::path::PathBuf>("out")
+                    .unwrap()
+                    .clone(),
+                width: *matches.get_one::<f32>("width").unwrap(),
+                height: *matches.get_one::<f32>("height").unwrap(),
+                scale: *matches.get_one::<f32>("scale").unwrap(),
+                fit: matches.get_flag("fit"),
+            });
+
         Self {
             show_fps: matches.get_flag("fps"),
             show_dev: matches.get_flag("dev"),
             continuous_mode: matches.get_flag("continuous_mode"),
             paths: matches.get_one::<String>("paths").cloned(),
             state: matches.get_one::<String>("state").cloned(),
+            view: matches.get_one::<String>("view").cloned(),
+            render,
         }
     }
 }
@@ -0,0 +1,20 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+// The web worker that generates contours for the web app
+
+#[cfg(target_arch = "wasm32")]
+#[path = "../contour_worker.rs"]
+mod contour_worker;
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    console_error_panic_hook::set_once();
+    wasm_logger::init(wasm_logger::Config::new(log::Level::Info));
+
+    contour_worker::run_worker();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    eprintln!("The contour worker is only used by the web app");
+}
@@ -0,0 +1,347 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use num::complex::Complex64;
+use pxu::kinematics::CouplingConstants;
+use rhai::{Array, Dynamic, Engine, EvalAltResult};
+
+// Scripts are stopped after this many operations, since they run on the UI
+// thread
+const MAX_OPERATIONS: u64 = 5_000_000;
+const MAX_HISTORY: usize = 100;
+
+// Things a script asks the app to do once it has finished, for anything that
+// needs more than the state, such as new contours or a change of view
+pub enum Request {
+    SetCoupling(CouplingConstants),
+    SweepH {
+        from: f64,
+        to: f64,
+        frames: usize,
+    },
+    SweepK {
+        from: f64,
+        to: f64,
+    },
+    SetActive(usize),
+    View {
+        component: pxu::Component,
+        origin: Complex64,
+        height: f32,
+    },
+    Fullscreen(pxu::Component),
+}
+
+// What the functions of the script act on. The app is moved in while the
+// script runs and taken back afterwards.
+struct Env {
+    pxu: pxu::Pxu,
+    requests: Vec<Request>,
+    output: String,
+}
+
+struct Entry {
+    script: String,
+    output: String,
+    failed: bool,
+}
+
+#[derive(Default)]
+pub struct Console {
+    pub input: String,
+    history: Vec<Entry>,
+}
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+fn component(name: &str) -> ScriptResult<pxu::Component> {
+    match name.to_lowercase().as_str() {
+        "p" => Ok(pxu::Component::P),
+        "xp" => Ok(pxu::Component::Xp),
+        "xm" => Ok(pxu::Component::Xm),
+        "u" => Ok(pxu::Component::U),
+        "e" => Ok(pxu::Component::E),
+        "x" => Ok(pxu::Component::X),
+        "mirrorp" => Ok(pxu::Component::MirrorP),
+        "mirrore" => Ok(pxu::Component::MirrorE),
+        "torus" => Ok(pxu::Component::Torus),
+        _ => Err(format!(
+            "Unknown plane {name}, expected one of p, xp, xm, u, e, x, mirrorp, mirrore or torus"
+        )
+        .into()),
+    }
+}
+
+fn complex(z: Complex64) -> Array {
+    vec![Dynamic::from_float(z.re), Dynamic::from_float(z.im)]
+}
+
+fn index(pxu: &pxu::Pxu, i: i64) -> ScriptResult<usize> {
+    usize::try_from(i)
+        .ok()
+        .filter(|i| *i < pxu.state.points.len())
+        .ok_or_else(|| format!("There is no excitation {i}").into())
+}
+
+fn build_engine(env: &Rc<RefCell<Env>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let e = env.clone();
+    engine.on_print(move |s| {
+        let output = &mut e.borrow_mut().output;
+        output.push_str(s);
+        output.push('\n');
+    });
+    let e = env.clone();
+    engine.on_debug(move |s, _, pos| {
+        let output = &mut e.borrow_mut().output;
+        output.push_str(&format!("{pos:?}: {s}\n"));
+    });
+
+    let e = env.clone();
+    engine.register_fn("h", move || e.borrow().pxu.consts.h);
+    let e = env.clone();
+    engine.register_fn("k", move || e.borrow().pxu.consts.k() as i64);
+    let e = env.clone();
+    engine.register_fn("m", move || e.borrow().pxu.state.points.len() as i64);
+
+    let e = env.clone();
+    engine.register_fn("get", move |i: i64, name: &str| -> ScriptResult<Array> {
+        let env = e.borrow();
+        let i = index(&env.pxu, i)?;
+        let component = component(name)?;
+        Ok(complex(env.pxu.state.points[i].get(component)))
+    });
+
+    // The excitation is moved in small steps, as when a value is typed in
+    let e = env.clone();
+    engine.register_fn(
+        "set",
+        move |i: i64, name: &str, re: f64, im: f64| -> ScriptResult<()> {
+            const STEPS: usize = 32;
+
+            let mut env = e.borrow_mut();
+            let i = index(&env.pxu, i)?;
+            let component = component(name)?;
+            let pxu = &mut env.pxu;
+            let start = pxu.state.points[i].get(component);
+            let value = Complex64::new(re, im);
+            let mut state = pxu.state.clone();
+            let consts = pxu.kinematic_consts();
+            for step in 1..=STEPS {
+                let z = start + (value - start) * (step as f64 / STEPS as f64);
+                state
+                    .update(i, component, z, &pxu.contours, consts)
+                    .map_err(|err| format!("Could not set {component}: {err}"))?;
+            }
+            pxu.state = state;
+            Ok(())
+        },
+    );
+
+    let e = env.clone();
+    engine.register_fn("reset", move |m: i64| -> ScriptResult<()> {
+        if !(1..=20).contains(&m) {
+            return Err(format!("Can not create a state with {m} excitations").into());
+        }
+        let pxu = &mut e.borrow_mut().pxu;
+        pxu.state = pxu::State::new(m as usize, pxu.kinematic_consts());
+        Ok(())
+    });
+
+    let e = env.clone();
+    engine.register_fn("momentum", move || complex(e.borrow().pxu.state.p()));
+    let e = env.clone();
+    engine.register_fn("energy", move || {
+        let pxu = &e.borrow().pxu;
+        complex(pxu.state.en(pxu.consts))
+    });
+    let e = env.clone();
+    engine.register_fn("sheet", move |i: i64| -> ScriptResult<String> {
+        let env = e.borrow();
+        let i = index(&env.pxu, i)?;
+        Ok(format!("{:?}", env.pxu.state.points[i].sheet_data))
+    });
+    let e = env.clone();
+    engine.register_fn("dump", move || e.borrow().pxu.state.to_mathematica());
+
+    let e = env.clone();
+    engine.register_fn("paths", move || e.borrow().pxu.paths.len() as i64);
+    let e = env.clone();
+    engine.register_fn("path_name", move |i: i64| -> ScriptResult<String> {
+        let env = e.borrow();
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| env.pxu.paths.get(i))
+            .map(|path| path.name.clone())
+            .ok_or_else(|| format!("There is no path {i}").into())
+    });
+    let e = env.clone();
+    engine.register_fn("play", move |i: i64, t: f64| -> ScriptResult<()> {
+        let pxu = &mut e.borrow_mut().pxu;
+        let path = usize::try_from(i)
+            .ok()
+            .and_then(|i| pxu.paths.get(i))
+            .ok_or_else(|| format!("There is no path {i}"))?;
+        pxu.state = path.sample(t.clamp(0.0, 1.0), pxu.consts);
+        Ok(())
+    });
+
+    let e = env.clone();
+    engine.register_fn("set_coupling", move |h: f64, k: i64| -> ScriptResult<()> {
+        if !(0.1..=10.0).contains(&h) || !(0..=10).contains(&k) {
+            return Err(format!("Can not use the coupling constants h = {h}, k = {k}").into());
+        }
+        let consts = CouplingConstants::new(h, k as i32);
+        e.borrow_mut().requests.push(Request::SetCoupling(consts));
+        Ok(())
+    });
+    let e = env.clone();
+    engine.register_fn("sweep_h", move |from: f64, to: f64, frames: i64| {
+        e.borrow_mut().requests.push(Request::SweepH {
+            from: from.clamp(0.1, 10.0),
+            to: to.clamp(0.1, 10.0),
+            frames: frames.clamp(2, 500) as usize,
+        });
+    });
+    let e = env.clone();
+    engine.register_fn("sweep_k", move |from: i64, to: i64| {
+        e.borrow_mut().requests.push(Request::SweepK {
+            from: from.clamp(0, 10) as f64,
+            to: to.clamp(0, 10) as f64,
+        });
+    });
+
+    let e = env.clone();
+    engine.register_fn("set_active", move |i: i64| -> ScriptResult<()> {
+        let mut env = e.borrow_mut();
+        let i = index(&env.pxu, i)?;
+        env.requests.push(Request::SetActive(i));
+        Ok(())
+    });
+    let e = env.clone();
+    engine.register_fn(
+        "view",
+        move |name: &str, re: f64, im: f64, height: f64| -> ScriptResult<()> {
+            let component = component(name)?;
+            e.borrow_mut().requests.push(Request::View {
+                component,
+                origin: Complex64::new(re, im),
+                height: height.max(1.0e-6) as f32,
+            });
+            Ok(())
+        },
+    );
+    let e = env.clone();
+    engine.register_fn("fullscreen", move |name: &str| -> ScriptResult<()> {
+        let component = component(name)?;
+        e.borrow_mut().requests.push(Request::Fullscreen(component));
+        Ok(())
+    });
+
+    engine
+}
+
+impl Console {
+    // Run the input on the given app, and return what else the script asked for.
+    // The state is only changed by the functions that succeeded before any error.
+    pub fn run(&mut self, pxu: &mut pxu::Pxu) -> Vec<Request> {
+        let script = std::mem::take(&mut self.input);
+        let env = Rc::new(RefCell::new(Env {
+            pxu: std::mem::replace(pxu, pxu::Pxu::new(pxu.consts)),
+            requests: vec![],
+            output: String::new(),
+        }));
+
+        let engine = build_engine(&env);
+        let result = engine.eval::<Dynamic>(&script);
+        drop(engine);
+
+        let Ok(env) = Rc::try_unwrap(env) else {
+            unreachable!("The engine has been dropped");
+        };
+        let mut env = env.into_inner();
+        *pxu = env.pxu;
+
+        let failed = match result {
+            Ok(value) if value.is_unit() => false,
+            Ok(value) => {
+                env.output.push_str(&format!("{value}\n"));
+                false
+            }
+            Err(err) => {
+                log::info!("Script failed: {err}");
+                env.output.push_str(&format!("{err}\n"));
+                true
+            }
+        };
+
+        if self.history.len() == MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.history.push(Entry {
+            script,
+            output: env.output,
+            failed,
+        });
+        env.requests
+    }
+
+    // Returns true when the input should be run
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> bool {
+        let mut run = false;
+        egui::Window::new("Script console")
+            .open(open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(250.0)
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for entry in self.history.iter() {
+                            ui.label(
+                                egui::RichText::new(format!("> {}", entry.script.trim()))
+                                    .monospace()
+                                    .weak(),
+                            );
+                            if !entry.output.is_empty() {
+                                let mut text =
+                                    egui::RichText::new(entry.output.trim_end()).monospace();
+                                if entry.failed {
+                                    text = text.color(ui.visuals().error_fg_color);
+                                }
+                                ui.label(text);
+                            }
+                        }
+                    });
+
+                ui.separator();
+                let response = ui.add(
+                    egui::TextEdit::multiline(&mut self.input)
+                        .code_editor()
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY)
+                        .hint_text("set(0, \"xp\", 2.0, 0.5); print(energy());"),
+                );
+                let submitted = response.has_focus()
+                    && ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Enter));
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Run")
+                        .on_hover_text("Run the script (Ctrl+Enter)")
+                        .clicked()
+                        || submitted
+                    {
+                        run = !self.input.trim().is_empty();
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.history.clear();
+                    }
+                });
+            });
+        run
+    }
+}
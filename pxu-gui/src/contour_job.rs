@@ -0,0 +1,248 @@
+use pxu::kinematics::CouplingConstants;
+use pxu::{Contours, KinematicsModel};
+
+use std::sync::mpsc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc,
+};
+
+#[cfg(target_arch = "wasm32")]
+use crate::contour_worker::{ContourRequest, ContourResponse};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+// Generates contours while the UI keeps running. Natively the work is done on a
+// separate thread, while on the web it is done in a web worker, or if that can
+// not be started in chunks that run between frames. Dropping the job cancels it.
+pub struct ContourJob {
+    consts: CouplingConstants,
+    // Only needed when generating in chunks
+    #[cfg(target_arch = "wasm32")]
+    model: KinematicsModel,
+    backend: Backend,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+enum Backend {
+    Thread {
+        progress: Arc<(AtomicUsize, AtomicUsize)>,
+        cancel: Arc<AtomicBool>,
+        rx: mpsc::Receiver<Contours>,
+    },
+}
+
+#[cfg(target_arch = "wasm32")]
+enum Backend {
+    Worker {
+        worker: web_sys::Worker,
+        rx: mpsc::Receiver<ContourResponse>,
+        progress: (usize, usize),
+        partial: Option<Contours>,
+        _onmessage: Closure<dyn FnMut(web_sys::MessageEvent)>,
+    },
+    Chunks {
+        p_range: i32,
+        contours: Contours,
+    },
+}
+
+// The new contours use the same settings as the ones they will replace
+fn empty_like(template: &Contours) -> Contours {
+    let mut contours = Contours::new();
+    contours.set_energy_grid(template.energy_grid());
+    contours.set_cut_visibility(template.cut_visibility().clone());
+    contours
+}
+
+impl ContourJob {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start(
+        ctx: &egui::Context,
+        template: &Contours,
+        p_range: i32,
+        consts: CouplingConstants,
+        model: KinematicsModel,
+    ) -> Self {
+        let mut contours = empty_like(template);
+        let progress = Arc::new((AtomicUsize::new(0), AtomicUsize::new(1)));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = mpsc::channel();
+
+        {
+            let progress = progress.clone();
+            let cancel = cancel.clone();
+            let ctx = ctx.clone();
+            std::thread::spawn(move || {
+                while !contours.update_with_model(p_range, consts, model) {
+                    if cancel.load(Ordering::Relaxed) {
+                        log::debug!("Cancelled contour generation");
+                        return;
+                    }
+                    let (current, total) = contours.progress();
+                    progress.0.store(current, Ordering::Relaxed);
+                    progress.1.store(total, Ordering::Relaxed);
+                }
+                let _ = tx.send(contours);
+                ctx.request_repaint(); // Wake up UI thread
+            });
+        }
+
+        Self {
+            consts,
+            backend: Backend::Thread {
+                progress,
+                cancel,
+                rx,
+            },
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn start(
+        ctx: &egui::Context,
+        template: &Contours,
+        p_range: i32,
+        consts: CouplingConstants,
+        model: KinematicsModel,
+    ) -> Self {
+        let backend = match Self::start_worker(ctx, template, p_range, consts, model) {
+            Ok(backend) => backend,
+            Err(err) => {
+                log::warn!("Could not start contour worker: {err:?}");
+                Backend::Chunks {
+                    p_range,
+                    contours: empty_like(template),
+                }
+            }
+        };
+        Self {
+            consts,
+            model,
+            backend,
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn start_worker(
+        ctx: &egui::Context,
+        template: &Contours,
+        p_range: i32,
+        consts: CouplingConstants,
+        model: KinematicsModel,
+    ) -> Result<Backend, JsValue> {
+        use wasm_bindgen::JsCast;
+
+        let worker = web_sys::Worker::new(crate::contour_worker::WORKER_URL)?;
+        let (tx, rx) = mpsc::channel();
+
+        let ctx = ctx.clone();
+        let onmessage = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(
+            move |event: web_sys::MessageEvent| {
+                if let Some(response) = crate::contour_worker::decode(&event.data()) {
+                    let _ = tx.send(response);
+                    ctx.request_repaint(); // Wake up UI thread
+                }
+            },
+        );
+        worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+        let request = ContourRequest {
+            consts,
+            p_range,
+            model,
+            energy_grid: template.energy_grid(),
+        };
+        let data = crate::contour_worker::encode(&request)
+            .ok_or_else(|| JsValue::from_str("Could not encode request"))?;
+        worker.post_message(&data)?;
+
+        Ok(Backend::Worker {
+            worker,
+            rx,
+            progress: (0, 1),
+            partial: None,
+            _onmessage: onmessage,
+        })
+    }
+
+    pub fn consts(&self) -> CouplingConstants {
+        self.consts
+    }
+
+    pub fn progress(&self) -> (usize, usize) {
+        match &self.backend {
+            #[cfg(not(target_arch = "wasm32"))]
+            Backend::Thread { progress, .. } => (
+                progress.0.load(Ordering::Relaxed),
+                progress.1.load(Ordering::Relaxed),
+            ),
+            #[cfg(target_arch = "wasm32")]
+            Backend::Worker { progress, .. } => *progress,
+            #[cfg(target_arch = "wasm32")]
+            Backend::Chunks { contours, .. } => contours.progress(),
+        }
+    }
+
+    // The contours generated so far, if they have changed since the last call.
+    // These can only be drawn, and must not be updated.
+    pub fn take_partial(&mut self) -> Option<Contours> {
+        match &mut self.backend {
+            #[cfg(target_arch = "wasm32")]
+            Backend::Worker { partial, .. } => partial.take(),
+            _ => None,
+        }
+    }
+
+    // Returns the new contours once they are ready
+    pub fn poll(&mut self) -> Option<Contours> {
+        match &mut self.backend {
+            #[cfg(not(target_arch = "wasm32"))]
+            Backend::Thread { rx, .. } => rx.try_recv().ok(),
+            #[cfg(target_arch = "wasm32")]
+            Backend::Worker {
+                rx,
+                progress,
+                partial,
+                ..
+            } => {
+                for response in rx.try_iter() {
+                    match response {
+                        ContourResponse::Progress(current, total) => *progress = (current, total),
+                        ContourResponse::Partial(contours) => *partial = Some(contours),
+                        ContourResponse::Done(contours) => return Some(contours),
+                    }
+                }
+                None
+            }
+            // Run the generation for a slice of a frame, so that the UI stays
+            // responsive
+            #[cfg(target_arch = "wasm32")]
+            Backend::Chunks { p_range, contours } => {
+                let start = chrono::Utc::now();
+                while (chrono::Utc::now() - start).num_milliseconds()
+                    < (1000.0 / 30.0f64).floor() as i64
+                {
+                    if contours.update_with_model(*p_range, self.consts, self.model) {
+                        return Some(std::mem::take(contours));
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+impl Drop for ContourJob {
+    fn drop(&mut self) {
+        match &self.backend {
+            #[cfg(not(target_arch = "wasm32"))]
+            Backend::Thread { cancel, .. } => cancel.store(true, Ordering::Relaxed),
+            #[cfg(target_arch = "wasm32")]
+            Backend::Worker { worker, .. } => worker.terminate(),
+            #[cfg(target_arch = "wasm32")]
+            Backend::Chunks { .. } => {}
+        }
+    }
+}
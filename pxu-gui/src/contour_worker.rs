@@ -0,0 +1,100 @@
+// Generation of contours in a web worker, so that the canvas stays responsive
+// during the initial computation. This module is shared between the app and the
+// worker binary, which exchange bincode encoded messages. Each of them only
+// uses one half of it.
+#![allow(dead_code)]
+
+use pxu::kinematics::CouplingConstants;
+use pxu::{Contours, KinematicsModel};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+// The loader that trunk creates for the worker binary
+pub const WORKER_URL: &str = "./contour_worker_loader.js";
+
+// How often progress and partial results are sent back, in milliseconds
+const PROGRESS_INTERVAL: f64 = 50.0;
+const PARTIAL_INTERVAL: f64 = 500.0;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ContourRequest {
+    pub consts: CouplingConstants,
+    pub p_range: i32,
+    pub model: KinematicsModel,
+    pub energy_grid: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum ContourResponse {
+    Progress(usize, usize),
+    // The contours generated so far. These can be drawn, but not updated
+    Partial(Contours),
+    Done(Contours),
+}
+
+pub fn encode<T: serde::Serialize>(value: &T) -> Option<js_sys::Uint8Array> {
+    match bincode::serialize(value) {
+        Ok(bytes) => Some(js_sys::Uint8Array::from(&bytes[..])),
+        Err(err) => {
+            log::warn!("Could not serialize message: {err}");
+            None
+        }
+    }
+}
+
+pub fn decode<T: serde::de::DeserializeOwned>(data: &JsValue) -> Option<T> {
+    let bytes = js_sys::Uint8Array::new(data).to_vec();
+    match bincode::deserialize(&bytes) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            log::warn!("Could not deserialize message: {err}");
+            None
+        }
+    }
+}
+
+// Entry point of the worker binary
+pub fn run_worker() {
+    let scope: web_sys::DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let onmessage = Closure::<dyn FnMut(web_sys::MessageEvent)>::new({
+        let scope = scope.clone();
+        move |event: web_sys::MessageEvent| {
+            if let Some(request) = decode::<ContourRequest>(&event.data()) {
+                generate(&scope, request);
+            }
+        }
+    });
+    scope.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+}
+
+fn post(scope: &web_sys::DedicatedWorkerGlobalScope, response: &ContourResponse) {
+    if let Some(data) = encode(response) {
+        if let Err(err) = scope.post_message(&data) {
+            log::warn!("Could not post message: {err:?}");
+        }
+    }
+}
+
+// A new request can only be handled once this returns, so the app cancels a
+// job by terminating the worker
+fn generate(scope: &web_sys::DedicatedWorkerGlobalScope, request: ContourRequest) {
+    let mut contours = Contours::new();
+    contours.set_energy_grid(request.energy_grid);
+
+    let mut last_progress = js_sys::Date::now();
+    let mut last_partial = last_progress;
+    while !contours.update_with_model(request.p_range, request.consts, request.model) {
+        let now = js_sys::Date::now();
+        if now - last_progress > PROGRESS_INTERVAL {
+            let (current, total) = contours.progress();
+            post(scope, &ContourResponse::Progress(current, total));
+            last_progress = now;
+        }
+        if now - last_partial > PARTIAL_INTERVAL {
+            post(scope, &ContourResponse::Partial(contours.clone()));
+            last_partial = now;
+        }
+    }
+    post(scope, &ContourResponse::Done(contours));
+}
@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+
+use pxu::kinematics::{SheetData, UBranch};
+
+const MAX_EVENTS: usize = 500;
+
+// When a change of sheet happened, either as a position along the path that is
+// being played or as the time of a drag
+#[derive(Clone, Copy)]
+pub enum EventTime {
+    Playback(f64),
+    Drag(f64),
+}
+
+impl std::fmt::Display for EventTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Playback(t) => write!(f, "at t={t:.3}"),
+            Self::Drag(time) => write!(f, "while dragging at {time:.1} s"),
+        }
+    }
+}
+
+// The cuts crossed by the excitations while dragging or playing paths, as read
+// off from the changes of their sheet data
+#[derive(Default)]
+pub struct EventLog {
+    events: VecDeque<String>,
+}
+
+impl EventLog {
+    pub fn record(&mut self, excitation: usize, old: &SheetData, new: &SheetData, time: EventTime) {
+        for change in describe_changes(old, new) {
+            if self.events.len() == MAX_EVENTS {
+                self.events.pop_front();
+            }
+            self.events
+                .push_back(format!("#{excitation}: {change} {time}"));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn to_text(&self) -> String {
+        self.events
+            .iter()
+            .map(|event| format!("{event}\n"))
+            .collect()
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical()
+            .max_height(150.0)
+            .stick_to_bottom(true)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                if self.events.is_empty() {
+                    ui.label(egui::RichText::new("No cuts crossed yet").small().weak());
+                }
+                for event in self.events.iter() {
+                    ui.label(egui::RichText::new(event).small().monospace());
+                }
+            });
+    }
+}
+
+// The u cut that separates two regions of the u plane
+fn u_cut(from: &UBranch, to: &UBranch) -> &'static str {
+    match (from, to) {
+        (UBranch::Outside, UBranch::Between) | (UBranch::Between, UBranch::Outside) => {
+            "UShortScallion"
+        }
+        (UBranch::Between, UBranch::Inside) | (UBranch::Inside, UBranch::Between) => "UShortKidney",
+        _ => "the short cuts",
+    }
+}
+
+fn describe_changes(old: &SheetData, new: &SheetData) -> Vec<String> {
+    let mut changes = vec![];
+
+    if old.e_branch != new.e_branch {
+        changes.push(format!(
+            "crossed E, e_branch {:+}→{:+}",
+            old.e_branch, new.e_branch
+        ));
+    }
+
+    if old.q_branch != new.q_branch {
+        changes.push(format!(
+            "crossed QLog, q_branch {:+}→{:+}",
+            old.q_branch, new.q_branch
+        ));
+    }
+
+    for (name, old_log, new_log) in [
+        ("x⁺", old.log_branch_p, new.log_branch_p),
+        ("x⁻", old.log_branch_m, new.log_branch_m),
+    ] {
+        if old_log != new_log {
+            changes.push(format!(
+                "{name} crossed Log, log_branch {old_log:+}→{new_log:+}"
+            ));
+        }
+    }
+
+    for (name, old_u, new_u) in [
+        ("x⁺", &old.u_branch.0, &new.u_branch.0),
+        ("x⁻", &old.u_branch.1, &new.u_branch.1),
+    ] {
+        if old_u != new_u {
+            changes.push(format!(
+                "{name} crossed {}, u_branch {old_u:?}→{new_u:?}",
+                u_cut(old_u, new_u)
+            ));
+        }
+    }
+
+    for (name, old_sign, new_sign) in [
+        ("x⁺", old.im_x_sign.0, new.im_x_sign.0),
+        ("x⁻", old.im_x_sign.1, new.im_x_sign.1),
+    ] {
+        if old_sign != new_sign {
+            changes.push(format!(
+                "{name} crossed ULongPositive, im_x_sign {old_sign:+}→{new_sign:+}"
+            ));
+        }
+    }
+
+    changes
+}
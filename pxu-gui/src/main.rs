@@ -3,8 +3,21 @@
 
 mod app;
 mod arguments;
+mod console;
+mod contour_job;
+#[cfg(target_arch = "wasm32")]
+mod contour_worker;
+mod event_log;
 mod frame_history;
+mod nr_diagnostics;
+mod path_recorder;
+#[cfg(not(target_arch = "wasm32"))]
+mod render;
+mod shortcuts;
+mod singlets;
+mod sweep;
 mod ui_state;
+mod undo_history;
 
 use crate::arguments::Arguments;
 
@@ -19,7 +32,15 @@ fn main() -> Result<(), eframe::Error> {
         .without_time()
         .init();
 
-    let arguments = Arguments::parse();
+    let mut arguments = Arguments::parse();
+
+    if let Some(render) = arguments.render.take() {
+        if let Err(err) = render::run(render) {
+            log::error!("{err}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     let icon_bytes = include_bytes!("../assets/icon-256.png");
     let native_options = eframe::NativeOptions {
@@ -0,0 +1,161 @@
+use egui::{pos2, vec2, Color32, Stroke};
+use pxu::ContinuationStats;
+
+// Residuals below this are drawn at the bottom of the plot
+const MIN_RESIDUAL: f64 = 1.0e-16;
+
+// A window showing how well the root finding converged during the last drag,
+// one step for each time the dragged excitation was moved
+pub fn show(ctx: &egui::Context, open: &mut bool, drag_stats: &[ContinuationStats]) {
+    egui::Window::new("Newton-Raphson diagnostics")
+        .open(open)
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            if drag_stats.is_empty() {
+                ui.label("Drag an excitation to collect data");
+                return;
+            }
+
+            let mut total = ContinuationStats::default();
+            for stats in drag_stats {
+                total.merge(stats);
+            }
+
+            egui::Grid::new("nr_diagnostics_grid")
+                .num_columns(2)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Steps:");
+                    ui.label(format!("{} ({} solves)", drag_stats.len(), total.solves));
+                    ui.end_row();
+
+                    ui.label("Max residual:");
+                    ui.label(format!("{:.2e}", total.max_residual));
+                    ui.end_row();
+
+                    ui.label("Iterations:");
+                    ui.label(format!(
+                        "{:.1} on average, at most {}",
+                        total.mean_iterations(),
+                        total.max_iterations
+                    ));
+                    ui.end_row();
+
+                    ui.label("Rejected steps:");
+                    ui.label(format!("{}", total.rejected_steps));
+                    ui.end_row();
+                });
+
+            ui.add_space(5.0);
+            ui.label("log₁₀ of the residual");
+            let residuals = drag_stats
+                .iter()
+                .map(|stats| stats.max_residual.max(MIN_RESIDUAL).log10())
+                .collect::<Vec<_>>();
+            draw_chart(ui, &residuals, drag_stats);
+
+            ui.label("Iterations");
+            let iterations = drag_stats
+                .iter()
+                .map(|stats| stats.max_iterations as f64)
+                .collect::<Vec<_>>();
+            draw_chart(ui, &iterations, drag_stats);
+
+            ui.add_space(5.0);
+            if ui
+                .button("Copy")
+                .on_hover_text("Copy the data of each step as tab separated text")
+                .clicked()
+            {
+                ui.output_mut(|writer| writer.copied_text = to_text(drag_stats));
+            }
+        });
+}
+
+// A line through the values, with the steps that were rejected marked in red
+fn draw_chart(ui: &mut egui::Ui, values: &[f64], drag_stats: &[ContinuationStats]) {
+    let size = vec2(ui.available_width(), 80.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+
+    painter.rect(
+        rect,
+        0.0,
+        visuals.extreme_bg_color,
+        visuals.widgets.noninteractive.bg_stroke,
+    );
+
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let (min, max) = if max - min < 1.0 {
+        (min - 0.5, max + 0.5)
+    } else {
+        (min, max)
+    };
+
+    let rect = rect.shrink(4.0);
+    let to_screen = |i: usize, value: f64| {
+        let x = if values.len() > 1 {
+            i as f32 / (values.len() - 1) as f32
+        } else {
+            0.5
+        };
+        let y = ((value - min) / (max - min)) as f32;
+        pos2(
+            rect.left() + x * rect.width(),
+            rect.bottom() - y * rect.height(),
+        )
+    };
+
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| to_screen(i, value))
+        .collect::<Vec<_>>();
+    let stroke = Stroke::new(1.0, visuals.text_color());
+    if points.len() > 1 {
+        painter.add(egui::Shape::line(points.clone(), stroke));
+    } else {
+        painter.circle_filled(points[0], 2.0, stroke.color);
+    }
+
+    for (pt, stats) in points.iter().zip(drag_stats) {
+        if stats.rejected_steps > 0 {
+            painter.circle_filled(*pt, 2.5, Color32::RED);
+        }
+    }
+
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let color = ui.visuals().weak_text_color();
+    painter.text(
+        rect.left_top(),
+        egui::Align2::LEFT_TOP,
+        format!("{max:.1}"),
+        font_id.clone(),
+        color,
+    );
+    painter.text(
+        rect.left_bottom(),
+        egui::Align2::LEFT_BOTTOM,
+        format!("{min:.1}"),
+        font_id,
+        color,
+    );
+}
+
+fn to_text(drag_stats: &[ContinuationStats]) -> String {
+    let mut text =
+        String::from("step\tmax residual\tsolves\titerations\tmax iterations\trejected\n");
+    for (i, stats) in drag_stats.iter().enumerate() {
+        text += &format!(
+            "{i}\t{:.3e}\t{}\t{}\t{}\t{}\n",
+            stats.max_residual,
+            stats.solves,
+            stats.iterations,
+            stats.max_iterations,
+            stats.rejected_steps
+        );
+    }
+    text
+}
@@ -0,0 +1,81 @@
+use num::complex::Complex64;
+use pxu::kinematics::CouplingConstants;
+
+const MAX_POINTS: usize = 400;
+
+// Records the trajectory of an excitation while it is being dragged
+pub struct PathRecorder {
+    start: pxu::State,
+    component: pxu::Component,
+    excitation: usize,
+    consts: CouplingConstants,
+    points: Vec<Complex64>,
+}
+
+impl PathRecorder {
+    pub fn new(
+        start: pxu::State,
+        component: pxu::Component,
+        excitation: usize,
+        consts: CouplingConstants,
+    ) -> Self {
+        let points = vec![start.points[excitation].get(component)];
+        Self {
+            start,
+            component,
+            excitation,
+            consts,
+            points,
+        }
+    }
+
+    pub fn push(&mut self, state: &pxu::State) {
+        let z = state.points[self.excitation].get(self.component);
+        if self.points.last() != Some(&z) {
+            self.points.push(z);
+        }
+    }
+
+    // Returns None if the excitation was never moved
+    pub fn finish(self, name: &str) -> Option<pxu::path::SavedPath> {
+        if self.points.len() < 2 {
+            return None;
+        }
+
+        let n = self.points.len().min(MAX_POINTS);
+        Some(pxu::path::SavedPath::new(
+            name,
+            resample(&self.points, n),
+            self.start,
+            self.component,
+            self.excitation,
+            self.consts,
+        ))
+    }
+}
+
+// Redistribute the points of a path evenly along its length, so that how fast
+// the mouse moved does not affect the spacing
+fn resample(path: &[Complex64], n: usize) -> Vec<Complex64> {
+    let mut lengths = vec![0.0];
+    for (z1, z2) in path.iter().zip(path.iter().skip(1)) {
+        lengths.push(lengths.last().unwrap() + (z2 - z1).norm());
+    }
+    let total = *lengths.last().unwrap();
+    if total == 0.0 || n < 2 {
+        return path.to_vec();
+    }
+
+    let mut result = Vec::with_capacity(n);
+    let mut j = 0;
+    for i in 0..n {
+        let s = total * i as f64 / (n - 1) as f64;
+        while j + 2 < lengths.len() && lengths[j + 1] < s {
+            j += 1;
+        }
+        let ds = lengths[j + 1] - lengths[j];
+        let t = if ds > 0.0 { (s - lengths[j]) / ds } else { 0.0 };
+        result.push(path[j] + t.clamp(0.0, 1.0) * (path[j + 1] - path[j]));
+    }
+    result
+}
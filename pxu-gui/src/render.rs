@@ -0,0 +1,96 @@
+use egui::{vec2, Pos2, Rect};
+use pxu::kinematics::CouplingConstants;
+
+use crate::app::{default_plot, parse_state_text};
+use crate::arguments::RenderArguments;
+
+// The width of TikZ figures in cm, as in the export dialog
+const TIKZ_WIDTH: f64 = 6.0;
+
+fn parse_component(name: &str) -> Result<pxu::Component, String> {
+    match name.to_lowercase().as_str() {
+        "p" => Ok(pxu::Component::P),
+        "xp" => Ok(pxu::Component::Xp),
+        "xm" => Ok(pxu::Component::Xm),
+        "u" => Ok(pxu::Component::U),
+        "e" => Ok(pxu::Component::E),
+        "x" => Ok(pxu::Component::X),
+        "mirrorp" => Ok(pxu::Component::MirrorP),
+        "mirrore" => Ok(pxu::Component::MirrorE),
+        "torus" => Ok(pxu::Component::Torus),
+        _ => Err(format!(
+            "Unknown plane {name}, expected one of p, xp, xm, u, e, x, mirrorp, mirrore or torus"
+        )),
+    }
+}
+
+// Draw a plane with the same code as the export dialog
+pub fn run(arguments: RenderArguments) -> Result<(), String> {
+    let component = parse_component(&arguments.component)?;
+    let extension = arguments
+        .out
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase())
+        .unwrap_or_default();
+    if !["png", "svg", "tex"].contains(&extension.as_str()) {
+        return Err(format!(
+            "Can not write {}, the file should end in .png, .svg or .tex",
+            arguments.out.display()
+        ));
+    }
+
+    let text = std::fs::read_to_string(&arguments.state)
+        .map_err(|err| format!("Could not read {}: {err}", arguments.state))?;
+    let saved_state = parse_state_text(&text, CouplingConstants::new(2.0, 5))?;
+
+    let consts = saved_state.consts;
+    let mut pxu = pxu::Pxu::new(consts);
+    pxu.state = saved_state.state;
+    if pxu.state.points.is_empty() {
+        return Err("The state has no excitations".to_owned());
+    }
+
+    let p_range = pxu.state.points[0].p.re.floor() as i32;
+    log::info!(
+        "Generating contours for h = {}, k = {}",
+        consts.h,
+        consts.k()
+    );
+    while !pxu.contours.update(p_range, consts) {}
+
+    let size = vec2(arguments.width, arguments.height);
+    let mut plot = default_plot(component, consts);
+    if arguments.fit {
+        plot.zoom_to_fit(Rect::from_min_size(Pos2::ZERO, size), &pxu);
+    }
+
+    let mut plot_state = plot::PlotState::default();
+    plot_state.point_styles = saved_state.point_styles;
+    let settings = plot::ExportSettings {
+        scale: arguments.scale,
+        ..Default::default()
+    };
+
+    let bytes = match extension.as_str() {
+        "png" => plot::Renderer::default()
+            .to_png(&plot, size, &settings, &pxu, &plot_state)
+            .ok_or_else(|| "Could not render the plot".to_owned())?,
+        "svg" => plot::Renderer::default()
+            .to_svg(&plot, size, &settings, &pxu, &plot_state)
+            .into_bytes(),
+        _ => plot
+            .to_tikz(
+                Rect::from_min_size(Pos2::ZERO, size),
+                TIKZ_WIDTH,
+                &pxu,
+                &plot_state,
+            )
+            .into_bytes(),
+    };
+
+    std::fs::write(&arguments.out, bytes)
+        .map_err(|err| format!("Could not write {}: {err}", arguments.out.display()))?;
+    log::info!("Saved {}", arguments.out.display());
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Action {
+    NextComponent,
+    PreviousComponent,
+    NextExcitation,
+    PreviousExcitation,
+    ToggleCuts,
+    ToggleGrid,
+    ToggleSnap,
+    ZoomToFit,
+    TogglePresentation,
+    ToggleMeasure,
+    ToggleScreenshot,
+    FocusNextPlane,
+    FocusPreviousPlane,
+}
+
+impl Action {
+    pub const ALL: [Self; 13] = [
+        Self::NextComponent,
+        Self::PreviousComponent,
+        Self::NextExcitation,
+        Self::PreviousExcitation,
+        Self::ToggleCuts,
+        Self::ToggleGrid,
+        Self::ToggleSnap,
+        Self::ZoomToFit,
+        Self::TogglePresentation,
+        Self::ToggleMeasure,
+        Self::ToggleScreenshot,
+        Self::FocusNextPlane,
+        Self::FocusPreviousPlane,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::NextComponent => "Show next plane",
+            Self::PreviousComponent => "Show previous plane",
+            Self::NextExcitation => "Next excitation",
+            Self::PreviousExcitation => "Previous excitation",
+            Self::ToggleCuts => "Show/hide cuts",
+            Self::ToggleGrid => "Show/hide grid",
+            Self::ToggleSnap => "Turn snapping on/off",
+            Self::ZoomToFit => "Zoom to fit state",
+            Self::TogglePresentation => "Presentation mode on/off",
+            Self::ToggleMeasure => "Measurement tool on/off",
+            Self::ToggleScreenshot => "Screenshot mode on/off",
+            Self::FocusNextPlane => "Focus next plane",
+            Self::FocusPreviousPlane => "Focus previous plane",
+        }
+    }
+
+    fn default_shortcut(&self) -> KeyboardShortcut {
+        match self {
+            Self::NextComponent => KeyboardShortcut::new(Modifiers::NONE, Key::C),
+            Self::PreviousComponent => KeyboardShortcut::new(Modifiers::SHIFT, Key::C),
+            Self::NextExcitation => KeyboardShortcut::new(Modifiers::NONE, Key::N),
+            Self::PreviousExcitation => KeyboardShortcut::new(Modifiers::SHIFT, Key::N),
+            Self::ToggleCuts => KeyboardShortcut::new(Modifiers::NONE, Key::X),
+            Self::ToggleGrid => KeyboardShortcut::new(Modifiers::NONE, Key::G),
+            Self::ToggleSnap => KeyboardShortcut::new(Modifiers::NONE, Key::S),
+            Self::ZoomToFit => KeyboardShortcut::new(Modifiers::NONE, Key::F),
+            Self::TogglePresentation => KeyboardShortcut::new(Modifiers::NONE, Key::P),
+            Self::ToggleMeasure => KeyboardShortcut::new(Modifiers::NONE, Key::M),
+            Self::ToggleScreenshot => KeyboardShortcut::new(Modifiers::SHIFT, Key::P),
+            Self::FocusNextPlane => KeyboardShortcut::new(Modifiers::NONE, Key::V),
+            Self::FocusPreviousPlane => KeyboardShortcut::new(Modifiers::SHIFT, Key::V),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Shortcuts {
+    bindings: Vec<(Action, KeyboardShortcut)>,
+}
+
+impl Default for Shortcuts {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_shortcut()))
+                .collect(),
+        }
+    }
+}
+
+impl Shortcuts {
+    pub fn get(&self, action: Action) -> KeyboardShortcut {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map_or_else(|| action.default_shortcut(), |(_, shortcut)| *shortcut)
+    }
+
+    pub fn set(&mut self, action: Action, shortcut: KeyboardShortcut) {
+        self.bindings.retain(|(a, _)| *a != action);
+        self.bindings.push((action, shortcut));
+    }
+
+    // Extra modifiers are ignored when matching shortcuts, so the shortcuts with
+    // the most modifiers have to be checked first
+    pub fn pressed(&self, input: &mut egui::InputState) -> Vec<Action> {
+        let mut actions = Action::ALL
+            .iter()
+            .map(|action| (*action, self.get(*action)))
+            .collect::<Vec<_>>();
+        actions.sort_by_key(|(_, shortcut)| {
+            let m = shortcut.modifiers;
+            std::cmp::Reverse(
+                [m.alt, m.ctrl, m.shift, m.command]
+                    .iter()
+                    .filter(|b| **b)
+                    .count(),
+            )
+        });
+
+        actions
+            .into_iter()
+            .filter(|(_, shortcut)| input.consume_shortcut(shortcut))
+            .map(|(action, _)| action)
+            .collect()
+    }
+}
@@ -0,0 +1,106 @@
+use pxu::kinematics::CouplingConstants;
+use pxu::{Contours, KinematicsModel};
+
+use crate::contour_job::ContourJob;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SweepParameter {
+    H,
+    K,
+}
+
+// Steps one of the coupling constants through a range of values, one frame at
+// a time. The contours of each frame are generated in the background, and the
+// next frame is only started once they are ready.
+pub struct Sweep {
+    pub parameter: SweepParameter,
+    pub from: f64,
+    pub to: f64,
+    pub frames: usize,
+    pub carry_state: bool,
+    pub export_frames: bool,
+    frame: Option<usize>,
+    job: Option<ContourJob>,
+}
+
+impl Default for Sweep {
+    fn default() -> Self {
+        Self {
+            parameter: SweepParameter::H,
+            from: 0.5,
+            to: 4.0,
+            frames: 40,
+            carry_state: true,
+            export_frames: false,
+            frame: None,
+            job: None,
+        }
+    }
+}
+
+impl Sweep {
+    // There is one frame for each integer value of k
+    pub fn frame_count(&self) -> usize {
+        match self.parameter {
+            SweepParameter::H => self.frames.max(2),
+            SweepParameter::K => (self.to - self.from).abs().round() as usize + 1,
+        }
+    }
+
+    // The value of h goes through the range geometrically, as on the slider
+    pub fn consts(&self, frame: usize, base: CouplingConstants) -> CouplingConstants {
+        let t = frame as f64 / (self.frame_count() - 1).max(1) as f64;
+        let mut consts = base;
+        match self.parameter {
+            SweepParameter::H => consts.h = self.from * (self.to / self.from).powf(t),
+            SweepParameter::K => {
+                consts.get_set_k(Some(self.from + t * (self.to - self.from)));
+            }
+        }
+        consts
+    }
+
+    pub fn frame(&self) -> Option<usize> {
+        self.frame
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.frame.is_some()
+    }
+
+    pub fn start(&mut self) {
+        self.frame = Some(0);
+        self.job = None;
+    }
+
+    pub fn stop(&mut self) {
+        self.frame = None;
+        self.job = None;
+    }
+
+    // Start generating the contours for the current frame if needed, and
+    // return them once they are ready
+    pub fn poll(
+        &mut self,
+        ctx: &egui::Context,
+        template: &Contours,
+        p_range: i32,
+        base: CouplingConstants,
+        model: KinematicsModel,
+    ) -> Option<(CouplingConstants, Contours)> {
+        let consts = self.consts(self.frame?, base);
+        let job = self
+            .job
+            .get_or_insert_with(|| ContourJob::start(ctx, template, p_range, consts, model));
+        let contours = job.poll()?;
+        self.job = None;
+        Some((consts, contours))
+    }
+
+    pub fn advance(&mut self) {
+        self.frame = self
+            .frame
+            .map(|frame| frame + 1)
+            .filter(|frame| *frame < self.frame_count());
+    }
+}
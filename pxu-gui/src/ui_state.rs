@@ -1,6 +1,7 @@
 use crate::arguments::Arguments;
+use crate::shortcuts::{Action, Shortcuts};
 
-#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct UiState {
     pub plot_state: plot::PlotState,
     #[serde(skip)]
@@ -12,11 +13,302 @@ pub struct UiState {
     #[serde(skip)]
     pub continuous_mode: bool,
     #[serde(skip)]
+    pub extra_plots: Vec<pxu::Component>,
+    #[serde(default)]
+    pub pane_layout: Option<PaneLayout>,
+    #[serde(skip)]
     pub saved_paths_to_load: Option<Vec<pxu::path::SavedPath>>,
     #[serde(skip)]
     pub path_load_progress: Option<(usize, usize)>,
     #[serde(skip)]
     pub inital_saved_state: Option<pxu::SavedState>,
+    #[serde(skip)]
+    pub initial_view: Option<SharedView>,
+    #[serde(default)]
+    pub shortcuts: Shortcuts,
+    #[serde(skip)]
+    pub show_shortcuts: bool,
+    #[serde(skip)]
+    pub show_nr_diagnostics: bool,
+    #[serde(skip)]
+    pub show_console: bool,
+    #[serde(skip)]
+    pub rebinding: Option<Action>,
+    #[serde(skip)]
+    pub hidden_cut_filter: Option<plot::CutFilter>,
+    #[serde(default)]
+    pub visual_theme: VisualTheme,
+    #[serde(default)]
+    pub accent: Option<egui::Color32>,
+    #[serde(default)]
+    pub view_presets: Vec<ViewPreset>,
+    #[serde(default)]
+    pub bookmarks: Vec<StateBookmark>,
+    #[serde(default)]
+    pub export_settings: plot::ExportSettings,
+    #[serde(default)]
+    pub power_saving: bool,
+    #[serde(default)]
+    pub screenshot_frame: bool,
+    // The step of the arrow keys, as a fraction of the height of the view
+    #[serde(default = "default_nudge_step")]
+    pub nudge_step: f64,
+}
+
+fn default_nudge_step() -> f64 {
+    0.01
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            plot_state: Default::default(),
+            hide_side_panel: Default::default(),
+            show_fps: Default::default(),
+            show_dev: Default::default(),
+            continuous_mode: Default::default(),
+            extra_plots: Default::default(),
+            pane_layout: Default::default(),
+            saved_paths_to_load: Default::default(),
+            path_load_progress: Default::default(),
+            inital_saved_state: Default::default(),
+            initial_view: Default::default(),
+            shortcuts: Default::default(),
+            show_shortcuts: Default::default(),
+            show_nr_diagnostics: Default::default(),
+            show_console: Default::default(),
+            rebinding: Default::default(),
+            hidden_cut_filter: Default::default(),
+            visual_theme: Default::default(),
+            accent: Default::default(),
+            view_presets: Default::default(),
+            bookmarks: Default::default(),
+            export_settings: Default::default(),
+            power_saving: Default::default(),
+            screenshot_frame: Default::default(),
+            nudge_step: default_nudge_step(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum VisualTheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl VisualTheme {
+    pub fn visuals(&self, accent: Option<egui::Color32>) -> egui::Visuals {
+        let mut visuals = match self {
+            Self::Light => egui::Visuals::light(),
+            Self::Dark => egui::Visuals::dark(),
+        };
+        if let Some(accent) = accent {
+            visuals.selection.bg_fill = accent;
+            visuals.hyperlink_color = accent;
+        }
+        visuals
+    }
+
+    pub fn palette(&self, accent: Option<egui::Color32>) -> plot::Palette {
+        let palette = match self {
+            Self::Light => plot::Palette::LIGHT,
+            Self::Dark => plot::Palette::DARK,
+        };
+        match accent {
+            Some(accent) => palette.with_accent(accent),
+            None => palette,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+pub struct PlotView {
+    pub component: pxu::Component,
+    pub height: f32,
+    pub origin: egui::Pos2,
+    #[serde(default = "default_width_factor")]
+    pub width_factor: f32,
+}
+
+fn default_width_factor() -> f32 {
+    1.0
+}
+
+// A user defined arrangement of the planes. The panes are filled in row by row,
+// and the panes of the last row share its full width.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PaneLayout {
+    pub panes: Vec<pxu::Component>,
+    pub columns: usize,
+}
+
+impl PaneLayout {
+    pub const MAX_PANES: usize = 6;
+
+    pub fn rows(&self) -> Vec<Vec<pxu::Component>> {
+        self.panes
+            .chunks(self.columns.max(1))
+            .map(|row| row.to_vec())
+            .collect()
+    }
+
+    // Panes are added and removed at the end, and new panes show a plane that
+    // is not shown yet if there is one
+    pub fn set_pane_count(&mut self, count: usize) {
+        use pxu::Component::*;
+
+        let count = count.clamp(1, Self::MAX_PANES);
+        self.panes.truncate(count);
+        while self.panes.len() < count {
+            let component = [P, U, Xp, Xm, E]
+                .into_iter()
+                .find(|c| !self.panes.contains(c))
+                .unwrap_or(P);
+            self.panes.push(component);
+        }
+        self.columns = self.columns.clamp(1, count);
+    }
+}
+
+// A named state with its coupling constants, kept in the app
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct StateBookmark {
+    pub name: String,
+    pub saved_state: pxu::SavedState,
+}
+
+// A named center and zoom for one of the planes
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ViewPreset {
+    pub name: String,
+    pub view: PlotView,
+}
+
+// Everything needed to show someone else exactly the same configuration. This
+// is shared as compressed base64 in the fragment of the url.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SharedView {
+    pub saved_state: pxu::SavedState,
+    pub active_point: usize,
+    pub ux_mode: bool,
+    // Kept for links made before other extra plots could be shown
+    #[serde(default)]
+    pub show_energy_plane: bool,
+    #[serde(default)]
+    pub extra_plots: Vec<pxu::Component>,
+    pub fullscreen_component: Option<pxu::Component>,
+    pub plots: Vec<PlotView>,
+}
+
+impl SharedView {
+    pub fn encode(&self) -> Option<String> {
+        use base64::Engine;
+        use std::io::Write;
+
+        let s = ron::to_string(self).ok()?;
+        let mut enc = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+        enc.write_all(s.as_bytes()).ok()?;
+        let data = enc.finish().ok()?;
+        Some(base64::engine::general_purpose::URL_SAFE.encode(data))
+    }
+
+    pub fn decode(input: &str) -> Option<Self> {
+        use base64::Engine;
+        use std::io::Write;
+
+        let data = base64::engine::general_purpose::URL_SAFE
+            .decode(input.trim())
+            .ok()?;
+        let mut dec = flate2::write::DeflateDecoder::new(Vec::new());
+        dec.write_all(&data[..]).ok()?;
+        let s = String::from_utf8(dec.finish().ok()?).ok()?;
+
+        let mut view = ron::from_str::<Self>(&s).ok()?;
+        view.saved_state = view.saved_state.migrate()?;
+        Some(view)
+    }
+}
+
+// The session is saved automatically together with the settings, and the
+// next launch offers to restore it. Besides the view it includes the loaded
+// and recorded paths and the pinned states.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct Session {
+    pub view: SharedView,
+    #[serde(default)]
+    pub paths: Vec<pxu::Path>,
+    #[serde(default)]
+    pub path_indices: Vec<usize>,
+    #[serde(default)]
+    pub overlays: Vec<plot::Overlay>,
+    #[serde(default)]
+    pub recorded_paths: Vec<pxu::path::SavedPath>,
+}
+
+impl Session {
+    const KEY: &'static str = "session";
+
+    // Loaded paths can be large, while the local storage of a browser is
+    // limited to a few megabytes
+    const MAX_SIZE: usize = 1 << 20;
+
+    pub fn store(&self, storage: &mut dyn eframe::Storage) {
+        let mut s = match ron::to_string(self) {
+            Ok(s) => s,
+            Err(err) => {
+                log::warn!("Could not serialize session: {err}");
+                return;
+            }
+        };
+        if s.len() > Self::MAX_SIZE {
+            log::info!("Session is too large, saving it without the loaded paths");
+            let session = Self {
+                paths: vec![],
+                path_indices: vec![],
+                ..self.clone()
+            };
+            let Ok(smaller) = ron::to_string(&session) else {
+                return;
+            };
+            s = smaller;
+        }
+        storage.set_string(Self::KEY, s);
+    }
+
+    pub fn load(storage: &dyn eframe::Storage) -> Option<Self> {
+        let s = storage.get_string(Self::KEY)?;
+        let mut session = match ron::from_str::<Self>(&s) {
+            Ok(session) => session,
+            Err(err) => {
+                log::warn!("Could not read saved session: {err}");
+                return None;
+            }
+        };
+        session.view.saved_state = session.view.saved_state.migrate()?;
+        Some(session)
+    }
+
+    pub fn summary(&self) -> String {
+        let saved_state = &self.view.saved_state;
+        let mut summary = format!(
+            "h = {}, k = {}, M = {}",
+            saved_state.consts.h,
+            saved_state.consts.k(),
+            saved_state.state.points.len()
+        );
+        for (count, what) in [
+            (self.paths.len(), "loaded paths"),
+            (self.recorded_paths.len(), "recorded paths"),
+            (self.overlays.len(), "pinned states"),
+        ] {
+            if count > 0 {
+                summary += &format!(", {count} {what}");
+            }
+        }
+        summary
+    }
 }
 
 impl UiState {
@@ -37,5 +329,12 @@ impl UiState {
         if let Some(ref s) = arguments.state {
             self.inital_saved_state = pxu::SavedState::decode(s);
         }
+
+        if let Some(ref s) = arguments.view {
+            self.initial_view = SharedView::decode(s);
+            if self.initial_view.is_none() {
+                log::warn!("Could not decode shared view");
+            }
+        }
     }
 }
@@ -0,0 +1,65 @@
+use pxu::kinematics::CouplingConstants;
+
+const MAX_LEN: usize = 100;
+
+// Snapshots of the state taken whenever it has settled after a change, so that
+// a drag across a cut counts as a single step
+#[derive(Default)]
+pub struct UndoHistory {
+    undo: Vec<pxu::State>,
+    redo: Vec<pxu::State>,
+    current: Option<(pxu::State, CouplingConstants)>,
+}
+
+// The continuation statistics change on every update, so only compare what the
+// user can see
+fn same_state(a: &pxu::State, b: &pxu::State) -> bool {
+    a.points == b.points && a.unlocked == b.unlocked && a.constraint == b.constraint
+}
+
+impl UndoHistory {
+    // Called once per frame when the state is not being dragged
+    pub fn update(&mut self, state: &pxu::State, consts: CouplingConstants) {
+        if let Some((current, current_consts)) = self.current.take() {
+            if current_consts != consts {
+                // States for different coupling constants can not be restored
+                self.undo.clear();
+                self.redo.clear();
+            } else if same_state(&current, state) {
+                self.current = Some((current, current_consts));
+                return;
+            } else {
+                self.undo.push(current);
+                if self.undo.len() > MAX_LEN {
+                    self.undo.remove(0);
+                }
+                self.redo.clear();
+            }
+        }
+        self.current = Some((state.clone(), consts));
+    }
+
+    pub fn undo(&mut self) -> Option<pxu::State> {
+        let state = self.undo.pop()?;
+        let (current, consts) = self.current.take()?;
+        self.redo.push(current);
+        self.current = Some((state.clone(), consts));
+        Some(state)
+    }
+
+    pub fn redo(&mut self) -> Option<pxu::State> {
+        let state = self.redo.pop()?;
+        let (current, consts) = self.current.take()?;
+        self.undo.push(current);
+        self.current = Some((state.clone(), consts));
+        Some(state)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
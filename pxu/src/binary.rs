@@ -0,0 +1,99 @@
+// Compact binary format for paths, figures and contours. The data is encoded
+// with bincode and compressed with zstd, and is preceded by a short header so
+// that it can be told apart from RON and JSON text.
+
+use std::io::Read;
+
+const MAGIC: &[u8; 4] = b"PXU\0";
+const FORMAT_VERSION: u8 = 1;
+
+#[cfg(not(target_arch = "wasm32"))]
+const COMPRESSION_LEVEL: i32 = 19;
+
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+// Only the native tools write the binary format, the wasm app just reads it
+#[cfg(not(target_arch = "wasm32"))]
+pub fn encode<T: serde::Serialize>(value: &T) -> Option<Vec<u8>> {
+    let data = match bincode::serialize(value) {
+        Ok(data) => data,
+        Err(err) => {
+            log::warn!("Could not serialize: {err}");
+            return None;
+        }
+    };
+    let compressed = match zstd::encode_all(&data[..], COMPRESSION_LEVEL) {
+        Ok(compressed) => compressed,
+        Err(err) => {
+            log::warn!("Could not compress: {err}");
+            return None;
+        }
+    };
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(FORMAT_VERSION);
+    bytes.extend(compressed);
+    Some(bytes)
+}
+
+pub fn decode<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    let Some(bytes) = bytes.strip_prefix(MAGIC) else {
+        log::warn!("Missing binary header");
+        return None;
+    };
+    let Some((&version, compressed)) = bytes.split_first() else {
+        log::warn!("Missing binary format version");
+        return None;
+    };
+    if version != FORMAT_VERSION {
+        log::warn!("Unknown binary format version {version}");
+        return None;
+    }
+
+    let mut input = compressed;
+    let mut decoder = match ruzstd::StreamingDecoder::new(&mut input) {
+        Ok(decoder) => decoder,
+        Err(err) => {
+            log::warn!("Could not decompress: {err}");
+            return None;
+        }
+    };
+    let mut data = vec![];
+    if let Err(err) = decoder.read_to_end(&mut data) {
+        log::warn!("Could not decompress: {err}");
+        return None;
+    }
+
+    match bincode::deserialize(&data) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            log::warn!("Could not deserialize: {err}");
+            None
+        }
+    }
+}
+
+// Load data in either the binary format or as RON or JSON text
+pub fn load<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    if is_binary(bytes) {
+        return decode(bytes);
+    }
+
+    let Ok(input) = std::str::from_utf8(bytes) else {
+        log::warn!("Data is neither binary nor text");
+        return None;
+    };
+    let input = input.trim();
+
+    if let Ok(value) = ron::from_str(input) {
+        return Some(value);
+    }
+    log::info!("Could not decode RON, trying JSON");
+    if let Ok(value) = serde_json::from_str(input) {
+        return Some(value);
+    }
+    log::warn!("Could not decode JSON");
+    None
+}
@@ -0,0 +1,378 @@
+// Output in a form that can be pasted into Mathematica or Maple
+
+use num::complex::Complex64;
+
+use crate::kinematics::SheetData;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Syntax {
+    Mathematica,
+    Maple,
+}
+
+impl Syntax {
+    // Rust never uses exponential notation when printing floats, so the numbers
+    // can be read by both Mathematica and Maple
+    pub(crate) fn complex(self, z: Complex64) -> String {
+        let sign = if z.im.is_sign_negative() { '-' } else { '+' };
+        let im = z.im.abs();
+        match self {
+            Self::Mathematica => format!("{} {sign} {im} I", z.re),
+            Self::Maple => format!("{}{sign}{im}*I", z.re),
+        }
+    }
+
+    pub(crate) fn list(self, items: impl IntoIterator<Item = String>) -> String {
+        let items = items.into_iter().collect::<Vec<_>>().join(", ");
+        match self {
+            Self::Mathematica => format!("{{{items}}}"),
+            Self::Maple => format!("[{items}]"),
+        }
+    }
+
+    // A list with one item per line
+    pub(crate) fn long_list(self, items: impl IntoIterator<Item = String>) -> String {
+        let items = items.into_iter().collect::<Vec<_>>();
+        if items.is_empty() {
+            return self.list(items);
+        }
+        let items = items
+            .iter()
+            .map(|item| item.replace('\n', "\n  "))
+            .collect::<Vec<_>>()
+            .join(",\n  ");
+        match self {
+            Self::Mathematica => format!("{{\n  {items}\n}}"),
+            Self::Maple => format!("[\n  {items}\n]"),
+        }
+    }
+
+    pub(crate) fn rule(self, name: &str, value: String) -> String {
+        match self {
+            Self::Mathematica => format!("{name} -> {value}"),
+            Self::Maple => format!("{name} = {value}"),
+        }
+    }
+
+    pub(crate) fn complex_list(self, values: &[Complex64]) -> String {
+        self.list(values.iter().map(|&z| self.complex(z)))
+    }
+
+    pub(crate) fn sheet_data_rules(self, sheet_data: &SheetData) -> Vec<String> {
+        vec![
+            self.rule(
+                "logBranch",
+                self.list([
+                    sheet_data.log_branch_p.to_string(),
+                    sheet_data.log_branch_m.to_string(),
+                ]),
+            ),
+            self.rule("eBranch", sheet_data.e_branch.to_string()),
+            self.rule(
+                "uBranch",
+                self.list([
+                    format!("\"{}\"", sheet_data.u_branch.0),
+                    format!("\"{}\"", sheet_data.u_branch.1),
+                ]),
+            ),
+            self.rule(
+                "imXSign",
+                self.list([
+                    sheet_data.im_x_sign.0.to_string(),
+                    sheet_data.im_x_sign.1.to_string(),
+                ]),
+            ),
+        ]
+    }
+}
+
+// A parsed value. Lists may contain both rules, such as p -> 0.1, and plain
+// values.
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    Number(Complex64),
+    String(String),
+    List(Vec<(Option<String>, Value)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    String(String),
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    OpenParen,
+    CloseParen,
+    Comma,
+    Arrow,
+    Plus,
+    Minus,
+    Star,
+}
+
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let token = match c {
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '{' => Token::Open,
+            '}' => Token::Close,
+            '<' if next == Some('|') => {
+                i += 1;
+                Token::Open
+            }
+            '|' if next == Some('>') => {
+                i += 1;
+                Token::Close
+            }
+            '[' => Token::OpenBracket,
+            ']' => Token::CloseBracket,
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            ',' => Token::Comma,
+            '+' => Token::Plus,
+            '*' => Token::Star,
+            '-' if next == Some('>') => {
+                i += 1;
+                Token::Arrow
+            }
+            ':' if next == Some('>') => {
+                i += 1;
+                Token::Arrow
+            }
+            '=' | ':' => Token::Arrow,
+            '-' => Token::Minus,
+            '"' => {
+                let start = i + 1;
+                let len = chars[start..].iter().position(|&c| c == '"')?;
+                i = start + len;
+                Token::String(chars[start..i].iter().collect())
+            }
+            _ if c.is_ascii_digit() || (c == '.' && next.is_some_and(|c| c.is_ascii_digit())) => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let mut number = chars[start..i].iter().collect::<String>();
+
+                // Skip Mathematica precision and accuracy marks such as 1.5`20.
+                while i < chars.len() && chars[i] == '`' {
+                    i += 1;
+                    while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                        i += 1;
+                    }
+                }
+
+                // Exponents are written as 1.5e-3, 1.5E-3 or 1.5*^-3
+                let exponent_start = match (chars.get(i), chars.get(i + 1)) {
+                    (Some('e' | 'E'), Some(c)) if c.is_ascii_digit() || *c == '-' || *c == '+' => {
+                        Some(i + 1)
+                    }
+                    (Some('*'), Some('^')) => Some(i + 2),
+                    _ => None,
+                };
+                if let Some(mut j) = exponent_start {
+                    let digits_start = j;
+                    if matches!(chars.get(j), Some('-' | '+')) {
+                        j += 1;
+                    }
+                    while j < chars.len() && chars[j].is_ascii_digit() {
+                        j += 1;
+                    }
+                    number.push('e');
+                    number.extend(&chars[digits_start..j]);
+                    i = j;
+                }
+
+                let Ok(number) = number.parse::<f64>() else {
+                    log::warn!("Could not parse number {number}");
+                    return None;
+                };
+                tokens.push(Token::Number(number));
+                continue;
+            }
+            _ if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+                continue;
+            }
+            _ => {
+                log::warn!("Unexpected character '{c}'");
+                return None;
+            }
+        };
+        tokens.push(token);
+        i += 1;
+    }
+
+    Some(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, token: Token) -> Option<()> {
+        if self.next().as_ref() == Some(&token) {
+            Some(())
+        } else {
+            log::warn!("Expected {token:?}");
+            None
+        }
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        match self.peek()? {
+            Token::Open => {
+                self.next();
+                self.list(Token::Close)
+            }
+            Token::OpenBracket => {
+                self.next();
+                self.list(Token::CloseBracket)
+            }
+            Token::String(s) => {
+                let s = s.clone();
+                self.next();
+                Some(Value::String(s))
+            }
+            _ => self.sum().map(Value::Number),
+        }
+    }
+
+    fn list(&mut self, close: Token) -> Option<Value> {
+        let mut items = vec![];
+        if self.peek() == Some(&close) {
+            self.next();
+            return Some(Value::List(items));
+        }
+        loop {
+            let name = match (self.peek(), self.tokens.get(self.pos + 1)) {
+                (Some(Token::Ident(name)), Some(Token::Arrow))
+                | (Some(Token::String(name)), Some(Token::Arrow)) => {
+                    let name = name.clone();
+                    self.pos += 2;
+                    Some(name)
+                }
+                _ => None,
+            };
+            items.push((name, self.value()?));
+
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(token) if token == close => return Some(Value::List(items)),
+                token => {
+                    log::warn!("Unexpected {token:?} in list");
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn sum(&mut self) -> Option<Complex64> {
+        let mut sum = self.product()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.next();
+                    sum += self.product()?;
+                }
+                Some(Token::Minus) => {
+                    self.next();
+                    sum -= self.product()?;
+                }
+                _ => return Some(sum),
+            }
+        }
+    }
+
+    fn product(&mut self) -> Option<Complex64> {
+        let mut product = self.factor()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.next();
+                }
+                // Implicit multiplication, as in 0.5 I or 0.5i
+                Some(Token::Number(_) | Token::Ident(_) | Token::OpenParen) => {}
+                _ => return Some(product),
+            }
+            product *= self.factor()?;
+        }
+    }
+
+    fn factor(&mut self) -> Option<Complex64> {
+        match self.next() {
+            Some(Token::Number(x)) => Some(x.into()),
+            Some(Token::Plus) => self.factor(),
+            Some(Token::Minus) => self.factor().map(|z| -z),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "I" | "i" | "j" => Some(Complex64::i()),
+                "Complex" => {
+                    self.expect(Token::OpenBracket)?;
+                    let re = self.sum()?;
+                    self.expect(Token::Comma)?;
+                    let im = self.sum()?;
+                    self.expect(Token::CloseBracket)?;
+                    Some(re + Complex64::i() * im)
+                }
+                _ => {
+                    log::warn!("Unknown symbol {name}");
+                    None
+                }
+            },
+            Some(Token::OpenParen) => {
+                let value = self.sum()?;
+                self.expect(Token::CloseParen)?;
+                Some(value)
+            }
+            token => {
+                log::warn!("Expected a number, found {token:?}");
+                None
+            }
+        }
+    }
+}
+
+// Parse a Mathematica or Maple style expression made up of lists, rules and
+// complex numbers
+pub(crate) fn parse(input: &str) -> Option<Value> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+    };
+    let value = parser.value()?;
+    if let Some(token) = parser.peek() {
+        log::warn!("Unexpected {token:?} after the end of the input");
+        return None;
+    }
+    Some(value)
+}
@@ -1,16 +1,26 @@
 use std::collections::VecDeque;
 
-use crate::cut::{Cut, CutType, CutVisibilityCondition};
+use crate::cut::{Cut, CutType, CutVisibility, CutVisibilityCheck, CutVisibilityCondition};
 use crate::interpolation::{EPInterpolator, InterpolationPoint, PInterpolatorMut, XInterpolator};
-use crate::kinematics::{xp, CouplingConstants, UBranch};
+use crate::kinematics::{
+    en, kidney, model_den_dp, model_en, scallion, torus_z, torus_z_continued, torus_z_reflected,
+    xp, CouplingConstants, KinematicsModel, UBranch,
+};
 use crate::Pxu;
 use crate::{nr, Point};
 use itertools::Itertools;
 use num::complex::Complex64;
+use std::f64::consts::{PI, TAU};
 
 const P_RANGE_MIN: i32 = -3;
 const P_RANGE_MAX: i32 = 3;
 
+// Grid lines in the p plane that lie entirely outside this region are far
+// outside what is plotted, and are not generated
+const P_GRID_RE_MIN: f64 = P_RANGE_MIN as f64 - 0.5;
+const P_GRID_RE_MAX: f64 = P_RANGE_MAX as f64 + 1.5;
+const P_GRID_IM_MAX: f64 = 1.0;
+
 const INFINITY: f64 = 150.0;
 
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -19,6 +29,14 @@ pub enum Component {
     Xp,
     Xm,
     U,
+    E,
+    // The single x variable, with x± = x(u ± i/h)
+    X,
+    // The momentum and energy of the mirror theory, p̃ = -iE/2π and Ẽ = -2πip
+    MirrorP,
+    MirrorE,
+    // The coordinate z = ∫ dp / E on the torus of the (p, E) curve
+    Torus,
 }
 
 impl Component {
@@ -28,6 +46,11 @@ impl Component {
             Self::Xp => Self::Xm,
             Self::Xm => Self::Xp,
             Self::U => Self::U,
+            Self::E => Self::E,
+            Self::X => Self::X,
+            Self::MirrorP => Self::MirrorP,
+            Self::MirrorE => Self::MirrorE,
+            Self::Torus => Self::Torus,
         }
     }
 }
@@ -40,6 +63,11 @@ impl std::str::FromStr for Component {
             "Xp" => Ok(Self::Xp),
             "Xm" => Ok(Self::Xm),
             "U" => Ok(Self::U),
+            "E" => Ok(Self::E),
+            "X" => Ok(Self::X),
+            "MirrorP" => Ok(Self::MirrorP),
+            "MirrorE" => Ok(Self::MirrorE),
+            "Torus" => Ok(Self::Torus),
             _ => Err("Unexpected component".to_owned()),
         }
     }
@@ -55,6 +83,11 @@ impl std::fmt::Display for Component {
                 Self::Xp => "Xp",
                 Self::Xm => "Xm",
                 Self::U => "U",
+                Self::E => "E",
+                Self::X => "X",
+                Self::MirrorP => "MirrorP",
+                Self::MirrorE => "MirrorE",
+                Self::Torus => "Torus",
             }
         )
     }
@@ -143,6 +176,10 @@ enum GeneratorCommand {
         m: f64,
     },
     AddGridLineP,
+    AddGridLineE {
+        p_range: i32,
+        level: EnergyLevel,
+    },
 
     ComputeBranchPoint {
         p_range: i32,
@@ -160,6 +197,7 @@ enum GeneratorCommand {
     ComputeCutEXp,
     ComputeCutEXm,
     ComputeCutEU,
+    ComputeCutQLogP(f64),
     SetCutPath {
         path: Vec<Complex64>,
         branch_point: Option<Complex64>,
@@ -201,6 +239,35 @@ struct RuntimeCutData {
     path: Option<Vec<Complex64>>,
 }
 
+#[derive(Clone)]
+struct GridLineSeed {
+    pt: InterpolationPoint,
+    m: f64,
+    path: Vec<(f64, Complex64)>,
+    traced_len: usize,
+}
+
+impl GridLineSeed {
+    fn is_outside_grid_bounds(&self) -> bool {
+        let re_min = self
+            .path
+            .iter()
+            .map(|(_, p)| p.re)
+            .fold(f64::INFINITY, f64::min);
+        let re_max = self
+            .path
+            .iter()
+            .map(|(_, p)| p.re)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let im_min = self
+            .path
+            .iter()
+            .map(|(_, p)| p.im.abs())
+            .fold(f64::INFINITY, f64::min);
+        re_max < P_GRID_RE_MIN || re_min > P_GRID_RE_MAX || im_min > P_GRID_IM_MAX
+    }
+}
+
 #[derive(Default, Clone)]
 struct ContourGeneratorRuntimeContext {
     p_int: Option<PInterpolatorMut>,
@@ -209,6 +276,7 @@ struct ContourGeneratorRuntimeContext {
     cut_data: RuntimeCutData,
     show_p_debug_path: bool,
     debug_path: Vec<Complex64>,
+    grid_line_seed: Option<GridLineSeed>,
 }
 
 struct ContourCommandGenerator {
@@ -221,14 +289,22 @@ struct ContourCommandGenerator {
     commands: VecDeque<GeneratorCommand>,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum EnergyLevel {
+    Re(f64),
+    Im(f64),
+}
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum GridLineComponent {
     Real,
     Xp(f64),
     Xm(f64),
+    Energy(EnergyLevel),
 }
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(into = "SavedGridLine", from = "SavedGridLine")]
 pub struct GridLine {
     pub path: Vec<Complex64>,
     pub component: GridLineComponent,
@@ -271,18 +347,53 @@ impl GridLine {
     }
 }
 
-#[derive(Default, Clone)]
+// The bounding box is recomputed when a grid line is loaded
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedGridLine {
+    path: Vec<Complex64>,
+    component: GridLineComponent,
+}
+
+impl From<GridLine> for SavedGridLine {
+    fn from(grid_line: GridLine) -> Self {
+        Self {
+            path: grid_line.path,
+            component: grid_line.component,
+        }
+    }
+}
+
+impl From<SavedGridLine> for GridLine {
+    fn from(saved: SavedGridLine) -> Self {
+        Self::new(saved.path, saved.component)
+    }
+}
+
+// Only finished contours are useful to save, so the pending commands and the
+// runtime context are skipped
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Contours {
     cuts: Vec<Cut>,
+    #[serde(skip)]
     commands: VecDeque<GeneratorCommand>,
     grid_p: Vec<GridLine>,
     grid_x: Vec<GridLine>,
     grid_u: Vec<GridLine>,
-
+    grid_e: Vec<GridLine>,
+    #[serde(default)]
+    grid_mirror_p: Vec<GridLine>,
+    #[serde(default)]
+    grid_mirror_e: Vec<GridLine>,
+    #[serde(default)]
+    grid_torus: Vec<GridLine>,
+
+    #[serde(skip)]
     rctx: ContourGeneratorRuntimeContext,
 
     num_commands: usize,
     loaded: bool,
+    energy_grid: bool,
+    visibility: CutVisibility,
 }
 
 fn branch_point_mass(p_start: f64, k: f64, branch_point_type: BranchPointType) -> f64 {
@@ -335,7 +446,7 @@ pub fn compute_branch_point(
         10,
     );
 
-    if let Some(x_branch_point) = x_branch_point {
+    if let Ok(x_branch_point) = x_branch_point {
         let p = x_branch_point.arg().abs() / std::f64::consts::PI;
         Some(BranchPointData {
             p,
@@ -348,19 +459,233 @@ pub fn compute_branch_point(
     }
 }
 
+// The branch points of the cuts of the given type in the given p range, in each
+// component where they can be located. Returns None for the cut types without
+// branch points: the QLog cuts of the standard model, the debug paths, which
+// are not cuts, and the x cuts other than those of x⁺ and x⁻.
+pub fn branch_points(
+    cut_type: &CutType,
+    p_range: i32,
+    consts: CouplingConstants,
+) -> Option<Vec<(Component, Complex64)>> {
+    let n = p_range as f64;
+    let k = consts.k() as f64;
+    let s = consts.s();
+    let us = s + 1.0 / s - (s - 1.0 / s) * s.ln();
+
+    let u_im = |shift: f64| (1.0 + shift * k) / consts.h;
+
+    let mut result = match cut_type {
+        CutType::E => {
+            let p = crate::kinematics::en_branch_point(p_range, consts)?;
+            [p, p.conj()]
+                .into_iter()
+                .flat_map(|p| {
+                    [
+                        (Component::P, p),
+                        (Component::Xp, EPInterpolator::cut_xp(p, 0.0, consts)),
+                        (Component::Xm, EPInterpolator::cut_xm(p, 0.0, consts)),
+                        (Component::U, EPInterpolator::cut_u(p, 0.0, consts, n)),
+                    ]
+                })
+                .collect()
+        }
+        CutType::Log(Component::Xp) => vec![(Component::Xp, Complex64::from(0.0))],
+        CutType::Log(Component::Xm) => vec![(Component::Xm, Complex64::from(0.0))],
+        CutType::UShortScallion(Component::Xp) | CutType::ULongPositive(Component::Xp) => vec![
+            (Component::Xp, Complex64::from(s)),
+            (Component::U, Complex64::new(us, -u_im(n))),
+        ],
+        CutType::UShortScallion(Component::Xm) | CutType::ULongPositive(Component::Xm) => vec![
+            (Component::Xm, Complex64::from(s)),
+            (Component::U, Complex64::new(us, u_im(n))),
+        ],
+        CutType::UShortKidney(Component::Xp) | CutType::ULongNegative(Component::Xp) => vec![
+            (Component::Xp, Complex64::from(-1.0 / s)),
+            (Component::U, Complex64::new(-us, -u_im(n + 1.0))),
+            (Component::U, Complex64::new(-us, -u_im(n - 1.0))),
+        ],
+        CutType::UShortKidney(Component::Xm) | CutType::ULongNegative(Component::Xm) => vec![
+            (Component::Xm, Complex64::from(-1.0 / s)),
+            (Component::U, Complex64::new(-us, u_im(n + 1.0))),
+            (Component::U, Complex64::new(-us, u_im(n - 1.0))),
+        ],
+        CutType::QLog => {
+            let im = consts.model().q_log_cut_start()?;
+            let (p, _) = EPInterpolator::new(p_range, consts).get_cut_p_above(im);
+            let p = p?;
+            vec![(Component::P, p), (Component::E, model_en(p, 1.0, consts))]
+        }
+        CutType::DebugPath => return None,
+        CutType::Log(_)
+        | CutType::UShortScallion(_)
+        | CutType::ULongPositive(_)
+        | CutType::UShortKidney(_)
+        | CutType::ULongNegative(_) => return None,
+    };
+
+    result.dedup();
+    Some(result)
+}
+
+// Follow a curve in the p plane along which E(p) moves in a straight line with
+// the given step, stopping when leaving the strip of the given p range.
+fn trace_energy_line(
+    p0: Complex64,
+    e0: Complex64,
+    de: Complex64,
+    max_steps: usize,
+    p_range: i32,
+    consts: CouplingConstants,
+) -> Vec<Complex64> {
+    let p_start = p_range as f64;
+    let mut path = vec![p0];
+    let mut p = p0;
+
+    for step in 1..=max_steps {
+        let e = e0 + step as f64 * de;
+        let guess = p + de / model_den_dp(p, 1.0, consts);
+        let Ok(next_p) = nr::find_root(
+            |p| model_en(p, 1.0, consts) - e,
+            |p| model_den_dp(p, 1.0, consts),
+            guess,
+            1.0e-6,
+            20,
+        ) else {
+            break;
+        };
+
+        if (next_p - p).norm() > 0.05
+            || !(p_start..=p_start + 1.0).contains(&next_p.re)
+            || next_p.im.abs() > 1.0
+        {
+            break;
+        }
+
+        p = next_p;
+        path.push(p);
+    }
+
+    path
+}
+
+fn energy_grid_lines(
+    p_range: i32,
+    level: EnergyLevel,
+    consts: CouplingConstants,
+) -> Vec<Vec<Complex64>> {
+    const STEP: f64 = 0.05;
+    const MAX_STEPS: usize = 400;
+
+    let join = |mut backward: Vec<Complex64>, forward: Vec<Complex64>| {
+        backward.reverse();
+        backward.extend(forward.into_iter().skip(1));
+        backward
+    };
+
+    let mut lines = vec![];
+
+    match level {
+        EnergyLevel::Re(re) => {
+            let samples = (0..=64)
+                .map(|i| p_range as f64 + i as f64 / 64.0)
+                .collect::<Vec<_>>();
+            for (p1, p2) in samples.into_iter().tuple_windows() {
+                if (model_en(p1, 1.0, consts).re - re).signum()
+                    == (model_en(p2, 1.0, consts).re - re).signum()
+                {
+                    continue;
+                }
+                let Ok(p0) = nr::find_root(
+                    |p| model_en(p, 1.0, consts) - re,
+                    |p| model_den_dp(p, 1.0, consts),
+                    Complex64::from((p1 + p2) / 2.0),
+                    1.0e-8,
+                    20,
+                ) else {
+                    continue;
+                };
+                let e0 = Complex64::from(re);
+                let de = Complex64::new(0.0, STEP);
+                let forward = trace_energy_line(p0, e0, de, MAX_STEPS, p_range, consts);
+                let backward = trace_energy_line(p0, e0, -de, MAX_STEPS, p_range, consts);
+                lines.push(join(backward, forward));
+            }
+        }
+        EnergyLevel::Im(im) => {
+            let p_mid = Complex64::from(p_range as f64 + 0.5);
+            let e_mid = model_en(p_mid, 1.0, consts);
+            let steps = (im.abs() / STEP).ceil() as usize;
+            let de = Complex64::new(0.0, im / steps as f64);
+            let vertical = trace_energy_line(p_mid, e_mid, de, steps, p_range, consts);
+            if vertical.len() == steps + 1 {
+                let p0 = *vertical.last().unwrap();
+                let e0 = e_mid + Complex64::new(0.0, im);
+                let de = Complex64::from(STEP);
+                let forward = trace_energy_line(p0, e0, de, MAX_STEPS, p_range, consts);
+                let backward = trace_energy_line(p0, e0, -de, MAX_STEPS, p_range, consts);
+                lines.push(join(backward, forward));
+            }
+        }
+    }
+
+    lines.retain(|path| path.len() > 1);
+    lines
+}
+
 impl Contours {
     pub fn new() -> Self {
         Self::default()
     }
 
+    pub fn energy_grid(&self) -> bool {
+        self.energy_grid
+    }
+
+    pub fn set_energy_grid(&mut self, energy_grid: bool) {
+        if self.energy_grid != energy_grid {
+            self.energy_grid = energy_grid;
+            self.clear();
+        }
+    }
+
+    pub fn cut_visibility(&self) -> &CutVisibility {
+        &self.visibility
+    }
+
+    pub fn set_cut_visibility(&mut self, visibility: CutVisibility) {
+        self.visibility = visibility;
+    }
+
     pub fn is_loaded(&self) -> bool {
         self.loaded
     }
 
     pub fn update(&mut self, p_range: i32, consts: CouplingConstants) -> bool {
+        self.update_with_model(p_range, consts, KinematicsModel::Standard)
+    }
+
+    // The model is owned by the caller, which has to clear the contours when it
+    // changes
+    pub fn update_with_model(
+        &mut self,
+        p_range: i32,
+        consts: CouplingConstants,
+        model: KinematicsModel,
+    ) -> bool {
+        let consts = consts.with_model(model);
+
         if self.num_commands == 0 {
             self.clear();
             self.commands = ContourCommandGenerator::generate_commands(p_range, consts);
+            self.commands
+                .extend(ContourCommandGenerator::generate_model_commands(consts));
+            if self.energy_grid {
+                self.commands
+                    .extend(ContourCommandGenerator::generate_energy_grid_commands(
+                        consts,
+                    ));
+            }
             self.num_commands = self.commands.len();
             log::debug!("Generated {} commands", self.num_commands,)
         }
@@ -369,6 +694,8 @@ impl Contours {
             if let Some(command) = self.commands.pop_front() {
                 self.execute(command, consts);
             } else {
+                self.compute_energy_plane(consts);
+                self.compute_derived_planes(consts);
                 self.cuts.sort_unstable_by_key(|cut| match cut.typ {
                     CutType::Log(_) => 2,
                     CutType::ULongNegative(_) => 3,
@@ -376,12 +703,13 @@ impl Contours {
                     CutType::UShortScallion(_) => 5,
                     CutType::UShortKidney(_) => 6,
                     CutType::E => {
-                        if cut.component == Component::P {
+                        if matches!(cut.component, Component::P | Component::E) {
                             7
                         } else {
                             1
                         }
                     }
+                    CutType::QLog => 7,
                     CutType::DebugPath => 8,
                 });
                 self.loaded = true;
@@ -396,6 +724,10 @@ impl Contours {
         self.num_commands = 0;
         self.grid_x.clear();
         self.grid_u.clear();
+        self.grid_e.clear();
+        self.grid_mirror_p.clear();
+        self.grid_mirror_e.clear();
+        self.grid_torus.clear();
         self.cuts.clear();
         self.loaded = false;
 
@@ -419,8 +751,12 @@ impl Contours {
     pub fn get_grid(&self, component: Component) -> &Vec<GridLine> {
         match component {
             Component::P => &self.grid_p,
-            Component::Xp | Component::Xm => &self.grid_x,
+            Component::Xp | Component::Xm | Component::X => &self.grid_x,
             Component::U => &self.grid_u,
+            Component::E => &self.grid_e,
+            Component::MirrorP => &self.grid_mirror_p,
+            Component::MirrorE => &self.grid_mirror_e,
+            Component::Torus => &self.grid_torus,
         }
     }
     pub fn get_visible_cuts(
@@ -443,7 +779,38 @@ impl Contours {
 
         self.cuts
             .iter()
-            .filter(move |c| c.component == component && c.is_visible(&pt))
+            .filter(move |c| c.component == component && self.visibility.is_visible(c, &pt))
+    }
+
+    pub fn explain_cuts_from_point(
+        &self,
+        pt: &Point,
+        component: Component,
+        consts: CouplingConstants,
+    ) -> Vec<(&Cut, Vec<CutVisibilityCheck>)> {
+        let mut pt = pt.clone();
+        pt.u += 2.0 * (pt.sheet_data.log_branch_p * consts.k()) as f64 * Complex64::i() / consts.h;
+
+        self.cuts
+            .iter()
+            .filter(|c| c.component == component)
+            .map(|c| (c, self.visibility.explain(c, &pt)))
+            .collect()
+    }
+
+    pub fn branch_points(&self, cut_type: &CutType, component: Component) -> Vec<Complex64> {
+        let mut result: Vec<Complex64> = vec![];
+        for branch_point in self
+            .cuts
+            .iter()
+            .filter(|cut| cut.component == component && &cut.typ == cut_type)
+            .filter_map(|cut| cut.branch_point)
+        {
+            if !result.iter().any(|z| (z - branch_point).norm() < 1.0e-8) {
+                result.push(branch_point);
+            }
+        }
+        result
     }
 
     pub fn get_crossed_cuts(
@@ -488,6 +855,218 @@ impl Contours {
         result
     }
 
+    // The energy plane shows the image of the p plane under E(p), using
+    // the sign of the E branch each cut is visible on.
+    fn compute_energy_plane(&mut self, consts: CouplingConstants) {
+        let e_branch = |cut: &Cut| {
+            cut.visibility
+                .iter()
+                .find_map(|cond| match cond {
+                    CutVisibilityCondition::EBranch(b) => Some(*b as f64),
+                    _ => None,
+                })
+                .unwrap_or(1.0)
+        };
+
+        let map_path = |path: &[Complex64], sign: f64| -> Vec<Complex64> {
+            let mut result = vec![];
+            for (&z1, &z2) in path.iter().tuple_windows() {
+                let steps = ((z2 - z1).norm() / 0.01).ceil().clamp(1.0, 256.0) as usize;
+                result.extend((0..steps).map(|i| z1 + (z2 - z1) * (i as f64 / steps as f64)));
+            }
+            result.extend(path.last());
+            result
+                .into_iter()
+                .map(|p| consts.model().energy(sign * en(p, 1.0, consts), 0))
+                .filter(|e| e.is_finite() && e.norm() < 1.0e4)
+                .collect()
+        };
+
+        let energy_cuts = self
+            .cuts
+            .iter()
+            .filter(|cut| cut.component == Component::P)
+            .map(|cut| {
+                let sign = e_branch(cut);
+                Cut {
+                    component: Component::E,
+                    path: map_path(&cut.path, sign),
+                    branch_point: cut
+                        .branch_point
+                        .map(|p| consts.model().energy(sign * en(p, 1.0, consts), 0)),
+                    typ: cut.typ.clone(),
+                    p_range: cut.p_range,
+                    periodic: false,
+                    visibility: cut.visibility.clone(),
+                }
+            })
+            .filter(|cut| cut.path.len() > 1)
+            .collect::<Vec<_>>();
+        self.cuts.extend(energy_cuts);
+
+        self.grid_e = self
+            .grid_p
+            .iter()
+            .map(|grid_line| (map_path(&grid_line.path, 1.0), grid_line.component.clone()))
+            .filter(|(path, _)| path.len() > 1)
+            .map(|(path, component)| GridLine::new(path, component))
+            .collect();
+    }
+
+    // The mirror planes are rescaled copies of the E and p planes. The torus
+    // shows both sheets of E at once, so the p grid is mapped on both of them
+    // and the E cuts, which are not cuts of the torus, are left out together
+    // with the visibility conditions on the E branch. The single x plane shows
+    // the scallion, the kidney and the cut of the logarithm in u(x).
+    fn compute_derived_planes(&mut self, consts: CouplingConstants) {
+        let e_branch = |cut: &Cut| {
+            cut.visibility
+                .iter()
+                .find_map(|cond| match cond {
+                    CutVisibilityCondition::EBranch(b) => Some(*b as f64),
+                    _ => None,
+                })
+                .unwrap_or(1.0)
+        };
+
+        let scaled = |cut: &Cut, component: Component, factor: Complex64| Cut {
+            component,
+            path: cut.path.iter().map(|z| factor * z).collect(),
+            branch_point: cut.branch_point.map(|z| factor * z),
+            typ: cut.typ.clone(),
+            p_range: cut.p_range,
+            periodic: false,
+            visibility: cut.visibility.clone(),
+        };
+
+        let torus_path = |path: &[Complex64], sign: f64| -> Vec<Complex64> {
+            let Some(&p0) = path.first() else {
+                return vec![];
+            };
+            let mut e = sign * en(p0, 1.0, consts);
+            let mut z = torus_z(p0, e, consts);
+            let mut p = p0;
+            let mut result = vec![z];
+            // Vertices closer than the integration step are skipped, apart from
+            // the last one
+            for (i, &p2) in path.iter().enumerate().skip(1) {
+                if (p2 - p).norm() < 0.02 && i + 1 < path.len() {
+                    continue;
+                }
+                let steps = ((p2 - p).norm() / 0.05).ceil().clamp(1.0, 64.0) as usize;
+                let p1 = p;
+                for step in 1..=steps {
+                    let next = p1 + (p2 - p1) * (step as f64 / steps as f64);
+                    (z, e) = torus_z_continued(z, p, e, next, consts);
+                    p = next;
+                    result.push(z);
+                }
+            }
+            result.retain(|z| z.is_finite() && z.norm() < INFINITY);
+            result
+        };
+
+        let mirror_p = -Complex64::i() / TAU;
+        let mirror_e = -Complex64::i() * TAU;
+
+        let mut derived = vec![];
+        for cut in self.cuts.iter() {
+            match cut.component {
+                Component::E => derived.push(scaled(cut, Component::MirrorP, mirror_p)),
+                Component::P => {
+                    derived.push(scaled(cut, Component::MirrorE, mirror_e));
+                    if cut.typ != CutType::E {
+                        let sign = e_branch(cut);
+                        derived.push(Cut {
+                            component: Component::Torus,
+                            path: torus_path(&cut.path, sign),
+                            branch_point: cut
+                                .branch_point
+                                .map(|p| torus_z(p, sign * en(p, 1.0, consts), consts)),
+                            typ: cut.typ.clone(),
+                            p_range: cut.p_range,
+                            periodic: false,
+                            visibility: cut
+                                .visibility
+                                .iter()
+                                .filter(|cond| !matches!(cond, CutVisibilityCondition::EBranch(_)))
+                                .cloned()
+                                .collect(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let x_cut = |path: Vec<Complex64>, branch_point: Option<Complex64>, typ: CutType| Cut {
+            component: Component::X,
+            path,
+            branch_point,
+            typ,
+            p_range: 0,
+            periodic: false,
+            visibility: vec![],
+        };
+        let angles = (1..512).map(|i| PI * (i as f64 / 256.0 - 1.0));
+        derived.push(x_cut(
+            angles
+                .clone()
+                .map(|theta| scallion(theta, consts))
+                .filter(|x| x.norm() < INFINITY)
+                .collect(),
+            Some(Complex64::from(consts.s())),
+            CutType::UShortScallion(Component::X),
+        ));
+        derived.push(x_cut(
+            angles.map(|theta| kidney(theta, consts)).collect(),
+            Some(Complex64::from(-1.0 / consts.s())),
+            CutType::UShortKidney(Component::X),
+        ));
+        derived.push(x_cut(
+            vec![Complex64::from(0.0), Complex64::from(-INFINITY)],
+            None,
+            CutType::Log(Component::X),
+        ));
+
+        self.cuts
+            .extend(derived.into_iter().filter(|cut| cut.path.len() > 1));
+
+        let scaled_grid = |grid: &[GridLine], factor: Complex64| -> Vec<GridLine> {
+            grid.iter()
+                .map(|grid_line| {
+                    GridLine::new(
+                        grid_line.path.iter().map(|z| factor * z).collect(),
+                        grid_line.component.clone(),
+                    )
+                })
+                .collect()
+        };
+        self.grid_mirror_p = scaled_grid(&self.grid_e, mirror_p);
+        self.grid_mirror_e = scaled_grid(&self.grid_p, mirror_e);
+
+        let grid_torus = self
+            .grid_p
+            .iter()
+            .map(|grid_line| {
+                (
+                    torus_path(&grid_line.path, 1.0),
+                    grid_line.component.clone(),
+                )
+            })
+            .filter(|(path, _)| path.len() > 1)
+            .collect::<Vec<_>>();
+        self.grid_torus = grid_torus
+            .iter()
+            .map(|(path, component)| {
+                let reflected = path.iter().map(|&z| torus_z_reflected(z, consts));
+                (reflected.collect(), component.clone())
+            })
+            .chain(grid_torus.iter().cloned())
+            .map(|(path, component)| GridLine::new(path, component))
+            .collect();
+    }
+
     fn execute(&mut self, command: GeneratorCommand, consts: CouplingConstants) {
         use GeneratorCommand::*;
 
@@ -572,7 +1151,59 @@ impl Contours {
                 let Some(ref mut p_int) = self.rctx.p_int else {
                     return;
                 };
-                let path = p_int.contour();
+
+                let path = match p_int.pt() {
+                    pt @ (InterpolationPoint::Xp(_, m) | InterpolationPoint::Xm(_, m)) => {
+                        // Adjacent lines have similar extents, so a line is skipped when
+                        // the previous one is far outside the plotted region. The seed is
+                        // kept so that the following lines are skipped as well.
+                        if let Some(seed) = self.rctx.grid_line_seed.as_mut() {
+                            if std::mem::discriminant(&seed.pt) == std::mem::discriminant(&pt)
+                                && (seed.m - m).abs() <= 1.0
+                                && seed.is_outside_grid_bounds()
+                            {
+                                seed.m = m;
+                                return;
+                            }
+                        }
+
+                        // Grid lines with adjacent values of m are very similar, so when
+                        // possible the previous line is used as a starting point
+                        let seeded = self
+                            .rctx
+                            .grid_line_seed
+                            .take()
+                            .filter(|seed| {
+                                std::mem::discriminant(&seed.pt) == std::mem::discriminant(&pt)
+                                    && (seed.m - m).abs() <= 1.0
+                            })
+                            .and_then(|seed| {
+                                let path = p_int.contour_from(&seed.path)?;
+                                Some((path, seed.traced_len))
+                            });
+
+                        let (path, traced_len) = seeded.unwrap_or_else(|| {
+                            let path = p_int.contour_with_parameters();
+                            let len = path.len();
+                            (path, len)
+                        });
+
+                        let points = path.iter().map(|(_, p)| *p).collect();
+
+                        // Refining a seeded line can add points, so trace the next line
+                        // from scratch once the lines have grown too much
+                        if path.len() <= 2 * traced_len {
+                            self.rctx.grid_line_seed = Some(GridLineSeed {
+                                pt,
+                                m,
+                                path,
+                                traced_len,
+                            });
+                        }
+                        points
+                    }
+                    _ => p_int.contour(),
+                };
 
                 let (component, conj_component) = match p_int.pt() {
                     InterpolationPoint::Xp(_, m) => {
@@ -597,6 +1228,13 @@ impl Contours {
                 }
             }
 
+            AddGridLineE { p_range, level } => {
+                for path in energy_grid_lines(p_range, level, consts) {
+                    self.grid_p
+                        .push(GridLine::new(path, GridLineComponent::Energy(level)));
+                }
+            }
+
             ClearCut => {
                 self.rctx.cut_data.path = None;
                 self.rctx.cut_data.branch_point = None;
@@ -753,6 +1391,15 @@ impl Contours {
                 self.rctx.cut_data.branch_point = branch_point;
             }
 
+            ComputeCutQLogP(im) => {
+                let Some(ref mut e_int) = self.rctx.e_int else {
+                    return;
+                };
+                let (branch_point, path) = e_int.get_cut_p_above(im);
+                self.rctx.cut_data.path = path;
+                self.rctx.cut_data.branch_point = branch_point;
+            }
+
             SetCutPath { path, branch_point } => {
                 self.rctx.cut_data.path = Some(path);
                 self.rctx.cut_data.branch_point = branch_point;
@@ -955,6 +1602,55 @@ impl ContourCommandGenerator {
         bctx.do_generate_commands(p_range, consts)
     }
 
+    fn generate_model_commands(consts: CouplingConstants) -> VecDeque<GeneratorCommand> {
+        let mut bctx = Self::new();
+
+        if let Some(im) = consts.model().q_log_cut_start() {
+            for p_range in P_RANGE_MIN..=P_RANGE_MAX {
+                bctx.e_start(p_range)
+                    .compute_cut_q_log_p(im)
+                    .create_cut(Component::P, CutType::QLog)
+                    .push_cut(p_range);
+            }
+        }
+
+        bctx.commands
+    }
+
+    fn generate_energy_grid_commands(consts: CouplingConstants) -> VecDeque<GeneratorCommand> {
+        let mut bctx = Self::new();
+
+        for p_range in P_RANGE_MIN..=P_RANGE_MAX {
+            let energies = (0..=16)
+                .map(|i| model_en(p_range as f64 + i as f64 / 16.0, 1.0, consts).re)
+                .collect::<Vec<_>>();
+            let e_min = energies.iter().copied().fold(f64::INFINITY, f64::min);
+            let e_max = energies.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+            let mut re = (2.0 * e_min).floor() / 2.0;
+            while re <= e_max {
+                if re > e_min {
+                    bctx.add(GeneratorCommand::AddGridLineE {
+                        p_range,
+                        level: EnergyLevel::Re(re),
+                    });
+                }
+                re += 0.5;
+            }
+
+            for i in 1..=(4.0 * consts.h).ceil() as i32 {
+                for im in [-0.5 * i as f64, 0.5 * i as f64] {
+                    bctx.add(GeneratorCommand::AddGridLineE {
+                        p_range,
+                        level: EnergyLevel::Im(im),
+                    });
+                }
+            }
+        }
+
+        bctx.commands
+    }
+
     fn new() -> Self {
         Self {
             component: None,
@@ -988,6 +1684,10 @@ impl ContourCommandGenerator {
         self.add(GeneratorCommand::ComputeCutEP)
     }
 
+    fn compute_cut_q_log_p(&mut self, im: f64) -> &mut Self {
+        self.add(GeneratorCommand::ComputeCutQLogP(im))
+    }
+
     fn compute_cut_e_xp(&mut self) -> &mut Self {
         self.add(GeneratorCommand::ComputeCutEXp)
     }
@@ -6,7 +6,7 @@ use itertools::Itertools;
 
 use num::complex::Complex64;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Cut {
     pub component: Component,
     pub path: Vec<Complex64>,
@@ -124,11 +124,16 @@ impl Cut {
     pub fn is_visible(&self, pt: &Point) -> bool {
         self.visibility.iter().all(|cond| cond.check(pt))
     }
+
+    pub fn visibility(&self) -> &[CutVisibilityCondition] {
+        &self.visibility
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 pub enum CutType {
     E,
+    QLog,
     DebugPath,
     Log(Component),
     ULongPositive(Component),
@@ -141,6 +146,7 @@ impl CutType {
     fn conj(&self) -> Self {
         match self {
             Self::E => Self::E,
+            Self::QLog => Self::QLog,
             Self::DebugPath => Self::DebugPath,
 
             Self::ULongPositive(component) => Self::ULongPositive(component.conj()),
@@ -152,7 +158,7 @@ impl CutType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum CutVisibilityCondition {
     ImXp(i8),
     ImXm(i8),
@@ -162,8 +168,34 @@ pub enum CutVisibilityCondition {
     UmBranch(UBranch),
 }
 
+impl std::fmt::Display for CutVisibilityCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ImXp(sign) if *sign > 0 => write!(f, "Im(x+) > 0"),
+            Self::ImXp(_) => write!(f, "Im(x+) < 0"),
+            Self::ImXm(sign) if *sign > 0 => write!(f, "Im(x-) > 0"),
+            Self::ImXm(_) => write!(f, "Im(x-) < 0"),
+            Self::LogBranch(b) => write!(f, "log branch = {b}"),
+            Self::EBranch(b) => write!(f, "E branch = {b}"),
+            Self::UpBranch(b) => write!(f, "u+ branch = {b}"),
+            Self::UmBranch(b) => write!(f, "u- branch = {b}"),
+        }
+    }
+}
+
 impl CutVisibilityCondition {
-    fn check(&self, pt: &Point) -> bool {
+    pub fn kind(&self) -> CutVisibilityKind {
+        match self {
+            Self::ImXp(_) => CutVisibilityKind::ImXp,
+            Self::ImXm(_) => CutVisibilityKind::ImXm,
+            Self::LogBranch(_) => CutVisibilityKind::LogBranch,
+            Self::EBranch(_) => CutVisibilityKind::EBranch,
+            Self::UpBranch(_) => CutVisibilityKind::UpBranch,
+            Self::UmBranch(_) => CutVisibilityKind::UmBranch,
+        }
+    }
+
+    pub fn check(&self, pt: &Point) -> bool {
         match self {
             Self::ImXp(sign) => pt.xp.im.signum() as i8 == sign.signum(),
             Self::ImXm(sign) => pt.xm.im.signum() as i8 == sign.signum(),
@@ -185,3 +217,101 @@ impl CutVisibilityCondition {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CutVisibilityKind {
+    ImXp,
+    ImXm,
+    LogBranch,
+    EBranch,
+    UpBranch,
+    UmBranch,
+}
+
+impl CutVisibilityKind {
+    pub const ALL: [Self; 6] = [
+        Self::ImXp,
+        Self::ImXm,
+        Self::LogBranch,
+        Self::EBranch,
+        Self::UpBranch,
+        Self::UmBranch,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CutVisibilityCheck {
+    pub condition: CutVisibilityCondition,
+    pub satisfied: bool,
+    pub ignored: bool,
+}
+
+impl std::fmt::Display for CutVisibilityCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = if self.ignored {
+            "ignored"
+        } else if self.satisfied {
+            "ok"
+        } else {
+            "fails"
+        };
+        write!(f, "{}: {status}", self.condition)
+    }
+}
+
+// The rules deciding which cuts are shown for a given point. By default a cut
+// is shown when all its conditions hold, but conditions of a given kind can be
+// ignored, e.g. to show the cuts on all sheets.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CutVisibility {
+    ignored: Vec<CutVisibilityKind>,
+}
+
+impl CutVisibility {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn show_all() -> Self {
+        Self {
+            ignored: CutVisibilityKind::ALL.to_vec(),
+        }
+    }
+
+    pub fn ignore(mut self, kind: CutVisibilityKind) -> Self {
+        self.set_ignored(kind, true);
+        self
+    }
+
+    pub fn set_ignored(&mut self, kind: CutVisibilityKind, ignored: bool) {
+        self.ignored.retain(|k| *k != kind);
+        if ignored {
+            self.ignored.push(kind);
+        }
+    }
+
+    pub fn is_ignored(&self, kind: CutVisibilityKind) -> bool {
+        self.ignored.contains(&kind)
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.ignored.is_empty()
+    }
+
+    pub fn is_visible(&self, cut: &Cut, pt: &Point) -> bool {
+        cut.visibility
+            .iter()
+            .all(|cond| self.is_ignored(cond.kind()) || cond.check(pt))
+    }
+
+    pub fn explain(&self, cut: &Cut, pt: &Point) -> Vec<CutVisibilityCheck> {
+        cut.visibility
+            .iter()
+            .map(|cond| CutVisibilityCheck {
+                condition: cond.clone(),
+                satisfied: cond.check(pt),
+                ignored: self.is_ignored(cond.kind()),
+            })
+            .collect()
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    kinematics::{den2_dp, dxp_dp, en2, xm, xp, CouplingConstants},
+    kinematics::{den2_dp, en2, en_branch_point, xm, xp, xp_with_derivative, CouplingConstants},
     nr,
 };
 
@@ -206,6 +206,16 @@ fn refine<T: Refiner + Clone>(
     points: impl Into<Vec<(T, Complex64)>>,
     eval: impl Fn(T, Complex64) -> Option<Complex64>,
 ) -> Vec<Complex64> {
+    refine_with_parameters(points, eval)
+        .into_iter()
+        .map(|(_, x)| x)
+        .collect()
+}
+
+fn refine_with_parameters<T: Refiner + Clone>(
+    points: impl Into<Vec<(T, Complex64)>>,
+    eval: impl Fn(T, Complex64) -> Option<Complex64>,
+) -> Vec<(T, Complex64)> {
     let mut points: Vec<(T, Complex64)> = points.into();
 
     let min_cos = (2.0 * TAU / 360.0).cos();
@@ -249,7 +259,7 @@ fn refine<T: Refiner + Clone>(
         }
     }
 
-    points.into_iter().map(|(_, x)| x).collect()
+    points
 }
 
 impl XInterpolator {
@@ -338,6 +348,22 @@ impl XInterpolator {
     }
 }
 
+fn zero_asymptote_dist((_, p): (f64, Complex64)) -> f64 {
+    if (p.re - p.re.floor()) < 0.5 {
+        (p - p.re.floor()).norm_sqr()
+    } else {
+        (p - p.re.ceil()).norm_sqr()
+    }
+}
+
+fn zero_asymptote_value((_, p): (f64, Complex64)) -> Complex64 {
+    if (p.re - p.re.floor()) < 0.5 {
+        Complex64::from(p.re.floor())
+    } else {
+        Complex64::from(p.re.ceil())
+    }
+}
+
 const MAX_RE_P_JUMP: f64 = 1.0 / 8.0;
 const MAX_IM_P_JUMP: f64 = 1.0 / 4.0;
 
@@ -465,7 +491,16 @@ impl PInterpolatorMut {
     }
 
     fn find_point(&self, w: Complex64, guess: Complex64) -> Option<Complex64> {
-        nr::find_root(|z| self.f(z) - w, |z| self.df(z), guess, 1.0e-5, 50)
+        nr::find_root_with_derivative(
+            |z| {
+                let (f, df) = xp_with_derivative(z, 1.0, self.consts);
+                (f - w, df)
+            },
+            guess,
+            1.0e-5,
+            50,
+        )
+        .ok()
     }
 
     fn goto(&mut self, pt: InterpolationPoint) -> bool {
@@ -571,30 +606,45 @@ impl PInterpolatorMut {
             return vec![];
         }
 
-        let (p1, p2) = match self.pt {
+        match self.pt {
             InterpolationPoint::C(z) => {
                 if z.im.abs() < 1.0 / 128.0 {
-                    return self.contour_re(z.re);
+                    self.contour_re(z.re)
                 } else {
                     log::warn!("Can't draw contour for non real C (found {:?})", self.pt);
-                    return vec![];
+                    vec![]
                 }
             }
-            InterpolationPoint::Re(re) => {
-                return self.contour_re(re);
-            }
-            InterpolationPoint::Xp(p, _) | InterpolationPoint::Xm(p, _) => {
-                (p.floor() + 1.0 / 256.0, p.ceil() - 1.0 / 256.0)
-            }
-        };
+            InterpolationPoint::Re(re) => self.contour_re(re),
+            InterpolationPoint::Xp(_, _) | InterpolationPoint::Xm(_, _) => self
+                .contour_with_parameters()
+                .into_iter()
+                .map(|(_, p)| p)
+                .collect(),
+        }
+    }
 
-        let pt_at = |p| match self.pt {
-            InterpolationPoint::C(_) | InterpolationPoint::Re(_) => {
-                unreachable!();
-            }
-            InterpolationPoint::Xp(_, m) => InterpolationPoint::Xp(p, m),
-            InterpolationPoint::Xm(_, m) => InterpolationPoint::Xm(p, m),
+    fn pt_at(&self, p: f64) -> Option<InterpolationPoint> {
+        match self.pt {
+            InterpolationPoint::Xp(_, m) => Some(InterpolationPoint::Xp(p, m)),
+            InterpolationPoint::Xm(_, m) => Some(InterpolationPoint::Xm(p, m)),
+            InterpolationPoint::C(_) | InterpolationPoint::Re(_) => None,
+        }
+    }
+
+    // The contour of constant m through the current point, together with the
+    // value of p along the contour.
+    pub fn contour_with_parameters(&self) -> Vec<(f64, Complex64)> {
+        if !self.valid {
+            return vec![];
+        }
+
+        let (InterpolationPoint::Xp(p, _) | InterpolationPoint::Xm(p, _)) = self.pt else {
+            return vec![];
         };
+        let (p1, p2) = (p.floor() + 1.0 / 256.0, p.ceil() - 1.0 / 256.0);
+
+        let pt_at = |p| self.pt_at(p).unwrap();
 
         let pt1 = pt_at(p1);
         let pt2 = pt_at(p2);
@@ -605,22 +655,6 @@ impl PInterpolatorMut {
         path.pop_back();
         path.extend(self.generate_path(pt2));
 
-        fn zero_asymptote_dist((_, p): (f64, Complex64)) -> f64 {
-            if (p.re - p.re.floor()) < 0.5 {
-                (p - p.re.floor()).norm_sqr()
-            } else {
-                (p - p.re.ceil()).norm_sqr()
-            }
-        }
-
-        fn zero_asymptote_value((_, p): (f64, Complex64)) -> Complex64 {
-            if (p.re - p.re.floor()) < 0.5 {
-                Complex64::from(p.re.floor())
-            } else {
-                Complex64::from(p.re.ceil())
-            }
-        }
-
         if path.len() < 2 {
             return vec![];
         }
@@ -637,19 +671,81 @@ impl PInterpolatorMut {
             path.push_back((p2.ceil(), zero_asymptote_value(*path.back().unwrap())));
         }
 
-        refine(path, |t, p| {
+        refine_with_parameters(path, |t, p| {
             let pt = pt_at(t);
             let w = pt.evaluate(self.consts);
             self.find_point(w, p)
         })
     }
 
-    fn f(&self, z: Complex64) -> Complex64 {
-        xp(z, 1.0, self.consts)
-    }
+    // Follow a contour computed for a nearby value of m to the current one,
+    // using its points as initial guesses. This is cheaper than tracing
+    // the contour from scratch. Returns None if the contour can not be
+    // followed, or if it does not pass through the current point.
+    pub fn contour_from(&self, previous: &[(f64, Complex64)]) -> Option<Vec<(f64, Complex64)>> {
+        if !self.valid || previous.len() < 2 {
+            return None;
+        }
 
-    fn df(&self, z: Complex64) -> Complex64 {
-        dxp_dp(z, 1.0, self.consts)
+        let (InterpolationPoint::Xp(p, _) | InterpolationPoint::Xm(p, _)) = self.pt else {
+            return None;
+        };
+
+        if previous
+            .iter()
+            .any(|(t, _)| *t < p.floor() || *t > p.ceil())
+        {
+            return None;
+        }
+
+        let pt_at = |p| self.pt_at(p).unwrap();
+        let find = |t: f64, guess: Complex64| {
+            let next_p = self.find_point(pt_at(t).evaluate(self.consts), guess)?;
+            ((next_p.re - guess.re).abs() < MAX_RE_P_JUMP / 8.0
+                && (next_p.im - guess.im).abs() < MAX_IM_P_JUMP / 8.0)
+                .then_some(next_p)
+        };
+
+        let mut path = Vec::with_capacity(previous.len());
+        for &(t, guess) in previous.iter() {
+            if t == t.floor() {
+                // The asymptotes are added back below
+                continue;
+            }
+            path.push((t, find(t, guess)?));
+        }
+
+        if path.len() < 2 {
+            return None;
+        }
+
+        let closest = path
+            .iter()
+            .min_by(|(t1, _), (t2, _)| (t1 - p).abs().total_cmp(&(t2 - p).abs()))?;
+        let current = find(p, closest.1)?;
+        if (current - self.p).norm() > 1.0e-3 {
+            return None;
+        }
+
+        if previous.first().unwrap().0 == previous.first().unwrap().0.floor()
+            && zero_asymptote_dist(*path.first().unwrap()) < 1.0 / 64.0
+        {
+            let (t, _) = *previous.first().unwrap();
+            path.insert(0, (t, zero_asymptote_value(*path.first().unwrap())));
+        }
+
+        if previous.last().unwrap().0 == previous.last().unwrap().0.floor()
+            && zero_asymptote_dist(*path.last().unwrap()) < 1.0 / 64.0
+        {
+            let (t, _) = *previous.last().unwrap();
+            path.push((t, zero_asymptote_value(*path.last().unwrap())));
+        }
+
+        Some(refine_with_parameters(path, |t, p| {
+            let pt = pt_at(t);
+            let w = pt.evaluate(self.consts);
+            self.find_point(w, p)
+        }))
     }
 }
 
@@ -682,15 +778,20 @@ impl EPInterpolator {
         numerator / denominator
     }
 
-    fn cut_xp(p: Complex64, im: f64, consts: CouplingConstants) -> Complex64 {
+    pub(crate) fn cut_xp(p: Complex64, im: f64, consts: CouplingConstants) -> Complex64 {
         Self::cut_x(p, im, consts) * (Complex64::i() * std::f64::consts::PI * p).exp()
     }
 
-    fn cut_xm(p: Complex64, im: f64, consts: CouplingConstants) -> Complex64 {
+    pub(crate) fn cut_xm(p: Complex64, im: f64, consts: CouplingConstants) -> Complex64 {
         Self::cut_x(p, im, consts) * (-Complex64::i() * std::f64::consts::PI * p).exp()
     }
 
-    fn cut_u(p: Complex64, im: f64, consts: CouplingConstants, p_branch: f64) -> Complex64 {
+    pub(crate) fn cut_u(
+        p: Complex64,
+        im: f64,
+        consts: CouplingConstants,
+        p_branch: f64,
+    ) -> Complex64 {
         let xp = Self::cut_xp(p, im, consts);
 
         let up = xp + 1.0 / xp - 2.0 * consts.kslash() / consts.h * xp.ln();
@@ -754,6 +855,33 @@ impl EPInterpolator {
         (branch_point, Some(path))
     }
 
+    // The part of the cut in the p plane where |E| > im. This is where the QLog
+    // cut of the q-deformed energy lies.
+    pub fn get_cut_p_above(&mut self, im: f64) -> (Option<Complex64>, Option<Vec<Complex64>>) {
+        let Some(starting_path) = self.compute_starting_path_p().clone() else {
+            return (None, None);
+        };
+        let Some(&(_, guess)) = starting_path.iter().find(|(im_start, _)| *im_start >= im) else {
+            return (None, None);
+        };
+        let Some(branch_point) = self.find_p_at_im(im, guess) else {
+            return (None, None);
+        };
+
+        let mut path = vec![((im, branch_point), branch_point)];
+        path.extend(
+            starting_path
+                .iter()
+                .filter(|(im_start, _)| *im_start > im)
+                .map(|(im, p)| ((*im, *p), *p)),
+        );
+
+        let eval = |(im, p_guess), _| self.find_p_at_im(im, p_guess);
+        let path = refine(path, eval);
+
+        (Some(branch_point), Some(path))
+    }
+
     fn find_p_at_im(&self, im: f64, guess: Complex64) -> Option<Complex64> {
         nr::find_root(
             |p| en2(p, 1.0, self.consts) + im * im,
@@ -762,12 +890,12 @@ impl EPInterpolator {
             1.0e-5,
             50,
         )
+        .ok()
     }
 
     fn compute_branch_point_p(&mut self) -> Option<Complex64> {
         if self.branch_point_p.is_none() {
-            let im_guess = if self.p_start >= 0.0 { 2.5 } else { -2.5 };
-            self.branch_point_p = self.find_p_at_im(0.0, Complex64::new(self.p_start, im_guess));
+            self.branch_point_p = en_branch_point(self.p_start as i32, self.consts);
         }
         self.branch_point_p
     }
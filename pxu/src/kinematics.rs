@@ -5,11 +5,25 @@ use std::f64::consts::{PI, TAU};
 pub struct CouplingConstants {
     pub h: f64,
     k: f64,
+    #[serde(default, skip_serializing_if = "KinematicsModel::is_standard")]
+    model: KinematicsModel,
 }
 
 impl CouplingConstants {
-    pub fn new(h: f64, k: i32) -> Self {
-        Self { h, k: k as f64 }
+    pub const fn new(h: f64, k: i32) -> Self {
+        Self {
+            h,
+            k: k as f64,
+            model: KinematicsModel::Standard,
+        }
+    }
+
+    pub fn with_model(self, model: KinematicsModel) -> Self {
+        Self { model, ..self }
+    }
+
+    pub fn model(&self) -> KinematicsModel {
+        self.model
     }
 
     pub fn k(&self) -> i32 {
@@ -54,6 +68,10 @@ const fn _i32_zero() -> i32 {
     0
 }
 
+fn _i32_is_zero(n: &i32) -> bool {
+    *n == 0
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct SheetData {
     pub log_branch_p: i32,
@@ -61,6 +79,9 @@ pub struct SheetData {
     pub e_branch: i32,
     pub u_branch: (UBranch, UBranch),
     pub im_x_sign: (i8, i8),
+    // The branch of the q-deformed energy, see KinematicsModel::energy
+    #[serde(default, skip_serializing_if = "_i32_is_zero")]
+    pub q_branch: i32,
 }
 
 pub fn en(p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Complex64 {
@@ -94,6 +115,27 @@ pub fn en2(p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Comple
     m_eff * m_eff + 4.0 * consts.h * consts.h * sin * sin
 }
 
+// The branch point of E(p) in the upper (lower for negative p ranges) half of
+// the strip of the given p range. The other one is its complex conjugate.
+pub fn en_branch_point(p_range: i32, consts: CouplingConstants) -> Option<Complex64> {
+    let p_start = p_range as f64;
+    if consts.k() == 0 {
+        let im = (1.0 / (2.0 * consts.h)).asinh() / PI;
+        let im = if p_start >= 0.0 { im } else { -im };
+        return Some(Complex64::new(p_start, im));
+    }
+
+    let im_guess = if p_start >= 0.0 { 2.5 } else { -2.5 };
+    crate::nr::find_root(
+        |p| en2(p, 1.0, consts),
+        |p| den2_dp(p, 1.0, consts),
+        Complex64::new(p_start, im_guess),
+        1.0e-5,
+        50,
+    )
+    .ok()
+}
+
 pub fn den2_dp(p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Complex64 {
     let p = p.into();
     let sin = (PI * p).sin();
@@ -141,6 +183,33 @@ pub fn dxp_dp(p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Com
     dxpm_common_dp(p, m, consts) * exp + (Complex64::i() * PI) * xpm_common(p, m, consts) * exp
 }
 
+// Evaluate x+ and its derivative together, sharing the common subexpressions
+pub fn xp_with_derivative(
+    p: impl Into<Complex64>,
+    m: f64,
+    consts: CouplingConstants,
+) -> (Complex64, Complex64) {
+    let p = p.into();
+    let sin = (PI * p).sin();
+    let cos = (PI * p).cos();
+    let exp = (Complex64::i() * PI * p).exp();
+    let m_eff = m + consts.k() as f64 * p;
+    let en = (m_eff * m_eff + 4.0 * consts.h * consts.h * sin * sin).sqrt();
+
+    let common = (m_eff + SIGN * en) / (2.0 * consts.h * sin);
+
+    let term1 = -common * (cos / sin) / 2.0;
+    let term2 = consts.kslash() / (2.0 * consts.h * sin);
+    let term3 = (consts.kslash() * m_eff + 2.0 * consts.h * consts.h * sin * cos)
+        / (en * 2.0 * consts.h * sin);
+    let dcommon = TAU * (term1 + term2 + SIGN * term3);
+
+    (
+        common * exp,
+        dcommon * exp + (Complex64::i() * PI) * common * exp,
+    )
+}
+
 pub fn xm(p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Complex64 {
     let p = p.into();
     xpm_common(p, m, consts) * (-Complex64::i() * PI * p).exp()
@@ -169,26 +238,10 @@ pub fn du_dp(
     sheet_data: &SheetData,
 ) -> Complex64 {
     let p = p.into();
-    let cot = 1.0 / (PI * p).tan();
-    let sin = (PI * p).sin();
-
-    let term1;
-    let term2;
-    let term3;
-
-    if sheet_data.e_branch > 0 {
-        term1 = den_dp(p, 1.0, consts) * cot;
-        term2 = -TAU * en(p, 1.0, consts) / (2.0 * sin * sin);
-        term3 =
-            -2.0 * consts.kslash() * dxpm_common_dp(p, 1.0, consts) / xpm_common(p, 1.0, consts);
-    } else {
-        term1 = -den_dp(p, 1.0, consts) * cot;
-        term2 = TAU * en(p, 1.0, consts) / (2.0 * sin * sin);
-        term3 = -2.0 * consts.kslash() * dxpm_common_crossed_dp(p, 1.0, consts)
-            / xpm_common_crossed(p, 1.0, consts);
-    }
+    let xp = xp_on_sheet(p, 1.0, consts, sheet_data);
+    let dxp_dp = dxp_dp_on_sheet(p, 1.0, consts, sheet_data);
 
-    (term1 + term2 + term3) * consts.h
+    (1.0 - 1.0 / (xp * xp) - 2.0 * consts.kslash() / (consts.h * xp)) * dxp_dp
 }
 
 fn xpm_common_crossed(p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Complex64 {
@@ -292,6 +345,158 @@ pub fn dxm_dp_on_sheet(
     }
 }
 
+// The energy of the standard model, which the Zhukovsky variables and the
+// torus are built from
+pub fn undeformed_en_on_sheet(
+    p: impl Into<Complex64>,
+    m: f64,
+    consts: CouplingConstants,
+    sheet_data: &SheetData,
+) -> Complex64 {
+    sheet_data.e_branch as f64 * en(p, m, consts)
+}
+
+pub fn en_on_sheet(
+    p: impl Into<Complex64>,
+    m: f64,
+    consts: CouplingConstants,
+    sheet_data: &SheetData,
+) -> Complex64 {
+    let en = undeformed_en_on_sheet(p, m, consts, sheet_data);
+    consts.model.energy(en, sheet_data.q_branch)
+}
+
+pub fn den_dp_on_sheet(
+    p: impl Into<Complex64>,
+    m: f64,
+    consts: CouplingConstants,
+    sheet_data: &SheetData,
+) -> Complex64 {
+    let p = p.into();
+    let en = undeformed_en_on_sheet(p, m, consts, sheet_data);
+    consts.model.den_den(en, sheet_data.q_branch)
+        * sheet_data.e_branch as f64
+        * den_dp(p, m, consts)
+}
+
+// The energy of the kinematics model on the principal branch of the physical
+// sheet, which is what the energy plane shows
+pub fn model_en(p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Complex64 {
+    consts.model.energy(en(p, m, consts), 0)
+}
+
+pub fn model_den_dp(p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Complex64 {
+    let p = p.into();
+    consts.model.den_den(en(p, m, consts), 0) * den_dp(p, m, consts)
+}
+
+// Derivatives of p with respect to the other components. These diverge at the
+// branch points where the derivatives above vanish.
+pub fn dp_dxp(
+    p: impl Into<Complex64>,
+    m: f64,
+    consts: CouplingConstants,
+    sheet_data: &SheetData,
+) -> Complex64 {
+    1.0 / dxp_dp_on_sheet(p, m, consts, sheet_data)
+}
+
+pub fn dp_dxm(
+    p: impl Into<Complex64>,
+    m: f64,
+    consts: CouplingConstants,
+    sheet_data: &SheetData,
+) -> Complex64 {
+    1.0 / dxm_dp_on_sheet(p, m, consts, sheet_data)
+}
+
+pub fn dp_du(
+    p: impl Into<Complex64>,
+    consts: CouplingConstants,
+    sheet_data: &SheetData,
+) -> Complex64 {
+    1.0 / du_dp(p, consts, sheet_data)
+}
+
+pub fn dp_den(
+    p: impl Into<Complex64>,
+    m: f64,
+    consts: CouplingConstants,
+    sheet_data: &SheetData,
+) -> Complex64 {
+    1.0 / den_dp_on_sheet(p, m, consts, sheet_data)
+}
+
+// A sheet of the energy E(p). Changing the log branch moves p between the
+// strips n <= Re(p) < n + 1, and each strip has a physical (e_branch = 1) and a
+// crossed (e_branch = -1) sheet. On a sheet p is measured from the start of its
+// strip, so that the sheets can be compared over the same region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct EnergySheet {
+    pub log_branch: i32,
+    pub e_branch: i32,
+}
+
+impl EnergySheet {
+    pub const PHYSICAL: Self = Self {
+        log_branch: 0,
+        e_branch: 1,
+    };
+
+    pub fn new(log_branch: i32, e_branch: i32) -> Self {
+        Self {
+            log_branch,
+            e_branch,
+        }
+    }
+
+    // The sheets for the given log branches, physical before crossed
+    pub fn all(log_branches: std::ops::RangeInclusive<i32>) -> Vec<Self> {
+        log_branches
+            .flat_map(|log_branch| [Self::new(log_branch, 1), Self::new(log_branch, -1)])
+            .collect()
+    }
+
+    // The sheet and reduced momentum of a point with the given p and sheet data
+    pub fn locate(p: Complex64, sheet_data: &SheetData) -> (Self, Complex64) {
+        let log_branch = p.re.floor() as i32;
+        (
+            Self::new(log_branch, sheet_data.e_branch),
+            p - log_branch as f64,
+        )
+    }
+
+    pub fn is_physical(&self) -> bool {
+        self.e_branch > 0
+    }
+
+    pub fn crossed(&self) -> Self {
+        Self::new(self.log_branch, -self.e_branch)
+    }
+
+    pub fn en(&self, p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Complex64 {
+        let en = self.e_branch as f64 * en(p.into() + self.log_branch as f64, m, consts);
+        consts.model.energy(en, 0)
+    }
+
+    pub fn den_dp(&self, p: impl Into<Complex64>, m: f64, consts: CouplingConstants) -> Complex64 {
+        let p = p.into() + self.log_branch as f64;
+        let en = self.e_branch as f64 * en(p, m, consts);
+        consts.model.den_den(en, 0) * self.e_branch as f64 * den_dp(p, m, consts)
+    }
+}
+
+impl std::fmt::Display for EnergySheet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = if self.is_physical() {
+            "physical"
+        } else {
+            "crossed"
+        };
+        write!(f, "{kind} ({:+})", self.log_branch)
+    }
+}
+
 pub fn u_of_x(x: impl Into<Complex64>, consts: CouplingConstants) -> Complex64 {
     let s = consts.s();
 
@@ -307,3 +512,375 @@ pub fn du_dx(x: impl Into<Complex64>, consts: CouplingConstants) -> Complex64 {
 
     (x - s) * (x + 1.0 / s) / (x * x)
 }
+
+// The scallion and the kidney are the curves in the x plane where u(x) has a
+// constant imaginary part. Writing x = r e^(iθ) for the scallion and
+// x = -r e^(iθ) for the kidney this becomes (r - 1/r) sin(θ) = ±(s - 1/s) θ,
+// which can be solved for r. The angle θ runs over (-π, π), with θ = 0 at the
+// branch points s and -1/s. The scallion goes to infinity and the kidney to
+// zero as |θ| approaches π.
+fn x_cut_radius(theta: f64, c: f64) -> f64 {
+    let c = if theta.abs() < 1.0e-8 {
+        c
+    } else {
+        c * theta / theta.sin()
+    };
+    (c + (c * c + 4.0).sqrt()) / 2.0
+}
+
+pub fn scallion(theta: f64, consts: CouplingConstants) -> Complex64 {
+    let s = consts.s();
+    x_cut_radius(theta, s - 1.0 / s) * Complex64::from_polar(1.0, theta)
+}
+
+pub fn kidney(theta: f64, consts: CouplingConstants) -> Complex64 {
+    let s = consts.s();
+    -x_cut_radius(theta, 1.0 / s - s) * Complex64::from_polar(1.0, theta)
+}
+
+// The image of the scallion in the u plane, which is a part of the real line
+pub fn scallion_u(theta: f64, consts: CouplingConstants) -> Complex64 {
+    u_of_x(scallion(theta, consts), consts)
+}
+
+// The image of the kidney in the u plane, with the logarithm continuous along
+// the kidney, i.e. arg(x) = π + θ. This has Im(u) = -k/h.
+pub fn kidney_u(theta: f64, consts: CouplingConstants) -> Complex64 {
+    let s = consts.s();
+    let x = kidney(theta, consts);
+    let log_x = Complex64::new(x.norm().ln(), PI + theta);
+    x + 1.0 / x - (s - 1.0 / s) * log_x
+}
+
+// The single x variable is x(u), with x± = x(u ± i/h). It is found by
+// continuing the root of u(x) = u from x⁺, which solves u(x) = u + i/h. Working
+// with w = ln(x) on the log branch of x⁺ keeps the logarithm continuous.
+pub fn x_from_xp(xp: Complex64, log_branch_p: i32, consts: CouplingConstants) -> Complex64 {
+    const STEPS: usize = 16;
+
+    if xp == Complex64::from(0.0) || !xp.is_finite() {
+        return xp;
+    }
+
+    let s = consts.s();
+    let a = s - 1.0 / s;
+    let f = |w: Complex64| w.exp() + (-w).exp() - a * w;
+    let df = |w: Complex64| w.exp() - (-w).exp() - a;
+
+    let mut w = xp.ln() + Complex64::new(0.0, TAU * log_branch_p as f64);
+    let start = f(w);
+    for step in 1..=STEPS {
+        let target = start - Complex64::i() * (step as f64 / STEPS as f64) / consts.h;
+        match crate::nr::find_root(|w| f(w) - target, df, w, 1.0e-10, 16) {
+            Ok(root) => w = root,
+            Err(_) => return Complex64::new(f64::NAN, f64::NAN),
+        }
+    }
+    w.exp()
+}
+
+// The change in u when x moves from x0 to x1 without crossing the log cut
+// in between
+pub fn u_shift_from_x(x0: Complex64, x1: Complex64, consts: CouplingConstants) -> Complex64 {
+    let s = consts.s();
+    (x1 + 1.0 / x1) - (x0 + 1.0 / x0) - (s - 1.0 / s) * (x1 / x0).ln()
+}
+
+// The integral of dp / E along the straight line from p0 to p1, with E
+// continued from e0 at p0. Returns the integral and the final value of E.
+fn integrate_inverse_en(
+    p0: Complex64,
+    e0: Complex64,
+    p1: Complex64,
+    consts: CouplingConstants,
+) -> (Complex64, Complex64) {
+    let intervals = ((p1 - p0).norm() / 0.05).ceil().clamp(1.0, 4096.0) as usize;
+    let dp = (p1 - p0) / (2 * intervals) as f64;
+
+    // Simpson's rule, with E continued through all the nodes
+    let mut e = e0;
+    let mut sum = 1.0 / e0;
+    for i in 1..=2 * intervals {
+        let next = en(p0 + dp * i as f64, 1.0, consts);
+        e = if (next - e).norm_sqr() <= (next + e).norm_sqr() {
+            next
+        } else {
+            -next
+        };
+        let weight = if i == 2 * intervals {
+            1.0
+        } else if i % 2 == 1 {
+            4.0
+        } else {
+            2.0
+        };
+        sum += weight / e;
+    }
+    (sum * dp / 3.0, e)
+}
+
+// The real period ω of the torus coordinate and the value of ∫ dp / E at the
+// branch point of E in the upper half of the first strip, both computed with E
+// continued from E(0) = 1. The branch point value uses p = p_b (1 - t^2) to
+// remove the square root singularity.
+fn torus_data(consts: CouplingConstants) -> (f64, Option<Complex64>) {
+    thread_local! {
+        static CACHE: std::cell::RefCell<Option<(CouplingConstants, f64, Option<Complex64>)>> =
+            const { std::cell::RefCell::new(None) };
+    }
+
+    if let Some((_, omega, z_b)) = CACHE
+        .with(|cache| *cache.borrow())
+        .filter(|c| c.0 == consts)
+    {
+        return (omega, z_b);
+    }
+
+    let (omega, _) = integrate_inverse_en(
+        Complex64::from(0.0),
+        Complex64::from(1.0),
+        Complex64::from(1.0),
+        consts,
+    );
+    let omega = omega.re;
+
+    let z_b = en_branch_point(0, consts).map(|p_b| {
+        const STEPS: usize = 512;
+        let mut e = Complex64::from(1.0);
+        let mut sum = Complex64::from(0.0);
+        let integrand = |t: f64, e: &mut Complex64| {
+            let p = p_b * (1.0 - t * t);
+            let next = en(p, 1.0, consts);
+            *e = if (next - *e).norm_sqr() <= (next + *e).norm_sqr() {
+                next
+            } else {
+                -next
+            };
+            2.0 * t * p_b / *e
+        };
+        // Midpoint rule from t = 1 (p = 0) down to t = 0 (p = p_b)
+        for i in 0..STEPS {
+            let t = 1.0 - (i as f64 + 0.5) / STEPS as f64;
+            sum += integrand(t, &mut e);
+        }
+        sum / STEPS as f64
+    });
+
+    CACHE.with(|cache| *cache.borrow_mut() = Some((consts, omega, z_b)));
+    (omega, z_b)
+}
+
+// The torus coordinate z = (1/ω) ∫ dp / E of the point (p, E), integrating
+// along the straight line from p = 0 with E(0) = 1. If this ends on the other
+// sign of E the point is reflected in the branch point, which maps one sheet
+// of E onto the other. For k ≠ 0 E is not periodic in p, so z is a coordinate
+// on a covering of the curve rather than on a torus.
+pub fn torus_z(p: Complex64, e: Complex64, consts: CouplingConstants) -> Complex64 {
+    let (omega, _) = torus_data(consts);
+    let (z, e_end) = integrate_inverse_en(Complex64::from(0.0), Complex64::from(1.0), p, consts);
+    if (e_end + e).norm_sqr() < (e_end - e).norm_sqr() {
+        torus_z_reflected(z / omega, consts)
+    } else {
+        z / omega
+    }
+}
+
+// Continue the torus coordinate z0 of (p0, e0) along the straight line to p1.
+// Returns the new coordinate and the continued value of E.
+pub fn torus_z_continued(
+    z0: Complex64,
+    p0: Complex64,
+    e0: Complex64,
+    p1: Complex64,
+    consts: CouplingConstants,
+) -> (Complex64, Complex64) {
+    let (omega, _) = torus_data(consts);
+    let (dz, e1) = integrate_inverse_en(p0, e0, p1, consts);
+    (z0 + dz / omega, e1)
+}
+
+// Continue the torus coordinate z0 of (p0, e0) to the point (p1, e1)
+pub fn torus_z_shifted(
+    z0: Complex64,
+    p0: Complex64,
+    e0: Complex64,
+    p1: Complex64,
+    e1: Complex64,
+    consts: CouplingConstants,
+) -> Complex64 {
+    let (z1, e_end) = torus_z_continued(z0, p0, e0, p1, consts);
+    if (e_end - e1).norm() > 1.0e-3 * e1.norm().max(1.0) {
+        // The straight line passed too close to a branch point to follow E
+        return torus_z(p1, e1, consts);
+    }
+    z1
+}
+
+// The torus coordinate of the point on the other sheet of E, which is the
+// reflection in the branch point
+pub fn torus_z_reflected(z: Complex64, consts: CouplingConstants) -> Complex64 {
+    match torus_data(consts) {
+        (omega, Some(z_b)) => 2.0 * z_b / omega - z,
+        _ => z,
+    }
+}
+
+// The derivative of the torus coordinate with respect to p
+pub fn dtorus_z_dp(e: Complex64, consts: CouplingConstants) -> Complex64 {
+    let (omega, _) = torus_data(consts);
+    1.0 / (omega * e)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum KinematicsModel {
+    #[default]
+    Standard,
+    QDeformed {
+        q: f64,
+    },
+}
+
+impl KinematicsModel {
+    pub fn q_deformed(q: f64) -> Self {
+        Self::QDeformed { q }
+    }
+
+    pub fn is_standard(&self) -> bool {
+        *self == Self::Standard
+    }
+
+    fn log_q(&self) -> Option<f64> {
+        match self {
+            Self::Standard => None,
+            Self::QDeformed { q } => Some(q.ln()).filter(|log_q| log_q.abs() >= 1.0e-8),
+        }
+    }
+
+    // The energy of the model given the energy E of the standard model. The
+    // q-deformed dispersion relation is [E_q]_q = E with
+    // [x]_q = sinh(x log q) / sinh(log q), so that
+    //   E_q = ((-1)^n asinh(sinh(log q) E) + i pi n) / log q
+    // on the branch n. The QLog cuts are the cuts of the asinh, which lie on
+    // the E cuts where |E| > 1 / |sinh(log q)|.
+    pub fn energy(&self, en: Complex64, q_branch: i32) -> Complex64 {
+        let Some(log_q) = self.log_q() else {
+            return en;
+        };
+        let sign = if q_branch % 2 == 0 { 1.0 } else { -1.0 };
+        (sign * (log_q.sinh() * en).asinh() + Complex64::i() * PI * q_branch as f64) / log_q
+    }
+
+    // The derivative of the energy of the model with respect to E
+    pub fn den_den(&self, en: Complex64, q_branch: i32) -> Complex64 {
+        let Some(log_q) = self.log_q() else {
+            return Complex64::from(1.0);
+        };
+        let sign = if q_branch % 2 == 0 { 1.0 } else { -1.0 };
+        let w = log_q.sinh() * en;
+        sign * log_q.sinh() / (log_q * (1.0 + w * w).sqrt())
+    }
+
+    // The value of |E| on the E cuts at which the QLog cuts start
+    pub fn q_log_cut_start(&self) -> Option<f64> {
+        self.log_q().map(|log_q| 1.0 / log_q.sinh().abs())
+    }
+
+    // The branch of the energy after crossing a QLog cut from a point with the
+    // standard energy E. The continuation of asinh(w) across the cut at
+    // w = i y, y > 1 is i pi - asinh(w), and across the one at w = -i y it is
+    // -i pi - asinh(w).
+    pub fn cross_q_log(&self, en: Complex64, q_branch: i32) -> i32 {
+        let Some(log_q) = self.log_q() else {
+            return q_branch;
+        };
+        let sign = if q_branch % 2 == 0 { 1 } else { -1 };
+        if (log_q.sinh() * en).im >= 0.0 {
+            q_branch + sign
+        } else {
+            q_branch - sign
+        }
+    }
+}
+
+impl std::fmt::Display for KinematicsModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Standard => write!(f, "standard"),
+            Self::QDeformed { q } => write!(f, "q-deformed (q={q})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_momenta() -> Vec<Complex64> {
+        vec![
+            Complex64::new(0.1, 0.0),
+            Complex64::new(0.35, 0.05),
+            Complex64::new(-0.4, 0.2),
+            Complex64::new(1.25, -0.1),
+        ]
+    }
+
+    #[test]
+    fn q_deformed_energy_differs_from_standard() {
+        let standard = CouplingConstants::new(2.0, 5);
+        let deformed = standard.with_model(KinematicsModel::q_deformed(2.0));
+        for p in sample_momenta() {
+            let e = model_en(p, 1.0, standard);
+            let e_q = model_en(p, 1.0, deformed);
+            assert!((e - e_q).norm() > 1.0e-2, "E({p}) = {e} is not deformed");
+        }
+    }
+
+    #[test]
+    fn q_deformed_energy_reduces_to_standard() {
+        let standard = CouplingConstants::new(2.0, 5);
+        for p in sample_momenta() {
+            let e = model_en(p, 1.0, standard);
+            let mut previous = f64::INFINITY;
+            for q in [1.1, 1.01, 1.001, 1.0001] {
+                let deformed = standard.with_model(KinematicsModel::q_deformed(q));
+                let difference = (model_en(p, 1.0, deformed) - e).norm();
+                assert!(
+                    difference < previous,
+                    "E_q({p}) does not approach E as q -> 1"
+                );
+                previous = difference;
+            }
+            assert!(previous < 1.0e-6);
+        }
+    }
+
+    #[test]
+    fn q_deformed_energy_solves_dispersion_relation() {
+        let consts = CouplingConstants::new(2.0, 5);
+        let log_q = 2.0f64.ln();
+        let model = KinematicsModel::q_deformed(2.0);
+        for p in sample_momenta() {
+            let e = en(p, 1.0, consts);
+            for q_branch in -2..=2 {
+                let e_q = model.energy(e, q_branch);
+                let q_number = (e_q * log_q).sinh() / log_q.sinh();
+                assert!((q_number - e).norm() < 1.0e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn q_deformed_energy_is_continuous_across_q_log_cuts() {
+        let model = KinematicsModel::q_deformed(2.0);
+        let im = 1.5 * model.q_log_cut_start().unwrap();
+        for q_branch in -2..=2 {
+            for e_cut in [Complex64::new(0.0, im), Complex64::new(0.0, -im)] {
+                let before = e_cut + 1.0e-9;
+                let after = e_cut - 1.0e-9;
+                let crossed = model.cross_q_log(before, q_branch);
+                let jump = (model.energy(after, crossed) - model.energy(before, q_branch)).norm();
+                assert!(jump < 1.0e-6, "E_q jumps by {jump} on branch {q_branch}");
+            }
+        }
+    }
+}
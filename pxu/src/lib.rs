@@ -1,5 +1,7 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+pub mod binary;
+mod cas;
 mod contours;
 mod cut;
 pub mod interpolation;
@@ -10,18 +12,29 @@ mod point;
 mod state;
 
 pub use contours::{
-    compute_branch_point, BranchPointType, Component, Contours, GridLine, GridLineComponent,
+    branch_points, compute_branch_point, BranchPointType, Component, Contours, EnergyLevel,
+    GridLine, GridLineComponent,
 };
-pub use cut::{Cut, CutType};
-pub use kinematics::CouplingConstants;
+pub use cut::{
+    Cut, CutType, CutVisibility, CutVisibilityCheck, CutVisibilityCondition, CutVisibilityKind,
+};
+pub use kinematics::{CouplingConstants, EnergySheet, KinematicsModel};
+pub use nr::{ContinuationError, ContinuationMethod, ContinuationStats};
 pub use path::Path;
-pub use point::Point;
+pub use point::{Point, Snap};
 pub use state::SavedState;
-pub use state::State;
+pub use state::{
+    BoundStateBuilder, Compensation, Conserved, Constraint, InterpolationError, Marker, PointStyle,
+    State, StateDisplay, StateError,
+};
 
 #[derive(Clone, serde::Deserialize, serde::Serialize)]
 pub struct Pxu {
     pub consts: CouplingConstants,
+    // The contours are generated for this model, so it is changed through
+    // set_model, which clears them
+    #[serde(default)]
+    model: KinematicsModel,
     #[serde(skip)]
     pub contours: Contours,
     pub state: State,
@@ -31,14 +44,52 @@ pub struct Pxu {
 
 impl Pxu {
     pub fn new(consts: CouplingConstants) -> Self {
+        Self::new_with_model(consts, KinematicsModel::Standard)
+    }
+
+    pub fn new_with_model(consts: CouplingConstants, model: KinematicsModel) -> Self {
         Self {
             consts,
-            contours: Default::default(),
+            model,
+            contours: Contours::new(),
             state: Default::default(),
             paths: Default::default(),
         }
     }
 
+    /// The coupling constants to use for points, states and paths. These are
+    /// `consts` together with the kinematics model.
+    pub fn kinematic_consts(&self) -> CouplingConstants {
+        self.consts.with_model(self.model)
+    }
+
+    pub fn model(&self) -> KinematicsModel {
+        self.model
+    }
+
+    pub fn set_model(&mut self, model: KinematicsModel) {
+        if self.model != model {
+            self.model = model;
+            self.contours.clear();
+        }
+    }
+
+    pub fn update_contours(&mut self, p_range: i32) -> bool {
+        self.contours
+            .update_with_model(p_range, self.consts, self.model)
+    }
+
+    pub fn update_state(
+        &mut self,
+        active_point: usize,
+        component: Component,
+        new_value: num::complex::Complex64,
+    ) -> Result<(), ContinuationError> {
+        let consts = self.kinematic_consts();
+        self.state
+            .update(active_point, component, new_value, &self.contours, consts)
+    }
+
     pub fn get_path_by_name(&self, name: &str) -> Option<&Path> {
         self.paths.iter().find(|path| path.name == name)
     }
@@ -2,19 +2,221 @@ use num::complex::{Complex, ComplexFloat};
 
 type C = Complex<f64>;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContinuationError {
+    NoConvergence,
+    HitBranchPoint,
+    LeftDomain,
+}
+
+impl std::fmt::Display for ContinuationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::NoConvergence => "Newton-Raphson did not converge",
+            Self::HitBranchPoint => "Hit a branch point",
+            Self::LeftDomain => "Left the domain of the function",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl std::error::Error for ContinuationError {}
+
 pub fn find_root(
     f: impl Fn(C) -> C,
     df: impl Fn(C) -> C,
     guess: C,
     precision_goal: f64,
     max_iterations: usize,
-) -> Option<C> {
+) -> Result<C, ContinuationError> {
+    find_root_with_residual(f, df, guess, precision_goal, max_iterations).map(|(z, _, _)| z)
+}
+
+// Returns the root together with the final residual and the number of
+// iterations it took
+pub fn find_root_with_residual(
+    f: impl Fn(C) -> C,
+    df: impl Fn(C) -> C,
+    guess: C,
+    precision_goal: f64,
+    max_iterations: usize,
+) -> Result<(C, f64, usize), ContinuationError> {
     let mut result = guess;
+    let mut value = f(result);
+    for iteration in 1..=max_iterations {
+        let derivative = df(result);
+        if derivative == C::from(0.0) || !derivative.is_finite() {
+            return Err(ContinuationError::HitBranchPoint);
+        }
+        result -= value / derivative;
+        value = f(result);
+        let residual = value.abs();
+        if !result.is_finite() || !residual.is_finite() {
+            return Err(ContinuationError::LeftDomain);
+        }
+        if residual < precision_goal {
+            return Ok((result, residual, iteration));
+        }
+    }
+    Err(ContinuationError::NoConvergence)
+}
+
+// Same as find_root, but with the function and its derivative evaluated together
+pub fn find_root_with_derivative(
+    fdf: impl Fn(C) -> (C, C),
+    guess: C,
+    precision_goal: f64,
+    max_iterations: usize,
+) -> Result<C, ContinuationError> {
+    let mut result = guess;
+    let (mut value, mut derivative) = fdf(result);
     for _ in 0..max_iterations {
-        result = result - f(result) / df(result);
-        if f(result).abs() < precision_goal {
-            return Some(result);
+        if derivative == C::from(0.0) || !derivative.is_finite() {
+            return Err(ContinuationError::HitBranchPoint);
+        }
+        result -= value / derivative;
+        (value, derivative) = fdf(result);
+        let residual = value.abs();
+        if !result.is_finite() || !residual.is_finite() {
+            return Err(ContinuationError::LeftDomain);
         }
+        if residual < precision_goal {
+            return Ok(result);
+        }
+    }
+    Err(ContinuationError::NoConvergence)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ContinuationMethod {
+    #[default]
+    NewtonRaphson,
+    PredictorCorrector,
+}
+
+// Integrate dp/ds = (z1 - z0) / f'(p) from s = 0 to s = 1 with an adaptive
+// Runge-Kutta-Fehlberg 4(5) step. The result is meant as a guess for a
+// Newton-Raphson correction.
+pub fn predict(
+    df: impl Fn(C) -> C,
+    p0: C,
+    z0: C,
+    z1: C,
+    tolerance: f64,
+    max_steps: usize,
+) -> Result<C, ContinuationError> {
+    let dz = z1 - z0;
+    let rhs = |p: C| -> Result<C, ContinuationError> {
+        let derivative = df(p);
+        if derivative == C::from(0.0) || !derivative.is_finite() {
+            return Err(ContinuationError::HitBranchPoint);
+        }
+        Ok(dz / derivative)
+    };
+
+    let mut p = p0;
+    let mut s = 0.0;
+    let mut h: f64 = 1.0;
+
+    for _ in 0..max_steps {
+        if s >= 1.0 {
+            return Ok(p);
+        }
+        h = h.min(1.0 - s);
+
+        let k1 = h * rhs(p)?;
+        let k2 = h * rhs(p + k1 / 4.0)?;
+        let k3 = h * rhs(p + 3.0 / 32.0 * k1 + 9.0 / 32.0 * k2)?;
+        let k4 = h * rhs(p + 1932.0 / 2197.0 * k1 - 7200.0 / 2197.0 * k2 + 7296.0 / 2197.0 * k3)?;
+        let k5 =
+            h * rhs(p + 439.0 / 216.0 * k1 - 8.0 * k2 + 3680.0 / 513.0 * k3 - 845.0 / 4104.0 * k4)?;
+        let k6 = h * rhs(p - 8.0 / 27.0 * k1 + 2.0 * k2 - 3544.0 / 2565.0 * k3
+            + 1859.0 / 4104.0 * k4
+            - 11.0 / 40.0 * k5)?;
+
+        let p4 = p + 25.0 / 216.0 * k1 + 1408.0 / 2565.0 * k3 + 2197.0 / 4104.0 * k4 - k5 / 5.0;
+        let p5 = p + 16.0 / 135.0 * k1 + 6656.0 / 12825.0 * k3 + 28561.0 / 56430.0 * k4
+            - 9.0 / 50.0 * k5
+            + 2.0 / 55.0 * k6;
+
+        if !p5.is_finite() {
+            return Err(ContinuationError::LeftDomain);
+        }
+
+        let error = (p5 - p4).abs();
+        if error <= tolerance {
+            s += h;
+            p = p5;
+        }
+
+        let factor = if error > 0.0 {
+            0.84 * (tolerance / error).powf(0.25)
+        } else {
+            4.0
+        };
+        h *= factor.clamp(0.1, 4.0);
+    }
+
+    if s >= 1.0 {
+        Ok(p)
+    } else {
+        Err(ContinuationError::NoConvergence)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ContinuationStats {
+    pub max_residual: f64,
+    pub rejected_steps: usize,
+    // The number of Newton-Raphson solves and iterations. These are only used
+    // for diagnostics, and are not stored with the paths.
+    #[serde(skip)]
+    pub solves: usize,
+    #[serde(skip)]
+    pub iterations: usize,
+    #[serde(skip)]
+    pub max_iterations: usize,
+}
+
+impl ContinuationStats {
+    pub fn record_solve(&mut self, residual: f64, iterations: usize) {
+        self.max_residual = self.max_residual.max(residual);
+        self.solves += 1;
+        self.iterations += iterations;
+        self.max_iterations = self.max_iterations.max(iterations);
+    }
+
+    pub fn record_rejection(&mut self) {
+        self.rejected_steps += 1;
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.max_residual = self.max_residual.max(other.max_residual);
+        self.rejected_steps += other.rejected_steps;
+        self.solves += other.solves;
+        self.iterations += other.iterations;
+        self.max_iterations = self.max_iterations.max(other.max_iterations);
+    }
+
+    pub fn mean_iterations(&self) -> f64 {
+        if self.solves == 0 {
+            0.0
+        } else {
+            self.iterations as f64 / self.solves as f64
+        }
+    }
+
+    pub fn is_suspect(&self, tolerance: f64) -> bool {
+        self.rejected_steps > 0 || self.max_residual > tolerance
+    }
+}
+
+impl std::fmt::Display for ContinuationStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "max residual {:.2e}, {} rejected steps",
+            self.max_residual, self.rejected_steps
+        )
     }
-    None
 }
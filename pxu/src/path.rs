@@ -1,16 +1,23 @@
 use itertools::Itertools;
 use num::complex::Complex64;
+use std::borrow::Cow;
+use std::f64::consts::TAU;
 
+use crate::cas::Syntax;
 use crate::kinematics::SheetData;
+use crate::nr::{ContinuationError, ContinuationMethod, ContinuationStats};
 use crate::Component;
 use crate::Contours;
 use crate::CouplingConstants;
+use crate::Point;
 use crate::State;
 
 #[derive(Clone, serde::Deserialize, serde::Serialize)]
 pub struct Path {
     pub segments: Vec<Vec<Segment>>,
     pub name: String,
+    #[serde(default)]
+    pub stats: ContinuationStats,
 }
 
 #[derive(Clone, serde::Deserialize, serde::Serialize)]
@@ -20,13 +27,38 @@ pub struct Segment {
     pub xm: Vec<Complex64>,
     pub u: Vec<Complex64>,
     pub x: Vec<Complex64>,
+    #[serde(default)]
+    pub e: Vec<Complex64>,
+    #[serde(default)]
+    pub z: Vec<Complex64>,
     pub sheet_data: SheetData,
 }
 
+#[derive(Debug, Default)]
+struct ContinuationTracker {
+    stats: ContinuationStats,
+    error: Option<ContinuationError>,
+}
+
+impl ContinuationTracker {
+    fn track(
+        &mut self,
+        state: &mut State,
+        result: Result<(), ContinuationError>,
+    ) -> Result<(), ContinuationError> {
+        self.stats.merge(&state.take_stats());
+        if let Err(err) = result {
+            self.error.get_or_insert(err);
+        }
+        result
+    }
+}
+
+// A part of the segment of the base path with the given index. The states are
+// labelled by their position in [0, 1] along the base path segment.
 #[derive(Debug)]
 struct ConstructedSegment {
-    start: Complex64,
-    end: Complex64,
+    index: usize,
     path: Vec<(f64, State)>,
 }
 
@@ -37,16 +69,25 @@ pub struct BasePath {
     pub component: Component,
     pub excitation: usize,
     pub name: String,
+    // Further excitations moved together with the main one, each along its own
+    // curve with one point for every point of the main path
+    #[serde(default)]
+    pub coordinated: Vec<(usize, Vec<Complex64>)>,
 }
 
-#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct SavedPath {
+    // Paths saved before the version field was introduced have version 0
+    #[serde(default)]
+    pub version: u32,
     pub start: State,
     pub deltas: Vec<[i32; 2]>,
     pub component: Component,
     pub excitation: usize,
     pub consts: crate::CouplingConstants,
     pub name: String,
+    #[serde(default)]
+    pub coordinated: Vec<(usize, Vec<[i32; 2]>)>,
 }
 
 const SCALE_FACTOR: f64 = 100_000.0;
@@ -54,29 +95,235 @@ const SCALE_FACTOR: f64 = 100_000.0;
 impl From<SavedPath> for BasePath {
     fn from(saved_path: SavedPath) -> Self {
         let SavedPath {
+            version: _version,
             start,
             deltas,
             component,
             excitation,
             consts: _consts,
             name,
+            coordinated,
         } = saved_path;
 
-        let mut z = start.points[excitation].get(component);
-        let mut path = vec![z];
-        for dz in deltas {
-            z += Complex64::new(dz[0] as f64 / SCALE_FACTOR, dz[1] as f64 / SCALE_FACTOR);
-            path.push(z);
-        }
+        let path = path_from_deltas(start.points[excitation].get(component), &deltas);
+        let coordinated = coordinated
+            .into_iter()
+            .map(|(excitation, deltas)| {
+                let z = start.points[excitation].get(component);
+                (excitation, path_from_deltas(z, &deltas))
+            })
+            .collect();
 
-        BasePath {
+        let mut base_path = BasePath {
             start,
             path,
             component,
             excitation,
             name,
+            coordinated,
+        };
+        base_path.close();
+        base_path
+    }
+}
+
+fn path_from_deltas(mut z: Complex64, deltas: &[[i32; 2]]) -> Vec<Complex64> {
+    let mut path = vec![z];
+    for dz in deltas {
+        z += Complex64::new(dz[0] as f64 / SCALE_FACTOR, dz[1] as f64 / SCALE_FACTOR);
+        path.push(z);
+    }
+    path
+}
+
+fn path_deltas(path: &[Complex64]) -> Vec<[i32; 2]> {
+    let mut deltas = path
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| b - a)
+        .map(|z| {
+            [
+                (z.re * SCALE_FACTOR).round() as i32,
+                (z.im * SCALE_FACTOR).round() as i32,
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    // The rounding errors add up along the path, so put the accumulated error
+    // in the last step to make closed paths end exactly where they start
+    if is_closed(path) {
+        let [re, im] = deltas
+            .iter()
+            .fold([0, 0], |[re, im], [dre, dim]| [re + dre, im + dim]);
+        if let Some(last) = deltas.last_mut() {
+            last[0] -= re;
+            last[1] -= im;
         }
     }
+
+    deltas
+}
+
+// A path is taken to be closed if its end point is this close to its start
+// point, relative to the length of the path
+const CLOSED_PATH_TOLERANCE: f64 = 1.0e-3;
+
+fn is_closed(path: &[Complex64]) -> bool {
+    let (Some(first), Some(last)) = (path.first(), path.last()) else {
+        return false;
+    };
+    path.len() > 2 && (last - first).norm() < CLOSED_PATH_TOLERANCE * line_length(path)
+}
+
+impl BasePath {
+    pub fn is_closed(&self) -> bool {
+        is_closed(&self.path)
+    }
+
+    // Move the end point of a closed path exactly onto its start point
+    pub fn close(&mut self) -> bool {
+        for (_, path) in self.coordinated.iter_mut() {
+            close(path);
+        }
+        close(&mut self.path)
+    }
+
+    // The excitations moved by the path together with their curves
+    fn curves(&self) -> impl Iterator<Item = (usize, &[Complex64])> {
+        std::iter::once((self.excitation, &self.path[..])).chain(
+            self.coordinated
+                .iter()
+                .map(|(excitation, path)| (*excitation, &path[..])),
+        )
+    }
+
+    // The largest distance any of the excitations moves along the segment with
+    // the given index
+    fn segment_length(&self, index: usize) -> f64 {
+        self.curves()
+            .filter_map(|(_, curve)| Some((curve.get(index + 1)? - curve.get(index)?).norm()))
+            .fold(0.0, f64::max)
+    }
+
+    // Move all excitations to the position t in [0, 1] along the segment with
+    // the given index
+    fn update_state(
+        &self,
+        state: &mut State,
+        index: usize,
+        t: f64,
+        contours: &Contours,
+        consts: CouplingConstants,
+    ) -> Result<(), ContinuationError> {
+        let mut result = Ok(());
+        for (excitation, curve) in self.curves() {
+            let (Some(start), Some(end)) = (curve.get(index), curve.get(index + 1)) else {
+                continue;
+            };
+            let z = start * (1.0 - t) + end * t;
+            let r = state.update(excitation, self.component, z, contours, consts);
+            if result.is_ok() {
+                result = r;
+            }
+        }
+        result
+    }
+}
+
+fn close(path: &mut [Complex64]) -> bool {
+    if !is_closed(path) {
+        return false;
+    }
+    let first = path[0];
+    if let Some(last) = path.last_mut() {
+        *last = first;
+    }
+    true
+}
+
+// The change of the state along a closed path
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monodromy {
+    pub start: State,
+    pub end: State,
+}
+
+impl Monodromy {
+    // Whether all excitations return to where they started on the same sheet
+    pub fn is_trivial(&self) -> bool {
+        self.start
+            .points
+            .iter()
+            .zip(self.end.points.iter())
+            .all(|(pt1, pt2)| {
+                pt1.sheet_data == pt2.sheet_data
+                    && [Component::P, Component::Xp, Component::Xm, Component::U]
+                        .into_iter()
+                        .all(|component| (pt1.get(component) - pt2.get(component)).norm() < 1.0e-6)
+            })
+    }
+}
+
+impl std::fmt::Display for Monodromy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_trivial() {
+            return write!(f, "trivial");
+        }
+
+        let mut changes = vec![];
+        for (i, (pt1, pt2)) in self
+            .start
+            .points
+            .iter()
+            .zip(self.end.points.iter())
+            .enumerate()
+        {
+            let (sd1, sd2) = (&pt1.sheet_data, &pt2.sheet_data);
+            if sd1.log_branch_p != sd2.log_branch_p {
+                changes.push(format!(
+                    "{i}: log_branch_p {} -> {}",
+                    sd1.log_branch_p, sd2.log_branch_p
+                ));
+            }
+            if sd1.log_branch_m != sd2.log_branch_m {
+                changes.push(format!(
+                    "{i}: log_branch_m {} -> {}",
+                    sd1.log_branch_m, sd2.log_branch_m
+                ));
+            }
+            if sd1.e_branch != sd2.e_branch {
+                changes.push(format!(
+                    "{i}: e_branch {} -> {}",
+                    sd1.e_branch, sd2.e_branch
+                ));
+            }
+            if sd1.q_branch != sd2.q_branch {
+                changes.push(format!(
+                    "{i}: q_branch {} -> {}",
+                    sd1.q_branch, sd2.q_branch
+                ));
+            }
+            if sd1.u_branch != sd2.u_branch {
+                changes.push(format!(
+                    "{i}: u_branch ({}, {}) -> ({}, {})",
+                    sd1.u_branch.0, sd1.u_branch.1, sd2.u_branch.0, sd2.u_branch.1
+                ));
+            }
+            if sd1.im_x_sign != sd2.im_x_sign {
+                changes.push(format!(
+                    "{i}: im_x_sign {:?} -> {:?}",
+                    sd1.im_x_sign, sd2.im_x_sign
+                ));
+            }
+            for component in [Component::P, Component::Xp, Component::Xm, Component::U] {
+                let (z1, z2) = (pt1.get(component), pt2.get(component));
+                if (z1 - z2).norm() >= 1.0e-6 {
+                    changes.push(format!("{i}: {component} {z1:.4} -> {z2:.4}"));
+                }
+            }
+        }
+        write!(f, "{}", changes.join(", "))
+    }
 }
 
 impl From<(BasePath, CouplingConstants)> for SavedPath {
@@ -87,31 +334,30 @@ impl From<(BasePath, CouplingConstants)> for SavedPath {
             component,
             excitation,
             name,
+            coordinated,
         } = base_path;
-        let deltas = path
-            .iter()
-            .tuple_windows()
-            .map(|(a, b)| (b - a))
-            .map(|z| {
-                [
-                    (z.re * SCALE_FACTOR).round() as i32,
-                    (z.im * SCALE_FACTOR).round() as i32,
-                ]
-            })
+        let deltas = path_deltas(&path);
+        let coordinated = coordinated
+            .into_iter()
+            .map(|(excitation, path)| (excitation, path_deltas(&path)))
             .collect();
 
         SavedPath {
+            version: SavedPath::VERSION,
             start,
             deltas,
             component,
             excitation,
             consts,
             name,
+            coordinated,
         }
     }
 }
 
 impl SavedPath {
+    pub const VERSION: u32 = 2;
+
     pub fn new(
         name: impl Into<String>,
         path: Vec<Complex64>,
@@ -120,29 +366,78 @@ impl SavedPath {
         excitation: usize,
         consts: CouplingConstants,
     ) -> Self {
-        let deltas = path
-            .into_iter()
-            .tuple_windows()
-            .map(|(a, b)| b - a)
-            .map(|z| {
-                [
-                    (z.re * 100000.0).round() as i32,
-                    (z.im * 100000.0).round() as i32,
-                ]
-            })
-            .collect::<Vec<_>>();
+        let deltas = path_deltas(&path);
 
         let name = name.into();
 
         SavedPath {
+            version: SavedPath::VERSION,
             start,
             deltas,
             component,
             excitation,
             consts,
             name,
+            coordinated: vec![],
+        }
+    }
+
+    // Move a further excitation along the given curve, which should have the
+    // same number of points as the main path
+    pub fn with_coordinated(mut self, excitation: usize, path: Vec<Complex64>) -> Self {
+        self.coordinated.push((excitation, path_deltas(&path)));
+        self
+    }
+
+    // The path reflected in the real axis of its plane, starting from the same
+    // state. This is only possible when the excitations that are moved start
+    // on the real axis, since the reflected path would otherwise start
+    // somewhere else.
+    pub fn conj(mut self, name: impl Into<String>) -> Option<Self> {
+        const TOLERANCE: f64 = 1.0e-9;
+
+        let mut moved = std::iter::once(self.excitation)
+            .chain(self.coordinated.iter().map(|(excitation, _)| *excitation));
+        if !moved.all(|excitation| {
+            self.start
+                .points
+                .get(excitation)
+                .is_some_and(|pt| pt.get(self.component).im.abs() < TOLERANCE)
+        }) {
+            return None;
+        }
+
+        let conj = |deltas: &mut Vec<[i32; 2]>| {
+            for delta in deltas.iter_mut() {
+                delta[1] = -delta[1];
+            }
+        };
+        conj(&mut self.deltas);
+        for (_, deltas) in self.coordinated.iter_mut() {
+            conj(deltas);
+        }
+        self.name = name.into();
+        Some(self)
+    }
+
+    // The path followed backwards from the given state, which should be the
+    // state at the end of the path
+    pub fn reversed(mut self, name: impl Into<String>, start: State) -> Self {
+        let reverse = |deltas: &mut Vec<[i32; 2]>| {
+            deltas.reverse();
+            for delta in deltas.iter_mut() {
+                *delta = [-delta[0], -delta[1]];
+            }
+        };
+        reverse(&mut self.deltas);
+        for (_, deltas) in self.coordinated.iter_mut() {
+            reverse(deltas);
         }
+        self.start = start;
+        self.name = name.into();
+        self
     }
+
     pub fn encode(&self) -> Option<String> {
         ron::to_string(&self).ok()
     }
@@ -158,7 +453,33 @@ impl SavedPath {
         Some(base64::engine::general_purpose::URL_SAFE.encode(data))
     }
 
+    // Bring a path saved by an older version of the format up to date
+    pub fn migrate(mut self) -> Option<Self> {
+        if self.version > Self::VERSION {
+            log::warn!(
+                "Saved path \"{}\" has unknown version {}",
+                self.name,
+                self.version
+            );
+            return None;
+        }
+
+        // The older versions have the same layout, but the points in the start
+        // state might be missing the energies (version 0) or the single x and
+        // the torus coordinate (version 1)
+        if self.version < 2 {
+            self.start = self.start.migrate(self.consts);
+            self.version = 2;
+        }
+
+        Some(self)
+    }
+
     pub fn decode(input: &str) -> Option<Self> {
+        Self::decode_any(input)?.migrate()
+    }
+
+    fn decode_any(input: &str) -> Option<Self> {
         use base64::Engine;
         use std::io::Write;
 
@@ -213,7 +534,31 @@ impl SavedPath {
         Some(base64::engine::general_purpose::URL_SAFE.encode(data))
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_binary(paths: &Vec<Self>) -> Option<Vec<u8>> {
+        crate::binary::encode(paths)
+    }
+
+    // Load paths in the binary format or in any of the text formats
+    pub fn load_bytes(input: &[u8]) -> Option<Vec<Self>> {
+        if crate::binary::is_binary(input) {
+            crate::binary::decode::<Vec<Self>>(input)?
+                .into_iter()
+                .map(Self::migrate)
+                .collect()
+        } else {
+            Self::load(std::str::from_utf8(input).ok()?)
+        }
+    }
+
     pub fn load(input: &str) -> Option<Vec<Self>> {
+        Self::load_any(input)?
+            .into_iter()
+            .map(Self::migrate)
+            .collect()
+    }
+
+    fn load_any(input: &str) -> Option<Vec<Self>> {
         use base64::Engine;
         use std::io::Write;
 
@@ -262,7 +607,9 @@ impl ConstructedSegment {
             let mut xp = vec![];
             let mut xm = vec![];
             let mut u = vec![];
-            let x = vec![];
+            let mut x = vec![];
+            let mut e = vec![];
+            let mut z = vec![];
 
             let sheet_data = self.path[0].1.points[i].sheet_data.clone();
 
@@ -278,6 +625,9 @@ impl ConstructedSegment {
                 xp.push(state.points[i].xp);
                 xm.push(state.points[i].xm);
                 u.push(state.points[i].u);
+                e.push(state.points[i].e);
+                x.push(state.points[i].x);
+                z.push(state.points[i].z);
             }
 
             segments.push(Segment {
@@ -286,6 +636,8 @@ impl ConstructedSegment {
                 xm,
                 u,
                 x,
+                e,
+                z,
                 sheet_data,
             });
         }
@@ -293,19 +645,13 @@ impl ConstructedSegment {
         segments
     }
 
-    fn normalize(&mut self) {
-        let Some(&(t0, _)) = self.path.first() else {
-            return;
-        };
-        let Some(&(t1, _)) = self.path.last() else {
-            return;
-        };
-
-        for entry in self.path.iter_mut() {
-            entry.0 = (entry.0 - t0) / (t1 - t0);
-        }
-    }
-    fn refine(&mut self, base_path: &BasePath, contours: &Contours, consts: CouplingConstants) {
+    fn refine(
+        &mut self,
+        base_path: &BasePath,
+        contours: &Contours,
+        consts: CouplingConstants,
+        tracker: &mut ContinuationTracker,
+    ) {
         let m = self.path[0].1.points.len();
 
         let min_cos = (2.0 * std::f64::consts::TAU / 360.0).cos();
@@ -348,15 +694,11 @@ impl ConstructedSegment {
             }
 
             for (t, state) in refinements.into_iter() {
-                let z = self.start * (1.0 - t) + self.end * t;
                 let mut state = state;
-                state.update(
-                    base_path.excitation,
-                    base_path.component,
-                    z,
-                    contours,
-                    consts,
-                );
+                let result = base_path.update_state(&mut state, self.index, t, contours, consts);
+                if let Err(err) = tracker.track(&mut state, result) {
+                    log::debug!("Could not refine path: {err}");
+                }
 
                 self.path.push((t, state));
             }
@@ -372,10 +714,11 @@ impl ConstructedSegment {
         base_path: &BasePath,
         contours: &Contours,
         consts: CouplingConstants,
+        tracker: &mut ContinuationTracker,
     ) -> Vec<Self> {
         segments
             .iter_mut()
-            .for_each(|segment| segment.refine(base_path, contours, consts));
+            .for_each(|segment| segment.refine(base_path, contours, consts, tracker));
         segments
     }
 
@@ -384,18 +727,16 @@ impl ConstructedSegment {
         base_path: &BasePath,
         contours: &Contours,
         consts: CouplingConstants,
+        tracker: &mut ContinuationTracker,
     ) -> Vec<Self> {
         let mut segments: Vec<ConstructedSegment> = vec![ConstructedSegment {
-            start: self.start,
-            end: self.end,
+            index: self.index,
             path: vec![self.path[0].clone()],
         }];
 
         for ((t1, s1), (t2, s2)) in self.path.into_iter().tuple_windows::<(_, _)>() {
             let mut state = s1.clone();
-
-            let z1 = s1.points[base_path.excitation].get(base_path.component);
-            let z2 = s2.points[base_path.excitation].get(base_path.component);
+            let mut ts = t1;
 
             loop {
                 let s = std::iter::zip(state.points.iter(), s2.points.iter())
@@ -417,60 +758,40 @@ impl ConstructedSegment {
                     break;
                 }
 
-                let zs = state.points[base_path.excitation].get(base_path.component);
-
-                let z = zs + (s - 0.01).clamp(0.0, 1.0) * (z2 - zs);
+                let t = ts + (s - 0.01).clamp(0.0, 1.0) * (t2 - ts);
 
-                if !state.update(
-                    base_path.excitation,
-                    base_path.component,
-                    z,
-                    contours,
-                    consts,
-                ) {
+                let result = base_path.update_state(&mut state, self.index, t, contours, consts);
+                if let Err(err) = tracker.track(&mut state, result) {
                     log::warn!(
-                        "Couldn't update #1 ({} {:?})",
+                        "Couldn't update #1 ({} {:?}): {err}",
                         base_path.excitation,
                         base_path.component
                     );
                     break;
                 }
 
-                let t = t1 + ((z - z1) / (z2 - z1)).re * (t2 - t1);
-
                 segments.last_mut().unwrap().path.push((t, state.clone()));
-                segments.last_mut().unwrap().end =
-                    state.points[base_path.excitation].get(base_path.component);
-
-                let z = zs + (s + 0.01).clamp(0.0, 1.0) * (z2 - zs);
-
-                if !state.update(
-                    base_path.excitation,
-                    base_path.component,
-                    z,
-                    contours,
-                    consts,
-                ) {
+
+                let t = ts + (s + 0.01).clamp(0.0, 1.0) * (t2 - ts);
+
+                let result = base_path.update_state(&mut state, self.index, t, contours, consts);
+                if let Err(err) = tracker.track(&mut state, result) {
                     log::warn!(
-                        "Couldn't update #2 ({} {:?})",
+                        "Couldn't update #2 ({} {:?}): {err}",
                         base_path.excitation,
                         base_path.component,
                     );
                     break;
                 }
 
-                let t = t1 + ((z - z1) / (z2 - z1)).re * (t2 - t1);
-
                 segments.push(ConstructedSegment {
-                    start: state.points[base_path.excitation].get(base_path.component),
-                    end: self.end,
+                    index: self.index,
                     path: vec![(t, state.clone())],
                 });
+                ts = t;
             }
         }
 
-        segments.iter_mut().for_each(|segment| segment.normalize());
-
         segments
     }
 
@@ -479,10 +800,11 @@ impl ConstructedSegment {
         base_path: &BasePath,
         contours: &Contours,
         consts: CouplingConstants,
+        tracker: &mut ContinuationTracker,
     ) -> Vec<Self> {
         let mut result = vec![];
         for segment in segments {
-            result.extend(segment.split(base_path, contours, consts));
+            result.extend(segment.split(base_path, contours, consts, tracker));
         }
         result
     }
@@ -494,43 +816,80 @@ impl Path {
         contours: &Contours,
         consts: CouplingConstants,
     ) -> Self {
+        Self::build(base_path, contours, consts, ContinuationMethod::default()).0
+    }
+
+    pub fn from_base_path_with_method(
+        base_path: BasePath,
+        contours: &Contours,
+        consts: CouplingConstants,
+        method: ContinuationMethod,
+    ) -> Self {
+        Self::build(base_path, contours, consts, method).0
+    }
+
+    pub fn try_from_base_path(
+        base_path: BasePath,
+        contours: &Contours,
+        consts: CouplingConstants,
+    ) -> Result<Self, ContinuationError> {
+        match Self::build(base_path, contours, consts, ContinuationMethod::default()) {
+            (path, None) => Ok(path),
+            (_, Some(err)) => Err(err),
+        }
+    }
+
+    fn build(
+        base_path: BasePath,
+        contours: &Contours,
+        consts: CouplingConstants,
+        method: ContinuationMethod,
+    ) -> (Self, Option<ContinuationError>) {
         let mut state = base_path.start.clone();
+        state.take_stats();
+        state.method = method;
 
         let mut segments = vec![];
+        let mut tracker = ContinuationTracker::default();
 
         let max_step = match base_path.component {
             Component::P => 0.05,
             Component::Xp | Component::Xm => 0.1,
             Component::U => 0.5 / consts.h,
+            Component::E => 0.1,
+            Component::X => 0.1,
+            Component::MirrorP => 0.1 / TAU,
+            Component::MirrorE => 0.05 * TAU,
+            Component::Torus => 0.05,
         };
 
-        for (start, end) in base_path.path.iter().tuple_windows() {
+        // The bound state constraint would otherwise move the coordinated
+        // excitations along with the main one
+        if !base_path.coordinated.is_empty() {
+            state.unlocked = true;
+        }
+
+        for index in 0..base_path.path.len().saturating_sub(1) {
             let mut path = vec![];
-            let steps = ((end - start).norm() / max_step).ceil() as usize;
+            let steps = (base_path.segment_length(index) / max_step).ceil() as usize;
 
             for step in 0..=steps {
                 let t = step as f64 / steps as f64;
-                let z = start * (1.0 - t) + end * t;
-                state.update(
-                    base_path.excitation,
-                    base_path.component,
-                    z,
-                    contours,
-                    consts,
-                );
+                let result = base_path.update_state(&mut state, index, t, contours, consts);
+                if let Err(err) = tracker.track(&mut state, result) {
+                    log::debug!("Could not follow path: {err}");
+                }
 
                 path.push((t, state.clone()));
             }
 
-            segments.push(ConstructedSegment {
-                start: *start,
-                end: *end,
-                path,
-            })
+            segments.push(ConstructedSegment { index, path })
         }
 
-        segments = ConstructedSegment::split_all(segments, &base_path, contours, consts);
-        segments = ConstructedSegment::refine_all(segments, &base_path, contours, consts);
+        segments =
+            ConstructedSegment::split_all(segments, &base_path, contours, consts, &mut tracker);
+        segments =
+            ConstructedSegment::refine_all(segments, &base_path, contours, consts, &mut tracker);
 
         let segments = segments
             .into_iter()
@@ -556,10 +915,20 @@ impl Path {
             segments[0].iter().map(|s| s.u.len()).sum::<usize>(),
         );
 
-        Self {
-            name: base_path.name,
-            segments,
+        let ContinuationTracker { stats, error } = tracker;
+
+        if stats.rejected_steps > 0 {
+            log::warn!("Path \"{}\": {stats}", base_path.name);
         }
+
+        (
+            Self {
+                name: base_path.name,
+                segments,
+                stats,
+            },
+            error,
+        )
     }
 
     pub fn first_coordinate(&self, component: Component, active_point: usize) -> Option<Complex64> {
@@ -572,6 +941,219 @@ impl Path {
         seg.get(component).last().cloned()
     }
 
+    // The state at the start of the path
+    pub fn first_state(&self) -> Option<State> {
+        let points = self
+            .segments
+            .iter()
+            .map(|segs| segs.first()?.first_point())
+            .collect::<Option<Vec<_>>>()?;
+        Some(State {
+            points,
+            ..Default::default()
+        })
+    }
+
+    // The state at the end of the path
+    pub fn last_state(&self) -> Option<State> {
+        let points = self
+            .segments
+            .iter()
+            .map(|segs| segs.last()?.last_point())
+            .collect::<Option<Vec<_>>>()?;
+        Some(State {
+            points,
+            ..Default::default()
+        })
+    }
+
+    // The monodromy of the path, if the given component of the given excitation
+    // follows a closed curve
+    pub fn monodromy(&self, component: Component, excitation: usize) -> Option<Monodromy> {
+        let path = self
+            .segments
+            .get(excitation)?
+            .iter()
+            .flat_map(|seg| seg.get(component).into_owned())
+            .collect::<Vec<_>>();
+        if !is_closed(&path) {
+            return None;
+        }
+        Some(Monodromy {
+            start: self.first_state()?,
+            end: self.last_state()?,
+        })
+    }
+
+    pub fn split_at_cuts(&self, contours: &Contours, consts: CouplingConstants) -> Vec<Self> {
+        let len = self
+            .segments
+            .iter()
+            .map(|segs| segs.len())
+            .min()
+            .unwrap_or(0);
+
+        let crosses_cut = |j: usize| {
+            self.segments.iter().any(|segs| {
+                let (seg1, seg2) = (&segs[j - 1], &segs[j]);
+                if seg1.sheet_data != seg2.sheet_data {
+                    return true;
+                }
+                let Some(pt) = seg1.last_point() else {
+                    return false;
+                };
+                [Component::P, Component::Xp, Component::Xm, Component::U]
+                    .into_iter()
+                    .any(|component| {
+                        seg2.get(component).first().is_some_and(|&z| {
+                            !contours
+                                .get_crossed_cuts(&pt, component, z, consts)
+                                .is_empty()
+                        })
+                    })
+            })
+        };
+
+        let mut boundaries = vec![0];
+        boundaries.extend((1..len).filter(|&j| crosses_cut(j)));
+        boundaries.push(len);
+
+        boundaries
+            .into_iter()
+            .tuple_windows()
+            .enumerate()
+            .map(|(i, (start, end))| Self {
+                segments: self
+                    .segments
+                    .iter()
+                    .map(|segs| segs[start..end].to_vec())
+                    .collect(),
+                name: format!("{} [{i}]", self.name),
+                stats: self.stats,
+            })
+            .collect()
+    }
+
+    // The state at the normalized position t in [0, 1] along the path. The
+    // position is measured by the arc length of p, and all other components are
+    // computed from p on the sheet of the segment, so they always agree with
+    // each other (this ignores any swap_xp_xm).
+    pub fn sample(&self, t: f64, consts: CouplingConstants) -> State {
+        let len = self
+            .segments
+            .iter()
+            .map(|segs| segs.len())
+            .min()
+            .unwrap_or(0);
+        if len == 0 {
+            return State::default();
+        }
+
+        let lengths = (0..len)
+            .map(|j| {
+                self.segments
+                    .iter()
+                    .map(|segs| line_length(&segs[j].p))
+                    .sum::<f64>()
+            })
+            .collect::<Vec<_>>();
+        let total_len = lengths.iter().sum::<f64>();
+
+        let mut pos = t.clamp(0.0, 1.0) * total_len;
+        let mut index = len - 1;
+        let mut s = 1.0;
+        for (j, &seg_len) in lengths.iter().enumerate() {
+            if pos <= seg_len {
+                index = j;
+                s = if seg_len > 0.0 { pos / seg_len } else { 0.0 };
+                break;
+            }
+            pos -= seg_len;
+        }
+
+        let points = self
+            .segments
+            .iter()
+            .map(|segs| {
+                let seg = &segs[index];
+                let p = point_on_line(&seg.p, s);
+                Point::on_sheet(p, &seg.sheet_data, consts)
+            })
+            .collect();
+
+        State {
+            points,
+            ..Default::default()
+        }
+    }
+
+    // The position in [0, 1] along the path, as used by sample, where the given
+    // excitation comes closest to z in the given plane
+    pub fn nearest(
+        &self,
+        component: Component,
+        excitation: usize,
+        z: Complex64,
+        consts: CouplingConstants,
+    ) -> f64 {
+        const SAMPLES: usize = 256;
+        const REFINEMENTS: usize = 24;
+
+        let distance = |t: f64| {
+            self.sample(t, consts)
+                .points
+                .get(excitation)
+                .map_or(f64::INFINITY, |pt| (pt.get(component) - z).norm())
+        };
+
+        let step = 1.0 / SAMPLES as f64;
+        let (best, _) = (0..=SAMPLES)
+            .map(|i| {
+                let t = i as f64 * step;
+                (t, distance(t))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or((0.0, 0.0));
+
+        // Narrow down the position between the neighbouring samples
+        let (mut lo, mut hi) = ((best - step).max(0.0), (best + step).min(1.0));
+        for _ in 0..REFINEMENTS {
+            let t1 = lo + (hi - lo) / 3.0;
+            let t2 = hi - (hi - lo) / 3.0;
+            if distance(t1) < distance(t2) {
+                hi = t2;
+            } else {
+                lo = t1;
+            }
+        }
+        (lo + hi) / 2.0
+    }
+
+    // One list per excitation, containing one list of rules per segment
+    pub fn to_mathematica(&self) -> String {
+        self.to_cas(Syntax::Mathematica)
+    }
+
+    pub fn to_maple(&self) -> String {
+        self.to_cas(Syntax::Maple)
+    }
+
+    fn to_cas(&self, syntax: Syntax) -> String {
+        syntax.long_list(self.segments.iter().map(|segments| {
+            syntax.long_list(segments.iter().map(|segment| {
+                let mut rules = vec![
+                    syntax.rule("p", syntax.complex_list(&segment.p)),
+                    syntax.rule("xp", syntax.complex_list(&segment.xp)),
+                    syntax.rule("xm", syntax.complex_list(&segment.xm)),
+                    syntax.rule("u", syntax.complex_list(&segment.u)),
+                    syntax.rule("en", syntax.complex_list(&segment.e)),
+                ];
+                rules.extend(syntax.sheet_data_rules(&segment.sheet_data));
+                syntax.list(rules)
+            }))
+        }))
+    }
+
     pub fn swap_xp_xm(&mut self) {
         for segs in self.segments.iter_mut() {
             for seg in segs.iter_mut() {
@@ -581,6 +1163,34 @@ impl Path {
     }
 }
 
+fn line_length(points: &[Complex64]) -> f64 {
+    points
+        .iter()
+        .tuple_windows()
+        .map(|(z1, z2)| (z2 - z1).norm())
+        .sum()
+}
+
+// The point at the fraction s of the arc length along the line
+fn point_on_line(points: &[Complex64], s: f64) -> Complex64 {
+    let Some(&first) = points.first() else {
+        return Complex64::default();
+    };
+    let mut pos = s * line_length(points);
+    for (z1, z2) in points.iter().tuple_windows() {
+        let d = (z2 - z1).norm();
+        if pos <= d {
+            return if d > 0.0 {
+                z1 + (z2 - z1) * (pos / d)
+            } else {
+                *z1
+            };
+        }
+        pos -= d;
+    }
+    *points.last().unwrap_or(&first)
+}
+
 impl Segment {
     fn simplify_line(points: &mut Vec<Complex64>) {
         if points.len() < 2 {
@@ -605,18 +1215,54 @@ impl Segment {
         Self::simplify_line(&mut self.xp);
         Self::simplify_line(&mut self.xm);
         Self::simplify_line(&mut self.u);
+        Self::simplify_line(&mut self.e);
+        Self::simplify_line(&mut self.x);
+        Self::simplify_line(&mut self.z);
     }
 
-    pub fn get(&self, component: Component) -> &Vec<Complex64> {
+    // The mirror components are rescaled copies of p and E, so they are not
+    // stored
+    pub fn get(&self, component: Component) -> Cow<'_, [Complex64]> {
         match component {
-            Component::P => &self.p,
-            Component::Xp => &self.xp,
-            Component::Xm => &self.xm,
-            Component::U => &self.u,
+            Component::P => Cow::Borrowed(&self.p),
+            Component::Xp => Cow::Borrowed(&self.xp),
+            Component::Xm => Cow::Borrowed(&self.xm),
+            Component::U => Cow::Borrowed(&self.u),
+            Component::E => Cow::Borrowed(&self.e),
+            Component::X => Cow::Borrowed(&self.x),
+            Component::Torus => Cow::Borrowed(&self.z),
+            Component::MirrorP => self.e.iter().map(|e| -Complex64::i() * e / TAU).collect(),
+            Component::MirrorE => self.p.iter().map(|p| -Complex64::i() * TAU * p).collect(),
         }
     }
 
     pub fn swap_xp_xm(&mut self) {
         std::mem::swap(&mut self.xp, &mut self.xm);
     }
+
+    fn first_point(&self) -> Option<Point> {
+        Some(Point {
+            p: *self.p.first()?,
+            xp: *self.xp.first()?,
+            xm: *self.xm.first()?,
+            u: *self.u.first()?,
+            e: self.e.first().copied().unwrap_or_default(),
+            x: self.x.first().copied().unwrap_or_default(),
+            z: self.z.first().copied().unwrap_or_default(),
+            sheet_data: self.sheet_data.clone(),
+        })
+    }
+
+    fn last_point(&self) -> Option<Point> {
+        Some(Point {
+            p: *self.p.last()?,
+            xp: *self.xp.last()?,
+            xm: *self.xm.last()?,
+            u: *self.u.last()?,
+            e: self.e.last().copied().unwrap_or_default(),
+            x: self.x.last().copied().unwrap_or_default(),
+            z: self.z.last().copied().unwrap_or_default(),
+            sheet_data: self.sheet_data.clone(),
+        })
+    }
 }
@@ -1,22 +1,97 @@
 use crate::contours::Component;
 use crate::cut::{Cut, CutType};
 use crate::kinematics::{
-    du_dp, dxm_dp_on_sheet, dxp_dp_on_sheet, u, xm, xm_on_sheet, xp, xp_on_sheet,
-    CouplingConstants, SheetData, UBranch,
+    den_dp_on_sheet, dtorus_z_dp, du_dp, du_dx, dxm_dp_on_sheet, dxp_dp_on_sheet, en, en_on_sheet,
+    torus_z, torus_z_shifted, u, u_shift_from_x, undeformed_en_on_sheet, x_from_xp, xm,
+    xm_on_sheet, xp, xp_on_sheet, CouplingConstants, SheetData, UBranch,
 };
-use crate::nr;
+use crate::nr::{self, ContinuationError, ContinuationMethod, ContinuationStats};
 use num::complex::Complex64;
+use std::f64::consts::TAU;
 
 fn _c_zero() -> Complex64 {
     Complex64::from(0.0)
 }
 
+// The distance in units of 1/h from the lines Im u = n/h that a point snapped
+// to one of these lines is kept at
+const U_SNAP_OFFSET: f64 = 1.0e-3;
+
+// Exact loci a point can be projected onto
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Snap {
+    RealP,
+    QuantizedImU,
+    UnitCircle(Component),
+    RealX(Component),
+}
+
+impl Snap {
+    // The most natural snap target for a value of the given component
+    pub fn nearest(component: Component, z: Complex64) -> Option<Self> {
+        match component {
+            Component::P => Some(Self::RealP),
+            Component::U => Some(Self::QuantizedImU),
+            Component::Xp | Component::Xm => {
+                if (z.norm() - 1.0).abs() < z.im.abs() {
+                    Some(Self::UnitCircle(component))
+                } else {
+                    Some(Self::RealX(component))
+                }
+            }
+            Component::E
+            | Component::X
+            | Component::MirrorP
+            | Component::MirrorE
+            | Component::Torus => None,
+        }
+    }
+
+    pub fn component(&self) -> Component {
+        match self {
+            Self::RealP => Component::P,
+            Self::QuantizedImU => Component::U,
+            Self::UnitCircle(component) | Self::RealX(component) => *component,
+        }
+    }
+
+    pub fn project(&self, z: Complex64, consts: CouplingConstants) -> Complex64 {
+        match self {
+            Self::RealP | Self::RealX(_) => Complex64::from(z.re),
+            Self::QuantizedImU => {
+                // The cuts in the u plane lie on these lines, so the point is kept
+                // just off the line, on the side it started from
+                let line = (consts.h * z.im).round() / consts.h;
+                let offset = U_SNAP_OFFSET / consts.h;
+                if z.im < line {
+                    Complex64::new(z.re, line - offset)
+                } else {
+                    Complex64::new(z.re, line + offset)
+                }
+            }
+            Self::UnitCircle(_) => {
+                if z.norm() == 0.0 {
+                    Complex64::from(1.0)
+                } else {
+                    z / z.norm()
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Point {
     pub p: Complex64,
     pub xp: Complex64,
     pub xm: Complex64,
     pub u: Complex64,
+    #[serde(default = "_c_zero")]
+    pub e: Complex64,
+    #[serde(default = "_c_zero")]
+    pub x: Complex64,
+    #[serde(default = "_c_zero")]
+    pub z: Complex64,
     pub sheet_data: SheetData,
 }
 
@@ -39,30 +114,74 @@ impl Point {
             e_branch: 1,
             u_branch,
             im_x_sign: (1, 1),
+            q_branch: 0,
         };
 
         let xp = xp(p, 1.0, consts);
         let xm = xm(p, 1.0, consts);
         let u = u(p, consts, &sheet_data);
+        let e = en_on_sheet(p, 1.0, consts, &sheet_data);
+        let x = x_from_xp(xp, log_branch_p, consts);
+        let z = torus_z(p, en(p, 1.0, consts), consts);
         Self {
             p,
             xp,
             xm,
             u,
+            e,
+            x,
+            z,
             sheet_data,
         }
     }
 
+    // The point with the given value of p on the given sheet
+    pub(crate) fn on_sheet(
+        p: Complex64,
+        sheet_data: &SheetData,
+        consts: CouplingConstants,
+    ) -> Self {
+        let xp = xp_on_sheet(p, 1.0, consts, sheet_data);
+        let e = en_on_sheet(p, 1.0, consts, sheet_data);
+        Self {
+            p,
+            xp,
+            xm: xm_on_sheet(p, 1.0, consts, sheet_data),
+            u: u(p, consts, sheet_data),
+            e,
+            x: x_from_xp(xp, sheet_data.log_branch_p, consts),
+            z: torus_z(
+                p,
+                undeformed_en_on_sheet(p, 1.0, consts, sheet_data),
+                consts,
+            ),
+            sheet_data: sheet_data.clone(),
+        }
+    }
+
+    // The single x and the torus coordinate are derived from the other
+    // components, so they can be recomputed for points saved without them
+    pub(crate) fn compute_derived(&mut self, consts: CouplingConstants) {
+        self.x = x_from_xp(self.xp, self.sheet_data.log_branch_p, consts);
+        self.z = torus_z(self.p, self.undeformed_e(consts), consts);
+    }
+
+    // The torus is built from the energy of the standard model, which differs
+    // from e in the q-deformed model
+    fn undeformed_e(&self, consts: CouplingConstants) -> Complex64 {
+        undeformed_en_on_sheet(self.p, 1.0, consts, &self.sheet_data)
+    }
+
     fn shifted(
         &self,
-        p: Option<Complex64>,
+        p: Complex64,
         sheet_data: &SheetData,
         consts: CouplingConstants,
-    ) -> Option<Self> {
-        let p = p?;
+    ) -> Result<Self, ContinuationError> {
         let new_xp = xp_on_sheet(p, 1.0, consts, sheet_data);
         let new_xm = xm_on_sheet(p, 1.0, consts, sheet_data);
         let new_u = u(p, consts, sheet_data);
+        let new_e = en_on_sheet(p, 1.0, consts, sheet_data);
 
         if (self.p - p).re.abs() > 0.125 || (self.p - p).im.abs() > 0.25 {
             log::debug!(
@@ -70,12 +189,12 @@ impl Point {
                 (self.p - p).norm_sqr(),
                 (self.p - p).re.abs()
             );
-            return None;
+            return Err(ContinuationError::NoConvergence);
         }
 
         if (p - p.re.round()).norm() < 0.005 {
             log::debug!("Too close to the origin");
-            return None;
+            return Err(ContinuationError::HitBranchPoint);
         }
 
         if (self.xp - new_xp).norm_sqr() > 16.0 / (consts.h * consts.h) {
@@ -110,12 +229,25 @@ impl Point {
         let xp = new_xp;
         let xm = new_xm;
         let u = new_u;
+        let e = new_e;
+        let x = x_from_xp(xp, sheet_data.log_branch_p, consts);
+        let z = torus_z_shifted(
+            self.z,
+            self.p,
+            self.undeformed_e(consts),
+            p,
+            undeformed_en_on_sheet(p, 1.0, consts, &sheet_data),
+            consts,
+        );
 
-        Some(Self {
+        Ok(Self {
             p,
             xp,
             xm,
             u,
+            e,
+            x,
+            z,
             sheet_data,
         })
     }
@@ -126,8 +258,8 @@ impl Point {
         sheet_data: &SheetData,
         guess: Complex64,
         consts: CouplingConstants,
-    ) -> Option<Complex64> {
-        nr::find_root(
+    ) -> Result<(Complex64, f64, usize), ContinuationError> {
+        nr::find_root_with_residual(
             |p| xp_on_sheet(p, 1.0, consts, sheet_data) - new_xp,
             |p| dxp_dp_on_sheet(p, 1.0, consts, sheet_data),
             guess,
@@ -142,8 +274,8 @@ impl Point {
         sheet_data: &SheetData,
         guess: Complex64,
         consts: CouplingConstants,
-    ) -> Option<Complex64> {
-        nr::find_root(
+    ) -> Result<(Complex64, f64, usize), ContinuationError> {
+        nr::find_root_with_residual(
             |p| xm_on_sheet(p, 1.0, consts, sheet_data) - new_xm,
             |p| dxm_dp_on_sheet(p, 1.0, consts, sheet_data),
             guess,
@@ -158,8 +290,8 @@ impl Point {
         sheet_data: &SheetData,
         guess: Complex64,
         consts: CouplingConstants,
-    ) -> Option<Complex64> {
-        nr::find_root(
+    ) -> Result<(Complex64, f64, usize), ContinuationError> {
+        nr::find_root_with_residual(
             |p| u(p, consts, sheet_data) - new_u,
             |p| du_dp(p, consts, sheet_data),
             guess,
@@ -168,12 +300,73 @@ impl Point {
         )
     }
 
+    fn shift_e(
+        &self,
+        new_e: Complex64,
+        sheet_data: &SheetData,
+        guess: Complex64,
+        consts: CouplingConstants,
+    ) -> Result<(Complex64, f64, usize), ContinuationError> {
+        nr::find_root_with_residual(
+            |p| en_on_sheet(p, 1.0, consts, sheet_data) - new_e,
+            |p| den_dp_on_sheet(p, 1.0, consts, sheet_data),
+            guess,
+            1.0e-6,
+            50,
+        )
+    }
+
     pub fn get(&self, component: Component) -> Complex64 {
         match component {
             Component::P => self.p,
             Component::U => self.u,
             Component::Xp => self.xp,
             Component::Xm => self.xm,
+            Component::E => self.e,
+            Component::X => self.x,
+            Component::MirrorP => -Complex64::i() * self.e / TAU,
+            Component::MirrorE => -Complex64::i() * TAU * self.p,
+            Component::Torus => self.z,
+        }
+    }
+
+    // The single x, the mirror components and the torus coordinate are moved
+    // by moving one of the components that the continuation works with. This
+    // returns that component together with its new value.
+    pub fn base_target(
+        &self,
+        component: Component,
+        new_value: Complex64,
+        consts: CouplingConstants,
+    ) -> (Component, Complex64) {
+        match component {
+            Component::P | Component::Xp | Component::Xm | Component::U | Component::E => {
+                (component, new_value)
+            }
+            Component::X => (
+                Component::U,
+                self.u + u_shift_from_x(self.x, new_value, consts),
+            ),
+            Component::MirrorP => (Component::E, Complex64::i() * TAU * new_value),
+            Component::MirrorE => (Component::P, Complex64::i() * new_value / TAU),
+            Component::Torus => {
+                // Newton's method in p, with z continued from this point
+                let mut p = self.p;
+                let mut e = self.undeformed_e(consts);
+                let mut z = self.z;
+                for _ in 0..8 {
+                    let dz = new_value - z;
+                    if dz.norm() < 1.0e-9 {
+                        break;
+                    }
+                    let next_p = p + dz / dtorus_z_dp(e, consts);
+                    let next_e = continued_en(next_p, e, consts);
+                    z = torus_z_shifted(z, p, e, next_p, next_e, consts);
+                    p = next_p;
+                    e = next_e;
+                }
+                (Component::P, p)
+            }
         }
     }
 
@@ -183,7 +376,53 @@ impl Point {
         new_value: Complex64,
         crossed_cuts: &[&Cut],
         consts: CouplingConstants,
-    ) -> bool {
+    ) -> Result<(), ContinuationError> {
+        let mut stats = ContinuationStats::default();
+        self.update_tracked(
+            component,
+            new_value,
+            crossed_cuts,
+            consts,
+            ContinuationMethod::default(),
+            &mut stats,
+        )
+    }
+
+    // An initial guess for the corrector, found by integrating dp = dz / z'(p)
+    // from the current point to the new value
+    fn predict(
+        &self,
+        component: Component,
+        new_value: Complex64,
+        sheet_data: &SheetData,
+        consts: CouplingConstants,
+    ) -> Result<Complex64, ContinuationError> {
+        let predict = |df: &dyn Fn(Complex64) -> Complex64| {
+            nr::predict(df, self.p, self.get(component), new_value, 1.0e-6, 64)
+        };
+
+        match component {
+            Component::P => Ok(new_value),
+            Component::Xp => predict(&|p| dxp_dp_on_sheet(p, 1.0, consts, sheet_data)),
+            Component::Xm => predict(&|p| dxm_dp_on_sheet(p, 1.0, consts, sheet_data)),
+            Component::U => predict(&|p| du_dp(p, consts, sheet_data)),
+            Component::E => predict(&|p| den_dp_on_sheet(p, 1.0, consts, sheet_data)),
+            Component::X | Component::MirrorP | Component::MirrorE | Component::Torus => {
+                unreachable!("{component} is moved through base_target")
+            }
+        }
+    }
+
+    pub fn update_tracked(
+        &mut self,
+        component: Component,
+        new_value: Complex64,
+        crossed_cuts: &[&Cut],
+        consts: CouplingConstants,
+        method: ContinuationMethod,
+        stats: &mut ContinuationStats,
+    ) -> Result<(), ContinuationError> {
+        let (component, new_value) = self.base_target(component, new_value, consts);
         let mut new_sheet_data = self.sheet_data.clone();
         for cut in crossed_cuts {
             match cut.typ {
@@ -234,11 +473,69 @@ impl Point {
                 CutType::ULongPositive(Component::Xm) => {
                     new_sheet_data.im_x_sign.1 = -new_sheet_data.im_x_sign.1;
                 }
+                CutType::QLog => {
+                    new_sheet_data.q_branch = consts
+                        .model()
+                        .cross_q_log(self.undeformed_e(consts), new_sheet_data.q_branch);
+                }
                 _ => {}
             }
             log::debug!("Intersection with {:?}: {:?}", cut.typ, new_sheet_data);
         }
 
+        let solve = |guess: Complex64| {
+            let root = match component {
+                Component::P => Ok((new_value, 0.0, 0)),
+                Component::Xp => self.shift_xp(new_value, &new_sheet_data, guess, consts),
+                Component::Xm => self.shift_xm(new_value, &new_sheet_data, guess, consts),
+                Component::U => self.shift_u(new_value, &new_sheet_data, guess, consts),
+                Component::E => self.shift_e(new_value, &new_sheet_data, guess, consts),
+                Component::X | Component::MirrorP | Component::MirrorE | Component::Torus => {
+                    unreachable!("{component} is moved through base_target")
+                }
+            };
+
+            root.and_then(|(p, residual, iterations)| {
+                self.shifted(p, &new_sheet_data, consts)
+                    .map(|pt| (pt, residual, iterations))
+            })
+        };
+
+        // Of all the roots that are found the one closest to the current point is used
+        let distance = |pt: &Point| {
+            (((pt.xp - self.xp).norm_sqr() + (pt.xm - self.xm).norm_sqr()) * 10000.0).round() as i32
+        };
+
+        // The derivatives change discontinuously across the cuts, so the predictor
+        // is only used for steps that stay on one sheet
+        let predicted =
+            if method == ContinuationMethod::PredictorCorrector && crossed_cuts.is_empty() {
+                match self.predict(component, new_value, &new_sheet_data, consts) {
+                    Ok(guess) => Some(guess),
+                    Err(err) => {
+                        log::debug!("Predictor failed ({err}), falling back to Newton-Raphson");
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+        // The prediction is the only start of the corrector, and its root is used
+        // when it stays close to the prediction. Otherwise all the guesses around
+        // the current point are tried.
+        if let Some(guess) = predicted {
+            match solve(guess) {
+                Ok((pt, residual, iterations)) if (pt.p - guess).norm() < 1.0e-2 => {
+                    *self = pt;
+                    stats.record_solve(residual, iterations);
+                    return Ok(());
+                }
+                Ok(_) => log::debug!("The corrector moved away from the prediction"),
+                Err(err) => log::debug!("The corrector failed ({err})"),
+            }
+        }
+
         let guesses = [
             self.p,
             self.p - 0.01,
@@ -249,30 +546,100 @@ impl Point {
             self.p + 0.1,
         ];
 
-        if let Some(pt) = guesses
-            .into_iter()
-            .filter_map(|guess| {
-                let p = match component {
-                    Component::P => Some(new_value),
-                    Component::Xp => self.shift_xp(new_value, &new_sheet_data, guess, consts),
-                    Component::Xm => self.shift_xm(new_value, &new_sheet_data, guess, consts),
-                    Component::U => self.shift_u(new_value, &new_sheet_data, guess, consts),
-                };
+        let mut first_error = None;
 
-                self.shifted(p, &new_sheet_data, consts)
-            })
-            .min_by_key(|pt| {
-                (((pt.xp - self.xp).norm_sqr() + (pt.xm - self.xm).norm_sqr()) * 10000.0).round()
-                    as i32
+        let best = guesses
+            .into_iter()
+            .filter_map(|guess| match solve(guess) {
+                Ok(value) => Some(value),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                    None
+                }
             })
-        {
+            .min_by_key(|(pt, _, _)| distance(pt));
+
+        if let Some((pt, residual, iterations)) = best {
             *self = pt;
-            true
+            stats.record_solve(residual, iterations);
+            Ok(())
         } else {
-            false
+            stats.record_rejection();
+            Err(first_error.unwrap_or(ContinuationError::NoConvergence))
         }
     }
 
+    // Solve for the value of the component to full precision without leaving
+    // the current sheet, and recompute all the components from the result
+    pub(crate) fn refine(
+        &mut self,
+        component: Component,
+        value: Complex64,
+        consts: CouplingConstants,
+    ) -> Result<(), ContinuationError> {
+        let sheet_data = &self.sheet_data;
+        let solve = |f: &dyn Fn(Complex64) -> Complex64, df: &dyn Fn(Complex64) -> Complex64| {
+            nr::find_root(|p| f(p) - value, df, self.p, 1.0e-12, 50)
+        };
+
+        let p = match component {
+            Component::P => value,
+            Component::Xp => solve(&|p| xp_on_sheet(p, 1.0, consts, sheet_data), &|p| {
+                dxp_dp_on_sheet(p, 1.0, consts, sheet_data)
+            })?,
+            Component::Xm => solve(&|p| xm_on_sheet(p, 1.0, consts, sheet_data), &|p| {
+                dxm_dp_on_sheet(p, 1.0, consts, sheet_data)
+            })?,
+            Component::U => solve(&|p| u(p, consts, sheet_data), &|p| {
+                du_dp(p, consts, sheet_data)
+            })?,
+            Component::E => solve(&|p| en_on_sheet(p, 1.0, consts, sheet_data), &|p| {
+                den_dp_on_sheet(p, 1.0, consts, sheet_data)
+            })?,
+            Component::X | Component::MirrorP | Component::MirrorE | Component::Torus => {
+                unreachable!("{component} can not be refined")
+            }
+        };
+
+        *self = self.shifted(p, &self.sheet_data, consts)?;
+
+        // Remove the numerical noise left by the root finding
+        match component {
+            Component::P => {}
+            Component::Xp => self.xp = value,
+            Component::Xm => self.xm = value,
+            Component::U => self.u = value,
+            Component::E => self.e = value,
+            Component::X | Component::MirrorP | Component::MirrorE | Component::Torus => {}
+        }
+        Ok(())
+    }
+
+    // The derivative of the component with respect to p at this point
+    pub fn derivative(&self, component: Component, consts: CouplingConstants) -> Complex64 {
+        let sheet_data = &self.sheet_data;
+        match component {
+            Component::P => Complex64::from(1.0),
+            Component::Xp => dxp_dp_on_sheet(self.p, 1.0, consts, sheet_data),
+            Component::Xm => dxm_dp_on_sheet(self.p, 1.0, consts, sheet_data),
+            Component::U => du_dp(self.p, consts, sheet_data),
+            Component::E => den_dp_on_sheet(self.p, 1.0, consts, sheet_data),
+            Component::X => du_dp(self.p, consts, sheet_data) / du_dx(self.x, consts),
+            Component::MirrorP => {
+                -Complex64::i() * den_dp_on_sheet(self.p, 1.0, consts, sheet_data) / TAU
+            }
+            Component::MirrorE => -Complex64::i() * TAU,
+            Component::Torus => dtorus_z_dp(self.undeformed_e(consts), consts),
+        }
+    }
+
+    // The local Jacobian of the map from one component to another. Since the
+    // maps are holomorphic this is a single complex number, whose argument
+    // gives the rotation and whose modulus gives the scale.
+    pub fn jacobian(&self, from: Component, to: Component, consts: CouplingConstants) -> Complex64 {
+        self.derivative(to, consts) / self.derivative(from, consts)
+    }
+
     pub fn same_sheet(&self, other: &Point, component: Component) -> bool {
         let sd1 = &self.sheet_data;
         let sd2 = &other.sheet_data;
@@ -280,7 +647,26 @@ impl Point {
     }
 
     pub fn en(&self, consts: CouplingConstants) -> Complex64 {
-        -Complex64::i() * consts.h / 2.0 * (self.xp - 1.0 / self.xp - self.xm + 1.0 / self.xm)
+        let en =
+            -Complex64::i() * consts.h / 2.0 * (self.xp - 1.0 / self.xp - self.xm + 1.0 / self.xm);
+        consts.model().energy(en, self.sheet_data.q_branch)
+    }
+
+    // The bound state number read off from the shortening condition. This is 1
+    // on the physical sheet but changes when crossing some of the cuts.
+    pub fn m(&self, consts: CouplingConstants) -> Complex64 {
+        -Complex64::i() * consts.h / 2.0 * (self.xp + 1.0 / self.xp - self.xm - 1.0 / self.xm)
+            - consts.k() as f64 * self.p
+    }
+}
+
+// E at p, continued from the value e at a nearby point
+fn continued_en(p: Complex64, e: Complex64, consts: CouplingConstants) -> Complex64 {
+    let next = en(p, 1.0, consts);
+    if (next - e).norm_sqr() <= (next + e).norm_sqr() {
+        next
+    } else {
+        -next
     }
 }
 
@@ -290,8 +676,12 @@ impl SheetData {
         let sd2 = other;
 
         match component {
-            Component::P => sd1.e_branch == sd2.e_branch,
-            Component::U => {
+            Component::P | Component::E | Component::MirrorP | Component::MirrorE => {
+                sd1.e_branch == sd2.e_branch && sd1.q_branch == sd2.q_branch
+            }
+            // Both sheets of E are parts of the torus
+            Component::Torus => true,
+            Component::U | Component::X => {
                 if sd1.u_branch == sd2.u_branch
                     && (sd1.u_branch.0 == UBranch::Between || sd1.u_branch.1 == UBranch::Between)
                 {
@@ -1,14 +1,80 @@
+use crate::cas::{self, Syntax, Value};
 use crate::contours::{Component, Contours};
 use crate::interpolation::PInterpolatorMut;
-use crate::kinematics::{xm_on_sheet, xp_on_sheet, CouplingConstants};
-use crate::point::Point;
+use crate::kinematics::{xm_on_sheet, xp_on_sheet, CouplingConstants, SheetData, UBranch};
+use crate::nr::{ContinuationError, ContinuationMethod, ContinuationStats};
+use crate::point::{Point, Snap};
 use num::complex::Complex64;
 
-#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+// A total quantity that is kept fixed when an excitation of an unlocked state is
+// moved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Conserved {
+    Momentum,
+    Energy,
+}
+
+impl Conserved {
+    fn component(&self) -> Component {
+        match self {
+            Self::Momentum => Component::P,
+            Self::Energy => Component::E,
+        }
+    }
+}
+
+// The excitations that are moved to keep the conserved quantity fixed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Compensation {
+    Point(usize),
+    Others,
+}
+
+impl Compensation {
+    // The compensating excitations, which can not include the active point
+    fn points(&self, active_point: usize, len: usize) -> Result<Vec<usize>, ContinuationError> {
+        let points = match *self {
+            Self::Point(i) if i != active_point && i < len => vec![i],
+            Self::Point(_) => vec![],
+            Self::Others => (0..len).filter(|&i| i != active_point).collect(),
+        };
+        if points.is_empty() {
+            log::warn!(
+                "Can not compensate the change of excitation {active_point} with {self:?} in a state with {len} points"
+            );
+            return Err(ContinuationError::LeftDomain);
+        }
+        Ok(points)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Constraint {
+    pub conserved: Conserved,
+    pub compensation: Compensation,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 #[serde(default)]
 pub struct State {
     pub points: Vec<Point>,
     pub unlocked: bool,
+    // Only used when the state is unlocked
+    pub constraint: Option<Constraint>,
+    #[serde(skip)]
+    pub stats: ContinuationStats,
+    #[serde(skip)]
+    pub method: ContinuationMethod,
+}
+
+// The statistics and the method only describe how the state was reached, and
+// are not saved, so they are left out when comparing states
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.points == other.points
+            && self.unlocked == other.unlocked
+            && self.constraint == other.constraint
+    }
 }
 
 impl State {
@@ -30,7 +96,9 @@ impl State {
         for _ in 0..max_steps {
             let du = u0 - pt.u.re;
             let u = pt.u.re + du.abs().min(step_size).copysign(du);
-            pt.update(Component::U, Complex64::new(u, pt.u.im), &[], consts);
+            if let Err(err) = pt.update(Component::U, Complex64::new(u, pt.u.im), &[], consts) {
+                log::debug!("Could not update u: {err}");
+            }
             if (u0 - pt.u.re).abs() < 0.01 {
                 break;
             }
@@ -50,7 +118,9 @@ impl State {
             let xm = pt.xm;
             let steps = 4;
             for _ in 1..=steps {
-                pt.update(Component::Xp, xm, &[], consts);
+                if let Err(err) = pt.update(Component::Xp, xm, &[], consts) {
+                    log::debug!("Could not update xp: {err}");
+                }
             }
             points.push(pt);
         }
@@ -58,6 +128,9 @@ impl State {
         Self {
             points,
             unlocked: false,
+            constraint: None,
+            stats: Default::default(),
+            method: Default::default(),
         }
     }
 
@@ -67,7 +140,12 @@ impl State {
         final_value: Complex64,
         contours: &Contours,
         consts: CouplingConstants,
-    ) -> bool {
+        method: ContinuationMethod,
+        stats: &mut ContinuationStats,
+    ) -> Result<(), ContinuationError> {
+        // The cuts are crossed in the plane of the component that is moved in
+        // the end
+        let (component, final_value) = pt.base_target(component, final_value, consts);
         loop {
             let current_value = pt.get(component);
 
@@ -81,36 +159,83 @@ impl State {
             };
 
             if crossings.is_empty() {
-                if !pt.update(component, next_value, &[], consts) {
-                    return false;
-                }
-            } else if !pt.update(component, next_value, &crossings[0].1, consts) {
-                return false;
+                pt.update_tracked(component, next_value, &[], consts, method, stats)?;
+            } else {
+                pt.update_tracked(
+                    component,
+                    next_value,
+                    &crossings[0].1,
+                    consts,
+                    method,
+                    stats,
+                )?;
             }
 
             if next_value == final_value {
-                return true;
+                return Ok(());
             }
         }
     }
 
+    // Apply several updates and then restore the bound state condition once.
+    // If any update fails all points are left as they were. For a locked
+    // bound state the last update determines the other excitations.
     pub fn update_points(
         &mut self,
-        active_point: usize,
-        component: Component,
-        new_value: Complex64,
+        updates: &[(usize, Component, Complex64)],
         contours: &Contours,
         consts: CouplingConstants,
-    ) -> bool {
-        let mut result = true;
+    ) -> Result<(), ContinuationError> {
+        if let Some((index, _, _)) = updates.iter().find(|(i, _, _)| *i >= self.points.len()) {
+            log::warn!(
+                "Can not update excitation {index} in a state with {} points",
+                self.points.len()
+            );
+            return Err(ContinuationError::LeftDomain);
+        }
 
-        result &= Self::update_point(
-            &mut self.points[active_point],
-            component,
-            new_value,
-            contours,
-            consts,
-        );
+        let points = self.points.clone();
+        let result = self.apply_updates(updates, contours, consts);
+        if result.is_err() {
+            self.points = points;
+        }
+        result
+    }
+
+    fn apply_updates(
+        &mut self,
+        updates: &[(usize, Component, Complex64)],
+        contours: &Contours,
+        consts: CouplingConstants,
+    ) -> Result<(), ContinuationError> {
+        let Some(&(anchor, _, _)) = updates.last() else {
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        for &(index, component, new_value) in updates {
+            let point_result = Self::update_point(
+                &mut self.points[index],
+                component,
+                new_value,
+                contours,
+                consts,
+                self.method,
+                &mut self.stats,
+            );
+            result = result.and(point_result);
+        }
+
+        result.and(self.update_bound_state(anchor, contours, consts))
+    }
+
+    fn update_bound_state(
+        &mut self,
+        active_point: usize,
+        contours: &Contours,
+        consts: CouplingConstants,
+    ) -> Result<(), ContinuationError> {
+        let mut result = Ok(());
 
         if !self.unlocked {
             for i in (active_point + 1)..self.points.len() {
@@ -120,13 +245,16 @@ impl State {
                     consts,
                     &self.points[i - 1].sheet_data,
                 );
-                result &= Self::update_point(
+                let point_result = Self::update_point(
                     &mut self.points[i],
                     Component::Xp,
                     new_value,
                     contours,
                     consts,
+                    self.method,
+                    &mut self.stats,
                 );
+                result = result.and(point_result);
             }
 
             for i in (0..active_point).rev() {
@@ -136,13 +264,16 @@ impl State {
                     consts,
                     &self.points[i + 1].sheet_data,
                 );
-                result &= Self::update_point(
+                let point_result = Self::update_point(
                     &mut self.points[i],
                     Component::Xm,
                     new_value,
                     contours,
                     consts,
+                    self.method,
+                    &mut self.stats,
                 );
+                result = result.and(point_result);
             }
         }
         result
@@ -155,30 +286,1056 @@ impl State {
         new_value: Complex64,
         contours: &Contours,
         consts: CouplingConstants,
-    ) -> bool {
-        self.update_points(active_point, component, new_value, contours, consts)
+    ) -> Result<(), ContinuationError> {
+        let Some(constraint) = self.constraint.filter(|_| self.unlocked) else {
+            return self.apply_updates(&[(active_point, component, new_value)], contours, consts);
+        };
+
+        // The active point and the compensating excitations are moved
+        // together, so if any of them fails all points are left as they were
+        let points = self.points.clone();
+        let result = self.apply_constrained_update(
+            active_point,
+            component,
+            new_value,
+            constraint,
+            contours,
+            consts,
+        );
+        if result.is_err() {
+            self.points = points;
+        }
+        result
+    }
+
+    fn apply_constrained_update(
+        &mut self,
+        active_point: usize,
+        component: Component,
+        new_value: Complex64,
+        constraint: Constraint,
+        contours: &Contours,
+        consts: CouplingConstants,
+    ) -> Result<(), ContinuationError> {
+        let compensating = constraint
+            .compensation
+            .points(active_point, self.points.len())?;
+        let conserved = constraint.conserved.component();
+        let total = |state: &Self| {
+            state
+                .points
+                .iter()
+                .map(|pt| pt.get(conserved))
+                .sum::<Complex64>()
+        };
+
+        let before = total(self);
+        self.apply_updates(&[(active_point, component, new_value)], contours, consts)?;
+
+        // Share the change equally between the compensating excitations
+        let delta = (before - total(self)) / compensating.len() as f64;
+
+        let updates = compensating
+            .iter()
+            .map(|&i| (i, conserved, self.points[i].get(conserved) + delta))
+            .collect::<Vec<_>>();
+        self.apply_updates(&updates, contours, consts)
+    }
+
+    pub fn snap(
+        &mut self,
+        active_point: usize,
+        snap: Snap,
+        contours: &Contours,
+        consts: CouplingConstants,
+    ) -> Result<(), ContinuationError> {
+        // The point is moved like any other point, crossing the cuts on the way,
+        // and then refined to lie exactly on the locus
+        const STEPS: usize = 16;
+
+        let component = snap.component();
+        let start = self.points[active_point].get(component);
+        let value = snap.project(start, consts);
+        for step in 1..=STEPS {
+            let next_value = start + (value - start) * (step as f64 / STEPS as f64);
+            self.update(active_point, component, next_value, contours, consts)?;
+        }
+        self.points[active_point].refine(component, value, consts)?;
+        self.update_bound_state(active_point, contours, consts)
+    }
+
+    // Deform the state a towards b, where t = 0 gives a and t = 1 gives b. The
+    // points move along straight lines in p, and at t = 1 they have to have
+    // reached the sheets of the points of b.
+    pub fn interpolate(
+        a: &Self,
+        b: &Self,
+        t: f64,
+        contours: &Contours,
+        consts: CouplingConstants,
+    ) -> Result<Self, InterpolationError> {
+        if a.points.len() != b.points.len() {
+            return Err(InterpolationError::PointCountMismatch);
+        }
+
+        let t = t.clamp(0.0, 1.0);
+        let max_step = 0.05;
+
+        let mut state = a.clone();
+        state.unlocked = true;
+        state.constraint = None;
+
+        // All the points are moved together, so that the number of steps is
+        // set by the point that moves the furthest
+        let dps = a
+            .points
+            .iter()
+            .zip(b.points.iter())
+            .map(|(pt_a, pt_b)| t * (pt_b.p - pt_a.p))
+            .collect::<Vec<_>>();
+        let steps = dps
+            .iter()
+            .map(|dp| (dp.norm() / max_step).ceil() as usize)
+            .max()
+            .unwrap_or_default()
+            .max(1);
+
+        for step in 1..=steps {
+            let s = step as f64 / steps as f64;
+            let updates = a
+                .points
+                .iter()
+                .zip(dps.iter())
+                .enumerate()
+                .map(|(i, (pt_a, dp))| (i, Component::P, pt_a.p + dp * s))
+                .collect::<Vec<_>>();
+            state.update_points(&updates, contours, consts)?;
+        }
+
+        if t == 1.0
+            && state
+                .points
+                .iter()
+                .zip(b.points.iter())
+                .any(|(pt, pt_b)| pt.sheet_data != pt_b.sheet_data)
+        {
+            return Err(InterpolationError::SheetMismatch);
+        }
+
+        state.unlocked = a.unlocked;
+        state.constraint = a.constraint;
+        Ok(state)
+    }
+
+    // Continue the state to another value of h at fixed k. Each excitation is
+    // moved so that h u stays fixed, which preserves the relations between the
+    // excitations of bound states and singlets, without changing sheets.
+    pub fn continue_in_h(
+        &mut self,
+        consts: CouplingConstants,
+        h: f64,
+    ) -> Result<(), ContinuationError> {
+        let max_step = 0.02;
+        let ratio = h / consts.h;
+        let steps = (ratio.ln().abs() / max_step).ceil() as usize;
+
+        let mut current = consts;
+        for step in 1..=steps {
+            let mut next = consts;
+            next.h = consts.h * ratio.powf(step as f64 / steps as f64);
+            for pt in self.points.iter_mut() {
+                let u = pt.u * current.h / next.h;
+                pt.update(Component::U, u, &[], next)?;
+            }
+            current = next;
+        }
+        Ok(())
+    }
+
+    // Add a copy of an excitation continued to the given value, and return its
+    // index. The new state is no longer a bound state, so it is unlocked.
+    pub fn add_point(
+        &mut self,
+        from: usize,
+        component: Component,
+        new_value: Complex64,
+        contours: &Contours,
+        consts: CouplingConstants,
+    ) -> Result<usize, ContinuationError> {
+        let Some(point) = self.points.get(from) else {
+            return Err(ContinuationError::LeftDomain);
+        };
+        let mut point = point.clone();
+        Self::update_point(
+            &mut point,
+            component,
+            new_value,
+            contours,
+            consts,
+            self.method,
+            &mut self.stats,
+        )?;
+
+        self.points.push(point);
+        self.unlocked = true;
+        Ok(self.points.len() - 1)
+    }
+
+    // A state always keeps at least one excitation. Removing the first or the
+    // last excitation of a bound state leaves a smaller bound state, while
+    // removing any other excitation unlocks the state.
+    pub fn remove_point(&mut self, index: usize) -> bool {
+        if self.points.len() < 2 || index >= self.points.len() {
+            return false;
+        }
+        self.points.remove(index);
+        if index != 0 && index != self.points.len() {
+            self.unlocked = true;
+        }
+        true
+    }
+
+    pub fn take_stats(&mut self) -> ContinuationStats {
+        std::mem::take(&mut self.stats)
     }
 
     pub fn p(&self) -> Complex64 {
         self.points.iter().map(|pxu| pxu.p).sum::<Complex64>()
     }
 
+    // States saved before the energy was stored have e = 0 for all points, and
+    // states saved before the single x and the torus coordinate were stored
+    // have x = z = 0
+    pub fn migrate(mut self, consts: CouplingConstants) -> Self {
+        for pt in self.points.iter_mut() {
+            if pt.e == Complex64::default() {
+                pt.e = pt.en(consts);
+            }
+            if pt.x == Complex64::default() || pt.z == Complex64::default() {
+                pt.compute_derived(consts);
+            }
+        }
+        self
+    }
+
     pub fn en(&self, consts: CouplingConstants) -> Complex64 {
         self.points
             .iter()
             .map(|pt| pt.en(consts))
             .sum::<Complex64>()
     }
+
+    pub fn to_mathematica(&self) -> String {
+        self.to_cas(Syntax::Mathematica)
+    }
+
+    pub fn to_maple(&self) -> String {
+        self.to_cas(Syntax::Maple)
+    }
+
+    // Read a state in the RON format. Unlike plain deserialization this reports
+    // which excitation could not be read, and recomputes coordinates and sheet
+    // data that are missing from hand edited states.
+    pub fn parse(input: &str, consts: CouplingConstants) -> Result<Self, StateError> {
+        let options = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+        let partial: PartialState =
+            options
+                .from_str(input.trim())
+                .map_err(|err| StateError::Syntax {
+                    line: err.position.line,
+                    col: err.position.col,
+                    message: err.code.to_string(),
+                })?;
+
+        if partial.points.0.is_empty() {
+            return Err(StateError::Empty);
+        }
+
+        let points = partial
+            .points
+            .0
+            .into_iter()
+            .enumerate()
+            .map(|(index, pt)| {
+                pt.recover(index, consts)
+                    .map_err(|message| StateError::Point { index, message })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            points,
+            unlocked: partial.unlocked,
+            constraint: partial.constraint,
+            stats: Default::default(),
+            method: Default::default(),
+        })
+    }
+
+    // Read a state from a Mathematica or Maple style list. Each excitation is
+    // given either as a list of rules such as {p -> 0.1, logBranch -> {0, 0}},
+    // as a plain list {p, xp, xm, u} or just as the momentum. Missing members
+    // are solved for from the first of p, xp, xm, u and E that is given.
+    pub fn from_mathematica(input: &str, consts: CouplingConstants) -> Option<Self> {
+        let value = cas::parse(input)?;
+        let excitations = match value {
+            Value::List(items) if items.iter().all(|(name, _)| name.is_none()) => {
+                items.into_iter().map(|(_, value)| value).collect()
+            }
+            value => vec![value],
+        };
+
+        let points = excitations
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let pt = point_from_cas(value, consts);
+                if pt.is_none() {
+                    log::warn!("Could not read excitation {i}");
+                }
+                pt
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if points.is_empty() {
+            log::warn!("No excitations in input");
+            return None;
+        }
+
+        let is_bound_state = points
+            .iter()
+            .zip(points.iter().skip(1))
+            .all(|(pt1, pt2)| (pt1.xm - pt2.xp).norm() < IMPORT_TOLERANCE * pt1.xm.norm().max(1.0));
+
+        Some(Self {
+            points,
+            unlocked: !is_bound_state,
+            constraint: None,
+            stats: Default::default(),
+            method: Default::default(),
+        })
+    }
+
+    fn to_cas(&self, syntax: Syntax) -> String {
+        syntax.long_list(self.points.iter().map(|pt| {
+            let mut rules = vec![
+                syntax.rule("p", syntax.complex(pt.p)),
+                syntax.rule("xp", syntax.complex(pt.xp)),
+                syntax.rule("xm", syntax.complex(pt.xm)),
+                syntax.rule("u", syntax.complex(pt.u)),
+                syntax.rule("en", syntax.complex(pt.e)),
+            ];
+            rules.extend(syntax.sheet_data_rules(&pt.sheet_data));
+            syntax.list(rules)
+        }))
+    }
+
+    pub fn display(&self, consts: CouplingConstants) -> StateDisplay<'_> {
+        StateDisplay {
+            state: self,
+            consts,
+        }
+    }
+
+    pub fn to_latex_table(&self, consts: CouplingConstants) -> String {
+        let precision = 4;
+        let mut lines = vec![
+            r"\begin{tabular}{rccccccccc}".to_owned(),
+            r"\hline".to_owned(),
+            r"& $p$ & $x^+$ & $x^-$ & $u$ & $m$ & $E$ & log branch & $E$ branch & $u$ branch \\"
+                .to_owned(),
+            r"\hline".to_owned(),
+        ];
+
+        for (i, pt) in self.points.iter().enumerate() {
+            let sd = &pt.sheet_data;
+            let values = [pt.p, pt.xp, pt.xm, pt.u, pt.m(consts), pt.en(consts)]
+                .into_iter()
+                .map(|z| format!("${}$", latex_complex(z, precision)))
+                .collect::<Vec<_>>()
+                .join(" & ");
+            lines.push(format!(
+                r"{i} & {values} & $({}, {})$ & ${:+}$ & {}/{} \\",
+                sd.log_branch_p, sd.log_branch_m, sd.e_branch, sd.u_branch.0, sd.u_branch.1
+            ));
+        }
+
+        lines.push(r"\hline".to_owned());
+        lines.push(format!(
+            r"Total & ${}$ & & & & & ${}$ & & & \\",
+            latex_complex(self.p(), precision),
+            latex_complex(self.en(consts), precision)
+        ));
+        lines.push(r"\hline".to_owned());
+        lines.push(r"\end{tabular}".to_owned());
+
+        lines.join("\n") + "\n"
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateError {
+    Syntax {
+        line: usize,
+        col: usize,
+        message: String,
+    },
+    Point {
+        index: usize,
+        message: String,
+    },
+    Empty,
+}
+
+impl std::fmt::Display for StateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { line, col, message } => {
+                write!(f, "line {line}, column {col}: {message}")
+            }
+            Self::Point { index, message } => write!(f, "excitation {index}: {message}"),
+            Self::Empty => write!(f, "the state has no excitations"),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+// The ways interpolating between two states can fail, in addition to the
+// continuation of the points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterpolationError {
+    Continuation(ContinuationError),
+    PointCountMismatch,
+    SheetMismatch,
+}
+
+impl From<ContinuationError> for InterpolationError {
+    fn from(err: ContinuationError) -> Self {
+        Self::Continuation(err)
+    }
+}
+
+impl std::fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Continuation(err) => write!(f, "{err}"),
+            Self::PointCountMismatch => write!(f, "The states have different numbers of points"),
+            Self::SheetMismatch => write!(f, "Ended up on a different sheet"),
+        }
+    }
+}
+
+impl std::error::Error for InterpolationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Continuation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// Lenient versions of State, Point and SheetData used by State::parse, where
+// any member can be left out. Unknown members are rejected to catch typos.
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PartialState {
+    points: PartialPoints,
+    #[serde(default)]
+    unlocked: bool,
+    #[serde(default)]
+    constraint: Option<Constraint>,
+}
+
+struct PartialPoints(Vec<PartialPoint>);
+
+// Deserialize the points one at a time so that errors name the excitation
+impl<'de> serde::Deserialize<'de> for PartialPoints {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PointsVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for PointsVisitor {
+            type Value = PartialPoints;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "a list of excitations")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                use serde::de::Error;
+
+                let mut points = vec![];
+                loop {
+                    match seq.next_element::<PartialPoint>() {
+                        Ok(Some(pt)) => points.push(pt),
+                        Ok(None) => return Ok(PartialPoints(points)),
+                        Err(err) => {
+                            return Err(A::Error::custom(format!(
+                                "excitation {}: {err}",
+                                points.len()
+                            )))
+                        }
+                    }
+                }
+            }
+        }
+
+        deserializer.deserialize_seq(PointsVisitor)
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PartialPoint {
+    #[serde(default)]
+    p: Option<Complex64>,
+    #[serde(default)]
+    xp: Option<Complex64>,
+    #[serde(default)]
+    xm: Option<Complex64>,
+    #[serde(default)]
+    u: Option<Complex64>,
+    #[serde(default)]
+    e: Option<Complex64>,
+    #[serde(default)]
+    sheet_data: Option<PartialSheetData>,
+    // Written by older versions
+    #[serde(default, rename = "x")]
+    _x: Option<serde::de::IgnoredAny>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PartialSheetData {
+    #[serde(default)]
+    log_branch_p: Option<i32>,
+    #[serde(default)]
+    log_branch_m: Option<i32>,
+    #[serde(default)]
+    e_branch: Option<i32>,
+    #[serde(default)]
+    u_branch: Option<(UBranch, UBranch)>,
+    #[serde(default)]
+    im_x_sign: Option<(i8, i8)>,
+    #[serde(default)]
+    q_branch: Option<i32>,
+    // Written by older versions
+    #[serde(default, rename = "log_branch_x")]
+    _log_branch_x: Option<serde::de::IgnoredAny>,
+}
+
+impl PartialPoint {
+    fn recover(self, index: usize, consts: CouplingConstants) -> Result<Point, String> {
+        let mut sheet_data = Point::new(self.p.unwrap_or(0.25.into()), consts).sheet_data;
+        let mut missing = vec![];
+
+        if let Some(sd) = self.sheet_data {
+            let PartialSheetData {
+                log_branch_p,
+                log_branch_m,
+                e_branch,
+                u_branch,
+                im_x_sign,
+                q_branch,
+                _log_branch_x: _,
+            } = sd;
+            for (name, given) in [
+                ("log_branch_p", log_branch_p.is_some()),
+                ("log_branch_m", log_branch_m.is_some()),
+                ("e_branch", e_branch.is_some()),
+                ("u_branch", u_branch.is_some()),
+                ("im_x_sign", im_x_sign.is_some()),
+            ] {
+                if !given {
+                    missing.push(name);
+                }
+            }
+            sheet_data.log_branch_p = log_branch_p.unwrap_or(sheet_data.log_branch_p);
+            sheet_data.log_branch_m = log_branch_m.unwrap_or(sheet_data.log_branch_m);
+            sheet_data.e_branch = e_branch.unwrap_or(sheet_data.e_branch);
+            sheet_data.u_branch = u_branch.unwrap_or(sheet_data.u_branch);
+            sheet_data.im_x_sign = im_x_sign.unwrap_or(sheet_data.im_x_sign);
+            // Only written for the q-deformed model
+            sheet_data.q_branch = q_branch.unwrap_or(sheet_data.q_branch);
+        } else {
+            missing.push("sheet_data");
+        }
+
+        let values = [
+            (Component::P, self.p),
+            (Component::Xp, self.xp),
+            (Component::Xm, self.xm),
+            (Component::U, self.u),
+        ];
+
+        let pt = if let [Some(p), Some(xp), Some(xm), Some(u)] = values.map(|(_, z)| z) {
+            let mut pt = Point {
+                p,
+                xp,
+                xm,
+                u,
+                e: Complex64::default(),
+                x: Complex64::default(),
+                z: Complex64::default(),
+                sheet_data,
+            };
+            // States saved before the energy was stored have no e
+            pt.e = self.e.unwrap_or_else(|| pt.en(consts));
+            pt.compute_derived(consts);
+            pt
+        } else {
+            let Some((component, z)) = values
+                .iter()
+                .chain(&[(Component::E, self.e)])
+                .find_map(|&(component, z)| z.map(|z| (component, z)))
+            else {
+                return Err("none of p, xp, xm, u or e is given".to_owned());
+            };
+
+            for (component, z) in values.iter().chain(&[(Component::E, self.e)]) {
+                if z.is_none() {
+                    missing.push(match component {
+                        Component::P => "p",
+                        Component::Xp => "xp",
+                        Component::Xm => "xm",
+                        Component::U => "u",
+                        Component::E => "e",
+                        _ => unreachable!("only the stored components can be given"),
+                    });
+                }
+            }
+
+            solve_point(component, z, &sheet_data, consts)
+                .map_err(|err| format!("could not solve for p from {component}: {err}"))?
+        };
+
+        if !missing.is_empty() {
+            log::warn!("Recomputed {} for excitation {index}", missing.join(", "));
+        }
+
+        Ok(pt)
+    }
+}
+
+const IMPORT_TOLERANCE: f64 = 1.0e-6;
+
+fn point_from_cas(value: &Value, consts: CouplingConstants) -> Option<Point> {
+    const COMPONENTS: [Component; 4] = [Component::P, Component::Xp, Component::Xm, Component::U];
+
+    let mut values: Vec<(Component, Complex64)> = vec![];
+    let mut sheet_items = vec![];
+
+    match value {
+        Value::Number(p) => values.push((Component::P, *p)),
+        Value::List(items) if items.iter().all(|(name, _)| name.is_none()) => {
+            if items.len() > COMPONENTS.len() {
+                log::warn!("Expected at most {} values", COMPONENTS.len());
+                return None;
+            }
+            for (component, (_, value)) in COMPONENTS.into_iter().zip(items.iter()) {
+                let Value::Number(z) = value else {
+                    log::warn!("Expected a number for {component}");
+                    return None;
+                };
+                values.push((component, *z));
+            }
+        }
+        Value::List(items) => {
+            for (name, value) in items {
+                let Some(name) = name else {
+                    log::warn!("Ignoring value without a name");
+                    continue;
+                };
+                let key = name
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .collect::<String>()
+                    .to_lowercase();
+                let component = match key.as_str() {
+                    "p" => Component::P,
+                    "xp" | "xplus" => Component::Xp,
+                    "xm" | "xminus" => Component::Xm,
+                    "u" => Component::U,
+                    "e" | "en" | "energy" => Component::E,
+                    "logbranch" | "ebranch" | "ubranch" | "imxsign" | "qbranch" => {
+                        sheet_items.push((key, value));
+                        continue;
+                    }
+                    _ => {
+                        log::warn!("Ignoring unknown member {name}");
+                        continue;
+                    }
+                };
+                let Value::Number(z) = value else {
+                    log::warn!("Expected a number for {name}");
+                    return None;
+                };
+                values.push((component, *z));
+            }
+        }
+        Value::String(s) => {
+            log::warn!("Expected a number or a list, found \"{s}\"");
+            return None;
+        }
+    }
+
+    let given_p = values.iter().find(|(c, _)| *c == Component::P).map(|v| v.1);
+    let mut sheet_data = Point::new(given_p.unwrap_or(0.25.into()), consts).sheet_data;
+    for (key, value) in sheet_items {
+        match (key.as_str(), value) {
+            ("logbranch", Value::List(items)) if items.len() == 2 => {
+                sheet_data.log_branch_p = integer(&items[0].1)?;
+                sheet_data.log_branch_m = integer(&items[1].1)?;
+            }
+            ("ebranch", value) => {
+                sheet_data.e_branch = integer(value)?;
+            }
+            ("qbranch", value) => {
+                sheet_data.q_branch = integer(value)?;
+            }
+            ("ubranch", Value::List(items)) if items.len() == 2 => {
+                sheet_data.u_branch = (u_branch(&items[0].1)?, u_branch(&items[1].1)?);
+            }
+            ("imxsign", Value::List(items)) if items.len() == 2 => {
+                sheet_data.im_x_sign = (integer(&items[0].1)? as i8, integer(&items[1].1)? as i8);
+            }
+            _ => {
+                log::warn!("Could not read {key}");
+                return None;
+            }
+        }
+    }
+
+    let Some(&(component, z)) = [
+        Component::P,
+        Component::Xp,
+        Component::Xm,
+        Component::U,
+        Component::E,
+    ]
+    .iter()
+    .find_map(|c| values.iter().find(|(component, _)| component == c)) else {
+        log::warn!("Excitation has neither p, xp, xm, u nor E");
+        return None;
+    };
+
+    let pt = match solve_point(component, z, &sheet_data, consts) {
+        Ok(pt) => pt,
+        Err(err) => {
+            log::warn!("Could not solve for p from {component}: {err}");
+            return None;
+        }
+    };
+
+    for (component, z) in values {
+        if (pt.get(component) - z).norm() > IMPORT_TOLERANCE * z.norm().max(1.0) {
+            log::warn!(
+                "Inconsistent value for {component}: given {z}, computed {}",
+                pt.get(component)
+            );
+        }
+    }
+
+    Some(pt)
+}
+
+// Find the point on the given sheet where the component takes the given value
+fn solve_point(
+    component: Component,
+    z: Complex64,
+    sheet_data: &SheetData,
+    consts: CouplingConstants,
+) -> Result<Point, ContinuationError> {
+    if component == Component::P {
+        return Ok(Point::on_sheet(z, sheet_data, consts));
+    }
+
+    // Continue to the given value from the closest of a few points on the sheet
+    let samples = 20;
+    let Some(mut pt) = (0..samples)
+        .map(|i| {
+            let p0 = sheet_data.log_branch_m as f64 + (i as f64 + 0.5) / samples as f64;
+            Point::on_sheet(p0.into(), sheet_data, consts)
+        })
+        .min_by(|pt1, pt2| {
+            let d1 = (pt1.get(component) - z).norm();
+            let d2 = (pt2.get(component) - z).norm();
+            d1.total_cmp(&d2)
+        })
+    else {
+        return Err(ContinuationError::LeftDomain);
+    };
+    let z0 = pt.get(component);
+    let steps = 32;
+    for step in 1..=steps {
+        let value = z0 + (z - z0) * (step as f64 / steps as f64);
+        pt.update(component, value, &[], consts)?;
+    }
+    Ok(pt)
+}
+
+fn integer(value: &Value) -> Option<i32> {
+    match value {
+        Value::Number(z) if z.im == 0.0 && z.re.fract() == 0.0 => Some(z.re as i32),
+        _ => {
+            log::warn!("Expected an integer, found {value:?}");
+            None
+        }
+    }
+}
+
+fn u_branch(value: &Value) -> Option<UBranch> {
+    let Value::String(s) = value else {
+        log::warn!("Expected a u branch, found {value:?}");
+        return None;
+    };
+    match s.to_lowercase().as_str() {
+        "out" | "outside" => Some(UBranch::Outside),
+        "bet" | "between" => Some(UBranch::Between),
+        "in" | "inside" => Some(UBranch::Inside),
+        _ => {
+            log::warn!("Unknown u branch {s}");
+            None
+        }
+    }
+}
+
+pub struct StateDisplay<'a> {
+    state: &'a State,
+    consts: CouplingConstants,
+}
+
+impl std::fmt::Display for StateDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let precision = f.precision().unwrap_or(4);
+        let (state, consts) = (self.state, self.consts);
+
+        writeln!(
+            f,
+            "{} excitations, p = {:+.precision$}, E = {:+.precision$}",
+            state.points.len(),
+            state.p(),
+            state.en(consts)
+        )?;
+
+        for (i, pt) in state.points.iter().enumerate() {
+            let sd = &pt.sheet_data;
+            writeln!(f, "#{i}:")?;
+            writeln!(f, "  p:  {:+.precision$}", pt.p)?;
+            writeln!(f, "  x⁺: {:+.precision$}", pt.xp)?;
+            writeln!(f, "  x⁻: {:+.precision$}", pt.xm)?;
+            writeln!(f, "  u:  {:+.precision$}", pt.u)?;
+            writeln!(f, "  m:  {:+.precision$}", pt.m(consts))?;
+            writeln!(f, "  E:  {:+.precision$}", pt.en(consts))?;
+            writeln!(
+                f,
+                "  log branch: {:+} {:+}, E branch: {:+}, u branch: ({},{}), Im x sign: ({:+},{:+})",
+                sd.log_branch_p,
+                sd.log_branch_m,
+                sd.e_branch,
+                sd.u_branch.0,
+                sd.u_branch.1,
+                sd.im_x_sign.0,
+                sd.im_x_sign.1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn latex_complex(z: Complex64, precision: usize) -> String {
+    let im = format!("{:.precision$}", z.im.abs());
+    // Avoid printing -0.0000 for small negative imaginary parts
+    let sign = if z.im < 0.0 && im.contains(|c: char| ('1'..='9').contains(&c)) {
+        '-'
+    } else {
+        '+'
+    };
+    format!("{:.precision$} {sign} {im}i", z.re)
+}
+
+// Builds a locked bound state with m excitations. The state is first moved to
+// the given p range and then, if a center is given, moved until the mean of its
+// excitations in that plane is at the center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundStateBuilder {
+    pub m: usize,
+    pub p_range: i32,
+    pub center: Option<(Component, Complex64)>,
+}
+
+impl BoundStateBuilder {
+    const MAX_STEP: f64 = 0.05;
+    const MAX_ITERATIONS: usize = 16;
+    const TOLERANCE: f64 = 1e-6;
+
+    pub fn new(m: usize) -> Self {
+        Self {
+            m: m.max(1),
+            p_range: 0,
+            center: None,
+        }
+    }
+
+    pub fn p_range(mut self, p_range: i32) -> Self {
+        self.p_range = p_range;
+        self
+    }
+
+    pub fn centered_at(mut self, component: Component, value: Complex64) -> Self {
+        self.center = Some((component, value));
+        self
+    }
+
+    pub fn build(
+        &self,
+        contours: &Contours,
+        consts: CouplingConstants,
+    ) -> Result<State, ContinuationError> {
+        let mut state = State::new(self.m, consts);
+
+        // Move the state in small steps, so that it stays on the same sheets
+        let goto = |state: &mut State,
+                    component: Component,
+                    delta: Complex64|
+         -> Result<(), ContinuationError> {
+            let start = state.points[0].get(component);
+            let steps = (delta.norm() / Self::MAX_STEP).ceil().max(1.0) as usize;
+            for step in 1..=steps {
+                let value = start + delta * (step as f64 / steps as f64);
+                state.update(0, component, value, contours, consts)?;
+            }
+            Ok(())
+        };
+
+        if self.p_range != 0 {
+            goto(
+                &mut state,
+                Component::P,
+                Complex64::from(self.p_range as f64),
+            )?;
+        }
+
+        let Some((component, center)) = self.center else {
+            return Ok(state);
+        };
+
+        // The excitations do not move rigidly, so the mean is approached
+        // iteratively
+        let mean = |state: &State| {
+            state
+                .points
+                .iter()
+                .map(|pt| pt.get(component))
+                .sum::<Complex64>()
+                / state.points.len() as f64
+        };
+        for _ in 0..Self::MAX_ITERATIONS {
+            let delta = center - mean(&state);
+            if delta.norm() < Self::TOLERANCE {
+                return Ok(state);
+            }
+            goto(&mut state, component, delta)?;
+        }
+
+        if (center - mean(&state)).norm() < Self::TOLERANCE {
+            Ok(state)
+        } else {
+            Err(ContinuationError::NoConvergence)
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Marker {
+    #[default]
+    Circle,
+    Square,
+    Diamond,
+    Triangle,
+}
+
+impl Marker {
+    pub const ALL: [Self; 4] = [Self::Circle, Self::Square, Self::Diamond, Self::Triangle];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Circle => "Circle",
+            Self::Square => "Square",
+            Self::Diamond => "Diamond",
+            Self::Triangle => "Triangle",
+        }
+    }
+}
+
+// How an excitation is drawn, so that it can be followed between the planes.
+// Without a color the usual colors for the active excitation and the sheets
+// are used.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PointStyle {
+    pub color: Option<[u8; 3]>,
+    pub marker: Marker,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct SavedState {
+    // States saved before the version field was introduced have version 0
+    #[serde(default)]
+    pub version: u32,
     pub consts: CouplingConstants,
     pub state: State,
+    // The styles of the excitations, in the same order. Excitations without a
+    // style are drawn as usual.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub point_styles: Vec<PointStyle>,
 }
 
 impl SavedState {
+    pub const VERSION: u32 = 2;
+
+    pub fn new(state: State, consts: CouplingConstants) -> Self {
+        Self {
+            version: Self::VERSION,
+            consts,
+            state,
+            point_styles: vec![],
+        }
+    }
+
+    // Only the styles that differ from the default ones are kept
+    pub fn with_point_styles(mut self, point_styles: &[PointStyle]) -> Self {
+        let len = point_styles
+            .iter()
+            .rposition(|style| *style != PointStyle::default())
+            .map_or(0, |index| index + 1);
+        self.point_styles = point_styles[..len].to_vec();
+        self
+    }
+
+    // Bring a state saved by an older version of the format up to date
+    pub fn migrate(mut self) -> Option<Self> {
+        if self.version > Self::VERSION {
+            log::warn!("Saved state has unknown version {}", self.version);
+            return None;
+        }
+
+        // Version 0 might lack the energies and version 1 the single x and the
+        // torus coordinate
+        if self.version < 2 {
+            self.state = self.state.migrate(self.consts);
+            self.version = 2;
+        }
+
+        Some(self)
+    }
+
     pub fn decode(input: &str) -> Option<Self> {
+        Self::decode_any(input)?.migrate()
+    }
+
+    fn decode_any(input: &str) -> Option<Self> {
         use base64::Engine;
         use std::io::Write;
 